@@ -1901,4 +1901,47 @@ impl<'a, 'll, 'tcx> Builder<'a, 'll, 'tcx> {
     ) {
         self.call_intrinsic("llvm.instrprof.increment", &[], &[fn_name, hash, num_counters, index]);
     }
+
+    /// Emits a call to `llvm.instrprof.mcdc.parameters`, which tells LLVM how
+    /// many bits to reserve in the function's MC/DC test-vector bitmap.
+    #[instrument(level = "debug", skip(self))]
+    pub(crate) fn mcdc_parameters(
+        &mut self,
+        fn_name: &'ll Value,
+        hash: &'ll Value,
+        bitmap_bits: &'ll Value,
+    ) {
+        self.call_intrinsic("llvm.instrprof.mcdc.parameters", &[], &[fn_name, hash, bitmap_bits]);
+    }
+
+    /// Emits a call to `llvm.instrprof.mcdc.tvbitmap.update`, which sets the
+    /// test-vector bit selected by the condition bitmap at `mcdc_temp`.
+    #[instrument(level = "debug", skip(self))]
+    pub(crate) fn mcdc_tvbitmap_update(
+        &mut self,
+        fn_name: &'ll Value,
+        hash: &'ll Value,
+        bitmap_index: &'ll Value,
+        mcdc_temp: &'ll Value,
+    ) {
+        let args = &[fn_name, hash, bitmap_index, mcdc_temp];
+        self.call_intrinsic("llvm.instrprof.mcdc.tvbitmap.update", &[], args);
+    }
+
+    /// Clears the condition bitmap at `mcdc_temp`, ready for the next
+    /// evaluation of its decision.
+    #[instrument(level = "debug", skip(self))]
+    pub(crate) fn mcdc_condbitmap_reset(&mut self, mcdc_temp: &'ll Value) {
+        self.store(self.const_i32(0), mcdc_temp, self.tcx.data_layout.i32_align);
+    }
+
+    /// Adds `cond_index` to the condition bitmap at `mcdc_temp`, recording
+    /// which outcome was taken by a condition of its decision.
+    #[instrument(level = "debug", skip(self))]
+    pub(crate) fn mcdc_condbitmap_update(&mut self, cond_index: &'ll Value, mcdc_temp: &'ll Value) {
+        let align = self.tcx.data_layout.i32_align;
+        let current_tv_index = self.load(self.cx.type_i32(), mcdc_temp, align);
+        let new_tv_index = self.add(current_tv_index, cond_index);
+        self.store(new_tv_index, mcdc_temp, align);
+    }
 }
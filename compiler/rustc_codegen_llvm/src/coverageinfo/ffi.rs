@@ -1,3 +1,5 @@
+use rustc_middle::mir::coverage;
+
 /// Must match the layout of `LLVMRustCounterKind`.
 #[derive(Copy, Clone, Debug)]
 #[repr(C)]
@@ -98,3 +100,58 @@ pub(crate) struct BranchRegion {
     pub(crate) true_counter: Counter,
     pub(crate) false_counter: Counter,
 }
+
+/// Must match the layout of `LLVMRustMCDCDecisionParameters`.
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub(crate) struct MCDCDecisionParameters {
+    pub(crate) bitmap_idx: u32,
+    pub(crate) num_conditions: u16,
+}
+
+/// Must match the layout of `LLVMRustMCDCBranchParameters`.
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub(crate) struct MCDCBranchParameters {
+    pub(crate) condition_id: i16,
+    /// IDs of the conditions that are evaluated next when this condition is
+    /// `[false, true]`, or -1 if the decision is complete at that point.
+    pub(crate) condition_ids: [i16; 2],
+}
+
+impl From<coverage::ConditionInfo> for MCDCBranchParameters {
+    fn from(info: coverage::ConditionInfo) -> Self {
+        let to_llvm_cond_id =
+            |cond_id: Option<coverage::ConditionId>| cond_id.map_or(-1, |id| id.as_u32() as i16);
+        let coverage::ConditionInfo { condition_id, true_next_id, false_next_id } = info;
+        Self {
+            condition_id: to_llvm_cond_id(Some(condition_id)),
+            condition_ids: [to_llvm_cond_id(false_next_id), to_llvm_cond_id(true_next_id)],
+        }
+    }
+}
+
+impl From<coverage::DecisionInfo> for MCDCDecisionParameters {
+    fn from(info: coverage::DecisionInfo) -> Self {
+        let coverage::DecisionInfo { bitmap_idx, num_conditions } = info;
+        Self { bitmap_idx, num_conditions }
+    }
+}
+
+/// Must match the layout of `LLVMRustCoverageMCDCBranchRegion`.
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub(crate) struct MCDCBranchRegion {
+    pub(crate) cov_span: CoverageSpan,
+    pub(crate) true_counter: Counter,
+    pub(crate) false_counter: Counter,
+    pub(crate) mcdc_branch_params: MCDCBranchParameters,
+}
+
+/// Must match the layout of `LLVMRustCoverageMCDCDecisionRegion`.
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub(crate) struct MCDCDecisionRegion {
+    pub(crate) cov_span: CoverageSpan,
+    pub(crate) mcdc_decision_params: MCDCDecisionParameters,
+}
@@ -240,6 +240,11 @@ impl CodegenBackend for LlvmCodegenBackend {
         "llvm"
     }
 
+    fn version(&self) -> String {
+        let (major, minor, patch) = llvm_util::get_version();
+        format!("{major}.{minor}.{patch}")
+    }
+
     fn init(&self, sess: &Session) {
         llvm_util::init(sess); // Make sure llvm is inited
     }
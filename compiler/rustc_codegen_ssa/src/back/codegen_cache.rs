@@ -0,0 +1,258 @@
+//! Reuse of the object files of codegen units across compilations, for `-Z codegen-cache-dir`.
+//!
+//! The key of a codegen unit is a stable hash of what its object files are generated from: its
+//! mono items, with the MIR that is code generated for them, the layouts of the types that they
+//! use and the values of the constants that they evaluate, combined with the options of the
+//! compilation, the target CPU and features as resolved by the backend, the version of the
+//! compiler and of the codegen backend, and the hashes of the dependencies of the crate. An edit
+//! of one module of the crate then only changes the keys of the codegen units that it is in. The
+//! storage of the entries is in `rustc_incremental`.
+
+use std::hash::Hash;
+use std::path::PathBuf;
+
+use rustc_data_structures::fingerprint::Fingerprint;
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::stable_hasher::{HashStable, StableHasher};
+use rustc_hir::def_id::LOCAL_CRATE;
+use rustc_incremental::{load_from_codegen_cache, save_to_codegen_cache};
+use rustc_middle::mir::MentionedItem;
+use rustc_middle::mir::mono::{CodegenUnit, MonoItem};
+use rustc_middle::ty::{self, Instance, Ty, TyCtxt, TypeFoldable};
+use rustc_query_system::ich::StableHashingContext;
+use rustc_session::Session;
+use rustc_session::config::{DebugInfo, Lto, RemapPathScopeComponents};
+use rustc_span::RealFileName;
+
+use crate::traits::CodegenBackend;
+use crate::{CachedModuleCodegen, CompiledModule, ModuleKind};
+
+/// The keys of the codegen units of the crate, if `-Z codegen-cache-dir` is used.
+#[derive(Default)]
+pub(crate) struct CodegenCache {
+    keys: FxHashMap<String, Fingerprint>,
+}
+
+impl CodegenCache {
+    pub(crate) fn new(
+        tcx: TyCtxt<'_>,
+        backend: &impl CodegenBackend,
+        target_cpu: &str,
+        codegen_units: &[CodegenUnit<'_>],
+    ) -> Self {
+        let sess = tcx.sess;
+        if sess.opts.unstable_opts.codegen_cache_dir.is_none() {
+            return Self::default();
+        }
+        // With fat LTO and cross-crate ThinLTO, the object files of the crate also depend on the
+        // code of the other crates in the LTO session.
+        if !matches!(sess.lto(), Lto::No | Lto::ThinLocal) {
+            return Self::default();
+        }
+
+        let _timer = sess.timer("codegen_cache_keys");
+
+        // The working directory only goes into the object files through the paths in the debuginfo,
+        // and in a form that doesn't depend on the machine if it is remapped.
+        let mut opts = sess.opts.clone();
+        opts.working_dir = RealFileName::LocalPath(PathBuf::new());
+        let working_dir = (sess.opts.debuginfo != DebugInfo::None).then(|| {
+            let preference = sess.filename_display_preference(RemapPathScopeComponents::DEBUGINFO);
+            sess.opts.working_dir.to_string_lossy(preference).into_owned()
+        });
+
+        let mut hasher = StableHasher::new();
+        opts.dep_tracking_hash(false).hash(&mut hasher);
+        working_dir.hash(&mut hasher);
+        sess.cfg_version.hash(&mut hasher);
+        backend.name().hash(&mut hasher);
+        backend.version().hash(&mut hasher);
+        sess.opts.incremental.is_some().hash(&mut hasher);
+        // The options only contain `-C target-cpu=native`, not the CPU of the host.
+        target_cpu.hash(&mut hasher);
+        let mut target_features: Vec<_> =
+            sess.unstable_target_features.iter().map(|feature| feature.as_str()).collect();
+        target_features.sort_unstable();
+        target_features.hash(&mut hasher);
+        // Untracked options that change the object files.
+        (sess.opts.unstable_opts.emit_stack_sizes || sess.opts.prints_stack_sizes())
+            .hash(&mut hasher);
+        sess.opts.unstable_opts.codegen_source_order.hash(&mut hasher);
+        // The symbol names of the items of the crate depend on its `StableCrateId`.
+        tcx.stable_crate_id(LOCAL_CRATE).hash(&mut hasher);
+        for &cnum in tcx.crates(()) {
+            tcx.crate_hash(cnum).hash(&mut hasher);
+        }
+        let crate_key: Fingerprint = hasher.finish();
+
+        let keys = tcx.with_stable_hashing_context(|mut hcx| {
+            codegen_units
+                .iter()
+                .map(|cgu| {
+                    let mut hasher = StableHasher::new();
+                    crate_key.hash_stable(&mut hcx, &mut hasher);
+                    cgu.hash_stable(&mut hcx, &mut hasher);
+                    for &item in cgu.items().keys() {
+                        hash_mono_item(tcx, item, &mut hcx, &mut hasher);
+                    }
+                    (cgu.name().to_string(), hasher.finish())
+                })
+                .collect()
+        });
+        CodegenCache { keys }
+    }
+
+    /// Looks up the codegen units that need to be compiled in the cache.
+    ///
+    /// With ThinLTO, the optimized module of a codegen unit depends on which symbols are
+    /// internalized in the other modules, so the entries are only used if all of the codegen units
+    /// of the crate are found.
+    pub(crate) fn lookup(
+        &self,
+        sess: &Session,
+        codegen_units: &[&CodegenUnit<'_>],
+        needs_codegen: impl Fn(usize) -> bool,
+    ) -> Vec<Option<CachedModuleCodegen>> {
+        if self.keys.is_empty() {
+            return codegen_units.iter().map(|_| None).collect();
+        }
+
+        let _timer = sess.timer("codegen_cache_lookup");
+
+        let mut modules: Vec<_> = codegen_units
+            .iter()
+            .enumerate()
+            .map(|(i, cgu)| {
+                if !needs_codegen(i) {
+                    return None;
+                }
+                let name = cgu.name().as_str();
+                let (entry, source) = load_from_codegen_cache(sess, name, self.keys[name])?;
+                Some(CachedModuleCodegen {
+                    name: name.to_string(),
+                    source,
+                    codegen_cache_entry: Some(entry),
+                })
+            })
+            .collect();
+
+        if sess.lto() == Lto::ThinLocal && modules.iter().any(|module| module.is_none()) {
+            modules.iter_mut().for_each(|module| *module = None);
+        }
+        modules
+    }
+
+    /// Adds the compiled modules to the cache.
+    pub(crate) fn save(&self, sess: &Session, modules: &[CompiledModule]) {
+        if self.keys.is_empty() {
+            return;
+        }
+
+        let _timer = sess.timer("codegen_cache_save");
+
+        for module in modules.iter().filter(|m| m.kind == ModuleKind::Regular) {
+            if let Some(&key) = self.keys.get(&module.name) {
+                save_to_codegen_cache(sess, key, &module.saved_files());
+            }
+        }
+    }
+}
+
+/// Hashes what the code of a mono item is generated from, beyond the item itself.
+fn hash_mono_item<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    item: MonoItem<'tcx>,
+    hcx: &mut StableHashingContext<'_>,
+    hasher: &mut StableHasher,
+) {
+    let typing_env = ty::TypingEnv::fully_monomorphized();
+    let hash_layout =
+        |ty: Ty<'tcx>, hcx: &mut StableHashingContext<'_>, hasher: &mut StableHasher| {
+            tcx.layout_of(typing_env.as_query_input(ty))
+                .ok()
+                .map(|layout| layout.layout)
+                .hash_stable(hcx, hasher)
+        };
+
+    match item {
+        MonoItem::Fn(instance) => {
+            tcx.symbol_name(instance).name.hash_stable(hcx, hasher);
+            tcx.codegen_instance_attrs(instance.def).hash_stable(hcx, hasher);
+            let body = tcx.codegen_mir(instance);
+            body.hash_stable(hcx, hasher);
+
+            for decl in &body.local_decls {
+                hash_layout(monomorphize(tcx, instance, decl.ty), hcx, hasher);
+            }
+            // The MIR only refers to the constants that are evaluated during codegen.
+            for constant in body.required_consts() {
+                monomorphize(tcx, instance, constant.const_)
+                    .eval(tcx, typing_env, constant.span)
+                    .ok()
+                    .hash_stable(hcx, hasher);
+            }
+            for item in body.mentioned_items() {
+                hash_mentioned_item(tcx, monomorphize(tcx, instance, item.node), hcx, hasher);
+            }
+        }
+        MonoItem::Static(def_id) => {
+            tcx.symbol_name(Instance::mono(tcx, def_id)).name.hash_stable(hcx, hasher);
+            tcx.codegen_fn_attrs(def_id).hash_stable(hcx, hasher);
+            tcx.eval_static_initializer(def_id).ok().hash_stable(hcx, hasher);
+            hash_layout(tcx.type_of(def_id).instantiate_identity(), hcx, hasher);
+        }
+        // The operands of `global_asm!` are not worth tracking one by one.
+        MonoItem::GlobalAsm(_) => tcx.crate_hash(LOCAL_CRATE).hash_stable(hcx, hasher),
+    }
+}
+
+/// Hashes what the calls and casts of an item depend on, but that the MIR of the item doesn't show.
+fn hash_mentioned_item<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    item: MentionedItem<'tcx>,
+    hcx: &mut StableHashingContext<'_>,
+    hasher: &mut StableHasher,
+) {
+    match item {
+        // Attributes of the callee like `#[track_caller]` change the calls to it.
+        MentionedItem::Fn(ty) => {
+            if let ty::FnDef(def_id, _) = *ty.kind() {
+                tcx.codegen_fn_attrs(def_id).hash_stable(hcx, hasher);
+            }
+        }
+        // The vtable of an unsizing cast to a trait object is made of the methods of the impl.
+        MentionedItem::UnsizeCast { source_ty, target_ty } => {
+            if let (Some(source), Some(target)) =
+                (source_ty.builtin_deref(true), target_ty.builtin_deref(true))
+            {
+                let typing_env = ty::TypingEnv::fully_monomorphized();
+                let (source, target) =
+                    tcx.struct_lockstep_tails_for_codegen(source, target, typing_env);
+                if let ty::Dynamic(predicates, ..) = target.kind()
+                    && let Some(principal) = predicates.principal()
+                {
+                    let trait_ref = tcx
+                        .instantiate_bound_regions_with_erased(principal.with_self_ty(tcx, source));
+                    tcx.vtable_entries(trait_ref).hash_stable(hcx, hasher);
+                }
+            } else {
+                // Custom unsizing casts, like those of `Rc`, are rare enough to not be worth
+                // finding the trait object of.
+                tcx.crate_hash(LOCAL_CRATE).hash_stable(hcx, hasher);
+            }
+        }
+        MentionedItem::Drop(_) | MentionedItem::Closure(_) => {}
+    }
+}
+
+fn monomorphize<'tcx, T: TypeFoldable<TyCtxt<'tcx>>>(
+    tcx: TyCtxt<'tcx>,
+    instance: Instance<'tcx>,
+    value: T,
+) -> T {
+    instance.instantiate_mir_and_normalize_erasing_regions(
+        tcx,
+        ty::TypingEnv::fully_monomorphized(),
+        ty::EarlyBinder::bind(value),
+    )
+}
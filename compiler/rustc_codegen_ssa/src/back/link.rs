@@ -57,6 +57,7 @@ use super::command::Command;
 use super::linker::{self, Linker};
 use super::metadata::{MetadataPosition, create_wrapper_file};
 use super::rpath::{self, RPathConfig};
+use super::stack_usage;
 use super::{apple, versioned_llvm_target};
 use crate::base::needs_allocator_shim_for_linking;
 use crate::{
@@ -82,6 +83,9 @@ pub fn link_binary(
     codegen_backend: &'static str,
 ) {
     let _timer = sess.timer("link_binary");
+    if sess.opts.prints_stack_sizes() {
+        stack_usage::print_stack_usage(sess, &codegen_results);
+    }
     let output_metadata = sess.opts.output_types.contains_key(&OutputType::Metadata);
     let mut tempfiles_for_stdout_output: Vec<PathBuf> = Vec::new();
     for &crate_type in &codegen_results.crate_info.crate_types {
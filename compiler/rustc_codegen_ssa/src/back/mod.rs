@@ -4,12 +4,14 @@ use rustc_session::Session;
 
 pub mod apple;
 pub mod archive;
+pub(crate) mod codegen_cache;
 pub(crate) mod command;
 pub mod link;
 pub(crate) mod linker;
 pub mod lto;
 pub mod metadata;
 pub(crate) mod rpath;
+pub mod stack_usage;
 pub mod symbol_export;
 pub mod write;
 
@@ -0,0 +1,501 @@
+//! The worst-case stack usage of the entry points of the crate, for `--print stack-sizes`.
+//!
+//! The frame size of each function is read from the `.stack_sizes` sections that the backend emits
+//! into the object files, like for `-Z emit-stack-sizes`. The call graph is the one found by the
+//! mono item collector, completed by the calls in the MIR that is code generated: calls through
+//! function pointers and trait objects make the stack usage unbounded, as does recursion, and
+//! calls to functions of other crates make it a lower bound.
+//!
+//! A function that is not in the object files was inlined everywhere, so its frame is already part
+//! of the frames of its callers. A function that is in the object files without a frame size, like
+//! a naked function, makes the stack usage a lower bound too. Calls inserted by the backend, e.g.
+//! to `memcpy`, are not known.
+
+use std::fmt::Write;
+use std::fs;
+
+use object::{Object, ObjectSection, ObjectSymbol, RelocationTarget, SectionIndex, SymbolKind};
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_data_structures::stack::ensure_sufficient_stack;
+use rustc_macros::{Decodable, Encodable};
+use rustc_middle::mir::{TerminatorKind, traversal};
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::{self, Instance, InstanceKind, TyCtxt};
+use rustc_session::Session;
+use rustc_session::config::PrintKind;
+use serde_json::json;
+
+use crate::{CodegenResults, errors};
+
+/// The call graph of the functions that are code generated by this crate.
+#[derive(Debug, Default, Encodable, Decodable)]
+pub struct CallGraph {
+    functions: Vec<Function>,
+}
+
+#[derive(Debug, Encodable, Decodable)]
+struct Function {
+    /// The path of the function, for the report.
+    name: String,
+    /// The symbol of the function, to find its frame size.
+    symbol: String,
+    /// The functions of this crate that may be called, as indices into `CallGraph::functions`.
+    callees: Vec<usize>,
+    /// The functions of other crates that are called, whose stack usage is unknown.
+    external_callees: Vec<String>,
+    /// Whether the function calls through a function pointer or a trait object.
+    indirect_calls: bool,
+    /// Whether the function is not called by the crate itself, or is exported under a fixed
+    /// symbol, like `main`, `#[no_mangle]` interrupt handlers and the public functions of a
+    /// library.
+    is_entry: bool,
+}
+
+pub(crate) fn collect_call_graph(tcx: TyCtxt<'_>) -> CallGraph {
+    let call_graph = tcx.collect_and_partition_mono_items(()).call_graph;
+    let indices: FxHashMap<Instance<'_>, usize> =
+        call_graph.keys().enumerate().map(|(index, &instance)| (instance, index)).collect();
+
+    let mut has_callers = vec![false; call_graph.len()];
+    let mut functions = Vec::with_capacity(call_graph.len());
+    for (index, (&instance, callees)) in call_graph.iter().enumerate() {
+        let callees: Vec<usize> =
+            callees.iter().filter_map(|callee| indices.get(callee)).copied().collect();
+        for &callee in &callees {
+            has_callers[callee] |= callee != index;
+        }
+
+        let (external_callees, indirect_calls) = scan_calls(tcx, instance, &indices);
+        functions.push(Function {
+            name: with_no_trimmed_paths!(instance.to_string()),
+            symbol: tcx.symbol_name(instance).name.to_string(),
+            callees,
+            external_callees,
+            indirect_calls,
+            is_entry: tcx.codegen_instance_attrs(instance.def).contains_extern_indicator(),
+        });
+    }
+
+    for (function, has_callers) in functions.iter_mut().zip(has_callers) {
+        function.is_entry |= !has_callers;
+    }
+    CallGraph { functions }
+}
+
+/// Returns the calls of `instance` that are not edges of the call graph: the functions of other
+/// crates that it calls, and whether it calls through function pointers or trait objects.
+fn scan_calls<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    instance: Instance<'tcx>,
+    indices: &FxHashMap<Instance<'tcx>, usize>,
+) -> (Vec<String>, bool) {
+    let typing_env = ty::TypingEnv::fully_monomorphized();
+    let body = tcx.codegen_mir(instance);
+    let mut external_callees = Vec::new();
+    let mut indirect_calls = false;
+
+    let monomorphize = |ty| {
+        instance.instantiate_mir_and_normalize_erasing_regions(
+            tcx,
+            typing_env,
+            ty::EarlyBinder::bind(ty),
+        )
+    };
+
+    for (_, data) in traversal::mono_reachable(body, tcx, instance) {
+        let terminator = data.terminator();
+        match &terminator.kind {
+            TerminatorKind::Call { func, .. } | TerminatorKind::TailCall { func, .. } => {
+                match *monomorphize(func.ty(body, tcx)).kind() {
+                    ty::FnPtr(..) => indirect_calls = true,
+                    ty::FnDef(def_id, args) => {
+                        let span = terminator.source_info.span;
+                        let callee = Instance::expect_resolve(tcx, typing_env, def_id, args, span);
+                        match callee.def {
+                            InstanceKind::Virtual(..) => indirect_calls = true,
+                            InstanceKind::Intrinsic(_) => {}
+                            _ if indices.contains_key(&callee) => {}
+                            _ => {
+                                let name = with_no_trimmed_paths!(callee.to_string());
+                                if !external_callees.contains(&name) {
+                                    external_callees.push(name);
+                                }
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            // The drop glue of trait objects is called through the vtable.
+            TerminatorKind::Drop { place, .. } => {
+                if monomorphize(place.ty(body, tcx).ty).is_trait() {
+                    indirect_calls = true;
+                }
+            }
+            _ => {}
+        }
+    }
+    (external_callees, indirect_calls)
+}
+
+#[derive(Copy, Clone, Debug)]
+enum StackUsage {
+    Bounded {
+        bytes: u64,
+        /// The callee on the deepest path.
+        next: Option<usize>,
+        /// A reachable function whose stack usage is unknown, if any.
+        incomplete: Option<Unknown>,
+    },
+    /// The stack usage is unbounded because the function reaches a cycle through this function.
+    Recursion(usize),
+    /// The stack usage is unbounded because the function reaches this function, which makes
+    /// indirect calls.
+    IndirectCall(usize),
+}
+
+/// Why the stack usage of a function is only a lower bound.
+#[derive(Copy, Clone, Debug)]
+enum Unknown {
+    /// The function calls functions of other crates.
+    ExternalCall(usize),
+    /// The frame size of the function was not emitted by the backend.
+    FrameSize(usize),
+}
+
+#[derive(Copy, Clone)]
+enum State {
+    NotVisited,
+    InProgress,
+    Done(StackUsage),
+}
+
+struct Analysis<'a> {
+    functions: &'a [Function],
+    /// The frame size of each function, `None` if it is unknown.
+    frame_sizes: Vec<Option<u64>>,
+    states: Vec<State>,
+}
+
+impl Analysis<'_> {
+    fn stack_usage(&mut self, index: usize) -> StackUsage {
+        match self.states[index] {
+            State::Done(usage) => return usage,
+            State::InProgress => return StackUsage::Recursion(index),
+            State::NotVisited => {}
+        }
+        self.states[index] = State::InProgress;
+
+        let functions = self.functions;
+        let function = &functions[index];
+        let usage = if function.indirect_calls {
+            StackUsage::IndirectCall(index)
+        } else {
+            let mut deepest = 0;
+            let mut next = None;
+            let mut incomplete = if self.frame_sizes[index].is_none() {
+                Some(Unknown::FrameSize(index))
+            } else {
+                (!function.external_callees.is_empty()).then_some(Unknown::ExternalCall(index))
+            };
+            let mut unbounded = None;
+            for &callee in &function.callees {
+                match ensure_sufficient_stack(|| self.stack_usage(callee)) {
+                    StackUsage::Bounded { bytes, incomplete: callee_incomplete, .. } => {
+                        if next.is_none() || bytes > deepest {
+                            deepest = bytes;
+                            next = Some(callee);
+                        }
+                        incomplete = incomplete.or(callee_incomplete);
+                    }
+                    usage => {
+                        unbounded = Some(usage);
+                        break;
+                    }
+                }
+            }
+            unbounded.unwrap_or(StackUsage::Bounded {
+                bytes: self.frame_sizes[index].unwrap_or(0) + deepest,
+                next,
+                incomplete,
+            })
+        };
+
+        self.states[index] = State::Done(usage);
+        usage
+    }
+
+    /// The functions on the deepest path from `index`.
+    fn deepest_path(&self, mut index: usize) -> Vec<usize> {
+        let mut path = vec![index];
+        while let State::Done(StackUsage::Bounded { next: Some(next), .. }) = self.states[index] {
+            path.push(next);
+            index = next;
+        }
+        path
+    }
+}
+
+pub(crate) fn print_stack_usage(sess: &Session, codegen_results: &CodegenResults) {
+    let functions = &codegen_results.crate_info.call_graph.functions;
+    let Some(frame_sizes) = frame_sizes(sess, codegen_results) else {
+        sess.dcx().emit_warn(errors::StackSizesUnavailable);
+        return;
+    };
+    let frame_sizes = functions.iter().map(|function| frame_sizes.get(&function.symbol));
+    let mut analysis = Analysis {
+        functions,
+        frame_sizes: frame_sizes.collect(),
+        states: vec![State::NotVisited; functions.len()],
+    };
+
+    let mut entries: Vec<(usize, StackUsage)> = (0..functions.len())
+        .filter(|&index| functions[index].is_entry)
+        .map(|index| (index, analysis.stack_usage(index)))
+        .collect();
+    entries.sort_by(|(a, _), (b, _)| functions[*a].name.cmp(&functions[*b].name));
+
+    for print in &sess.opts.prints {
+        let content = match print.kind {
+            PrintKind::StackSizes => text_report(&analysis, &entries),
+            PrintKind::StackSizesJson => json_report(&analysis, &entries),
+            _ => continue,
+        };
+        print.out.overwrite(&content, sess);
+    }
+}
+
+fn text_report(analysis: &Analysis<'_>, entries: &[(usize, StackUsage)]) -> String {
+    let functions = analysis.functions;
+    let mut report = String::new();
+    for &(index, usage) in entries {
+        let name = &functions[index].name;
+        match usage {
+            StackUsage::Bounded { bytes, incomplete: None, .. } => {
+                writeln!(report, "{name}: {bytes} bytes").unwrap();
+            }
+            StackUsage::Bounded {
+                bytes, incomplete: Some(Unknown::ExternalCall(caller)), ..
+            } => {
+                let caller = &functions[caller];
+                writeln!(
+                    report,
+                    "{name}: at least {bytes} bytes, `{}` calls `{}` whose stack usage is unknown",
+                    caller.name, caller.external_callees[0],
+                )
+                .unwrap();
+            }
+            StackUsage::Bounded {
+                bytes, incomplete: Some(Unknown::FrameSize(function)), ..
+            } => {
+                let function = &functions[function].name;
+                writeln!(
+                    report,
+                    "{name}: at least {bytes} bytes, the frame size of `{function}` is unknown",
+                )
+                .unwrap();
+            }
+            StackUsage::Recursion(function) => {
+                let function = &functions[function].name;
+                writeln!(report, "{name}: unbounded, `{function}` is recursive").unwrap();
+                continue;
+            }
+            StackUsage::IndirectCall(function) => {
+                let function = &functions[function].name;
+                writeln!(report, "{name}: unbounded, `{function}` makes indirect calls").unwrap();
+                continue;
+            }
+        }
+
+        // The frames on the deepest path, from the entry point.
+        for function in analysis.deepest_path(index) {
+            let name = &functions[function].name;
+            match analysis.frame_sizes[function] {
+                Some(frame_size) => writeln!(report, "    {name} ({frame_size} bytes)").unwrap(),
+                None => writeln!(report, "    {name} (unknown frame size)").unwrap(),
+            }
+        }
+    }
+    report
+}
+
+fn json_report(analysis: &Analysis<'_>, entries: &[(usize, StackUsage)]) -> String {
+    let functions = analysis.functions;
+    let function_json = |index: usize| {
+        json!({
+            "name": functions[index].name,
+            "symbol": functions[index].symbol,
+            "frame_size": analysis.frame_sizes[index],
+        })
+    };
+
+    let entries: Vec<_> = entries
+        .iter()
+        .map(|&(index, usage)| {
+            let (bytes, reason, cause) = match usage {
+                StackUsage::Bounded { bytes, incomplete: None, .. } => (Some(bytes), None, None),
+                StackUsage::Bounded { bytes, incomplete: Some(unknown), .. } => match unknown {
+                    Unknown::ExternalCall(caller) => {
+                        (Some(bytes), Some("external-call"), Some(caller))
+                    }
+                    Unknown::FrameSize(function) => {
+                        (Some(bytes), Some("unknown-frame-size"), Some(function))
+                    }
+                },
+                StackUsage::Recursion(function) => (None, Some("recursion"), Some(function)),
+                StackUsage::IndirectCall(function) => (None, Some("indirect-call"), Some(function)),
+            };
+            let path: Vec<_> = match bytes {
+                Some(_) => analysis.deepest_path(index).into_iter().map(function_json).collect(),
+                None => Vec::new(),
+            };
+            json!({
+                "name": functions[index].name,
+                "symbol": functions[index].symbol,
+                "bounded": bytes.is_some(),
+                "exact": reason.is_none(),
+                "stack_usage": bytes,
+                "reason": reason,
+                "cause": cause.map(function_json),
+                "path": path,
+            })
+        })
+        .collect();
+
+    let mut report = serde_json::to_string_pretty(&json!({ "entry_points": entries })).unwrap();
+    report.push('\n');
+    report
+}
+
+/// The frame sizes read from the object files.
+#[derive(Default)]
+struct FrameSizes {
+    /// The frame sizes in the `.stack_sizes` sections, by symbol.
+    sizes: FxHashMap<String, u64>,
+    /// The symbols of the functions that are defined in the object files.
+    defined: FxHashSet<String>,
+}
+
+impl FrameSizes {
+    /// Returns the frame size of the function with `symbol`, which is 0 if the function is not in
+    /// the object files, or `None` if the function is there but its frame size is not.
+    fn get(&self, symbol: &str) -> Option<u64> {
+        match self.sizes.get(symbol) {
+            Some(&size) => Some(size),
+            None if self.defined.contains(symbol) => None,
+            None => Some(0),
+        }
+    }
+}
+
+/// Removes the suffix that LLVM appends to the symbols of the local functions that it makes
+/// global, e.g. for ThinLTO.
+fn base_symbol(name: &str) -> &str {
+    name.split_once(".llvm.").map_or(name, |(name, _)| name)
+}
+
+/// Reads the frame sizes from the `.stack_sizes` sections of the object files, by symbol. Returns
+/// `None` if the backend did not emit any.
+fn frame_sizes(sess: &Session, codegen_results: &CodegenResults) -> Option<FrameSizes> {
+    let mut frame_sizes = FrameSizes::default();
+    let mut found = false;
+
+    let objects = codegen_results
+        .modules
+        .iter()
+        .chain(&codegen_results.allocator_module)
+        .filter_map(|module| module.object.as_ref());
+    for path in objects {
+        let data = match fs::read(path) {
+            Ok(data) => data,
+            Err(message) => {
+                sess.dcx().emit_err(errors::ReadFileError { message });
+                continue;
+            }
+        };
+        let Ok(file) = object::File::parse(&*data) else { continue };
+
+        // The functions by section and address, to find the targets of the relocations. The lowest
+        // bit of the address of Thumb functions is set, so it is ignored.
+        let mut functions = FxHashMap::default();
+        for function in file.symbols() {
+            if function.kind() != SymbolKind::Text || !function.is_definition() {
+                continue;
+            }
+            let (Some(section), Ok(name)) = (function.section_index(), function.name()) else {
+                continue;
+            };
+            functions.insert((section, function.address() | 1), base_symbol(name));
+            frame_sizes.defined.insert(base_symbol(name).to_owned());
+        }
+
+        for section in file.sections() {
+            if section.name() != Ok(".stack_sizes") {
+                continue;
+            }
+            found = true;
+            let Ok(data) = section.data() else { continue };
+            let relocations: FxHashMap<u64, _> = section.relocations().collect();
+
+            // Each entry is the address of a function, followed by its frame size as ULEB128.
+            let address_size = if file.is_64() { 8 } else { 4 };
+            let mut offset = 0;
+            while offset + address_size <= data.len() {
+                let address = &data[offset..offset + address_size];
+                let symbol = relocations
+                    .get(&(offset as u64))
+                    .and_then(|relocation| function_symbol(&file, &functions, relocation, address));
+                offset += address_size;
+                let Some(frame_size) = read_uleb128(data, &mut offset) else { break };
+                if let Some(symbol) = symbol {
+                    frame_sizes.sizes.insert(symbol.to_owned(), frame_size);
+                }
+            }
+        }
+    }
+
+    found.then_some(frame_sizes)
+}
+
+/// Returns the name of the function that `relocation` points to, without the LLVM suffix.
+fn function_symbol<'data>(
+    file: &object::File<'data>,
+    functions: &FxHashMap<(SectionIndex, u64), &'data str>,
+    relocation: &object::Relocation,
+    address: &[u8],
+) -> Option<&'data str> {
+    let RelocationTarget::Symbol(index) = relocation.target() else { return None };
+    let symbol = file.symbol_by_index(index).ok()?;
+    if symbol.kind() != SymbolKind::Section {
+        return symbol.name().ok().map(base_symbol);
+    }
+
+    // Relocations to local functions may point into the section of the function instead.
+    let offset = if relocation.has_implicit_addend() {
+        let mut bytes = [0; 8];
+        if file.is_little_endian() {
+            bytes[..address.len()].copy_from_slice(address);
+            u64::from_le_bytes(bytes)
+        } else {
+            bytes[8 - address.len()..].copy_from_slice(address);
+            u64::from_be_bytes(bytes)
+        }
+    } else {
+        relocation.addend() as u64
+    };
+    functions.get(&(symbol.section_index()?, offset | 1)).copied()
+}
+
+fn read_uleb128(data: &[u8], offset: &mut usize) -> Option<u64> {
+    let mut value = 0;
+    let mut shift = 0;
+    loop {
+        let byte = *data.get(*offset)?;
+        *offset += 1;
+        value |= u64::from(byte & 0x7f).checked_shl(shift)?;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+        shift += 7;
+    }
+}
@@ -35,6 +35,7 @@ use rustc_span::{FileName, InnerSpan, Span, SpanData, sym};
 use rustc_target::spec::{MergeFunctions, SanitizerSet};
 use tracing::debug;
 
+use super::codegen_cache::CodegenCache;
 use super::link::{self, ensure_removed};
 use super::lto::{self, SerializedModule};
 use crate::back::lto::check_lto_allowed;
@@ -401,6 +402,7 @@ fn generate_thin_lto_work<B: ExtraBackendMethods>(
                 WorkItem::CopyPostLtoArtifacts(CachedModuleCodegen {
                     name: wp.cgu_name.clone(),
                     source: wp,
+                    codegen_cache_entry: None,
                 }),
                 0, // copying is very cheap
             )
@@ -436,6 +438,7 @@ pub(crate) fn start_async_codegen<B: ExtraBackendMethods>(
     tcx: TyCtxt<'_>,
     target_cpu: String,
     allocator_module: Option<ModuleCodegen<B::Module>>,
+    codegen_cache: CodegenCache,
 ) -> OngoingCodegen<B> {
     let (coordinator_send, coordinator_receive) = channel();
 
@@ -466,6 +469,7 @@ pub(crate) fn start_async_codegen<B: ExtraBackendMethods>(
     OngoingCodegen {
         backend,
         crate_info,
+        codegen_cache,
 
         codegen_worker_receive,
         shared_emitter_main,
@@ -491,26 +495,10 @@ fn copy_all_cgu_workproducts_to_incr_comp_cache_dir(
     let _timer = sess.timer("copy_all_cgu_workproducts_to_incr_comp_cache_dir");
 
     for module in compiled_modules.modules.iter().filter(|m| m.kind == ModuleKind::Regular) {
-        let mut files = Vec::new();
-        if let Some(object_file_path) = &module.object {
-            files.push((OutputType::Object.extension(), object_file_path.as_path()));
-        }
-        if let Some(dwarf_object_file_path) = &module.dwarf_object {
-            files.push(("dwo", dwarf_object_file_path.as_path()));
-        }
-        if let Some(path) = &module.assembly {
-            files.push((OutputType::Assembly.extension(), path.as_path()));
-        }
-        if let Some(path) = &module.llvm_ir {
-            files.push((OutputType::LlvmAssembly.extension(), path.as_path()));
-        }
-        if let Some(path) = &module.bytecode {
-            files.push((OutputType::Bitcode.extension(), path.as_path()));
-        }
         if let Some((id, product)) = copy_cgu_workproduct_to_incr_comp_cache_dir(
             sess,
             &module.name,
-            files.as_slice(),
+            &module.saved_files(),
             &module.links_from_incr_cache,
         ) {
             work_products.insert(id, product);
@@ -896,21 +884,31 @@ fn execute_copy_from_cache_work_item<B: ExtraBackendMethods>(
         .prof
         .generic_activity_with_arg("codegen_copy_artifacts_from_incr_cache", &*module.name);
 
-    let incr_comp_session_dir = cgcx.incr_comp_session_dir.as_ref().unwrap();
-
     let mut links_from_incr_cache = Vec::new();
 
     let mut load_from_incr_comp_dir = |output_path: PathBuf, saved_path: &str| {
-        let source_file = in_incr_comp_dir(incr_comp_session_dir, saved_path);
+        let source_file = match &module.codegen_cache_entry {
+            Some(entry) => entry.join(saved_path),
+            None => in_incr_comp_dir(cgcx.incr_comp_session_dir.as_ref().unwrap(), saved_path),
+        };
         debug!(
             "copying preexisting module `{}` from {:?} to {}",
             module.name,
             source_file,
             output_path.display()
         );
-        match link_or_copy(&source_file, &output_path) {
-            Ok(_) => {
-                links_from_incr_cache.push(source_file);
+        // The entries of the codegen cache are shared with other compilations, so their files are
+        // copied rather than linked, and are saved to the incremental directory like new ones.
+        let result = if module.codegen_cache_entry.is_some() {
+            fs::copy(&source_file, &output_path).map(|_| ())
+        } else {
+            link_or_copy(&source_file, &output_path).map(|_| ())
+        };
+        match result {
+            Ok(()) => {
+                if module.codegen_cache_entry.is_none() {
+                    links_from_incr_cache.push(source_file);
+                }
                 Some(output_path)
             }
             Err(error) => {
@@ -1934,6 +1932,7 @@ impl<B: ExtraBackendMethods> Drop for Coordinator<B> {
 pub struct OngoingCodegen<B: ExtraBackendMethods> {
     pub backend: B,
     pub crate_info: CrateInfo,
+    pub(crate) codegen_cache: CodegenCache,
     pub output_filenames: Arc<OutputFilenames>,
     // Field order below is intended to terminate the coordinator thread before two fields below
     // drop and prematurely close channels used by coordinator thread. See `Coordinator`'s
@@ -1961,6 +1960,7 @@ impl<B: ExtraBackendMethods> OngoingCodegen<B> {
 
         let work_products =
             copy_all_cgu_workproducts_to_incr_comp_cache_dir(sess, &compiled_modules);
+        self.codegen_cache.save(sess, &compiled_modules.modules);
         produce_final_output_artifacts(sess, &compiled_modules, &self.output_filenames);
 
         // FIXME: time_llvm_passes support - does this use a global context or
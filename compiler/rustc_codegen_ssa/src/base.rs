@@ -36,7 +36,9 @@ use rustc_trait_selection::traits::{ObligationCause, ObligationCtxt};
 use tracing::{debug, info};
 
 use crate::assert_module_sources::CguReuse;
+use crate::back::codegen_cache::CodegenCache;
 use crate::back::link::are_upstream_rust_objects_already_included;
+use crate::back::stack_usage;
 use crate::back::write::{
     ComputedLtoType, OngoingCodegen, compute_per_cgu_lto_type, start_async_codegen,
     submit_codegened_module_to_llvm, submit_post_lto_module_to_llvm, submit_pre_lto_module_to_llvm,
@@ -662,7 +664,8 @@ pub fn codegen_crate<B: ExtraBackendMethods>(
 ) -> OngoingCodegen<B> {
     // Skip crate items and just output metadata in -Z no-codegen mode.
     if tcx.sess.opts.unstable_opts.no_codegen || !tcx.sess.opts.output_types.should_codegen() {
-        let ongoing_codegen = start_async_codegen(backend, tcx, target_cpu, None);
+        let ongoing_codegen =
+            start_async_codegen(backend, tcx, target_cpu, None, CodegenCache::default());
 
         ongoing_codegen.codegen_finished(tcx);
 
@@ -713,7 +716,9 @@ pub fn codegen_crate<B: ExtraBackendMethods>(
         None
     };
 
-    let ongoing_codegen = start_async_codegen(backend.clone(), tcx, target_cpu, allocator_module);
+    let codegen_cache = CodegenCache::new(tcx, &backend, &target_cpu, codegen_units);
+    let ongoing_codegen =
+        start_async_codegen(backend.clone(), tcx, target_cpu, allocator_module, codegen_cache);
 
     // For better throughput during parallel processing by LLVM, we used to sort
     // CGUs largest to smallest. This would lead to better thread utilization
@@ -739,6 +744,11 @@ pub fn codegen_crate<B: ExtraBackendMethods>(
         codegen_units.iter().map(|cgu| determine_cgu_reuse(tcx, cgu)).collect::<Vec<_>>()
     });
 
+    let mut codegen_cache_hits =
+        ongoing_codegen
+            .codegen_cache
+            .lookup(tcx.sess, &codegen_units, |i| cgu_reuse[i] == CguReuse::No);
+
     crate::assert_module_sources::assert_module_sources(tcx, &|cgu_reuse_tracker| {
         for (i, cgu) in codegen_units.iter().enumerate() {
             let cgu_reuse = cgu_reuse[i];
@@ -765,7 +775,7 @@ pub fn codegen_crate<B: ExtraBackendMethods>(
             let cgus: Vec<_> = cgu_reuse
                 .iter()
                 .enumerate()
-                .filter(|&(_, reuse)| reuse == &CguReuse::No)
+                .filter(|&(i, reuse)| reuse == &CguReuse::No && codegen_cache_hits[i].is_none())
                 .take(tcx.sess.threads())
                 .collect();
 
@@ -791,6 +801,11 @@ pub fn codegen_crate<B: ExtraBackendMethods>(
 
         let cgu_reuse = cgu_reuse[i];
 
+        if let Some(module) = codegen_cache_hits[i].take() {
+            submit_post_lto_module_to_llvm(&ongoing_codegen.coordinator, module);
+            continue;
+        }
+
         match cgu_reuse {
             CguReuse::No => {
                 let (module, cost) = if let Some(cgu) = pre_compiled_cgus.remove(&i) {
@@ -815,6 +830,7 @@ pub fn codegen_crate<B: ExtraBackendMethods>(
                     CachedModuleCodegen {
                         name: cgu.name().to_string(),
                         source: cgu.previous_work_product(tcx),
+                        codegen_cache_entry: None,
                     },
                 );
             }
@@ -824,6 +840,7 @@ pub fn codegen_crate<B: ExtraBackendMethods>(
                     CachedModuleCodegen {
                         name: cgu.name().to_string(),
                         source: cgu.previous_work_product(tcx),
+                        codegen_cache_entry: None,
                     },
                 );
             }
@@ -946,6 +963,11 @@ impl CrateInfo {
             natvis_debugger_visualizers: Default::default(),
             lint_levels: CodegenLintLevels::from_tcx(tcx),
             metadata_symbol: exported_symbols::metadata_symbol_name(tcx),
+            call_graph: if tcx.sess.opts.prints_stack_sizes() {
+                stack_usage::collect_call_graph(tcx)
+            } else {
+                Default::default()
+            },
         };
 
         info.native_libraries.reserve(n_crates);
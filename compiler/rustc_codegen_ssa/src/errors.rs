@@ -722,6 +722,11 @@ pub(crate) struct ReadFileError {
     pub message: std::io::Error,
 }
 
+#[derive(Diagnostic)]
+#[diag(codegen_ssa_stack_sizes_unavailable)]
+#[note]
+pub(crate) struct StackSizesUnavailable;
+
 #[derive(Diagnostic)]
 #[diag(codegen_ssa_unsupported_link_self_contained)]
 pub(crate) struct UnsupportedLinkSelfContained;
@@ -47,6 +47,8 @@ use rustc_session::cstore::{self, CrateSource};
 use rustc_session::lint::builtin::LINKER_MESSAGES;
 use rustc_span::Symbol;
 
+use crate::back::stack_usage::CallGraph;
+
 pub mod assert_module_sources;
 pub mod back;
 pub mod base;
@@ -159,11 +161,35 @@ impl CompiledModule {
             emit(path, OutputType::Assembly);
         }
     }
+
+    /// The files that are saved when the module is cached, along with their extension.
+    pub(crate) fn saved_files(&self) -> Vec<(&'static str, &Path)> {
+        let mut files = Vec::new();
+        if let Some(object_file_path) = &self.object {
+            files.push((OutputType::Object.extension(), object_file_path.as_path()));
+        }
+        if let Some(dwarf_object_file_path) = &self.dwarf_object {
+            files.push(("dwo", dwarf_object_file_path.as_path()));
+        }
+        if let Some(path) = &self.assembly {
+            files.push((OutputType::Assembly.extension(), path.as_path()));
+        }
+        if let Some(path) = &self.llvm_ir {
+            files.push((OutputType::LlvmAssembly.extension(), path.as_path()));
+        }
+        if let Some(path) = &self.bytecode {
+            files.push((OutputType::Bitcode.extension(), path.as_path()));
+        }
+        files
+    }
 }
 
 pub(crate) struct CachedModuleCodegen {
     pub name: String,
     pub source: WorkProduct,
+    /// The entry of `-Z codegen-cache-dir` that contains the saved files of `source`, if they are
+    /// not in the incremental directory.
+    pub codegen_cache_entry: Option<PathBuf>,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Encodable, Decodable)]
@@ -233,6 +259,8 @@ pub struct CrateInfo {
     pub natvis_debugger_visualizers: BTreeSet<DebuggerVisualizerFile>,
     pub lint_levels: CodegenLintLevels,
     pub metadata_symbol: String,
+    /// Only computed for `--print stack-sizes`.
+    pub call_graph: CallGraph,
 }
 
 /// Target-specific options that get set in `cfg(...)`.
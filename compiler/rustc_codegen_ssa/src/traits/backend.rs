@@ -43,6 +43,13 @@ pub trait CodegenBackend {
 
     fn name(&self) -> &'static str;
 
+    /// The version of the backend, e.g. of the code generator that it uses. Object files are only
+    /// shared through `-Z codegen-cache-dir` between compilations with the same name and version
+    /// of the backend.
+    fn version(&self) -> String {
+        String::new()
+    }
+
     fn init(&self, _sess: &Session) {}
 
     fn print(&self, _req: &PrintRequest, _out: &mut String, _sess: &Session) {}
@@ -591,6 +591,8 @@ impl<'tcx, M: Machine<'tcx>> InterpCx<'tcx, M> {
             | ty::InstanceKind::AsyncDropGlueCtorShim(..)
             | ty::InstanceKind::AsyncDropGlue(..)
             | ty::InstanceKind::FutureDropPollShim(..)
+            | ty::InstanceKind::ColdPathShim(..)
+            | ty::InstanceKind::ConstSpecialized(..)
             | ty::InstanceKind::Item(_) => {
                 // We need MIR for this fn.
                 // Note that this can be an intrinsic, if we are executing its fallback body.
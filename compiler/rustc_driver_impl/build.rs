@@ -0,0 +1,98 @@
+//! Collects the docs of the lints declared with `declare_lint!`, which `rustc --explain` prints.
+//!
+//! The docs are read the same way as the `lint-docs` tool does for the lint listing of the rustc
+//! book, but the output of the examples can't be generated without a compiler, so the
+//! `{{produces}}` markers are removed instead.
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+/// The crates whose sources declare the lints of the compiler.
+const LINT_CRATES: &[&str] = &["rustc_lint", "rustc_lint_defs"];
+
+fn main() {
+    let manifest_dir = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap());
+    let mut lints = vec![];
+    for krate in LINT_CRATES {
+        let src = manifest_dir.join("..").join(krate).join("src");
+        println!("cargo:rerun-if-changed={}", src.display());
+        collect_lints(&src, &mut lints);
+    }
+    println!("cargo:rerun-if-changed=build.rs");
+    // Sorted by name, so that the explanation of a lint can be looked up with a binary search.
+    lints.sort();
+
+    let mut out = String::from("&[\n");
+    for (name, doc) in lints {
+        writeln!(out, "    ({name:?}, {doc:?}),").unwrap();
+    }
+    out.push_str("]\n");
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").unwrap());
+    fs::write(out_dir.join("lint_docs.rs"), out).unwrap();
+}
+
+fn collect_lints(dir: &Path, lints: &mut Vec<(String, String)>) {
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            collect_lints(&path, lints);
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            lints_from_file(&fs::read_to_string(&path).unwrap(), lints);
+        }
+    }
+}
+
+/// Collects the name and the docs of each `declare_lint!` in `contents`. The docs are the doc
+/// comments between the start of the macro and the name of the lint, like `pub UNUSED_MUT,`.
+fn lints_from_file(contents: &str, lints: &mut Vec<(String, String)>) {
+    let mut lines = contents.lines().map(str::trim);
+    while lines.by_ref().any(|line| line.starts_with("declare_lint!")) {
+        let mut doc = String::new();
+        for line in lines.by_ref() {
+            let text = if line == "///" {
+                ""
+            } else if let Some(text) = line.strip_prefix("/// ") {
+                text
+            } else if let Some(text) = line.strip_prefix("#[doc = \"") {
+                &unescape(text.strip_suffix("\"]").unwrap_or(text))
+            } else if line.starts_with("//") || line.starts_with("#[") {
+                // Comments, and attributes like `#[allow]`.
+                continue;
+            } else {
+                let name = line.rsplit(' ').next().unwrap().trim_end_matches(',');
+                if !doc.is_empty() {
+                    // The marker is on its own paragraph.
+                    let doc = doc.replace("{{produces}}\n\n", "").replace("{{produces}}\n", "");
+                    lints.push((name.to_lowercase(), doc));
+                }
+                break;
+            };
+            doc.push_str(text);
+            doc.push('\n');
+        }
+    }
+}
+
+/// Unescapes the string literal of a `#[doc = "..."]` attribute.
+fn unescape(text: &str) -> String {
+    let mut result = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some('u') => {
+                let code: String = chars.by_ref().skip(1).take_while(|&c| c != '}').collect();
+                result.extend(u32::from_str_radix(&code, 16).ok().and_then(char::from_u32));
+            }
+            Some(c) => result.push(c),
+            None => {}
+        }
+    }
+    result
+}
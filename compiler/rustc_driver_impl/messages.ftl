@@ -1,5 +1,7 @@
 driver_impl_cant_emit_mir = could not emit MIR: {$error}
 
+driver_impl_cant_emit_public_mir = could not emit public MIR to `{$path}`: {$error}
+
 driver_impl_ice = the compiler unexpectedly panicked. this is a bug.
 driver_impl_ice_bug_report = we would appreciate a bug report: {$bug_report_url}
 driver_impl_ice_bug_report_internal_feature = using internal features is not supported and expected to cause internal compiler errors when used incorrectly
@@ -0,0 +1,142 @@
+//! `--explain`: the explanations of error codes and lints, and searching them with `--search`.
+
+use std::io::{self, IsTerminal};
+
+use rustc_errors::registry::Registry;
+use rustc_errors::{ColorConfig, ErrCode};
+use rustc_session::EarlyDiagCtxt;
+
+use crate::show_md_content_with_pager;
+
+/// The name and the docs of each lint, sorted by name. They are collected by the build script
+/// from the `declare_lint!` docs, like the lint listing of the rustc book, but without the output
+/// of the examples.
+const LINT_DOCS: &[(&str, &str)] = include!(concat!(env!("OUT_DIR"), "/lint_docs.rs"));
+
+pub(crate) fn handle_explain(
+    early_dcx: &EarlyDiagCtxt,
+    registry: Registry,
+    code: &str,
+    color: ColorConfig,
+) {
+    let text = if let Some(description) = error_code_explanation(&registry, code) {
+        description
+    } else if let Some(doc) = lint_explanation(code) {
+        doc
+    } else {
+        early_dcx.early_fatal(format!("{code} is not a valid error code or lint name"));
+    };
+    show_explanation(&text, color);
+}
+
+/// Implements `--search`.
+pub(crate) fn handle_search(registry: Registry, term: &str, color: ColorConfig) {
+    show_explanation(&search_explanations(&registry, term), color);
+}
+
+fn show_explanation(text: &str, color: ColorConfig) {
+    if io::stdout().is_terminal() {
+        show_md_content_with_pager(text, color);
+    } else {
+        safe_print!("{text}");
+    }
+}
+
+fn error_code_explanation(registry: &Registry, code: &str) -> Option<String> {
+    // Allow "E0123" or "0123" form.
+    let upper_cased_code = code.to_ascii_uppercase();
+    if let Ok(code) = upper_cased_code.strip_prefix('E').unwrap_or(&upper_cased_code).parse::<u32>()
+        && code <= ErrCode::MAX_AS_U32
+        && let Ok(description) = registry.try_find_description(ErrCode::from_u32(code))
+    {
+        Some(strip_hidden_lines(description))
+    } else {
+        None
+    }
+}
+
+/// Removes the attributes of code blocks, and the lines hidden from the docs in them.
+fn strip_hidden_lines(markdown: &str) -> String {
+    let mut is_in_code_block = false;
+    let mut text = String::new();
+    for line in markdown.lines() {
+        let indent_level = line.find(|c: char| !c.is_whitespace()).unwrap_or(line.len());
+        let dedented_line = &line[indent_level..];
+        if dedented_line.starts_with("```") {
+            is_in_code_block = !is_in_code_block;
+            text.push_str(&line[..(indent_level + 3)]);
+        } else if is_in_code_block && dedented_line.starts_with("# ") {
+            continue;
+        } else {
+            text.push_str(line);
+        }
+        text.push('\n');
+    }
+    text
+}
+
+/// Lint names are accepted as written in attributes (`unused_must_use`) or on the command line
+/// (`unused-must-use`).
+fn lint_explanation(name: &str) -> Option<String> {
+    let name = name.to_ascii_lowercase().replace('-', "_");
+    let index = LINT_DOCS.binary_search_by_key(&&*name, |&(name, _)| name).ok()?;
+    let (name, doc) = LINT_DOCS[index];
+    Some(format!("# {name}\n\n{}", strip_hidden_lines(doc)))
+}
+
+/// An explanation which mentions the searched term.
+struct SearchResult {
+    name: String,
+    /// The first line of the explanation.
+    summary: String,
+    occurrences: usize,
+}
+
+impl SearchResult {
+    fn new(name: String, explanation: &str, term: &str) -> Option<SearchResult> {
+        let occurrences = explanation.to_lowercase().matches(term).count();
+        if occurrences == 0 {
+            return None;
+        }
+        let summary = explanation
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#'))
+            .unwrap_or_default()
+            .to_owned();
+        Some(SearchResult { name, summary, occurrences })
+    }
+}
+
+/// Searches the explanations of error codes and lints, case insensitively, and lists those which
+/// mention `term` the most first.
+fn search_explanations(registry: &Registry, term: &str) -> String {
+    let term = term.to_lowercase();
+
+    let mut error_codes: Vec<_> = registry
+        .descriptions()
+        .filter_map(|(code, description)| SearchResult::new(format!("{code}"), description, &term))
+        .collect();
+    let mut lints: Vec<_> = LINT_DOCS
+        .iter()
+        .filter_map(|&(name, doc)| SearchResult::new(name.to_owned(), doc, &term))
+        .collect();
+
+    let mut text = format!("# Explanations mentioning `{term}`\n\n");
+    if error_codes.is_empty() && lints.is_empty() {
+        text.push_str("No error code or lint explanation mentions this term.\n");
+    } else {
+        text.push_str("Use `rustc --explain <OPT>` to read one of these explanations.\n");
+    }
+    for (title, results) in [("Error codes", &mut error_codes), ("Lints", &mut lints)] {
+        if results.is_empty() {
+            continue;
+        }
+        results.sort_by(|a, b| b.occurrences.cmp(&a.occurrences).then_with(|| a.name.cmp(&b.name)));
+        text.push_str(&format!("\n## {title}\n\n"));
+        for SearchResult { name, summary, occurrences } in results.iter() {
+            text.push_str(&format!("- `{name}` ({occurrences}): {summary}\n"));
+        }
+    }
+    text
+}
@@ -21,7 +21,7 @@ use std::collections::{BTreeMap, BTreeSet};
 use std::ffi::OsString;
 use std::fmt::Write as _;
 use std::fs::{self, File};
-use std::io::{self, IsTerminal, Read, Write};
+use std::io::{self, Read, Write};
 use std::panic::{self, PanicHookInfo, catch_unwind};
 use std::path::{Path, PathBuf};
 use std::process::{self, Command, Stdio};
@@ -37,9 +37,10 @@ use rustc_data_structures::profiling::{
     TimePassesFormat, get_resident_set_size, print_time_passes_entry,
 };
 use rustc_errors::emitter::stderr_destination;
+use rustc_errors::fix::FixMode;
 use rustc_errors::registry::Registry;
 use rustc_errors::translation::Translator;
-use rustc_errors::{ColorConfig, DiagCtxt, ErrCode, FatalError, PResult, markdown};
+use rustc_errors::{ColorConfig, DiagCtxt, FatalError, PResult, markdown};
 use rustc_feature::find_gated_cfg;
 // This avoids a false positive with `-Wunused_crate_dependencies`.
 // `rust_index` isn't used in this crate's code, but it must be named in the
@@ -53,9 +54,10 @@ use rustc_metadata::locator;
 use rustc_middle::ty::TyCtxt;
 use rustc_parse::lexer::StripTokens;
 use rustc_parse::{new_parser_from_file, new_parser_from_source_str, unwrap_or_emit_fatal};
+use rustc_public::rustc_internal::snapshot::{SnapshotError, SnapshotFormat, write_snapshot};
 use rustc_session::config::{
     CG_OPTIONS, CrateType, ErrorOutputType, Input, OptionDesc, OutFileName, OutputType, Sysroot,
-    UnstableOptions, Z_OPTIONS, nightly_options, parse_target_triple,
+    UnstableOptions, UnstableOutputType, Z_OPTIONS, nightly_options, parse_target_triple,
 };
 use rustc_session::getopts::{self, Matches};
 use rustc_session::lint::{Lint, LintId};
@@ -89,6 +91,7 @@ pub mod args;
 pub mod pretty;
 #[macro_use]
 mod print;
+mod explain;
 mod session_diagnostics;
 
 // Keep the OS parts of this `cfg` in sync with the `cfg` on the `libc`
@@ -105,8 +108,9 @@ mod signal_handler {
 }
 
 use crate::session_diagnostics::{
-    CantEmitMIR, RLinkEmptyVersionNumber, RLinkEncodingVersionMismatch, RLinkRustcVersionMismatch,
-    RLinkWrongFileType, RlinkCorruptFile, RlinkNotAFile, RlinkUnableToRead, UnstableFeatureUsage,
+    CantEmitMIR, CantEmitPublicMir, RLinkEmptyVersionNumber, RLinkEncodingVersionMismatch,
+    RLinkRustcVersionMismatch, RLinkWrongFileType, RlinkCorruptFile, RlinkNotAFile,
+    RlinkUnableToRead, UnstableFeatureUsage,
 };
 
 rustc_fluent_macro::fluent_messages! { "../messages.ftl" }
@@ -240,7 +244,12 @@ pub fn run_compiler(at_args: &[String], callbacks: &mut (dyn Callbacks + Send))
     let ice_file = ice_path_with_config(Some(&sopts.unstable_opts)).clone();
 
     if let Some(ref code) = matches.opt_str("explain") {
-        handle_explain(&default_early_dcx, diagnostics_registry(), code, sopts.color);
+        explain::handle_explain(&default_early_dcx, diagnostics_registry(), code, sopts.color);
+        return;
+    }
+
+    if let Some(ref term) = matches.opt_str("search") {
+        explain::handle_search(diagnostics_registry(), term, sopts.color);
         return;
     }
 
@@ -269,130 +278,203 @@ pub fn run_compiler(at_args: &[String], callbacks: &mut (dyn Callbacks + Send))
         make_codegen_backend: None,
         registry: diagnostics_registry(),
         using_internal_features: &USING_INTERNAL_FEATURES,
-        expanded_args: args,
+        expanded_args: args.clone(),
     };
 
     callbacks.config(&mut config);
 
     let registered_lints = config.register_lints.is_some();
 
-    interface::run_compiler(config, |compiler| {
-        let sess = &compiler.sess;
-        let codegen_backend = &*compiler.codegen_backend;
+    let fix = config.opts.unstable_opts.fix;
+    let mut fixed_files = 0;
+    let res = catch_fatal_errors(|| {
+        interface::run_compiler(config, |compiler| {
+            let res = catch_fatal_errors(|| {
+                run_compilation(compiler, callbacks, has_input, registered_lints)
+            });
+            // The fixes are applied once all the diagnostics of the compilation were emitted,
+            // even if it failed with errors. After an ICE, the panic goes through
+            // `catch_fatal_errors` and the sources are left alone.
+            if fix.is_some() {
+                fixed_files = compiler.sess.dcx().apply_fixes();
+            }
+            if let Err(error) = res {
+                error.raise();
+            }
+        })
+    });
+
+    // Check the fixed sources with another compilation, whose result replaces the one of the
+    // compilation that fixed them.
+    if fix == Some(FixMode::Recheck) && fixed_files > 0 {
+        let mut recheck_args = vec![String::from("rustc")];
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if arg == "-Z" {
+                match args.next() {
+                    Some(opt) if !is_fix_option(&opt) => recheck_args.extend([arg, opt]),
+                    _ => {}
+                }
+            } else if !arg.strip_prefix("-Z").is_some_and(is_fix_option) {
+                recheck_args.push(arg);
+            }
+        }
+        return run_compiler(&recheck_args, callbacks);
+    }
+    if let Err(error) = res {
+        error.raise();
+    }
+}
+
+/// Returns whether the value of a `-Z` flag is the `-Z fix` option.
+fn is_fix_option(opt: &str) -> bool {
+    opt == "fix" || opt.starts_with("fix=")
+}
+
+/// Compiles the crate, the main part of [`run_compiler`].
+fn run_compilation(
+    compiler: &interface::Compiler,
+    callbacks: &mut (dyn Callbacks + Send),
+    has_input: bool,
+    registered_lints: bool,
+) {
+    let sess = &compiler.sess;
+    let codegen_backend = &*compiler.codegen_backend;
+
+    // This is used for early exits unrelated to errors. E.g. when just
+    // printing some information without compiling, or exiting immediately
+    // after parsing, etc.
+    let early_exit = || {
+        sess.dcx().abort_if_errors();
+    };
+
+    // This implements `-Whelp`. It should be handled very early, like
+    // `--help`/`-Zhelp`/`-Chelp`. This is the earliest it can run, because
+    // it must happen after lints are registered, during session creation.
+    if sess.opts.describe_lints {
+        describe_lints(sess, registered_lints);
+        return early_exit();
+    }
+
+    if print_crate_info(codegen_backend, sess, has_input) == Compilation::Stop {
+        return early_exit();
+    }
+
+    if !has_input {
+        #[allow(rustc::diagnostic_outside_of_impl)]
+        sess.dcx().fatal("no input filename given"); // this is fatal
+    }
+
+    if !sess.opts.unstable_opts.ls.is_empty() {
+        list_metadata(sess, &*codegen_backend.metadata_loader());
+        return early_exit();
+    }
+
+    if sess.opts.unstable_opts.link_only {
+        process_rlink(sess, compiler);
+        return early_exit();
+    }
+
+    // Parse the crate root source code (doesn't parse submodules yet)
+    // Everything else is parsed during macro expansion.
+    let mut krate = passes::parse(sess);
+
+    // If pretty printing is requested: Figure out the representation, print it and exit
+    if let Some(pp_mode) = sess.opts.pretty {
+        if pp_mode.needs_ast_map() {
+            create_and_enter_global_ctxt(compiler, krate, |tcx| {
+                tcx.ensure_ok().early_lint_checks(());
+                pretty::print(sess, pp_mode, pretty::PrintExtra::NeedsAstMap { tcx });
+                passes::write_dep_info(tcx);
+            });
+        } else {
+            pretty::print(sess, pp_mode, pretty::PrintExtra::AfterParsing { krate: &krate });
+        }
+        trace!("finished pretty-printing");
+        return early_exit();
+    }
 
-        // This is used for early exits unrelated to errors. E.g. when just
-        // printing some information without compiling, or exiting immediately
-        // after parsing, etc.
+    if callbacks.after_crate_root_parsing(compiler, &mut krate) == Compilation::Stop {
+        return early_exit();
+    }
+
+    if sess.opts.unstable_opts.parse_crate_root_only {
+        return early_exit();
+    }
+
+    let linker = create_and_enter_global_ctxt(compiler, krate, |tcx| {
         let early_exit = || {
             sess.dcx().abort_if_errors();
+            None
         };
 
-        // This implements `-Whelp`. It should be handled very early, like
-        // `--help`/`-Zhelp`/`-Chelp`. This is the earliest it can run, because
-        // it must happen after lints are registered, during session creation.
-        if sess.opts.describe_lints {
-            describe_lints(sess, registered_lints);
-            return early_exit();
-        }
+        // Make sure name resolution and macro expansion is run.
+        let _ = tcx.resolver_for_lowering();
 
-        if print_crate_info(codegen_backend, sess, has_input) == Compilation::Stop {
+        if callbacks.after_expansion(compiler, tcx) == Compilation::Stop {
             return early_exit();
         }
 
-        if !has_input {
-            #[allow(rustc::diagnostic_outside_of_impl)]
-            sess.dcx().fatal("no input filename given"); // this is fatal
-        }
+        passes::write_dep_info(tcx);
 
-        if !sess.opts.unstable_opts.ls.is_empty() {
-            list_metadata(sess, &*codegen_backend.metadata_loader());
-            return early_exit();
-        }
+        passes::write_interface(tcx);
 
-        if sess.opts.unstable_opts.link_only {
-            process_rlink(sess, compiler);
+        if sess.opts.output_types.contains_key(&OutputType::DepInfo)
+            && sess.opts.output_types.len() == 1
+        {
             return early_exit();
         }
 
-        // Parse the crate root source code (doesn't parse submodules yet)
-        // Everything else is parsed during macro expansion.
-        let mut krate = passes::parse(sess);
-
-        // If pretty printing is requested: Figure out the representation, print it and exit
-        if let Some(pp_mode) = sess.opts.pretty {
-            if pp_mode.needs_ast_map() {
-                create_and_enter_global_ctxt(compiler, krate, |tcx| {
-                    tcx.ensure_ok().early_lint_checks(());
-                    pretty::print(sess, pp_mode, pretty::PrintExtra::NeedsAstMap { tcx });
-                    passes::write_dep_info(tcx);
-                });
-            } else {
-                pretty::print(sess, pp_mode, pretty::PrintExtra::AfterParsing { krate: &krate });
-            }
-            trace!("finished pretty-printing");
+        if sess.opts.unstable_opts.no_analysis {
             return early_exit();
         }
 
-        if callbacks.after_crate_root_parsing(compiler, &mut krate) == Compilation::Stop {
-            return early_exit();
+        tcx.ensure_ok().analysis(());
+
+        if let Some(metrics_dir) = &sess.opts.unstable_opts.metrics_dir {
+            dump_feature_usage_metrics(tcx, metrics_dir);
         }
 
-        if sess.opts.unstable_opts.parse_crate_root_only {
+        if callbacks.after_analysis(compiler, tcx) == Compilation::Stop {
             return early_exit();
         }
 
-        let linker = create_and_enter_global_ctxt(compiler, krate, |tcx| {
-            let early_exit = || {
-                sess.dcx().abort_if_errors();
-                None
-            };
-
-            // Make sure name resolution and macro expansion is run.
-            let _ = tcx.resolver_for_lowering();
-
-            if callbacks.after_expansion(compiler, tcx) == Compilation::Stop {
-                return early_exit();
-            }
-
-            passes::write_dep_info(tcx);
-
-            passes::write_interface(tcx);
-
-            if sess.opts.output_types.contains_key(&OutputType::DepInfo)
-                && sess.opts.output_types.len() == 1
-            {
-                return early_exit();
-            }
-
-            if sess.opts.unstable_opts.no_analysis {
-                return early_exit();
-            }
-
-            tcx.ensure_ok().analysis(());
-
-            if let Some(metrics_dir) = &sess.opts.unstable_opts.metrics_dir {
-                dump_feature_usage_metrics(tcx, metrics_dir);
+        if tcx.sess.opts.output_types.contains_key(&OutputType::Mir) {
+            if let Err(error) = rustc_mir_transform::dump_mir::emit_mir(tcx) {
+                tcx.dcx().emit_fatal(CantEmitMIR { error });
             }
+        }
 
-            if callbacks.after_analysis(compiler, tcx) == Compilation::Stop {
-                return early_exit();
+        for &output in &sess.opts.unstable_opts.emit {
+            match output {
+                UnstableOutputType::PublicMir => emit_public_mir(tcx, SnapshotFormat::Json),
+                UnstableOutputType::PublicMirBinary => emit_public_mir(tcx, SnapshotFormat::Binary),
             }
+        }
 
-            if tcx.sess.opts.output_types.contains_key(&OutputType::Mir) {
-                if let Err(error) = rustc_mir_transform::dump_mir::emit_mir(tcx) {
-                    tcx.dcx().emit_fatal(CantEmitMIR { error });
-                }
-            }
+        Some(Linker::codegen_and_build_linker(tcx, &*compiler.codegen_backend))
+    });
 
-            Some(Linker::codegen_and_build_linker(tcx, &*compiler.codegen_backend))
-        });
+    // Linking is done outside the `compiler.enter()` so that the
+    // `GlobalCtxt` within `Queries` can be freed as early as possible.
+    if let Some(linker) = linker {
+        linker.link(sess, codegen_backend);
+    }
+}
 
-        // Linking is done outside the `compiler.enter()` so that the
-        // `GlobalCtxt` within `Queries` can be freed as early as possible.
-        if let Some(linker) = linker {
-            linker.link(sess, codegen_backend);
-        }
-    })
+/// Writes the snapshot of `-Z emit=public-mir` next to the outputs of the crate.
+fn emit_public_mir(tcx: TyCtxt<'_>, format: SnapshotFormat) {
+    // The bodies of a crate with errors may not be representable.
+    if tcx.dcx().has_errors().is_some() {
+        return;
+    }
+    let path = tcx.output_filenames(()).with_extension(format.extension());
+    let result = File::create(&path)
+        .map_err(SnapshotError::Io)
+        .and_then(|file| write_snapshot(tcx, format, io::BufWriter::new(file)));
+    if let Err(error) = result {
+        tcx.dcx().emit_fatal(CantEmitPublicMir { path, error: error.to_string() });
+    }
 }
 
 fn dump_feature_usage_metrics(tcxt: TyCtxt<'_>, metrics_dir: &Path) {
@@ -464,39 +546,6 @@ pub enum Compilation {
     Continue,
 }
 
-fn handle_explain(early_dcx: &EarlyDiagCtxt, registry: Registry, code: &str, color: ColorConfig) {
-    // Allow "E0123" or "0123" form.
-    let upper_cased_code = code.to_ascii_uppercase();
-    if let Ok(code) = upper_cased_code.strip_prefix('E').unwrap_or(&upper_cased_code).parse::<u32>()
-        && code <= ErrCode::MAX_AS_U32
-        && let Ok(description) = registry.try_find_description(ErrCode::from_u32(code))
-    {
-        let mut is_in_code_block = false;
-        let mut text = String::new();
-        // Slice off the leading newline and print.
-        for line in description.lines() {
-            let indent_level = line.find(|c: char| !c.is_whitespace()).unwrap_or(line.len());
-            let dedented_line = &line[indent_level..];
-            if dedented_line.starts_with("```") {
-                is_in_code_block = !is_in_code_block;
-                text.push_str(&line[..(indent_level + 3)]);
-            } else if is_in_code_block && dedented_line.starts_with("# ") {
-                continue;
-            } else {
-                text.push_str(line);
-            }
-            text.push('\n');
-        }
-        if io::stdout().is_terminal() {
-            show_md_content_with_pager(&text, color);
-        } else {
-            safe_print!("{text}");
-        }
-    } else {
-        early_dcx.early_fatal(format!("{code} is not a valid error code"));
-    }
-}
-
 /// If `color` is `always` or `auto`, try to print pretty (formatted & colorized) markdown. If
 /// that fails or `color` is `never`, print the raw markdown.
 ///
@@ -630,9 +679,14 @@ fn print_crate_info(
     #[allow(unused_imports)]
     use {do_not_use_safe_print as safe_print, do_not_use_safe_print as safe_println};
 
-    // NativeStaticLibs and LinkArgs are special - printed during linking
+    // NativeStaticLibs, LinkArgs and the stack sizes are special - printed during linking
     // (empty iterator returns true)
-    if sess.opts.prints.iter().all(|p| p.kind == NativeStaticLibs || p.kind == LinkArgs) {
+    if sess
+        .opts
+        .prints
+        .iter()
+        .all(|p| matches!(p.kind, NativeStaticLibs | LinkArgs | StackSizes | StackSizesJson))
+    {
         return Compilation::Continue;
     }
 
@@ -808,6 +862,7 @@ fn print_crate_info(
             // Any output here interferes with Cargo's parsing of other printed output
             NativeStaticLibs => {}
             LinkArgs => {}
+            StackSizes | StackSizesJson => {}
             SplitDebuginfo => {
                 use rustc_target::spec::SplitDebuginfo::{Off, Packed, Unpacked};
 
@@ -8,6 +8,13 @@ pub struct CantEmitMIR {
     pub error: std::io::Error,
 }
 
+#[derive(Diagnostic)]
+#[diag(driver_impl_cant_emit_public_mir)]
+pub(crate) struct CantEmitPublicMir {
+    pub path: std::path::PathBuf,
+    pub error: String,
+}
+
 #[derive(Diagnostic)]
 #[diag(driver_impl_rlink_unable_to_read)]
 pub(crate) struct RlinkUnableToRead {
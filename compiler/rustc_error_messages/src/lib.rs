@@ -7,9 +7,9 @@
 
 use std::borrow::Cow;
 use std::error::Error;
-use std::path::Path;
+use std::path::PathBuf;
 use std::sync::{Arc, LazyLock};
-use std::{fmt, fs, io};
+use std::{fmt, io};
 
 use fluent_bundle::FluentResource;
 pub use fluent_bundle::types::FluentType;
@@ -19,7 +19,7 @@ use intl_memoizer::concurrent::IntlLangMemoizer;
 use rustc_data_structures::sync::{DynSend, IntoDynSyncSend};
 use rustc_macros::{Decodable, Encodable};
 use rustc_span::Span;
-use tracing::{instrument, trace};
+use tracing::instrument;
 pub use unic_langid::{LanguageIdentifier, langid};
 
 mod diagnostic_impls;
@@ -48,6 +48,12 @@ pub enum TranslationBundleError {
     ReadLocalesDirEntry(io::Error),
     /// `$sysroot/share/locale/$locale` is not a directory.
     LocaleIsNotDir,
+    /// Failed to parse one of the messages of an `.ftl` file of a locale bundle, the message was
+    /// skipped.
+    ParseLocaleMessage(PathBuf, ParserError),
+    /// A message of an `.ftl` file of a locale bundle was already loaded from another file, the
+    /// later message was skipped.
+    DuplicateLocaleMessage(PathBuf, FluentError),
 }
 
 impl fmt::Display for TranslationBundleError {
@@ -68,6 +74,12 @@ impl fmt::Display for TranslationBundleError {
             TranslationBundleError::LocaleIsNotDir => {
                 write!(f, "`$sysroot/share/locales/$locale` is not a directory")
             }
+            TranslationBundleError::ParseLocaleMessage(path, e) => {
+                write!(f, "skipped unparsable message in `{}`: {e}", path.display())
+            }
+            TranslationBundleError::DuplicateLocaleMessage(path, e) => {
+                write!(f, "skipped duplicate message in `{}`: {e}", path.display())
+            }
         }
     }
 }
@@ -82,6 +94,8 @@ impl Error for TranslationBundleError {
             TranslationBundleError::ReadLocalesDir(e) => Some(e),
             TranslationBundleError::ReadLocalesDirEntry(e) => Some(e),
             TranslationBundleError::LocaleIsNotDir => None,
+            TranslationBundleError::ParseLocaleMessage(_, e) => Some(e),
+            TranslationBundleError::DuplicateLocaleMessage(_, e) => Some(e),
         }
     }
 }
@@ -100,32 +114,11 @@ impl From<Vec<FluentError>> for TranslationBundleError {
     }
 }
 
-/// Returns Fluent bundle with the user's locale resources from
-/// `$sysroot/share/locale/$requested_locale/*.ftl`.
-///
-/// If `-Z additional-ftl-path` was provided, load that resource and add it  to the bundle
-/// (overriding any conflicting messages).
-#[instrument(level = "trace")]
-pub fn fluent_bundle(
-    sysroot_candidates: &[&Path],
-    requested_locale: Option<LanguageIdentifier>,
-    additional_ftl_path: Option<&Path>,
+/// Returns an empty bundle for `locale`, with the convenience functions available to ftl authors.
+pub fn new_fluent_bundle(
+    locale: LanguageIdentifier,
     with_directionality_markers: bool,
-) -> Result<Option<Arc<FluentBundle>>, TranslationBundleError> {
-    if requested_locale.is_none() && additional_ftl_path.is_none() {
-        return Ok(None);
-    }
-
-    let fallback_locale = langid!("en-US");
-    let requested_fallback_locale = requested_locale.as_ref() == Some(&fallback_locale);
-    trace!(?requested_fallback_locale);
-    if requested_fallback_locale && additional_ftl_path.is_none() {
-        return Ok(None);
-    }
-    // If there is only `-Z additional-ftl-path`, assume locale is "en-US", otherwise use user
-    // provided locale.
-    let locale = requested_locale.clone().unwrap_or(fallback_locale);
-    trace!(?locale);
+) -> FluentBundle {
     let mut bundle = new_bundle(vec![locale]);
 
     // Add convenience functions available to ftl authors.
@@ -138,60 +131,7 @@ pub fn fluent_bundle(
     // surrounding diagnostic messages are right-to-left, then these might be helpful).
     bundle.set_use_isolating(with_directionality_markers);
 
-    // If the user requests the default locale then don't try to load anything.
-    if let Some(requested_locale) = requested_locale {
-        let mut found_resources = false;
-        for sysroot in sysroot_candidates {
-            let mut sysroot = sysroot.to_path_buf();
-            sysroot.push("share");
-            sysroot.push("locale");
-            sysroot.push(requested_locale.to_string());
-            trace!(?sysroot);
-
-            if !sysroot.exists() {
-                trace!("skipping");
-                continue;
-            }
-
-            if !sysroot.is_dir() {
-                return Err(TranslationBundleError::LocaleIsNotDir);
-            }
-
-            for entry in sysroot.read_dir().map_err(TranslationBundleError::ReadLocalesDir)? {
-                let entry = entry.map_err(TranslationBundleError::ReadLocalesDirEntry)?;
-                let path = entry.path();
-                trace!(?path);
-                if path.extension().and_then(|s| s.to_str()) != Some("ftl") {
-                    trace!("skipping");
-                    continue;
-                }
-
-                let resource_str =
-                    fs::read_to_string(path).map_err(TranslationBundleError::ReadFtl)?;
-                let resource =
-                    FluentResource::try_new(resource_str).map_err(TranslationBundleError::from)?;
-                trace!(?resource);
-                bundle.add_resource(resource).map_err(TranslationBundleError::from)?;
-                found_resources = true;
-            }
-        }
-
-        if !found_resources {
-            return Err(TranslationBundleError::MissingLocale);
-        }
-    }
-
-    if let Some(additional_ftl_path) = additional_ftl_path {
-        let resource_str =
-            fs::read_to_string(additional_ftl_path).map_err(TranslationBundleError::ReadFtl)?;
-        let resource =
-            FluentResource::try_new(resource_str).map_err(TranslationBundleError::from)?;
-        trace!(?resource);
-        bundle.add_resource_overriding(resource);
-    }
-
-    let bundle = Arc::new(bundle);
-    Ok(Some(bundle))
+    bundle
 }
 
 fn register_functions(bundle: &mut FluentBundle) {
@@ -215,12 +155,8 @@ pub fn fallback_fluent_bundle(
     with_directionality_markers: bool,
 ) -> LazyFallbackBundle {
     Arc::new(LazyLock::new(Box::new(move || {
-        let mut fallback_bundle = new_bundle(vec![langid!("en-US")]);
-
-        register_functions(&mut fallback_bundle);
-
-        // See comment in `fluent_bundle`.
-        fallback_bundle.set_use_isolating(with_directionality_markers);
+        let mut fallback_bundle =
+            new_fluent_bundle(langid!("en-US"), with_directionality_markers);
 
         for resource in resources {
             let resource = FluentResource::try_new(resource.to_string())
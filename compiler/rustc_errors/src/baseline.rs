@@ -0,0 +1,176 @@
+//! Diagnostic baselines (`--diagnostic-baseline`), to only report the lints which are new
+//! compared to a recorded baseline, e.g. in legacy codebases with many existing warnings.
+//!
+//! A baseline is a text file with one entry per lint emitted: the lint name, the path of the item
+//! the lint was emitted in and the normalized message, separated by tabs. Entries don't mention
+//! line numbers, so that they keep matching when unrelated code moves around. Lints which aren't
+//! emitted in a HIR item, e.g. the lints emitted before macro expansion, use the name of the file
+//! they are emitted in instead of an item path.
+//!
+//! The baseline is applied when the level of a lint is turned into the level of its diagnostic:
+//! only the lints at the `warn` or `deny` level are recorded, and the ones in the baseline are
+//! allowed. Like attributes, the baseline doesn't lower the level of lints forced with
+//! `--force-warn` or `forbid`, and lints covered by an `#[expect]` fulfill their expectation
+//! without using up a baseline entry. The same entry may appear several times in a baseline, then
+//! it allows as many occurrences of the lint.
+//!
+//! The lints allowed by the baseline are still tracked as side effects of the queries that emit
+//! them, so that they use up their entry again when incremental compilation replays them.
+
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::{fmt, fs, io};
+
+use rustc_data_structures::fx::FxIndexMap;
+
+use crate::DiagInner;
+use crate::emitter::Emitter;
+use crate::translation::to_fluent_args;
+
+#[cfg(test)]
+mod tests;
+
+const HEADER: &str = "# rustc diagnostic baseline: <lint>\\t<item>\\t<message>";
+
+/// What to do with the diagnostic baseline.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum BaselineMode {
+    /// Write the lints emitted during the session to the baseline.
+    Record,
+    /// Suppress the lints which are in the baseline, and report the stale entries of the baseline.
+    Enforce,
+}
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct BaselineEntry {
+    pub lint: String,
+    pub item: String,
+    pub message: String,
+}
+
+impl BaselineEntry {
+    /// The entry a lint has in a baseline.
+    fn for_diagnostic(emitter: &dyn Emitter, diag: &DiagInner) -> Option<BaselineEntry> {
+        let is_lint = diag.is_lint.as_ref()?;
+        let item = match &is_lint.item {
+            Some(item) => item.clone(),
+            None => match (emitter.source_map(), diag.span.primary_span()) {
+                (Some(sm), Some(span)) => sm.span_to_filename(span).prefer_local().to_string(),
+                _ => String::new(),
+            },
+        };
+        let args = to_fluent_args(diag.args.iter());
+        let message = emitter.translator().translate_messages(&diag.messages, &args);
+        Some(BaselineEntry { lint: is_lint.name.clone(), item, message: normalize(&message) })
+    }
+
+    fn parse(line: &str) -> Option<BaselineEntry> {
+        let mut fields = line.splitn(3, '\t');
+        let (lint, item, message) = (fields.next()?, fields.next()?, fields.next()?);
+        if lint.is_empty() {
+            return None;
+        }
+        let message = normalize(message);
+        Some(BaselineEntry { lint: lint.to_owned(), item: item.to_owned(), message })
+    }
+}
+
+impl fmt::Display for BaselineEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}\t{}\t{}", self.lint, self.item, self.message)
+    }
+}
+
+/// Messages are compared with their whitespace collapsed and without the Unicode directionality
+/// markers that translation may insert around arguments, so that a baseline is a text file with
+/// one entry per line which doesn't depend on `-Z translate-directionality-markers`.
+fn normalize(message: &str) -> String {
+    message
+        .split(|c: char| c.is_whitespace())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.replace(['\u{2068}', '\u{2069}'], ""))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub struct DiagnosticBaseline {
+    path: PathBuf,
+    mode: BaselineMode,
+    /// When recording, the entries recorded so far. When enforcing, the entries of the baseline
+    /// which weren't matched yet.
+    entries: FxIndexMap<BaselineEntry, usize>,
+}
+
+impl DiagnosticBaseline {
+    /// Starts recording the baseline to `path`, or loads the baseline in `path` to enforce it.
+    pub fn new(mode: BaselineMode, path: PathBuf) -> io::Result<DiagnosticBaseline> {
+        let mut entries = FxIndexMap::default();
+        if mode == BaselineMode::Enforce {
+            let contents = fs::read_to_string(&path)?;
+            for (i, line) in contents.lines().enumerate() {
+                if line.is_empty() || line.starts_with('#') {
+                    continue;
+                }
+                let Some(entry) = BaselineEntry::parse(line) else {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("malformed entry on line {}", i + 1),
+                    ));
+                };
+                *entries.entry(entry).or_default() += 1;
+            }
+        }
+        Ok(DiagnosticBaseline { path, mode, entries })
+    }
+
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    pub fn mode(&self) -> BaselineMode {
+        self.mode
+    }
+
+    /// Returns whether the lint is in the baseline being enforced, and has to be allowed.
+    pub(crate) fn contains(&self, emitter: &dyn Emitter, diag: &DiagInner) -> bool {
+        self.mode == BaselineMode::Enforce
+            && BaselineEntry::for_diagnostic(emitter, diag)
+                .is_some_and(|entry| self.entries.get(&entry).is_some_and(|&count| count > 0))
+    }
+
+    /// Records the lint in the baseline being recorded, or uses up its entry of the baseline
+    /// being enforced.
+    pub(crate) fn track(&mut self, emitter: &dyn Emitter, diag: &DiagInner) {
+        let Some(entry) = BaselineEntry::for_diagnostic(emitter, diag) else {
+            return;
+        };
+        match self.mode {
+            BaselineMode::Record => *self.entries.entry(entry).or_default() += 1,
+            BaselineMode::Enforce => {
+                if let Some(count) = self.entries.get_mut(&entry) {
+                    *count = count.saturating_sub(1);
+                }
+            }
+        }
+    }
+
+    /// When recording, writes the baseline. When enforcing, returns the stale entries of the
+    /// baseline, which didn't match any diagnostic (as many times as they appear in it).
+    pub(crate) fn finish(self) -> io::Result<Vec<(BaselineEntry, usize)>> {
+        let mut entries: Vec<_> = self.entries.into_iter().filter(|&(_, n)| n > 0).collect();
+        entries.sort();
+        match self.mode {
+            BaselineMode::Record => {
+                let mut contents = format!("{HEADER}\n");
+                for (entry, count) in entries {
+                    for _ in 0..count {
+                        writeln!(contents, "{entry}").unwrap();
+                    }
+                }
+                fs::write(&self.path, contents)?;
+                Ok(vec![])
+            }
+            BaselineMode::Enforce => Ok(entries),
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use super::*;
+
+fn entry(lint: &str, item: &str, message: &str) -> BaselineEntry {
+    BaselineEntry { lint: lint.to_owned(), item: item.to_owned(), message: message.to_owned() }
+}
+
+#[test]
+fn normalize_message() {
+    assert_eq!(normalize("unused variable: `x`"), "unused variable: `x`");
+    assert_eq!(normalize("  unused\n  variable:\t`x` "), "unused variable: `x`");
+    assert_eq!(normalize("unused variable: `\u{2068}x\u{2069}`"), "unused variable: `x`");
+}
+
+#[test]
+fn parse_entry() {
+    let e = BaselineEntry::parse("unused_variables\tfoo::bar\tunused variable: `x`").unwrap();
+    assert_eq!(e, entry("unused_variables", "foo::bar", "unused variable: `x`"));
+    // Tabs in the message are kept as part of it.
+    let e = BaselineEntry::parse("dead_code\tsrc/lib.rs\tfunction\t`f` is never used").unwrap();
+    assert_eq!(e, entry("dead_code", "src/lib.rs", "function `f` is never used"));
+    assert_eq!(BaselineEntry::parse("dead_code\tsrc/lib.rs"), None);
+    assert_eq!(BaselineEntry::parse("\tsrc/lib.rs\tmessage"), None);
+}
+
+#[test]
+fn entry_roundtrip() {
+    let e = entry("unused_variables", "foo::bar", "unused variable: `x`");
+    assert_eq!(BaselineEntry::parse(&e.to_string()), Some(e));
+}
+
+#[test]
+fn stale_entries() {
+    let a = entry("unused_variables", "foo", "unused variable: `x`");
+    let b = entry("dead_code", "bar", "function `f` is never used");
+    let entries = [(a.clone(), 0), (b.clone(), 2)].into_iter().collect();
+    let baseline =
+        DiagnosticBaseline { path: PathBuf::new(), mode: BaselineMode::Enforce, entries };
+    assert_eq!(baseline.finish().unwrap(), vec![(b, 2)]);
+}
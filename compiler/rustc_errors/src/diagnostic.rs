@@ -179,6 +179,10 @@ pub struct IsLint {
     pub(crate) name: String,
     /// Indicates whether this lint should show up in cargo's future breakage report.
     has_future_breakage: bool,
+    /// The path of the item this lint was emitted in, used by `--diagnostic-baseline`.
+    pub(crate) item: Option<String>,
+    /// Whether this lint is recorded in, or allowed by, the `--diagnostic-baseline`.
+    pub(crate) in_baseline: bool,
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -260,6 +264,11 @@ pub struct DiagInner {
     pub is_lint: Option<IsLint>,
 
     pub long_ty_path: Option<PathBuf>,
+
+    /// The tree of causes which led to this diagnostic, if it was built. Only rendered by the
+    /// JSON emitter, with `--json=cause-tree`.
+    pub cause_tree: Option<Box<CauseNode>>,
+
     /// With `-Ztrack_diagnostics` enabled,
     /// we print where in rustc this error was emitted.
     pub(crate) emitted_at: DiagLocation,
@@ -286,6 +295,7 @@ impl DiagInner {
             sort_span: DUMMY_SP,
             is_lint: None,
             long_ty_path: None,
+            cause_tree: None,
             emitted_at: DiagLocation::caller(),
         }
     }
@@ -409,6 +419,7 @@ impl DiagInner {
             self.args.iter().collect(),
             // omit self.sort_span
             &self.is_lint,
+            // omit self.cause_tree
             // omit self.emitted_at
         )
     }
@@ -438,6 +449,24 @@ pub struct Subdiag {
     pub span: MultiSpan,
 }
 
+/// A node of the cause tree of a diagnostic, e.g. one obligation in the chain of obligations
+/// which led to an unsatisfied trait bound. The `children` of a node are the reasons it was
+/// required in the first place.
+#[derive(Clone, Debug, PartialEq, Hash, Encodable, Decodable)]
+pub struct CauseNode {
+    pub message: String,
+    pub span: Span,
+    /// The types involved in this node, already rendered.
+    pub types: Vec<String>,
+    pub children: Vec<CauseNode>,
+}
+
+impl CauseNode {
+    pub fn new(message: impl Into<String>, span: Span) -> Self {
+        CauseNode { message: message.into(), span, types: vec![], children: vec![] }
+    }
+}
+
 /// Used for emitting structured error messages and other diagnostic information.
 /// Wraps a `DiagInner`, adding some useful things.
 /// - The `dcx` field, allowing it to (a) emit itself, and (b) do a drop check
@@ -1254,7 +1283,17 @@ impl<'a, G: EmissionGuarantee> Diag<'a, G> {
 
     #[rustc_lint_diagnostics]
     pub fn is_lint(&mut self, name: String, has_future_breakage: bool) -> &mut Self {
-        self.is_lint = Some(IsLint { name, has_future_breakage });
+        self.is_lint = Some(IsLint { name, has_future_breakage, item: None, in_baseline: false });
+        self
+    }
+
+    /// Records the path of the item a lint is emitted in, to identify it in diagnostic baselines
+    /// independently of line numbers. Does nothing if this diagnostic isn't a lint.
+    #[rustc_lint_diagnostics]
+    pub fn lint_item(&mut self, item: String) -> &mut Self {
+        if let Some(is_lint) = &mut self.is_lint {
+            is_lint.item = Some(item);
+        }
         self
     }
 
@@ -1266,6 +1305,13 @@ impl<'a, G: EmissionGuarantee> Diag<'a, G> {
         self
     } }
 
+    with_fn! { with_cause_tree,
+    /// Attach the tree of causes which led to this diagnostic.
+    pub fn cause_tree(&mut self, tree: CauseNode) -> &mut Self {
+        self.cause_tree = Some(Box::new(tree));
+        self
+    } }
+
     with_fn! { with_lint_id,
     /// Add an argument.
     #[rustc_lint_diagnostics]
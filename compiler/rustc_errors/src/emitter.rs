@@ -199,6 +199,12 @@ pub trait Emitter {
     ) {
     }
 
+    /// Applies the machine-applicable suggestions collected for `-Z fix`, and returns the number
+    /// of source files that were rewritten. Only supported by the `FixEmitter`.
+    fn apply_fixes(&mut self) -> usize {
+        0
+    }
+
     /// Checks if should show explanations about "rustc --explain"
     fn should_show_explain(&self) -> bool {
         true
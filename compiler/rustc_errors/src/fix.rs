@@ -0,0 +1,379 @@
+//! Applying machine-applicable suggestions to the source files (`-Z fix`).
+//!
+//! `FixEmitter` wraps the emitter that would otherwise be used. Every diagnostic is forwarded
+//! to it unchanged, but the suggestions marked as `Applicability::MachineApplicable` are kept
+//! aside. Once the compilation is done, the driver calls `DiagCtxt::apply_fixes`, and those
+//! suggestions are either written back to the source files or printed as a unified diff,
+//! depending on `FixMode`.
+//!
+//! Suggestions are applied in the order they were emitted in. A suggestion that overlaps with
+//! one that was already accepted is skipped as a whole, since applying only some of its parts
+//! could leave the code in a worse state than before.
+
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::{fs, mem};
+
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_lint_defs::Applicability;
+use rustc_span::source_map::SourceMap;
+use rustc_span::{SourceFile, Span};
+
+use crate::emitter::{DynEmitter, Emitter, TimingEvent};
+use crate::registry::Registry;
+use crate::timings::TimingRecord;
+use crate::translation::Translator;
+use crate::{CodeSuggestion, DiagInner, Level, Suggestions};
+
+#[cfg(test)]
+mod tests;
+
+/// Number of unchanged lines shown around each change in the unified diff.
+const DIFF_CONTEXT_LINES: usize = 3;
+
+/// What to do with the machine-applicable suggestions collected during a session.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum FixMode {
+    /// Rewrite the source files in place.
+    Apply,
+    /// Rewrite the source files in place, then compile them again without fixing them, to check
+    /// that the fixed code still compiles.
+    Recheck,
+    /// Print the changes as a unified diff on stdout, leaving the source files untouched.
+    DryRun,
+}
+
+pub struct FixEmitter {
+    inner: Box<DynEmitter>,
+    mode: FixMode,
+    /// The machine-applicable suggestions seen so far, each as the list of edits that have
+    /// to be applied together.
+    fixes: Vec<Vec<Edit>>,
+}
+
+/// A single `SubstitutionPart`, resolved to the file it applies to.
+struct Edit {
+    file: Arc<SourceFile>,
+    /// Byte range in the file as it is on disk, i.e. before any normalization.
+    range: Range<usize>,
+    snippet: String,
+}
+
+impl FixEmitter {
+    pub fn new(inner: Box<DynEmitter>, mode: FixMode) -> FixEmitter {
+        FixEmitter { inner, mode, fixes: Vec::new() }
+    }
+
+    fn collect_fixes(&mut self, suggestions: &[CodeSuggestion]) {
+        let Some(sm) = self.inner.source_map() else { return };
+        for suggestion in suggestions {
+            // Suggestions with several alternatives need a human to pick one.
+            if suggestion.applicability != Applicability::MachineApplicable
+                || suggestion.substitutions.len() != 1
+            {
+                continue;
+            }
+            let edits: Option<Vec<_>> = suggestion.substitutions[0]
+                .parts
+                .iter()
+                .map(|part| edit_for_span(sm, part.span, &part.snippet))
+                .collect();
+            if let Some(edits) = edits
+                && !edits.is_empty()
+            {
+                self.fixes.push(edits);
+            }
+        }
+    }
+
+    fn apply(&mut self) -> usize {
+        let mut files: FxIndexMap<PathBuf, (Arc<SourceFile>, Vec<Edit>)> = Default::default();
+        let mut skipped = 0;
+        'fixes: for fix in mem::take(&mut self.fixes) {
+            let mut is_duplicate = true;
+            for (i, edit) in fix.iter().enumerate() {
+                let path = local_path(&edit.file).unwrap();
+                let accepted = files.get(&path).map_or(&[][..], |(_, edits)| &edits[..]);
+                if accepted.iter().any(|e| e.range == edit.range && e.snippet == edit.snippet) {
+                    continue;
+                }
+                is_duplicate = false;
+                let overlaps_accepted = accepted.iter().any(|e| overlaps(&e.range, &edit.range));
+                let overlaps_sibling = fix[..i]
+                    .iter()
+                    .any(|e| Arc::ptr_eq(&e.file, &edit.file) && overlaps(&e.range, &edit.range));
+                if overlaps_accepted || overlaps_sibling {
+                    skipped += 1;
+                    continue 'fixes;
+                }
+            }
+            // The same suggestion is often emitted more than once, e.g. for code generated by
+            // a macro; there is nothing new to apply then.
+            if is_duplicate {
+                continue;
+            }
+            for edit in fix {
+                let path = local_path(&edit.file).unwrap();
+                let (_, accepted) =
+                    files.entry(path).or_insert_with(|| (Arc::clone(&edit.file), vec![]));
+                if !accepted.iter().any(|e| e.range == edit.range && e.snippet == edit.snippet) {
+                    accepted.push(edit);
+                }
+            }
+        }
+
+        if skipped > 0 {
+            self.emit_message(
+                Level::Warning,
+                format!(
+                    "skipped {skipped} suggestion{} overlapping with another one; \
+                     run the compiler again to apply {}",
+                    if skipped == 1 { "" } else { "s" },
+                    if skipped == 1 { "it" } else { "them" },
+                ),
+            );
+        }
+
+        let mut stdout = io::stdout().lock();
+        let mut fixed_files = 0;
+        for (path, (file, mut edits)) in files {
+            let old = match fs::read_to_string(&path) {
+                Ok(old) if file.src_hash.matches(&old) => old,
+                Ok(_) => {
+                    self.emit_message(
+                        Level::Warning,
+                        format!("`{}` changed during compilation, not fixing it", path.display()),
+                    );
+                    continue;
+                }
+                Err(err) => {
+                    self.emit_message(
+                        Level::Warning,
+                        format!("failed to read `{}` to fix it: {err}", path.display()),
+                    );
+                    continue;
+                }
+            };
+            edits.sort_by_key(|edit| (edit.range.start, edit.range.end));
+            let edits: Vec<_> =
+                edits.iter().map(|edit| (edit.range.clone(), edit.snippet.as_str())).collect();
+
+            match self.mode {
+                FixMode::Apply | FixMode::Recheck => {
+                    if let Err(err) = fs::write(&path, apply_edits(&old, &edits)) {
+                        self.emit_message(
+                            Level::Warning,
+                            format!("failed to write fixes to `{}`: {err}", path.display()),
+                        );
+                        continue;
+                    }
+                    self.emit_message(
+                        Level::Note,
+                        format!(
+                            "fixed `{}` ({} fix{})",
+                            path.display(),
+                            edits.len(),
+                            if edits.len() == 1 { "" } else { "es" },
+                        ),
+                    );
+                    fixed_files += 1;
+                }
+                FixMode::DryRun => {
+                    if let Err(e) = stdout.write_all(unified_diff(&path, &old, &edits).as_bytes()) {
+                        panic!("failed to print fixes: {e:?}");
+                    }
+                }
+            }
+        }
+        fixed_files
+    }
+
+    fn emit_message(&mut self, level: Level, msg: String) {
+        self.inner.emit_diagnostic(DiagInner::new(level, msg), &Registry::new(&[]));
+    }
+}
+
+impl Emitter for FixEmitter {
+    fn emit_diagnostic(&mut self, diag: DiagInner, registry: &Registry) {
+        match &diag.suggestions {
+            Suggestions::Enabled(suggestions) => self.collect_fixes(suggestions),
+            Suggestions::Sealed(suggestions) => self.collect_fixes(suggestions),
+            Suggestions::Disabled => {}
+        }
+        self.inner.emit_diagnostic(diag, registry);
+    }
+
+    fn emit_artifact_notification(&mut self, path: &Path, artifact_type: &str) {
+        self.inner.emit_artifact_notification(path, artifact_type);
+    }
+
+    fn emit_timing_section(&mut self, record: TimingRecord, event: TimingEvent) {
+        self.inner.emit_timing_section(record, event);
+    }
+
+    fn emit_future_breakage_report(&mut self, diags: Vec<DiagInner>, registry: &Registry) {
+        self.inner.emit_future_breakage_report(diags, registry);
+    }
+
+    fn emit_unused_externs(&mut self, lint_level: rustc_lint_defs::Level, unused_externs: &[&str]) {
+        self.inner.emit_unused_externs(lint_level, unused_externs);
+    }
+
+    fn apply_fixes(&mut self) -> usize {
+        self.apply()
+    }
+
+    fn should_show_explain(&self) -> bool {
+        self.inner.should_show_explain()
+    }
+
+    fn supports_color(&self) -> bool {
+        self.inner.supports_color()
+    }
+
+    fn source_map(&self) -> Option<&SourceMap> {
+        self.inner.source_map()
+    }
+
+    fn translator(&self) -> &Translator {
+        self.inner.translator()
+    }
+}
+
+/// Resolves `span` to a range in a source file that we can rewrite, if there is one.
+fn edit_for_span(sm: &SourceMap, span: Span, snippet: &str) -> Option<Edit> {
+    if span.is_dummy() {
+        return None;
+    }
+    let file = sm.lookup_source_file(span.lo());
+    // Files of other crates, and files that don't exist on disk, can't be fixed.
+    if file.src.is_none() || local_path(&file).is_none() || !file.contains(span.hi()) {
+        return None;
+    }
+    let start = file.original_relative_byte_pos(span.lo()).0 as usize;
+    let end = file.original_relative_byte_pos(span.hi()).0 as usize;
+    Some(Edit { file, range: start..end, snippet: snippet.to_owned() })
+}
+
+fn local_path(file: &SourceFile) -> Option<PathBuf> {
+    file.name.clone().into_local_path()
+}
+
+fn overlaps(a: &Range<usize>, b: &Range<usize>) -> bool {
+    // Two insertions at the same position conflict too, as their order would be arbitrary.
+    (a.start < b.end && b.start < a.end) || (a.is_empty() && b.is_empty() && a.start == b.start)
+}
+
+/// Applies non-overlapping `edits`, sorted by position, to `text`.
+fn apply_edits(text: &str, edits: &[(Range<usize>, &str)]) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut pos = 0;
+    for (range, snippet) in edits {
+        result.push_str(&text[pos..range.start]);
+        result.push_str(snippet);
+        pos = range.end;
+    }
+    result.push_str(&text[pos..]);
+    result
+}
+
+/// Renders `edits`, sorted by position, as a unified diff against `old`.
+fn unified_diff(path: &Path, old: &str, edits: &[(Range<usize>, &str)]) -> String {
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(old.match_indices('\n').map(|(i, _)| i + 1).filter(|&i| i < old.len()))
+        .collect();
+    let line_of = |pos: usize| line_starts.partition_point(|&start| start <= pos) - 1;
+    let line_end = |line: usize| line_starts.get(line + 1).copied().unwrap_or(old.len());
+
+    // First group the edits into blocks of whole lines, merging edits touching the same line.
+    // Each block is the range of old lines it replaces and the edits falling into it.
+    let mut blocks: Vec<(Range<usize>, Vec<(Range<usize>, &str)>)> = vec![];
+    for (range, snippet) in edits {
+        let first = line_of(range.start);
+        // A range ending right after a newline does not touch the following line.
+        let last = if range.end > range.start { line_of(range.end - 1) } else { first };
+        match blocks.last_mut() {
+            Some((lines, block_edits)) if first < lines.end => {
+                lines.end = lines.end.max(last + 1);
+                block_edits.push((range.clone(), *snippet));
+            }
+            _ => blocks.push((first..last + 1, vec![(range.clone(), *snippet)])),
+        }
+    }
+
+    let mut diff = format!("--- a/{}\n+++ b/{}\n", path.display(), path.display());
+    let line_count = line_starts.len();
+    // Difference between the new and old line numbers, from the blocks already printed.
+    let mut offset: isize = 0;
+    let mut blocks = blocks.into_iter().peekable();
+    while let Some(block) = blocks.next() {
+        // Gather all blocks close enough to share their context lines into a single hunk.
+        let mut hunk = vec![block];
+        while let Some((next, _)) = blocks.peek()
+            && next.start <= hunk.last().unwrap().0.end + 2 * DIFF_CONTEXT_LINES
+        {
+            hunk.push(blocks.next().unwrap());
+        }
+        let hunk_start = hunk[0].0.start.saturating_sub(DIFF_CONTEXT_LINES);
+        let hunk_end = (hunk.last().unwrap().0.end + DIFF_CONTEXT_LINES).min(line_count);
+
+        let mut body = String::new();
+        let (mut old_lines, mut new_lines) = (0, 0);
+        let mut line = hunk_start;
+        for (lines, block_edits) in &hunk {
+            for context in line..lines.start {
+                push_diff_line(&mut body, ' ', &old[line_starts[context]..line_end(context)]);
+            }
+            old_lines += lines.start - line;
+            new_lines += lines.start - line;
+
+            let block_start = line_starts[lines.start];
+            let block_end = line_end(lines.end - 1);
+            let shifted: Vec<_> = block_edits
+                .iter()
+                .map(|(range, snippet)| {
+                    (range.start - block_start..range.end - block_start, *snippet)
+                })
+                .collect();
+            let new_text = apply_edits(&old[block_start..block_end], &shifted);
+            for removed in old[block_start..block_end].split_inclusive('\n') {
+                push_diff_line(&mut body, '-', removed);
+                old_lines += 1;
+            }
+            for added in new_text.split_inclusive('\n') {
+                push_diff_line(&mut body, '+', added);
+                new_lines += 1;
+            }
+            line = lines.end;
+        }
+        for context in line..hunk_end {
+            push_diff_line(&mut body, ' ', &old[line_starts[context]..line_end(context)]);
+        }
+        old_lines += hunk_end - line;
+        new_lines += hunk_end - line;
+
+        let new_start = hunk_start as isize + offset;
+        writeln!(
+            diff,
+            "@@ -{},{old_lines} +{},{new_lines} @@",
+            hunk_start + 1,
+            // An empty range starts at the line before it, like in `diff -u`.
+            if new_lines == 0 { new_start } else { new_start + 1 },
+        )
+        .unwrap();
+        diff.push_str(&body);
+        offset += new_lines as isize - old_lines as isize;
+    }
+    diff
+}
+
+fn push_diff_line(diff: &mut String, prefix: char, line: &str) {
+    diff.push(prefix);
+    diff.push_str(line);
+    if !line.ends_with('\n') {
+        diff.push_str("\n\\ No newline at end of file\n");
+    }
+}
@@ -0,0 +1,60 @@
+use super::*;
+
+#[test]
+fn overlapping_edits() {
+    assert!(overlaps(&(0..2), &(1..3)));
+    assert!(overlaps(&(1..3), &(0..2)));
+    assert!(!overlaps(&(0..1), &(1..2)));
+    // Two insertions at the same position.
+    assert!(overlaps(&(2..2), &(2..2)));
+    // An insertion right before a replacement.
+    assert!(!overlaps(&(2..2), &(2..4)));
+}
+
+#[test]
+fn apply_sorted_edits() {
+    let text = "let x = foo(a, b);";
+    let edits = [(4..5, "_x"), (8..11, "bar"), (17..17, ".unwrap()")];
+    assert_eq!(apply_edits(text, &edits), "let _x = bar(a, b).unwrap();");
+}
+
+#[test]
+fn diff_single_hunk() {
+    let old = "fn main() {\n    let x = 1;\n}\n";
+    let diff = unified_diff(Path::new("test.rs"), old, &[(20..21, "_x")]);
+    assert_eq!(
+        diff,
+        "--- a/test.rs\n\
+         +++ b/test.rs\n\
+         @@ -1,3 +1,3 @@\n \
+         fn main() {\n\
+         -    let x = 1;\n\
+         +    let _x = 1;\n \
+         }\n"
+    );
+}
+
+#[test]
+fn diff_separate_hunks() {
+    let old: String = (1..=20).map(|i| format!("l{i}\n")).collect();
+    // Insert a whole line before `l2`, and replace `l15`.
+    let diff = unified_diff(Path::new("test.rs"), &old, &[(3..3, "new\n"), (47..50, "x")]);
+    assert_eq!(
+        diff,
+        "--- a/test.rs\n\
+         +++ b/test.rs\n\
+         @@ -1,5 +1,6 @@\n l1\n-l2\n+new\n+l2\n l3\n l4\n l5\n\
+         @@ -12,7 +13,7 @@\n l12\n l13\n l14\n-l15\n+x\n l16\n l17\n l18\n"
+    );
+}
+
+#[test]
+fn diff_missing_trailing_newline() {
+    let diff = unified_diff(Path::new("test.rs"), "a\nb", &[(2..3, "c")]);
+    assert_eq!(
+        diff,
+        "--- a/test.rs\n\
+         +++ b/test.rs\n\
+         @@ -1,2 +1,2 @@\n a\n-b\n\\ No newline at end of file\n+c\n\\ No newline at end of file\n"
+    );
+}
@@ -33,7 +33,7 @@ use crate::emitter::{
 use crate::registry::Registry;
 use crate::timings::{TimingRecord, TimingSection};
 use crate::translation::{Translator, to_fluent_args};
-use crate::{CodeSuggestion, MultiSpan, SpanLabel, Subdiag, Suggestions, TerminalUrl};
+use crate::{CauseNode, CodeSuggestion, MultiSpan, SpanLabel, Subdiag, Suggestions, TerminalUrl};
 
 #[cfg(test)]
 mod tests;
@@ -200,6 +200,19 @@ struct Diagnostic {
     children: Vec<Diagnostic>,
     /// The message as rustc would render it.
     rendered: Option<String>,
+    /// The tree of causes which led to this diagnostic, with `--json=cause-tree`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cause_tree: Option<DiagnosticCauseNode>,
+}
+
+#[derive(Serialize)]
+struct DiagnosticCauseNode {
+    message: String,
+    /// Empty if the node has no location in the source.
+    spans: Vec<DiagnosticSpan>,
+    types: Vec<String>,
+    /// The nodes which caused this node to be required.
+    children: Vec<DiagnosticCauseNode>,
 }
 
 #[derive(Serialize)]
@@ -322,6 +335,7 @@ impl Diagnostic {
                 spans: DiagnosticSpan::from_suggestion(sugg, &args, je),
                 children: vec![],
                 rendered: None,
+                cause_tree: None,
             }
         };
         let sugg = match &diag.suggestions {
@@ -372,6 +386,7 @@ impl Diagnostic {
         };
         let level = diag.level.to_str();
         let spans = DiagnosticSpan::from_multispan(&diag.span, &args, je);
+        let cause_tree = diag.cause_tree.as_deref().map(|node| DiagnosticCauseNode::new(node, je));
         let mut children: Vec<Diagnostic> = diag
             .children
             .iter()
@@ -414,6 +429,7 @@ impl Diagnostic {
             spans,
             children,
             rendered: Some(buf),
+            cause_tree,
         }
     }
 
@@ -430,6 +446,23 @@ impl Diagnostic {
             spans: DiagnosticSpan::from_multispan(&subdiag.span, args, je),
             children: vec![],
             rendered: None,
+            cause_tree: None,
+        }
+    }
+}
+
+impl DiagnosticCauseNode {
+    fn new(node: &CauseNode, je: &JsonEmitter) -> DiagnosticCauseNode {
+        let spans = if node.span.is_dummy() {
+            vec![]
+        } else {
+            vec![DiagnosticSpan::from_span_etc(node.span, true, None, None, je)]
+        };
+        DiagnosticCauseNode {
+            message: node.message.clone(),
+            spans,
+            types: node.types.clone(),
+            children: node.children.iter().map(|child| Self::new(child, je)).collect(),
         }
     }
 }
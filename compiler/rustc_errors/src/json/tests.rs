@@ -1,6 +1,6 @@
 use std::str;
 
-use rustc_span::BytePos;
+use rustc_span::{BytePos, DUMMY_SP};
 use rustc_span::source_map::FilePathMapping;
 use serde::Deserialize;
 
@@ -194,3 +194,68 @@ fn span_after_crlf() {
         },
     )
 }
+
+#[test]
+fn cause_tree_is_nested() {
+    rustc_span::create_default_session_globals_then(|| {
+        let sm = Arc::new(SourceMap::new(FilePathMapping::empty()));
+        sm.new_source_file(Path::new("test.rs").to_owned().into(), "fn f() {}".to_owned());
+        let translator =
+            Translator::with_fallback_bundle(vec![crate::DEFAULT_LOCALE_RESOURCE], false);
+
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let je = JsonEmitter::new(
+            Box::new(Shared { data: output.clone() }),
+            Some(sm),
+            translator,
+            false,
+            HumanReadableErrorType::Short,
+            ColorConfig::Never,
+        );
+
+        let span = Span::with_root_ctxt(BytePos(3), BytePos(4));
+        let mut root = CauseNode::new("the trait bound `Foo: Clone` is not satisfied", span);
+        root.types.push("Foo".to_owned());
+        root.children.push(CauseNode::new("required by a bound in `f`", DUMMY_SP));
+        DiagCtxt::new(Box::new(je))
+            .handle()
+            .struct_span_err(span, "foo")
+            .with_cause_tree(root)
+            .emit();
+
+        let bytes = output.lock().unwrap();
+        let actual_output: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        let tree = &actual_output["cause_tree"];
+        assert_eq!(tree["message"], "the trait bound `Foo: Clone` is not satisfied");
+        assert_eq!(tree["types"], serde_json::json!(["Foo"]));
+        assert_eq!(tree["spans"][0]["byte_start"], 3);
+        assert_eq!(tree["children"][0]["message"], "required by a bound in `f`");
+        assert_eq!(tree["children"][0]["spans"], serde_json::json!([]));
+        assert_eq!(tree["children"][0]["children"], serde_json::json!([]));
+    })
+}
+
+#[test]
+fn no_cause_tree_field_by_default() {
+    rustc_span::create_default_session_globals_then(|| {
+        let sm = Arc::new(SourceMap::new(FilePathMapping::empty()));
+        let translator =
+            Translator::with_fallback_bundle(vec![crate::DEFAULT_LOCALE_RESOURCE], false);
+
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let je = JsonEmitter::new(
+            Box::new(Shared { data: output.clone() }),
+            Some(sm),
+            translator,
+            false,
+            HumanReadableErrorType::Short,
+            ColorConfig::Never,
+        );
+
+        DiagCtxt::new(Box::new(je)).handle().err("foo");
+
+        let bytes = output.lock().unwrap();
+        let actual_output: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert!(actual_output.get("cause_tree").is_none());
+    })
+}
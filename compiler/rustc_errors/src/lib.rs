@@ -42,8 +42,9 @@ use Level::*;
 pub use codes::*;
 pub use decorate_diag::{BufferedEarlyLint, DecorateDiagCompat, LintBuffer};
 pub use diagnostic::{
-    BugAbort, Diag, DiagArgMap, DiagInner, DiagStyledString, Diagnostic, EmissionGuarantee,
-    FatalAbort, LintDiagnostic, LintDiagnosticBox, StringPart, Subdiag, Subdiagnostic,
+    BugAbort, CauseNode, Diag, DiagArgMap, DiagInner, DiagStyledString, Diagnostic,
+    EmissionGuarantee, FatalAbort, LintDiagnostic, LintDiagnosticBox, StringPart, Subdiag,
+    Subdiagnostic,
 };
 pub use diagnostic_impls::{
     DiagSymbolList, ElidedLifetimeInPathSubdiag, ExpectedLifetimeParameter,
@@ -73,22 +74,29 @@ pub use snippet::Style;
 // See https://github.com/rust-lang/rust/pull/115393.
 pub use termcolor::{Color, ColorSpec, WriteColor};
 use tracing::debug;
+// The `rustc_error_messages::fluent_bundle` re-export above is the `fluent_bundle` crate, this is
+// the locale bundle loader.
+pub use translation::fluent_bundle;
 
+use crate::baseline::{BaselineMode, DiagnosticBaseline};
 use crate::emitter::TimingEvent;
 use crate::registry::Registry;
 use crate::timings::TimingRecord;
 
 pub mod annotate_snippet_emitter_writer;
+pub mod baseline;
 pub mod codes;
 mod decorate_diag;
 mod diagnostic;
 mod diagnostic_impls;
 pub mod emitter;
 pub mod error;
+pub mod fix;
 pub mod json;
 mod lock;
 pub mod markdown;
 pub mod registry;
+pub mod sarif;
 mod snippet;
 mod styled_buffer;
 #[cfg(test)]
@@ -633,6 +641,9 @@ struct DiagCtxtInner {
     /// The file where the ICE information is stored. This allows delayed_span_bug backtraces to be
     /// stored along side the main panic backtrace.
     ice_file: Option<PathBuf>,
+
+    /// The `--diagnostic-baseline` being recorded or enforced, if any.
+    diagnostic_baseline: Option<DiagnosticBaseline>,
 }
 
 /// A key denoting where from a diagnostic was stashed.
@@ -753,6 +764,11 @@ impl DiagCtxt {
         self
     }
 
+    pub fn with_diagnostic_baseline(mut self, baseline: DiagnosticBaseline) -> Self {
+        self.inner.get_mut().diagnostic_baseline = Some(baseline);
+        self
+    }
+
     pub fn new(emitter: Box<DynEmitter>) -> Self {
         Self { inner: Lock::new(DiagCtxtInner::new(emitter)) }
     }
@@ -822,6 +838,7 @@ impl DiagCtxt {
             future_breakage_diagnostics,
             fulfilled_expectations,
             ice_file: _,
+            diagnostic_baseline: _,
         } = inner.deref_mut();
 
         // For the `Vec`s and `HashMap`s, we overwrite with an empty container to free the
@@ -1155,6 +1172,74 @@ impl<'a> DiagCtxtHandle<'a> {
         }
     }
 
+    /// Applies the machine-applicable suggestions collected for `-Z fix` to the source files, or
+    /// prints them with `-Z fix=dry-run`. Returns the number of files that were rewritten.
+    ///
+    /// This is called by the driver once all the diagnostics of the compilation were emitted,
+    /// including when the compilation failed with errors, but not after an ICE.
+    pub fn apply_fixes(&self) -> usize {
+        self.inner.borrow_mut().emitter.apply_fixes()
+    }
+
+    /// Applies the `--diagnostic-baseline` to a lint at the `warn` or `deny` level: the lint is
+    /// allowed if it is in the baseline being enforced, and it is recorded in the baseline being
+    /// recorded once it is emitted.
+    pub fn apply_diagnostic_baseline(&self, diag: &mut DiagInner) {
+        let inner = self.inner.borrow();
+        let Some(baseline) = &inner.diagnostic_baseline else {
+            return;
+        };
+        let allowed = baseline.contains(&*inner.emitter, diag);
+        if baseline.mode() == BaselineMode::Record || allowed {
+            if let Some(is_lint) = &mut diag.is_lint {
+                is_lint.in_baseline = true;
+            }
+        }
+        if allowed {
+            diag.level = Allow;
+        }
+    }
+
+    /// Writes the `--diagnostic-baseline` being recorded, or reports the stale entries of the
+    /// baseline being enforced.
+    pub fn finish_diagnostic_baseline(&self) {
+        let Some(baseline) = self.inner.borrow_mut().diagnostic_baseline.take() else {
+            return;
+        };
+        let path = baseline.path().clone();
+        let stale = match baseline.finish() {
+            Ok(stale) => stale,
+            Err(err) => {
+                self.handle().err(format!(
+                    "failed to write the diagnostic baseline `{}`: {err}",
+                    path.display()
+                ));
+                return;
+            }
+        };
+        // When compilation stopped early because of errors, some lints were never checked, so
+        // their entries aren't necessarily stale.
+        if stale.is_empty() || self.has_errors_excluding_lint_errors().is_some() {
+            return;
+        }
+
+        let count: usize = stale.iter().map(|&(_, n)| n).sum();
+        let mut diag = self.handle().struct_warn(format!(
+            "{count} {} in the diagnostic baseline `{}` did not match any lint",
+            if count == 1 { "entry" } else { "entries" },
+            path.display()
+        ));
+        for (entry, n) in stale {
+            let times = if n > 1 { format!(" ({n} times)") } else { String::new() };
+            diag.note(format!(
+                "stale entry{times}: `{}` in `{}`: {}",
+                entry.lint, entry.item, entry.message
+            ));
+        }
+        diag.help("record the baseline again with `--diagnostic-baseline=record=<FILE>`");
+        diag.emit();
+    }
+
     pub fn emit_unused_externs(
         &self,
         lint_level: rustc_lint_defs::Level,
@@ -1499,6 +1584,7 @@ impl DiagCtxtInner {
             future_breakage_diagnostics: Vec::new(),
             fulfilled_expectations: Default::default(),
             ice_file: None,
+            diagnostic_baseline: None,
         }
     }
 
@@ -1593,6 +1679,16 @@ impl DiagCtxtInner {
             }
             Note | Help | FailureNote => {}
             OnceNote | OnceHelp => panic!("bad level: {:?}", diagnostic.level),
+            Allow if diagnostic.is_lint.as_ref().is_some_and(|lint| lint.in_baseline) => {
+                // Nothing emitted for the lints allowed by the baseline either, but they use up
+                // their entry of the baseline, also when incremental compilation replays them.
+                if let Some(baseline) = &mut self.diagnostic_baseline {
+                    baseline.track(&*self.emitter, &diagnostic);
+                }
+                TRACK_DIAGNOSTIC(diagnostic, &mut |_| None);
+                self.suppressed_expected_diag = true;
+                return None;
+            }
             Allow => {
                 // Nothing emitted for allowed lints.
                 if diagnostic.has_future_breakage() {
@@ -1614,6 +1710,14 @@ impl DiagCtxtInner {
         }
 
         TRACK_DIAGNOSTIC(diagnostic, &mut |mut diagnostic| {
+            // This is done here rather than above so that the lints are recorded again when
+            // incremental compilation replays them.
+            if diagnostic.is_lint.as_ref().is_some_and(|lint| lint.in_baseline)
+                && let Some(baseline) = &mut self.diagnostic_baseline
+            {
+                baseline.track(&*self.emitter, &diagnostic);
+            }
+
             if let Some(code) = diagnostic.code {
                 self.emitted_diagnostic_codes.insert(code);
             }
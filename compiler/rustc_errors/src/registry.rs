@@ -20,4 +20,10 @@ impl Registry {
     pub fn try_find_description(&self, code: ErrCode) -> Result<&'static str, InvalidErrorCode> {
         self.long_descriptions.get(&code).copied().ok_or(InvalidErrorCode)
     }
+
+    /// Returns all the error codes which have a description, with their description, in no
+    /// particular order.
+    pub fn descriptions(&self) -> impl Iterator<Item = (ErrCode, &'static str)> {
+        self.long_descriptions.iter().map(|(&code, &description)| (code, description))
+    }
 }
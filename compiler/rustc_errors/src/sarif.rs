@@ -0,0 +1,453 @@
+//! A SARIF 2.1.0 emitter for errors.
+//!
+//! SARIF (the Static Analysis Results Interchange Format) describes a whole analysis run in a
+//! single log object, so unlike the JSON emitter this one cannot print diagnostics as they come
+//! in. Instead, every diagnostic is converted to a SARIF `result` and buffered, and the log is
+//! written out once the emitter is dropped together with its `DiagCtxt`.
+//!
+//! Error codes and lint names become SARIF `rules`, secondary spans and child diagnostics become
+//! `relatedLocations`, and suggestions become `fixes`.
+//!
+//! See <https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html> for the format.
+
+use std::io::{self, Write};
+use std::sync::Arc;
+
+use derive_setters::Setters;
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_data_structures::sync::IntoDynSyncSend;
+use rustc_error_messages::FluentArgs;
+use rustc_lint_defs::Applicability;
+use rustc_span::Span;
+use rustc_span::source_map::SourceMap;
+use serde::Serialize;
+
+use crate::diagnostic::IsLint;
+use crate::emitter::Emitter;
+use crate::registry::Registry;
+use crate::translation::{Translator, to_fluent_args};
+use crate::{CodeSuggestion, DiagInner, Level, MultiSpan, Subdiag, Suggestions};
+
+#[cfg(test)]
+mod tests;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+
+#[derive(Setters)]
+pub struct SarifEmitter {
+    #[setters(skip)]
+    dst: IntoDynSyncSend<Box<dyn Write + Send>>,
+    #[setters(skip)]
+    sm: Option<Arc<SourceMap>>,
+    #[setters(skip)]
+    translator: Translator,
+    /// Every distinct error code or lint name seen so far, in order of first appearance.
+    #[setters(skip)]
+    rules: FxIndexMap<String, ReportingDescriptor>,
+    #[setters(skip)]
+    results: Vec<SarifResult>,
+    /// Whether to write a log even if nothing was emitted. Short-lived emitters, like the ones
+    /// used for early errors, turn this off so they don't print a stray empty log.
+    write_empty_log: bool,
+}
+
+impl SarifEmitter {
+    pub fn new(
+        dst: Box<dyn Write + Send>,
+        sm: Option<Arc<SourceMap>>,
+        translator: Translator,
+    ) -> SarifEmitter {
+        SarifEmitter {
+            dst: IntoDynSyncSend(dst),
+            sm,
+            translator,
+            rules: Default::default(),
+            results: Vec::new(),
+            write_empty_log: true,
+        }
+    }
+
+    fn write_log(&mut self) -> io::Result<()> {
+        if self.results.is_empty() && !self.write_empty_log {
+            return Ok(());
+        }
+        let log = SarifLog {
+            schema: SARIF_SCHEMA,
+            version: SARIF_VERSION,
+            runs: vec![Run {
+                tool: Tool {
+                    driver: ToolComponent {
+                        name: "rustc",
+                        information_uri: "https://www.rust-lang.org/",
+                        rules: std::mem::take(&mut self.rules).into_values().collect(),
+                    },
+                },
+                column_kind: "unicodeCodePoints",
+                results: std::mem::take(&mut self.results),
+            }],
+        };
+        serde_json::to_writer(&mut *self.dst, &log)?;
+        self.dst.write_all(b"\n")?;
+        self.dst.flush()
+    }
+
+    /// Returns the index of the rule for `id` in the tool's rule table, adding it if needed.
+    fn rule_index(&mut self, id: &str, explanation: Option<&'static str>, is_code: bool) -> usize {
+        if let Some(index) = self.rules.get_index_of(id) {
+            return index;
+        }
+        let rule = ReportingDescriptor {
+            id: id.to_owned(),
+            full_description: explanation.map(|text| MultiformatMessage { text: text.to_owned() }),
+            help_uri: is_code.then(|| format!("https://doc.rust-lang.org/error_codes/{id}.html")),
+        };
+        self.rules.insert_full(id.to_owned(), rule).0
+    }
+
+    fn result_from_diagnostic(&mut self, diag: DiagInner, registry: &Registry) -> SarifResult {
+        let args = to_fluent_args(diag.args.iter());
+        let mut message = self.translator.translate_messages(&diag.messages, &args).into_owned();
+
+        let (rule_id, rule_index) = if let Some(code) = diag.code {
+            let id = code.to_string();
+            let index = self.rule_index(&id, registry.try_find_description(code).ok(), true);
+            (Some(id), Some(index))
+        } else if let Some(IsLint { name, .. }) = &diag.is_lint {
+            let index = self.rule_index(name, None, false);
+            (Some(name.clone()), Some(index))
+        } else {
+            (None, None)
+        };
+
+        let locations = self.locations_from_multispan(&diag.span, true, &args);
+        let mut related_locations = self.locations_from_multispan(&diag.span, false, &args);
+        for child in &diag.children {
+            self.add_sub_diagnostic(child, &mut message, &mut related_locations, &args);
+        }
+
+        let fixes = match &diag.suggestions {
+            Suggestions::Enabled(suggestions) => self.fixes_from_suggestions(suggestions, &args),
+            Suggestions::Sealed(suggestions) => self.fixes_from_suggestions(suggestions, &args),
+            Suggestions::Disabled => vec![],
+        };
+
+        SarifResult {
+            rule_id,
+            rule_index,
+            level: sarif_level(diag.level),
+            message: Message { text: message },
+            locations,
+            related_locations,
+            fixes,
+        }
+    }
+
+    /// Child diagnostics that point at code become related locations, the others are appended
+    /// to the result's message the way the human emitter renders `= note: ...` lines.
+    fn add_sub_diagnostic(
+        &self,
+        subdiag: &Subdiag,
+        message: &mut String,
+        related_locations: &mut Vec<Location>,
+        args: &FluentArgs<'_>,
+    ) {
+        let text = self.translator.translate_messages(&subdiag.messages, args);
+        let text = format!("{}: {text}", subdiag.level.to_str());
+        if subdiag.span.primary_spans().is_empty() {
+            message.push('\n');
+            message.push_str(&text);
+        } else {
+            related_locations.extend(
+                subdiag
+                    .span
+                    .primary_spans()
+                    .iter()
+                    .filter_map(|&span| self.location(span, Some(text.clone()))),
+            );
+        }
+    }
+
+    /// Converts either the primary or the secondary spans of `msp` into locations.
+    fn locations_from_multispan(
+        &self,
+        msp: &MultiSpan,
+        primary: bool,
+        args: &FluentArgs<'_>,
+    ) -> Vec<Location> {
+        msp.span_labels()
+            .into_iter()
+            .filter(|span_label| span_label.is_primary == primary)
+            .filter_map(|span_label| {
+                let label = span_label
+                    .label
+                    .as_ref()
+                    .map(|m| self.translator.translate_message(m, args).unwrap().into_owned());
+                self.location(span_label.span, label)
+            })
+            .collect()
+    }
+
+    fn location(&self, span: Span, label: Option<String>) -> Option<Location> {
+        Some(Location {
+            physical_location: self.physical_location(span)?,
+            message: label.map(|text| Message { text }),
+        })
+    }
+
+    fn physical_location(&self, span: Span) -> Option<PhysicalLocation> {
+        let sm = self.sm.as_ref()?;
+        if span.is_dummy() {
+            return None;
+        }
+        let (artifact_location, region) = self.region(sm, span);
+        Some(PhysicalLocation { artifact_location, region })
+    }
+
+    fn region(&self, sm: &SourceMap, span: Span) -> (ArtifactLocation, Region) {
+        let start = sm.lookup_char_pos(span.lo());
+        let end = sm.lookup_char_pos(span.hi());
+        let byte_offset = start.file.original_relative_byte_pos(span.lo()).0;
+        let byte_end = start.file.original_relative_byte_pos(span.hi()).0;
+        let artifact_location =
+            ArtifactLocation { uri: sm.filename_for_diagnostics(&start.file.name).to_string() };
+        let region = Region {
+            start_line: start.line,
+            start_column: start.col.0 + 1,
+            end_line: end.line,
+            end_column: end.col.0 + 1,
+            byte_offset,
+            byte_length: byte_end - byte_offset,
+        };
+        (artifact_location, region)
+    }
+
+    /// Each alternative substitution of a suggestion becomes its own fix, with the replaced
+    /// parts grouped by the file they apply to.
+    fn fixes_from_suggestions(
+        &self,
+        suggestions: &[CodeSuggestion],
+        args: &FluentArgs<'_>,
+    ) -> Vec<Fix> {
+        let Some(sm) = &self.sm else { return vec![] };
+        let mut fixes = vec![];
+        for suggestion in suggestions {
+            let description =
+                self.translator.translate_message(&suggestion.msg, args).unwrap().into_owned();
+            for substitution in &suggestion.substitutions {
+                let mut artifact_changes: FxIndexMap<String, Vec<Replacement>> =
+                    Default::default();
+                for part in &substitution.parts {
+                    if part.span.is_dummy() {
+                        continue;
+                    }
+                    let (artifact_location, deleted_region) = self.region(sm, part.span);
+                    artifact_changes.entry(artifact_location.uri).or_default().push(Replacement {
+                        deleted_region,
+                        inserted_content: ArtifactContent { text: part.snippet.clone() },
+                    });
+                }
+                if artifact_changes.is_empty() {
+                    continue;
+                }
+                fixes.push(Fix {
+                    description: Message { text: description.clone() },
+                    artifact_changes: artifact_changes
+                        .into_iter()
+                        .map(|(uri, replacements)| ArtifactChange {
+                            artifact_location: ArtifactLocation { uri },
+                            replacements,
+                        })
+                        .collect(),
+                    properties: FixProperties { applicability: suggestion.applicability },
+                });
+            }
+        }
+        fixes
+    }
+}
+
+impl Drop for SarifEmitter {
+    fn drop(&mut self) {
+        // Still try to write the log when unwinding from a fatal error, but don't turn a failure
+        // to do so into a double panic.
+        if let Err(e) = self.write_log()
+            && !std::thread::panicking()
+        {
+            panic!("failed to print SARIF log: {e:?}");
+        }
+    }
+}
+
+impl Emitter for SarifEmitter {
+    fn emit_diagnostic(&mut self, diag: DiagInner, registry: &Registry) {
+        // Failure notes like "aborting due to N previous errors" summarize the run for humans
+        // and are not analysis results of their own.
+        if diag.level.is_failure_note() {
+            return;
+        }
+        let result = self.result_from_diagnostic(diag, registry);
+        self.results.push(result);
+    }
+
+    fn source_map(&self) -> Option<&SourceMap> {
+        self.sm.as_deref()
+    }
+
+    fn should_show_explain(&self) -> bool {
+        // Explanations are attached to the rules instead.
+        false
+    }
+
+    fn translator(&self) -> &Translator {
+        &self.translator
+    }
+}
+
+fn sarif_level(level: Level) -> &'static str {
+    match level {
+        Level::Bug | Level::DelayedBug | Level::Fatal | Level::Error => "error",
+        Level::ForceWarning | Level::Warning => "warning",
+        Level::Note | Level::OnceNote | Level::Help | Level::OnceHelp | Level::FailureNote => {
+            "note"
+        }
+        Level::Allow | Level::Expect => "none",
+    }
+}
+
+// The following data types are provided just for serialisation.
+
+#[derive(Serialize)]
+struct SarifLog {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: Vec<Run>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Run {
+    tool: Tool,
+    /// Columns are counted in `char`s, like everywhere else in rustc's output.
+    column_kind: &'static str,
+    results: Vec<SarifResult>,
+}
+
+#[derive(Serialize)]
+struct Tool {
+    driver: ToolComponent,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ToolComponent {
+    name: &'static str,
+    information_uri: &'static str,
+    rules: Vec<ReportingDescriptor>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReportingDescriptor {
+    /// The error code (e.g. "E0308") or the lint name (e.g. "unused_variables").
+    id: String,
+    /// The long explanation of an error code, as shown by `rustc --explain`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    full_description: Option<MultiformatMessage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    help_uri: Option<String>,
+}
+
+#[derive(Serialize)]
+struct MultiformatMessage {
+    text: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_index: Option<usize>,
+    /// One of "error", "warning", "note" or "none".
+    level: &'static str,
+    message: Message,
+    locations: Vec<Location>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    related_locations: Vec<Location>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    fixes: Vec<Fix>,
+}
+
+#[derive(Serialize)]
+struct Message {
+    text: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    physical_location: PhysicalLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<Message>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation {
+    artifact_location: ArtifactLocation,
+    region: Region,
+}
+
+#[derive(Serialize)]
+struct ArtifactLocation {
+    uri: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Region {
+    /// 1-based.
+    start_line: usize,
+    /// 1-based, character offset.
+    start_column: usize,
+    end_line: usize,
+    end_column: usize,
+    byte_offset: u32,
+    byte_length: u32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Fix {
+    description: Message,
+    artifact_changes: Vec<ArtifactChange>,
+    properties: FixProperties,
+}
+
+#[derive(Serialize)]
+struct FixProperties {
+    /// Whether the fix can be applied without human review.
+    applicability: Applicability,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ArtifactChange {
+    artifact_location: ArtifactLocation,
+    replacements: Vec<Replacement>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Replacement {
+    deleted_region: Region,
+    inserted_content: ArtifactContent,
+}
+
+#[derive(Serialize)]
+struct ArtifactContent {
+    text: String,
+}
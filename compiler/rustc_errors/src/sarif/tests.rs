@@ -0,0 +1,117 @@
+use std::path::Path;
+use std::str;
+use std::sync::Mutex;
+
+use rustc_span::BytePos;
+use rustc_span::source_map::FilePathMapping;
+use serde_json::Value;
+
+use super::*;
+use crate::{DiagCtxt, E0308};
+
+struct Shared<T> {
+    data: Arc<Mutex<T>>,
+}
+
+impl<T: Write> Write for Shared<T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.data.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.data.lock().unwrap().flush()
+    }
+}
+
+/// Runs `f` against a `DiagCtxt` backed by a SARIF emitter and returns the log it wrote.
+fn with_sarif_log(code: &str, f: impl FnOnce(&DiagCtxt)) -> Value {
+    rustc_span::create_default_session_globals_then(|| {
+        let sm = Arc::new(SourceMap::new(FilePathMapping::empty()));
+        sm.new_source_file(Path::new("test.rs").to_owned().into(), code.to_owned());
+        let translator =
+            Translator::with_fallback_bundle(vec![crate::DEFAULT_LOCALE_RESOURCE], false);
+
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let se = SarifEmitter::new(Box::new(Shared { data: output.clone() }), Some(sm), translator);
+
+        let dcx = DiagCtxt::new(Box::new(se));
+        f(&dcx);
+        // The log is only written once the emitter goes away.
+        drop(dcx);
+
+        let bytes = output.lock().unwrap();
+        serde_json::from_str(str::from_utf8(&bytes).unwrap()).unwrap()
+    })
+}
+
+fn span(lo: u32, hi: u32) -> Span {
+    Span::with_root_ctxt(BytePos(lo), BytePos(hi))
+}
+
+#[test]
+fn single_log_for_all_diagnostics() {
+    let log = with_sarif_log("fn main() {}\n", |dcx| {
+        dcx.handle().span_warn(span(0, 2), "first");
+        dcx.handle().span_warn(span(3, 7), "second");
+    });
+
+    assert_eq!(log["version"], "2.1.0");
+    let runs = log["runs"].as_array().unwrap();
+    assert_eq!(runs.len(), 1);
+    let results = runs[0]["results"].as_array().unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0]["level"], "warning");
+    assert_eq!(results[0]["message"]["text"], "first");
+    assert_eq!(results[1]["message"]["text"], "second");
+
+    let region = &results[1]["locations"][0]["physicalLocation"]["region"];
+    assert_eq!(region["startLine"], 1);
+    assert_eq!(region["startColumn"], 4);
+    assert_eq!(region["endColumn"], 8);
+    assert_eq!(region["byteOffset"], 3);
+    assert_eq!(region["byteLength"], 4);
+}
+
+#[test]
+fn codes_become_rules() {
+    let log = with_sarif_log("let x: u8 = 'a';\n", |dcx| {
+        dcx.handle().struct_span_err(span(12, 15), "mismatched types").with_code(E0308).emit();
+        dcx.handle().struct_span_err(span(4, 5), "mismatched types").with_code(E0308).emit();
+    });
+
+    let rules = log["runs"][0]["tool"]["driver"]["rules"].as_array().unwrap();
+    assert_eq!(rules.len(), 1);
+    assert_eq!(rules[0]["id"], "E0308");
+
+    let results = log["runs"][0]["results"].as_array().unwrap();
+    for result in results {
+        assert_eq!(result["ruleId"], "E0308");
+        assert_eq!(result["ruleIndex"], 0);
+        assert_eq!(result["level"], "error");
+    }
+}
+
+#[test]
+fn suggestions_become_fixes() {
+    let log = with_sarif_log("let x: u8 = 'a';\n", |dcx| {
+        dcx.handle()
+            .struct_span_err(span(12, 15), "mismatched types")
+            .with_span_suggestion(
+                span(12, 15),
+                "use a byte literal",
+                "b'a'",
+                Applicability::MachineApplicable,
+            )
+            .emit();
+    });
+
+    let fix = &log["runs"][0]["results"][0]["fixes"][0];
+    assert_eq!(fix["description"]["text"], "use a byte literal");
+    assert_eq!(fix["properties"]["applicability"], "MachineApplicable");
+    let change = &fix["artifactChanges"][0];
+    assert_eq!(change["artifactLocation"]["uri"], "test.rs");
+    let replacement = &change["replacements"][0];
+    assert_eq!(replacement["deletedRegion"]["byteOffset"], 12);
+    assert_eq!(replacement["deletedRegion"]["byteLength"], 3);
+    assert_eq!(replacement["insertedContent"]["text"], "b'a'");
+}
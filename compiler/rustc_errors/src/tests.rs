@@ -2,24 +2,25 @@ use std::sync::{Arc, LazyLock};
 
 use rustc_data_structures::sync::IntoDynSyncSend;
 use rustc_error_messages::fluent_bundle::resolver::errors::{ReferenceKind, ResolverError};
-use rustc_error_messages::{DiagMessage, langid};
+use rustc_error_messages::{DiagMessage, LanguageIdentifier, langid};
 
 use crate::FluentBundle;
 use crate::error::{TranslateError, TranslateErrorKind};
 use crate::fluent_bundle::*;
 use crate::translation::Translator;
 
-fn make_translator(ftl: &'static str) -> Translator {
+fn make_bundle(locale: LanguageIdentifier, ftl: &'static str) -> FluentBundle {
     let resource = FluentResource::try_new(ftl.into()).expect("Failed to parse an FTL string.");
 
-    let langid_en = langid!("en-US");
-
     let mut bundle: FluentBundle =
-        IntoDynSyncSend(crate::fluent_bundle::bundle::FluentBundle::new_concurrent(vec![
-            langid_en,
-        ]));
+        IntoDynSyncSend(crate::fluent_bundle::bundle::FluentBundle::new_concurrent(vec![locale]));
 
     bundle.add_resource(resource).expect("Failed to add FTL resources to the bundle.");
+    bundle
+}
+
+fn make_translator(ftl: &'static str) -> Translator {
+    let bundle = make_bundle(langid!("en-US"), ftl);
 
     Translator {
         fluent_bundle: None,
@@ -180,3 +181,43 @@ fn misformed_fluent() {
         );
     }
 }
+
+#[test]
+fn partial_locale_bundle() {
+    let fallback = make_bundle(
+        langid!("en-US"),
+        "mir_build_borrow_of_moved_value = borrow of moved value
+    .label = value moved into `{$name}` here
+    .suggestion = borrow this binding in the pattern to avoid moving the value
+mir_build_moved = value used here after move",
+    );
+    let primary = make_bundle(
+        langid!("fr"),
+        "mir_build_borrow_of_moved_value = emprunt d'une valeur déplacée
+    .label = valeur déplacée dans `{$name}` ici",
+    );
+    let translator = Translator {
+        fluent_bundle: Some(Arc::new(primary)),
+        fallback_fluent_bundle: Arc::new(LazyLock::new(Box::new(|| fallback))),
+    };
+
+    let mut args = FluentArgs::new();
+    args.set("name", "Foo");
+    let translate = |id: &'static str, attr: Option<&'static str>| {
+        let message = DiagMessage::FluentIdentifier(id.into(), attr.map(Into::into));
+        translator.translate_message(&message, &args).unwrap().into_owned()
+    };
+
+    // Messages and attributes which are in the locale bundle are translated..
+    assert_eq!(translate("mir_build_borrow_of_moved_value", None), "emprunt d'une valeur déplacée");
+    assert_eq!(
+        translate("mir_build_borrow_of_moved_value", Some("label")),
+        "valeur déplacée dans `\u{2068}Foo\u{2069}` ici"
+    );
+    // ..while missing attributes and messages fall back to English one by one.
+    assert_eq!(
+        translate("mir_build_borrow_of_moved_value", Some("suggestion")),
+        "borrow this binding in the pattern to avoid moving the value"
+    );
+    assert_eq!(translate("mir_build_moved", None), "value used here after move");
+}
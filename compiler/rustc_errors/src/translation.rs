@@ -1,10 +1,13 @@
 use std::borrow::Cow;
-use std::env;
 use std::error::Report;
+use std::path::Path;
 use std::sync::Arc;
+use std::{env, fs};
 
-pub use rustc_error_messages::{FluentArgs, LazyFallbackBundle};
-use tracing::{debug, trace};
+use rustc_error_messages::fluent_bundle::FluentResource;
+pub use rustc_error_messages::{FluentArgs, LazyFallbackBundle, TranslationBundleError};
+use rustc_error_messages::{LanguageIdentifier, langid, new_fluent_bundle};
+use tracing::{debug, instrument, trace};
 
 use crate::error::{TranslateError, TranslateErrorKind};
 use crate::snippet::Style;
@@ -29,6 +32,108 @@ pub fn to_fluent_args<'iter>(iter: impl Iterator<Item = DiagArg<'iter>>) -> Flue
     args
 }
 
+/// Returns the Fluent bundle with the translations for the locale requested by the user, loaded
+/// from the locale bundle in the sysroot.
+///
+/// A locale bundle is a `$sysroot/share/locale/$locale` directory, holding a `$crate.ftl` file for
+/// each compiler crate it translates, with translations of the messages in `$crate/messages.ftl`.
+/// Bundles need not be complete: messages which are missing from the bundle, or which fail to
+/// parse, are taken from the English fallback bundle one by one (see `Translator`). The messages
+/// which were skipped while loading the bundle are reported to `warn`.
+///
+/// If `-Z translate-additional-ftl` was provided, load that resource and add it to the bundle
+/// (overriding any conflicting messages).
+#[instrument(level = "trace", skip(warn))]
+pub fn fluent_bundle(
+    sysroot_candidates: &[&Path],
+    requested_locale: Option<LanguageIdentifier>,
+    additional_ftl_path: Option<&Path>,
+    with_directionality_markers: bool,
+    warn: &mut dyn FnMut(TranslationBundleError),
+) -> Result<Option<Arc<FluentBundle>>, TranslationBundleError> {
+    if requested_locale.is_none() && additional_ftl_path.is_none() {
+        return Ok(None);
+    }
+
+    let fallback_locale = langid!("en-US");
+    let requested_fallback_locale = requested_locale.as_ref() == Some(&fallback_locale);
+    trace!(?requested_fallback_locale);
+    if requested_fallback_locale && additional_ftl_path.is_none() {
+        return Ok(None);
+    }
+    // If there is only `-Z additional-ftl-path`, assume locale is "en-US", otherwise use user
+    // provided locale.
+    let locale = requested_locale.clone().unwrap_or(fallback_locale);
+    trace!(?locale);
+    let mut bundle = new_fluent_bundle(locale, with_directionality_markers);
+
+    // If the user requests the default locale then don't try to load anything.
+    if let Some(requested_locale) = requested_locale {
+        let mut found_resources = false;
+        for sysroot in sysroot_candidates {
+            let mut sysroot = sysroot.to_path_buf();
+            sysroot.push("share");
+            sysroot.push("locale");
+            sysroot.push(requested_locale.to_string());
+            trace!(?sysroot);
+
+            if !sysroot.exists() {
+                trace!("skipping");
+                continue;
+            }
+
+            if !sysroot.is_dir() {
+                return Err(TranslationBundleError::LocaleIsNotDir);
+            }
+
+            for entry in sysroot.read_dir().map_err(TranslationBundleError::ReadLocalesDir)? {
+                let entry = entry.map_err(TranslationBundleError::ReadLocalesDirEntry)?;
+                let path = entry.path();
+                trace!(?path);
+                if path.extension().and_then(|s| s.to_str()) != Some("ftl") {
+                    trace!("skipping");
+                    continue;
+                }
+
+                let resource_str =
+                    fs::read_to_string(&path).map_err(TranslationBundleError::ReadFtl)?;
+                // Messages which fail to parse are left out of the resource, so that only those
+                // messages fall back to English rather than the whole bundle.
+                let resource = FluentResource::try_new(resource_str).unwrap_or_else(|(res, errs)| {
+                    for err in errs {
+                        warn(TranslationBundleError::ParseLocaleMessage(path.clone(), err));
+                    }
+                    res
+                });
+                trace!(?resource);
+                // The conflicting messages are not added, but the rest of the resource is.
+                if let Err(errs) = bundle.add_resource(resource) {
+                    for err in errs {
+                        warn(TranslationBundleError::DuplicateLocaleMessage(path.clone(), err));
+                    }
+                }
+                found_resources = true;
+            }
+        }
+
+        if !found_resources {
+            return Err(TranslationBundleError::MissingLocale);
+        }
+    }
+
+    if let Some(additional_ftl_path) = additional_ftl_path {
+        let resource_str =
+            fs::read_to_string(additional_ftl_path).map_err(TranslationBundleError::ReadFtl)?;
+        let resource =
+            FluentResource::try_new(resource_str).map_err(TranslationBundleError::from)?;
+        trace!(?resource);
+        bundle.add_resource_overriding(resource);
+    }
+
+    let bundle = Arc::new(bundle);
+    Ok(Some(bundle))
+}
+
 #[derive(Clone)]
 pub struct Translator {
     /// Localized diagnostics for the locale requested by the user. If no language was requested by
@@ -111,11 +216,16 @@ impl Translator {
                 Some(Ok(t)) => t,
 
                 // If `translate_with_bundle` returns `Err` with the primary bundle, this is likely
-                // just that the primary bundle doesn't contain the message being translated, so
-                // proceed to the fallback bundle.
+                // just that the primary bundle doesn't contain the message (or the attribute or
+                // value of the message) being translated, as locale bundles need not be complete,
+                // so proceed to the fallback bundle for this message.
                 Some(Err(
                     primary @ TranslateError::One {
-                        kind: TranslateErrorKind::MessageMissing, ..
+                        kind:
+                            TranslateErrorKind::MessageMissing
+                            | TranslateErrorKind::AttributeMissing { .. }
+                            | TranslateErrorKind::ValueMissing,
+                        ..
                     },
                 )) => translate_with_bundle(&self.fallback_fluent_bundle)
                     .map_err(|fallback| primary.and(fallback))?,
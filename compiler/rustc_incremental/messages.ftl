@@ -99,4 +99,6 @@ incremental_unrecognized_depnode = unrecognized `DepNode` variant: {$name}
 
 incremental_unrecognized_depnode_label = dep-node label `{$label}` not recognized
 
+incremental_write_codegen_cache = failed to add an entry to the codegen cache at `{$path}`: {$err}
+
 incremental_write_new = failed to write {$name} to `{$path}`: {$err}
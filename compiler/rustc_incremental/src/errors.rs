@@ -293,6 +293,13 @@ pub(crate) struct CopyWorkProductToCache<'a> {
     pub err: std::io::Error,
 }
 
+#[derive(Diagnostic)]
+#[diag(incremental_write_codegen_cache)]
+pub(crate) struct WriteCodegenCache<'a> {
+    pub path: &'a Path,
+    pub err: std::io::Error,
+}
+
 #[derive(Diagnostic)]
 #[diag(incremental_delete_workproduct)]
 pub(crate) struct DeleteWorkProduct<'a> {
@@ -14,9 +14,10 @@ mod errors;
 mod persist;
 
 pub use persist::{
-    LoadResult, copy_cgu_workproduct_to_incr_comp_cache_dir, finalize_session_directory,
-    in_incr_comp_dir, in_incr_comp_dir_sess, load_query_result_cache, save_work_product_index,
-    setup_dep_graph,
+    DEP_GRAPH_FILENAME, LoadResult, QUERY_CACHE_FILENAME,
+    copy_cgu_workproduct_to_incr_comp_cache_dir, finalize_session_directory, in_incr_comp_dir,
+    in_incr_comp_dir_sess, load_from_codegen_cache, load_query_result_cache, read_incr_comp_file,
+    save_to_codegen_cache, save_work_product_index, setup_dep_graph,
 };
 use rustc_middle::util::Providers;
 
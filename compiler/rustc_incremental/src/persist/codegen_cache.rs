@@ -0,0 +1,83 @@
+//! A content-addressed cache of the [work products] of codegen units, for
+//! `-Z codegen-cache-dir`.
+//!
+//! The work products in the incremental directory are only reused by the next compilation of the
+//! same crate in the same place. The entries of this cache are instead addressed by a key that the
+//! codegen backend computes from everything that goes into the object files of a codegen unit, so
+//! they can be reused by any compilation that shares the directory, e.g. in another workspace, on
+//! another branch or on another machine.
+//!
+//! Each entry is a directory named after its key, which contains the saved files of the work
+//! product, named after their extension. An entry is written to a temporary directory, which is
+//! then renamed into place, so concurrent compilations see either all of an entry or none of it.
+//! The files are always copied, never hard linked, as the outputs of a compilation may be
+//! overwritten in place by the next one.
+//!
+//! [work products]: WorkProduct
+
+use std::fs as std_fs;
+use std::path::{Path, PathBuf};
+
+use rand::{RngCore, rng};
+use rustc_data_structures::fingerprint::Fingerprint;
+use rustc_data_structures::unord::UnordMap;
+use rustc_middle::dep_graph::WorkProduct;
+use rustc_session::Session;
+use tracing::debug;
+
+use crate::errors;
+
+const FILE_STEM: &str = "module";
+
+fn entry_dir(cache_dir: &Path, key: Fingerprint) -> PathBuf {
+    cache_dir.join(key.to_hex())
+}
+
+/// Looks up the entry of a codegen unit in the cache. Returns the directory of the entry, and the
+/// work product whose saved files are in that directory.
+pub fn load_from_codegen_cache(
+    sess: &Session,
+    cgu_name: &str,
+    key: Fingerprint,
+) -> Option<(PathBuf, WorkProduct)> {
+    let cache_dir = sess.opts.unstable_opts.codegen_cache_dir.as_deref()?;
+    let entry = entry_dir(cache_dir, key);
+
+    let mut saved_files = UnordMap::default();
+    for file in std_fs::read_dir(&entry).ok()? {
+        let file_name = file.ok()?.file_name().into_string().ok()?;
+        if let Some((FILE_STEM, ext)) = file_name.split_once('.') {
+            saved_files.insert(ext.to_string(), file_name.clone());
+        }
+    }
+    debug!(?cgu_name, ?entry, ?saved_files, "found codegen cache entry");
+
+    Some((entry, WorkProduct { cgu_name: cgu_name.to_string(), saved_files }))
+}
+
+/// Adds the files of a codegen unit to the cache, unless there already is an entry for `key`.
+pub fn save_to_codegen_cache(sess: &Session, key: Fingerprint, files: &[(&'static str, &Path)]) {
+    let Some(cache_dir) = sess.opts.unstable_opts.codegen_cache_dir.as_deref() else { return };
+    let entry = entry_dir(cache_dir, key);
+    if entry.exists() {
+        return;
+    }
+
+    let temp_entry = cache_dir.join(format!(".{}-{:08x}.tmp", key.to_hex(), rng().next_u32()));
+    let result = std_fs::create_dir_all(&temp_entry).and_then(|()| {
+        for (ext, path) in files {
+            std_fs::copy(path, temp_entry.join(format!("{FILE_STEM}.{ext}")))?;
+        }
+        Ok(())
+    });
+    if let Err(err) = result {
+        sess.dcx().emit_warn(errors::WriteCodegenCache { path: &temp_entry, err });
+        let _ = std_fs::remove_dir_all(&temp_entry);
+        return;
+    }
+
+    // If another compilation added the same entry in the meantime, it has the same contents.
+    if std_fs::rename(&temp_entry, &entry).is_err() {
+        let _ = std_fs::remove_dir_all(&temp_entry);
+    }
+}
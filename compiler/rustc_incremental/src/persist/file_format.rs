@@ -153,6 +153,18 @@ pub(crate) fn read_file(
     Ok(Some((mmap, post_header_start_pos)))
 }
 
+/// Reads a file of an incremental compilation directory, like the dependency graph, for tools
+/// that inspect these directories. The header of the file is checked like the compiler does when
+/// it loads the file, and `None` is returned if the file doesn't exist or wasn't written by the
+/// compiler version `cfg_version`.
+pub fn read_incr_comp_file(
+    path: &Path,
+    is_nightly_build: bool,
+    cfg_version: &'static str,
+) -> io::Result<Option<(Mmap, usize)>> {
+    read_file(path, false, is_nightly_build, cfg_version)
+}
+
 fn report_format_mismatch(report_incremental_info: bool, file: &Path, message: &str) {
     debug!("read_file: {}", message);
 
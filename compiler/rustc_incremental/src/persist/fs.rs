@@ -128,10 +128,12 @@ use crate::errors;
 mod tests;
 
 const LOCK_FILE_EXT: &str = ".lock";
-const DEP_GRAPH_FILENAME: &str = "dep-graph.bin";
+/// The name of the dependency graph in a session directory.
+pub const DEP_GRAPH_FILENAME: &str = "dep-graph.bin";
 const STAGING_DEP_GRAPH_FILENAME: &str = "dep-graph.part.bin";
 const WORK_PRODUCTS_FILENAME: &str = "work-products.bin";
-const QUERY_CACHE_FILENAME: &str = "query-cache.bin";
+/// The name of the query result cache in a session directory.
+pub const QUERY_CACHE_FILENAME: &str = "query-cache.bin";
 
 // We encode integers using the following base, so they are shorter than decimal
 // or hexadecimal numbers (we want short file and directory names). Since these
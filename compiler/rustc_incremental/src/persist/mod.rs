@@ -2,6 +2,7 @@
 //! into the given directory. At the same time, it also hashes the
 //! various HIR nodes.
 
+mod codegen_cache;
 mod data;
 mod dirty_clean;
 mod file_format;
@@ -10,7 +11,12 @@ mod load;
 mod save;
 mod work_product;
 
-pub use fs::{finalize_session_directory, in_incr_comp_dir, in_incr_comp_dir_sess};
+pub use codegen_cache::{load_from_codegen_cache, save_to_codegen_cache};
+pub use file_format::read_incr_comp_file;
+pub use fs::{
+    DEP_GRAPH_FILENAME, QUERY_CACHE_FILENAME, finalize_session_directory, in_incr_comp_dir,
+    in_incr_comp_dir_sess,
+};
 pub use load::{LoadResult, load_query_result_cache, setup_dep_graph};
 pub(crate) use save::save_dep_graph;
 pub use save::save_work_product_index;
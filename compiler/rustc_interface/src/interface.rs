@@ -8,6 +8,7 @@ use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_data_structures::jobserver::{self, Proxy};
 use rustc_data_structures::stable_hasher::StableHasher;
 use rustc_errors::registry::Registry;
+use rustc_errors::translation::TranslationBundleError;
 use rustc_errors::{DiagCtxtHandle, ErrorGuaranteed};
 use rustc_lint::LintStore;
 use rustc_middle::ty;
@@ -447,11 +448,17 @@ pub fn run_compiler<R: Send>(config: Config, f: impl FnOnce(&Compiler) -> R + Se
 
             let temps_dir = config.opts.unstable_opts.temps_dir.as_deref().map(PathBuf::from);
 
+            // Messages of the locale bundle which can't be loaded fall back to English.
+            #[allow(rustc::untranslatable_diagnostic)]
+            let mut warn_incomplete_bundle = |e: TranslationBundleError| {
+                early_dcx.early_warn(format!("incomplete fluent bundle: {e}"))
+            };
             let bundle = match rustc_errors::fluent_bundle(
                 &config.opts.sysroot.all_paths().collect::<Vec<_>>(),
                 config.opts.unstable_opts.translate_lang.clone(),
                 config.opts.unstable_opts.translate_additional_ftl.as_deref(),
                 config.opts.unstable_opts.translate_directionality_markers,
+                &mut warn_incomplete_bundle,
             ) {
                 Ok(bundle) => bundle,
                 Err(e) => {
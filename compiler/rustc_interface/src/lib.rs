@@ -12,12 +12,14 @@ mod limits;
 pub mod passes;
 mod proc_macro_decls;
 mod queries;
+pub mod session;
 pub mod util;
 
 pub use callbacks::setup_callbacks;
 pub use interface::{Config, run_compiler};
 pub use passes::{DEFAULT_QUERY_PROVIDERS, create_and_enter_global_ctxt, parse};
 pub use queries::Linker;
+pub use session::CompilationSession;
 
 #[cfg(test)]
 mod tests;
@@ -11,7 +11,7 @@ use rustc_codegen_ssa::traits::CodegenBackend;
 use rustc_data_structures::jobserver::Proxy;
 use rustc_data_structures::steal::Steal;
 use rustc_data_structures::sync::{AppendOnlyIndexVec, FreezeLock, WorkerLocal};
-use rustc_data_structures::{parallel, thousands};
+use rustc_data_structures::{defer, parallel, thousands};
 use rustc_errors::timings::TimingSection;
 use rustc_expand::base::{ExtCtxt, LintStoreExpand};
 use rustc_feature::Features;
@@ -1013,6 +1013,10 @@ pub fn create_and_enter_global_ctxt<T, F: for<'tcx> FnOnce(TyCtxt<'tcx>) -> T>(
                 feed.crate_for_resolver(tcx.arena.alloc(Steal::new((krate, pre_configured_attrs))));
                 feed.output_filenames(Arc::new(outputs));
 
+                // Replayed warnings are normally emitted when the dep-graph is saved. Make
+                // sure they still show up if compilation stops before that.
+                let _replayed_diagnostics = defer(|| tcx.emit_replayed_diagnostics());
+
                 let res = f(tcx);
                 // FIXME maybe run finish even when a fatal error occurred? or at least tcx.alloc_self_profile_query_strings()?
                 tcx.finish();
@@ -1245,6 +1249,17 @@ pub(crate) fn start_codegen<'tcx>(
         tcx.sess.code_stats.print_type_sizes();
     }
 
+    // Likewise, all the MIR that we will optimize has been optimized by now.
+    if !tcx.sess.opts.unstable_opts.mir_remarks.is_empty()
+        && let Some(dir) = &tcx.sess.opts.unstable_opts.remark_dir
+    {
+        let path = dir.join(format!("{}.mir.opt.yaml", tcx.crate_name(LOCAL_CRATE)));
+        let result = fs::create_dir_all(dir).and_then(|()| tcx.sess.mir_remarks.write_yaml(&path));
+        if let Err(error) = result {
+            tcx.dcx().emit_err(errors::FailedWritingFile { path: &path, error });
+        }
+    }
+
     (codegen, metadata)
 }
 
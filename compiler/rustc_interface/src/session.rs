@@ -0,0 +1,92 @@
+//! A compilation session that is kept alive across compilations of the same crate.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_span::source_map::{FileLoader, RealFileLoader};
+
+use crate::interface::{Compiler, Config, run_compiler};
+
+/// A compilation session for long-lived tools, like IDEs and build servers, that compile the same
+/// crate again and again while its files are edited.
+///
+/// Each [`recompute`](Self::recompute) runs the compiler like [`run_compiler`](crate::run_compiler)
+/// does, with a fresh [`Config`] in which the files that were updated with
+/// [`update_file`](Self::update_file) have their updated contents instead of their contents on
+/// disk. All the files are read again by each recomputation, and the changes are found by the
+/// red-green algorithm of the dep graph, so files that change on disk need no invalidation.
+///
+/// The session only overlays the contents of the files, it keeps no compiler state between
+/// recomputations. With incremental compilation, each recomputation loads the previous dep graph
+/// and query result cache from the incremental compilation directory, like any other
+/// compilation.
+pub struct CompilationSession {
+    make_config: Box<dyn FnMut() -> Config + Send>,
+    /// The updated contents of files, by the path that the compiler reads them from.
+    files: FxHashMap<PathBuf, Arc<str>>,
+}
+
+impl CompilationSession {
+    /// Creates a session that builds the configuration of each recomputation with `make_config`.
+    ///
+    /// The [`Config::file_loader`] of the configurations, if any, loads the files that were not
+    /// updated.
+    pub fn new(make_config: impl FnMut() -> Config + Send + 'static) -> CompilationSession {
+        CompilationSession { make_config: Box::new(make_config), files: FxHashMap::default() }
+    }
+
+    /// Sets the contents of a file for the next recomputations, until the file is updated again
+    /// or invalidated. The path must be the one that the compiler reads the file from, e.g. the
+    /// path of the input or the path of a module file next to it.
+    pub fn update_file(&mut self, path: impl Into<PathBuf>, contents: impl Into<Arc<str>>) {
+        self.files.insert(path.into(), contents.into());
+    }
+
+    /// Discards the updated contents of a file, so that the next recomputations read it from the
+    /// file loader of the configuration again. Returns whether the file had updated contents.
+    pub fn invalidate(&mut self, path: &Path) -> bool {
+        self.files.remove(path).is_some()
+    }
+
+    /// Runs the compiler with the current contents of the files, and calls `f` with it like
+    /// [`run_compiler`](crate::run_compiler) does.
+    ///
+    /// Errors unwind out of this function like they unwind out of `run_compiler`, and the session
+    /// can still be used afterwards.
+    pub fn recompute<R: Send>(&mut self, f: impl FnOnce(&Compiler) -> R + Send) -> R {
+        let mut config = (self.make_config)();
+        let fallback = config.file_loader.take().unwrap_or_else(|| Box::new(RealFileLoader));
+        config.file_loader =
+            Some(Box::new(SessionFileLoader { files: self.files.clone(), fallback }));
+        run_compiler(config, f)
+    }
+}
+
+/// Loads the updated files of a [`CompilationSession`], and the other files with the file loader
+/// of the configuration.
+struct SessionFileLoader {
+    files: FxHashMap<PathBuf, Arc<str>>,
+    fallback: Box<dyn FileLoader + Send + Sync>,
+}
+
+impl FileLoader for SessionFileLoader {
+    fn file_exists(&self, path: &Path) -> bool {
+        self.files.contains_key(path) || self.fallback.file_exists(path)
+    }
+
+    fn read_file(&self, path: &Path) -> io::Result<String> {
+        match self.files.get(path) {
+            Some(contents) => Ok(contents.to_string()),
+            None => self.fallback.read_file(path),
+        }
+    }
+
+    fn read_binary_file(&self, path: &Path) -> io::Result<Arc<[u8]>> {
+        match self.files.get(path) {
+            Some(contents) => Ok(Arc::from(contents.as_bytes())),
+            None => self.fallback.read_binary_file(path),
+        }
+    }
+}
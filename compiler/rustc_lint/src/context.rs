@@ -33,6 +33,7 @@ use {rustc_abi as abi, rustc_hir as hir};
 use self::TargetLint::*;
 use crate::levels::LintLevelsBuilder;
 use crate::passes::{EarlyLintPassObject, LateLintPassObject};
+use crate::stats::LintStats;
 
 type EarlyLintPassFactory = dyn Fn() -> EarlyLintPassObject + sync::DynSend + sync::DynSync;
 type LateLintPassFactory =
@@ -60,6 +61,9 @@ pub struct LintStore {
 
     /// Map of registered lint groups to what lints they expand to.
     lint_groups: FxIndexMap<&'static str, LintGroup>,
+
+    /// Time spent in each lint pass, only recorded with `-Z lint-stats`.
+    pub(crate) stats: LintStats,
 }
 
 impl DynLintStore for LintStore {
@@ -135,6 +139,7 @@ impl LintStore {
             late_module_passes: vec![],
             by_name: Default::default(),
             lint_groups: Default::default(),
+            stats: Default::default(),
         }
     }
 
@@ -493,12 +498,17 @@ pub struct LateContext<'tcx> {
 
     /// We are only looking at one module
     pub only_module: bool,
+
+    /// The number of lints emitted through this context so far, for `-Z lint-stats`.
+    pub(super) emitted_lints: Cell<usize>,
 }
 
 /// Context for lint checking of the AST, after expansion, before lowering to HIR.
 pub struct EarlyContext<'a> {
     pub builder: LintLevelsBuilder<'a, crate::levels::TopDown>,
     pub buffered: LintBuffer,
+    /// The number of lints emitted through this context so far, for `-Z lint-stats`.
+    pub(super) emitted_lints: Cell<usize>,
 }
 
 pub trait LintContext {
@@ -620,6 +630,7 @@ impl<'a> EarlyContext<'a> {
                 registered_tools,
             ),
             buffered,
+            emitted_lints: Cell::new(0),
         }
     }
 }
@@ -640,8 +651,14 @@ impl<'tcx> LintContext for LateContext<'tcx> {
         let hir_id = self.last_node_with_lint_attrs;
 
         match span {
-            Some(s) => self.tcx.node_span_lint(lint, hir_id, s, decorate),
-            None => self.tcx.node_lint(lint, hir_id, decorate),
+            Some(s) => self.tcx.node_span_lint(lint, hir_id, s, |diag| {
+                self.emitted_lints.set(self.emitted_lints.get() + 1);
+                decorate(diag);
+            }),
+            None => self.tcx.node_lint(lint, hir_id, |diag| {
+                self.emitted_lints.set(self.emitted_lints.get() + 1);
+                decorate(diag);
+            }),
         }
     }
 
@@ -663,7 +680,10 @@ impl LintContext for EarlyContext<'_> {
         span: Option<S>,
         decorate: impl for<'a, 'b> FnOnce(&'b mut Diag<'a, ()>),
     ) {
-        self.builder.opt_span_lint(lint, span.map(|s| s.into()), decorate)
+        self.builder.opt_span_lint(lint, span.map(|s| s.into()), |diag| {
+            self.emitted_lints.set(self.emitted_lints.get() + 1);
+            decorate(diag);
+        })
     }
 
     fn get_lint_level(&self, lint: &'static Lint) -> LevelAndSource {
@@ -16,7 +16,8 @@ use rustc_span::{Ident, Span};
 use tracing::debug;
 
 use crate::context::{EarlyContext, LintContext, LintStore};
-use crate::passes::{EarlyLintPass, EarlyLintPassObject};
+use crate::passes::{CombinedLintPass, EarlyLintPass, EarlyLintPassObject};
+use crate::stats::{PassKind, PassStats};
 
 pub(super) mod diagnostics;
 
@@ -278,6 +279,37 @@ macro_rules! impl_early_lint_pass {
 
 crate::early_lint_methods!(impl_early_lint_pass, []);
 
+// Like `RuntimeCombinedEarlyLintPass`, but also measures each pass, for
+// `-Z lint-stats`.
+struct LintStatsEarlyLintPass<'a> {
+    passes: &'a mut [EarlyLintPassObject],
+    stats: &'a mut [PassStats],
+}
+
+#[allow(rustc::lint_pass_impl_without_macro)]
+impl LintPass for LintStatsEarlyLintPass<'_> {
+    fn name(&self) -> &'static str {
+        panic!()
+    }
+    fn get_lints(&self) -> crate::LintVec {
+        panic!()
+    }
+}
+
+macro_rules! impl_early_lint_stats_pass {
+    ([], [$($(#[$attr:meta])* fn $f:ident($($param:ident: $arg:ty),*);)*]) => (
+        impl EarlyLintPass for LintStatsEarlyLintPass<'_> {
+            $(fn $f(&mut self, context: &EarlyContext<'_>, $($param: $arg),*) {
+                for (pass, stats) in self.passes.iter_mut().zip(self.stats.iter_mut()) {
+                    stats.measure(&context.emitted_lints, || pass.$f(context, $($param),*));
+                }
+            })*
+        }
+    )
+}
+
+crate::early_lint_methods!(impl_early_lint_stats_pass, []);
+
 /// Early lints work on different nodes - either on the crate root, or on freshly loaded modules.
 /// This trait generalizes over those nodes.
 pub trait EarlyCheckNode<'a>: Copy {
@@ -321,7 +353,7 @@ pub fn check_ast_node<'a>(
     lint_store: &LintStore,
     registered_tools: &RegisteredTools,
     lint_buffer: Option<LintBuffer>,
-    builtin_lints: impl EarlyLintPass + 'static,
+    builtin_lints: impl EarlyLintPass + CombinedLintPass<EarlyLintPassObject> + 'static,
     check_node: impl EarlyCheckNode<'a>,
 ) {
     let context = EarlyContext::new(
@@ -338,7 +370,15 @@ pub fn check_ast_node<'a>(
     // `RuntimeCombinedEarlyLintPass`.
     let passes =
         if pre_expansion { &lint_store.pre_expansion_passes } else { &lint_store.early_passes };
-    if passes.is_empty() {
+    if sess.opts.unstable_opts.lint_stats {
+        let mut passes: Vec<_> = passes.iter().map(|mk_pass| (mk_pass)()).collect();
+        passes.extend(builtin_lints.into_passes());
+        let mut stats = vec![PassStats::default(); passes.len()];
+        let pass = LintStatsEarlyLintPass { passes: &mut passes[..], stats: &mut stats };
+        check_ast_node_inner(sess, tcx, check_node, context, pass);
+        let kind = if pre_expansion { PassKind::PreExpansion } else { PassKind::Early };
+        lint_store.stats.record(kind, &passes, &stats);
+    } else if passes.is_empty() {
         check_ast_node_inner(sess, tcx, check_node, context, builtin_lints);
     } else {
         let mut passes: Vec<_> = passes.iter().map(|mk_pass| (mk_pass)()).collect();
@@ -19,6 +19,7 @@ use rustc_span::Span;
 use tracing::debug;
 
 use crate::passes::LateLintPassObject;
+use crate::stats::{PassKind, PassStats};
 use crate::{LateContext, LateLintPass, LintId, LintStore};
 
 /// Extract the [`LintStore`] from [`Session`].
@@ -334,12 +335,39 @@ macro_rules! impl_late_lint_pass {
 
 crate::late_lint_methods!(impl_late_lint_pass, []);
 
-pub fn late_lint_mod<'tcx, T: LateLintPass<'tcx> + 'tcx>(
-    tcx: TyCtxt<'tcx>,
-    module_def_id: LocalModDefId,
-    builtin_lints: T,
-) {
-    let context = LateContext {
+// Like `RuntimeCombinedLateLintPass`, but also measures each pass, for
+// `-Z lint-stats`.
+struct LintStatsLateLintPass<'a, 'tcx> {
+    passes: &'a mut [LateLintPassObject<'tcx>],
+    stats: &'a mut [PassStats],
+}
+
+#[allow(rustc::lint_pass_impl_without_macro)]
+impl LintPass for LintStatsLateLintPass<'_, '_> {
+    fn name(&self) -> &'static str {
+        panic!()
+    }
+    fn get_lints(&self) -> crate::LintVec {
+        panic!()
+    }
+}
+
+macro_rules! impl_late_lint_stats_pass {
+    ([], [$($(#[$attr:meta])* fn $f:ident($($param:ident: $arg:ty),*);)*]) => {
+        impl<'tcx> LateLintPass<'tcx> for LintStatsLateLintPass<'_, 'tcx> {
+            $(fn $f(&mut self, context: &LateContext<'tcx>, $($param: $arg),*) {
+                for (pass, stats) in self.passes.iter_mut().zip(self.stats.iter_mut()) {
+                    stats.measure(&context.emitted_lints, || pass.$f(context, $($param),*));
+                }
+            })*
+        }
+    };
+}
+
+crate::late_lint_methods!(impl_late_lint_stats_pass, []);
+
+fn module_context<'tcx>(tcx: TyCtxt<'tcx>, module_def_id: LocalModDefId) -> LateContext<'tcx> {
+    LateContext {
         tcx,
         enclosing_body: None,
         cached_typeck_results: Cell::new(None),
@@ -348,7 +376,39 @@ pub fn late_lint_mod<'tcx, T: LateLintPass<'tcx> + 'tcx>(
         last_node_with_lint_attrs: tcx.local_def_id_to_hir_id(module_def_id),
         generics: None,
         only_module: true,
-    };
+        emitted_lints: Cell::new(0),
+    }
+}
+
+/// Leaves out the passes none of whose lints need to run. Passes without lints are always kept.
+fn filter_passes<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    passes: Vec<LateLintPassObject<'tcx>>,
+) -> Vec<LateLintPassObject<'tcx>> {
+    let lints_that_dont_need_to_run = tcx.lints_that_dont_need_to_run(());
+    passes
+        .into_iter()
+        .filter(|pass| {
+            let lints = (**pass).get_lints();
+            // Lintless passes are always in
+            lints.is_empty() ||
+            // If the pass doesn't have a single needed lint, omit it
+            !lints.iter().all(|lint| lints_that_dont_need_to_run.contains(&LintId::of(lint)))
+        })
+        .collect()
+}
+
+pub fn late_lint_mod<'tcx, T: LateLintPass<'tcx> + 'tcx>(
+    tcx: TyCtxt<'tcx>,
+    module_def_id: LocalModDefId,
+    builtin_lints: T,
+) {
+    if tcx.sess.opts.unstable_opts.lint_stats {
+        let builtin_lints = Box::new(builtin_lints) as LateLintPassObject<'tcx>;
+        return late_lint_mod_with_stats(tcx, module_def_id, vec![builtin_lints]);
+    }
+
+    let context = module_context(tcx, module_def_id);
 
     // Note: `passes` is often empty. In that case, it's faster to run
     // `builtin_lints` directly rather than bundling it up into the
@@ -380,6 +440,26 @@ pub fn late_lint_mod<'tcx, T: LateLintPass<'tcx> + 'tcx>(
     }
 }
 
+/// Like `late_lint_mod`, but measures each of the passes for `-Z lint-stats`. The builtin lints
+/// are given as separate passes, so that they are measured on their own too.
+pub(crate) fn late_lint_mod_with_stats<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    module_def_id: LocalModDefId,
+    builtin_lints: Vec<LateLintPassObject<'tcx>>,
+) {
+    let store = unerased_lint_store(tcx.sess);
+    let passes = store.late_module_passes.iter().map(|mk_pass| (mk_pass)(tcx));
+    let mut passes = filter_passes(tcx, passes.chain(builtin_lints).collect());
+    if passes.is_empty() {
+        return;
+    }
+
+    let mut stats = vec![PassStats::default(); passes.len()];
+    let pass = LintStatsLateLintPass { passes: &mut passes[..], stats: &mut stats };
+    late_lint_mod_inner(tcx, module_def_id, module_context(tcx, module_def_id), pass);
+    store.stats.record(PassKind::LateModule, &passes, &stats);
+}
+
 fn late_lint_mod_inner<'tcx, T: LateLintPass<'tcx>>(
     tcx: TyCtxt<'tcx>,
     module_def_id: LocalModDefId,
@@ -422,20 +502,18 @@ fn late_lint_crate<'tcx>(tcx: TyCtxt<'tcx>) {
         last_node_with_lint_attrs: hir::CRATE_HIR_ID,
         generics: None,
         only_module: false,
+        emitted_lints: Cell::new(0),
     };
 
-    let lints_that_dont_need_to_run = tcx.lints_that_dont_need_to_run(());
+    let mut filtered_passes = filter_passes(tcx, passes);
 
-    let mut filtered_passes: Vec<Box<dyn LateLintPass<'tcx>>> = passes
-        .into_iter()
-        .filter(|pass| {
-            let lints = (**pass).get_lints();
-            // Lintless passes are always in
-            lints.is_empty() ||
-            // If the pass doesn't have a single needed lint, omit it
-            !lints.iter().all(|lint| lints_that_dont_need_to_run.contains(&LintId::of(lint)))
-        })
-        .collect();
+    if tcx.sess.opts.unstable_opts.lint_stats {
+        let mut stats = vec![PassStats::default(); filtered_passes.len()];
+        let pass = LintStatsLateLintPass { passes: &mut filtered_passes[..], stats: &mut stats };
+        late_lint_crate_inner(tcx, context, pass);
+        unerased_lint_store(tcx.sess).stats.record(PassKind::Late, &filtered_passes, &stats);
+        return;
+    }
 
     filtered_passes.push(Box::new(HardwiredLints));
     let pass = RuntimeCombinedLateLintPass { passes: &mut filtered_passes[..] };
@@ -475,4 +553,8 @@ pub fn check_crate<'tcx>(tcx: TyCtxt<'tcx>) {
             });
         },
     );
+
+    if tcx.sess.opts.unstable_opts.lint_stats {
+        unerased_lint_store(tcx.sess).stats.print(tcx.sess.opts.unstable_opts.lint_stats_format);
+    }
 }
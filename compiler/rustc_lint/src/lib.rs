@@ -74,6 +74,7 @@ mod redundant_semicolon;
 mod reference_casting;
 mod shadowed_into_iter;
 mod static_mut_refs;
+mod stats;
 mod traits;
 mod transmute;
 mod types;
@@ -132,7 +133,7 @@ pub use early::diagnostics::decorate_builtin_lint;
 pub use early::{EarlyCheckNode, check_ast_node};
 pub use late::{check_crate, late_lint_mod, unerased_lint_store};
 pub use levels::LintLevelsBuilder;
-pub use passes::{EarlyLintPass, LateLintPass};
+pub use passes::{CombinedLintPass, EarlyLintPass, LateLintPass};
 pub use rustc_errors::BufferedEarlyLint;
 pub use rustc_session::lint::Level::{self, *};
 pub use rustc_session::lint::{FutureIncompatibleInfo, Lint, LintId, LintPass, LintVec};
@@ -147,7 +148,15 @@ pub fn provide(providers: &mut Providers) {
 }
 
 fn lint_mod(tcx: TyCtxt<'_>, module_def_id: LocalModDefId) {
-    late_lint_mod(tcx, module_def_id, BuiltinCombinedModuleLateLintPass::new());
+    if tcx.sess.opts.unstable_opts.lint_stats {
+        late::late_lint_mod_with_stats(
+            tcx,
+            module_def_id,
+            BuiltinCombinedModuleLateLintPass::new().into_passes(),
+        );
+    } else {
+        late_lint_mod(tcx, module_def_id, BuiltinCombinedModuleLateLintPass::new());
+    }
 }
 
 early_lint_methods!(
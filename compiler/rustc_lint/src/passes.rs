@@ -120,6 +120,12 @@ macro_rules! declare_combined_late_lint_pass {
             $crate::expand_combined_late_lint_pass_methods!([$($pass),*], $methods);
         }
 
+        impl<'tcx> $crate::CombinedLintPass<Box<dyn $crate::LateLintPass<'tcx> + 'tcx>> for $name {
+            fn into_passes(self) -> Vec<Box<dyn $crate::LateLintPass<'tcx> + 'tcx>> {
+                vec![$(Box::new(self.$pass),)*]
+            }
+        }
+
         #[allow(rustc::lint_pass_impl_without_macro)]
         impl $crate::LintPass for $name {
             fn name(&self) -> &'static str {
@@ -237,6 +243,12 @@ macro_rules! declare_combined_early_lint_pass {
             $crate::expand_combined_early_lint_pass_methods!([$($pass),*], $methods);
         }
 
+        impl $crate::CombinedLintPass<Box<dyn $crate::EarlyLintPass>> for $name {
+            fn into_passes(self) -> Vec<Box<dyn $crate::EarlyLintPass>> {
+                vec![$(Box::new(self.$pass),)*]
+            }
+        }
+
         #[allow(rustc::lint_pass_impl_without_macro)]
         impl $crate::LintPass for $name {
             fn name(&self) -> &'static str {
@@ -249,6 +261,13 @@ macro_rules! declare_combined_early_lint_pass {
     )
 }
 
+/// Implemented by the passes declared with `declare_combined_early_lint_pass` and
+/// `declare_combined_late_lint_pass`, to take them apart again so that `-Z lint-stats` can
+/// measure each of the combined passes on its own.
+pub trait CombinedLintPass<P> {
+    fn into_passes(self) -> Vec<P>;
+}
+
 /// A lint pass boxed up as a trait object.
 pub(crate) type EarlyLintPassObject = Box<dyn EarlyLintPass + 'static>;
 pub(crate) type LateLintPassObject<'tcx> = Box<dyn LateLintPass<'tcx> + 'tcx>;
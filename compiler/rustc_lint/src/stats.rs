@@ -0,0 +1,109 @@
+//! Per-pass timings and emitted lint counts, for `-Z lint-stats`.
+
+use std::cell::Cell;
+use std::cmp;
+use std::time::{Duration, Instant};
+
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_data_structures::sync::Lock;
+use rustc_session::config::DumpMonoStatsFormat;
+use rustc_session::lint::LintPass;
+
+/// The kind of pass some stats are about. The same pass type may be registered as more than
+/// one kind, e.g. both as a pre-expansion and as an early pass.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub(crate) enum PassKind {
+    PreExpansion,
+    Early,
+    Late,
+    LateModule,
+}
+
+impl PassKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            PassKind::PreExpansion => "pre-expansion",
+            PassKind::Early => "early",
+            PassKind::Late => "late",
+            PassKind::LateModule => "late-module",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, Debug)]
+pub(crate) struct PassStats {
+    time: Duration,
+    emitted: usize,
+}
+
+impl PassStats {
+    /// Runs one of the `check_*` methods of a pass, and charges it with the time it took and the
+    /// lints it emitted, as counted by `emitted`.
+    #[inline]
+    pub(crate) fn measure(&mut self, emitted: &Cell<usize>, f: impl FnOnce()) {
+        let emitted_before = emitted.get();
+        let start = Instant::now();
+        f();
+        self.time += start.elapsed();
+        self.emitted += emitted.get() - emitted_before;
+    }
+}
+
+/// The stats of every lint pass run so far, summed over all the nodes it was run on.
+#[derive(Default)]
+pub(crate) struct LintStats {
+    passes: Lock<FxIndexMap<(PassKind, &'static str), PassStats>>,
+}
+
+impl LintStats {
+    pub(crate) fn record<P: LintPass + ?Sized>(
+        &self,
+        kind: PassKind,
+        passes: &[Box<P>],
+        stats: &[PassStats],
+    ) {
+        let mut totals = self.passes.lock();
+        for (pass, stats) in passes.iter().zip(stats) {
+            let total = totals.entry((kind, pass.name())).or_default();
+            total.time += stats.time;
+            total.emitted += stats.emitted;
+        }
+    }
+
+    /// Prints the stats to stderr, from the slowest pass to the fastest one.
+    pub(crate) fn print(&self, format: DumpMonoStatsFormat) {
+        #[derive(serde::Serialize)]
+        struct Pass {
+            name: &'static str,
+            kind: &'static str,
+            time_secs: f64,
+            emitted: usize,
+        }
+
+        let mut totals: Vec<_> =
+            self.passes.lock().iter().map(|(&key, &stats)| (key, stats)).collect();
+        totals.sort_by_key(|&(_, stats)| cmp::Reverse(stats.time));
+        let passes: Vec<_> = totals
+            .into_iter()
+            .map(|((kind, name), stats)| Pass {
+                name,
+                kind: kind.as_str(),
+                time_secs: stats.time.as_secs_f64(),
+                emitted: stats.emitted,
+            })
+            .collect();
+
+        match format {
+            DumpMonoStatsFormat::Json => {
+                eprintln!("{}", serde_json::to_string(&passes).unwrap());
+            }
+            DumpMonoStatsFormat::Markdown => {
+                eprintln!("| Pass | Kind | Time (s) | Emitted lints |");
+                eprintln!("| --- | --- | ---: | ---: |");
+                for Pass { name, kind, time_secs, emitted } in passes {
+                    eprintln!("| `{name}` | {kind} | {time_secs:.6} | {emitted} |");
+                }
+            }
+        }
+    }
+}
@@ -5,6 +5,7 @@
 
 use rustc_hir::def_id::{DefId, DefPathHash};
 use rustc_session::StableCrateId;
+use rustc_session::mir_remarks::MirRemark;
 use rustc_span::def_id::{CrateNum, LocalDefId};
 use rustc_span::{ExpnHash, ExpnId};
 
@@ -102,6 +103,15 @@ declare_hooks! {
     /// Ensure the given scalar is valid for the given type.
     /// This checks non-recursive runtime validity.
     hook validate_scalar_in_layout(scalar: crate::ty::ScalarInt, ty: Ty<'tcx>) -> bool;
+
+    /// Checks a MIR body like `-Zvalidate-mir` does, but returns the failures instead of reporting
+    /// them as bugs. This is for bodies that were not built by the compiler, e.g. the ones that
+    /// `rustc_public` tools provide.
+    hook validate_mir_body(body: &mir::Body<'tcx>, when: &str) -> Vec<(mir::Location, String)>;
+
+    /// Records a remark of a MIR pass for `-Z remark-dir`, as a side effect of the current query
+    /// so that it is recorded again when incremental compilation reuses the result of the query.
+    hook record_mir_remark(remark: MirRemark) -> ();
 }
 
 #[cold]
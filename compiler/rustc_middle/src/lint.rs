@@ -450,6 +450,11 @@ pub fn lint_level(
 
         if !skip {
             decorate(&mut err);
+            // Like attributes, the `--diagnostic-baseline` can't lower the level of the lints
+            // forced with `--force-warn` or `forbid`.
+            if matches!(level, Level::Warn | Level::Deny) {
+                sess.dcx().apply_diagnostic_baseline(&mut err);
+            }
         }
 
         explain_lint_level_source(sess, lint, level, src, &mut err);
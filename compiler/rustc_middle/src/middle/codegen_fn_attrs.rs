@@ -23,6 +23,13 @@ impl<'tcx> TyCtxt<'tcx> {
             }
         }
 
+        // Cold paths were outlined to keep them out of their parent, so they should stay out.
+        if let InstanceKind::ColdPathShim(..) = instance_kind {
+            let attrs = attrs.to_mut();
+            attrs.flags.insert(CodegenFnAttrFlags::COLD);
+            attrs.inline = InlineAttr::Never;
+        }
+
         attrs
     }
 }
@@ -16,6 +16,22 @@ rustc_index::newtype_index! {
     pub struct BlockMarkerId {}
 }
 
+rustc_index::newtype_index! {
+    /// ID of a condition within an MC/DC decision. Values ascend from 0, in
+    /// the order that the conditions appear in the decision's source code.
+    ///
+    /// LLVM handles condition IDs as `int16_t`, so decisions that have more
+    /// conditions than can be represented that way are not instrumented.
+    #[derive(HashStable)]
+    #[encodable]
+    #[orderable]
+    #[max = 0x7FFF]
+    #[debug_format = "ConditionId({})"]
+    pub struct ConditionId {
+        const START = 0;
+    }
+}
+
 rustc_index::newtype_index! {
     /// ID of a coverage counter. Values ascend from 0.
     ///
@@ -90,6 +106,22 @@ pub enum CoverageKind {
     /// During codegen, this might be lowered to `llvm.instrprof.increment` or
     /// to a no-op, depending on the outcome of counter-creation.
     VirtualCounter { bcb: BasicCoverageBlock },
+
+    /// Marks the point in a decision where one of its conditions was
+    /// evaluated to true, by adding `index` to the condition bitmap of the
+    /// decision at `decision_depth`.
+    ///
+    /// If this statement does not survive MIR optimizations, the condition
+    /// will never be recorded as true in any test vector.
+    CondBitmapUpdate { index: u32, decision_depth: u16 },
+
+    /// Marks the point at which a decision has been fully evaluated, so that
+    /// the test vector recorded in its condition bitmap can be stored in the
+    /// function's global test-vector bitmap, starting at `bitmap_idx`.
+    ///
+    /// If this statement does not survive MIR optimizations, the decision
+    /// will be treated as having never been evaluated.
+    TestVectorBitmapUpdate { bitmap_idx: u32, decision_depth: u16 },
 }
 
 impl Debug for CoverageKind {
@@ -99,6 +131,12 @@ impl Debug for CoverageKind {
             SpanMarker => write!(fmt, "SpanMarker"),
             BlockMarker { id } => write!(fmt, "BlockMarker({:?})", id.index()),
             VirtualCounter { bcb } => write!(fmt, "VirtualCounter({bcb:?})"),
+            CondBitmapUpdate { index, decision_depth } => {
+                write!(fmt, "CondBitmapUpdate(index={index:?}, depth={decision_depth:?})")
+            }
+            TestVectorBitmapUpdate { bitmap_idx, decision_depth } => {
+                write!(fmt, "TestVectorUpdate({bitmap_idx:?}, depth={decision_depth:?})")
+            }
         }
     }
 }
@@ -135,6 +173,16 @@ pub enum MappingKind {
     Code { bcb: BasicCoverageBlock },
     /// Associates a branch region with separate counters for true and false.
     Branch { true_bcb: BasicCoverageBlock, false_bcb: BasicCoverageBlock },
+    /// Associates a branch region with separate counters for true and false,
+    /// plus the information that MC/DC needs to locate the condition within
+    /// its decision.
+    MCDCBranch {
+        true_bcb: BasicCoverageBlock,
+        false_bcb: BasicCoverageBlock,
+        mcdc_params: ConditionInfo,
+    },
+    /// Associates a decision region with the bitmap that records its test vectors.
+    MCDCDecision(DecisionInfo),
 }
 
 #[derive(Clone, Debug)]
@@ -158,6 +206,13 @@ pub struct FunctionCoverageInfo {
     pub priority_list: Vec<BasicCoverageBlock>,
 
     pub mappings: Vec<Mapping>,
+
+    /// Number of bits in the test-vector bitmap of all MC/DC decisions in
+    /// this function, or 0 if MC/DC is not enabled.
+    pub mcdc_bitmap_bits: usize,
+    /// The depth of the deepest decision is used to know how many
+    /// temp condbitmaps should be allocated for the function.
+    pub mcdc_num_condition_bitmaps: usize,
 }
 
 /// Coverage information for a function, recorded during MIR building and
@@ -174,6 +229,10 @@ pub struct CoverageInfoHi {
     /// data structures without having to scan the entire body first.
     pub num_block_markers: usize,
     pub branch_spans: Vec<BranchSpan>,
+    /// Branch spans generated by MC/DC for conditions whose decision could
+    /// not be instrumented, which are reported as plain branches instead.
+    pub mcdc_degraded_branch_spans: Vec<MCDCBranchSpan>,
+    pub mcdc_spans: Vec<(MCDCDecisionSpan, Vec<MCDCBranchSpan>)>,
 }
 
 #[derive(Clone, Debug)]
@@ -184,6 +243,41 @@ pub struct BranchSpan {
     pub false_marker: BlockMarkerId,
 }
 
+/// Identifies a condition within its decision, along with the conditions
+/// that are evaluated next when it is true or false.
+#[derive(Copy, Clone, Debug)]
+#[derive(TyEncodable, TyDecodable, Hash, HashStable)]
+pub struct ConditionInfo {
+    pub condition_id: ConditionId,
+    pub true_next_id: Option<ConditionId>,
+    pub false_next_id: Option<ConditionId>,
+}
+
+#[derive(Clone, Debug)]
+#[derive(TyEncodable, TyDecodable, Hash, HashStable)]
+pub struct MCDCBranchSpan {
+    pub span: Span,
+    pub condition_info: ConditionInfo,
+    pub true_marker: BlockMarkerId,
+    pub false_marker: BlockMarkerId,
+}
+
+#[derive(Copy, Clone, Debug)]
+#[derive(TyEncodable, TyDecodable, Hash, HashStable)]
+pub struct DecisionInfo {
+    pub bitmap_idx: u32,
+    pub num_conditions: u16,
+}
+
+#[derive(Clone, Debug)]
+#[derive(TyEncodable, TyDecodable, Hash, HashStable)]
+pub struct MCDCDecisionSpan {
+    pub span: Span,
+    pub end_markers: Vec<BlockMarkerId>,
+    pub decision_depth: u16,
+    pub num_conditions: usize,
+}
+
 /// Contains information needed during codegen, obtained by inspecting the
 /// function's MIR after MIR optimizations.
 ///
@@ -10,7 +10,7 @@ use std::ops::{Index, IndexMut};
 pub use basic_blocks::{BasicBlocks, SwitchTargetValue};
 use either::Either;
 use polonius_engine::Atom;
-use rustc_abi::{FieldIdx, VariantIdx};
+use rustc_abi::{ExternAbi, FieldIdx, VariantIdx};
 pub use rustc_ast::Mutability;
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_data_structures::graph::dominators::Dominators;
@@ -235,6 +235,10 @@ pub struct Body<'tcx> {
     /// information.
     pub coroutine: Option<Box<CoroutineInfo<'tcx>>>,
 
+    /// The cold paths that the `OutlineColdPaths` pass outlined from this body. They are
+    /// called through `InstanceKind::ColdPathShim`s, which index into this list.
+    pub cold_paths: Vec<Body<'tcx>>,
+
     /// Declarations of locals.
     ///
     /// The first local is the return value pointer, followed by `arg_count`
@@ -361,6 +365,7 @@ impl<'tcx> Body<'tcx> {
             basic_blocks: BasicBlocks::new(basic_blocks),
             source_scopes,
             coroutine,
+            cold_paths: Vec::new(),
             local_decls,
             user_type_annotations,
             arg_count,
@@ -392,6 +397,7 @@ impl<'tcx> Body<'tcx> {
             basic_blocks: BasicBlocks::new(basic_blocks),
             source_scopes: IndexVec::new(),
             coroutine: None,
+            cold_paths: Vec::new(),
             local_decls: IndexVec::new(),
             user_type_annotations: IndexVec::new(),
             arg_count: 0,
@@ -502,6 +508,18 @@ impl<'tcx> Body<'tcx> {
         }
     }
 
+    /// Returns the signature of a cold path outlined by the `OutlineColdPaths` pass: it takes the
+    /// types of its arguments, and never returns.
+    pub fn cold_path_fn_sig(&self, tcx: TyCtxt<'tcx>) -> ty::FnSig<'tcx> {
+        tcx.mk_fn_sig(
+            self.args_iter().map(|arg| self.local_decls[arg].ty),
+            tcx.types.never,
+            false,
+            hir::Safety::Safe,
+            ExternAbi::Rust,
+        )
+    }
+
     /// Returns the return type; it always return first element from `local_decls` array.
     #[inline]
     pub fn return_ty(&self) -> Ty<'tcx> {
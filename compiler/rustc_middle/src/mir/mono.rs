@@ -20,6 +20,7 @@ use tracing::debug;
 
 use crate::dep_graph::{DepNode, WorkProduct, WorkProductId};
 use crate::middle::codegen_fn_attrs::CodegenFnAttrFlags;
+use crate::mir::{BasicBlock, Const, Local};
 use crate::ty::{self, GenericArgs, Instance, InstanceKind, SymbolName, Ty, TyCtxt};
 
 /// Describes how a monomorphization will be instantiated in object files.
@@ -335,6 +336,35 @@ impl ToStableHashKey<StableHashingContext<'_>> for MonoItem<'_> {
 pub struct MonoItemPartitions<'tcx> {
     pub codegen_units: &'tcx [CodegenUnit<'tcx>],
     pub all_mono_items: &'tcx DefIdSet,
+    pub const_specializations: &'tcx ConstSpecializations<'tcx>,
+    /// The functions that each function may call, see `MonoCallGraph`.
+    pub call_graph: &'tcx MonoCallGraph<'tcx>,
+}
+
+/// The functions that each function of the crate uses, as found by the mono item collector. This
+/// over-approximates the calls, as it includes functions that are only turned into a function
+/// pointer, and misses calls through function pointers and trait objects.
+///
+/// This is only computed for `--print stack-sizes`, and is empty otherwise.
+pub type MonoCallGraph<'tcx> = FxIndexMap<Instance<'tcx>, Vec<Instance<'tcx>>>;
+
+/// The functions that were cloned for the constant arguments that their call sites pass, see
+/// `InstanceKind::ConstSpecialized`.
+#[derive(Debug, Default, HashStable)]
+pub struct ConstSpecializations<'tcx> {
+    /// Indexed by the `u32` of `InstanceKind::ConstSpecialized`.
+    pub specializations: Vec<ConstSpecialization<'tcx>>,
+    /// For each caller, the blocks ending in a call that is redirected to a clone, along with the
+    /// index of that clone.
+    pub redirects: FxIndexMap<Instance<'tcx>, Vec<(BasicBlock, u32)>>,
+}
+
+#[derive(Debug, HashStable)]
+pub struct ConstSpecialization<'tcx> {
+    /// The function that was cloned.
+    pub instance: Instance<'tcx>,
+    /// The arguments that are constant in the clone, and their values.
+    pub args: Vec<(Local, Const<'tcx>)>,
 }
 
 #[derive(Debug, HashStable)]
@@ -531,7 +561,9 @@ impl<'tcx> CodegenUnit<'tcx> {
                     | InstanceKind::FnPtrAddrShim(..)
                     | InstanceKind::AsyncDropGlue(..)
                     | InstanceKind::FutureDropPollShim(..)
-                    | InstanceKind::AsyncDropGlueCtorShim(..) => None,
+                    | InstanceKind::AsyncDropGlueCtorShim(..)
+                    | InstanceKind::ColdPathShim(..)
+                    | InstanceKind::ConstSpecialized(..) => None,
                 },
                 MonoItem::Static(def_id) => def_id.as_local().map(|_| def_id),
                 MonoItem::GlobalAsm(item_id) => Some(item_id.owner_id.def_id.to_def_id()),
@@ -268,6 +268,8 @@ impl<'dis, 'de, 'tcx> MirDumper<'dis, 'de, 'tcx> {
                 }));
                 s
             }
+            ty::InstanceKind::ColdPathShim(_, index) => format!(".cold{index}"),
+            ty::InstanceKind::ConstSpecialized(_, index) => format!(".const{index}"),
             _ => String::new(),
         };
 
@@ -567,7 +569,12 @@ fn write_coverage_info_hi(
     coverage_info_hi: &coverage::CoverageInfoHi,
     w: &mut dyn io::Write,
 ) -> io::Result<()> {
-    let coverage::CoverageInfoHi { num_block_markers: _, branch_spans } = coverage_info_hi;
+    let coverage::CoverageInfoHi {
+        num_block_markers: _,
+        branch_spans,
+        mcdc_degraded_branch_spans,
+        mcdc_spans,
+    } = coverage_info_hi;
 
     // Only add an extra trailing newline if we printed at least one thing.
     let mut did_print = false;
@@ -580,6 +587,38 @@ fn write_coverage_info_hi(
         did_print = true;
     }
 
+    for coverage::MCDCBranchSpan { span, true_marker, false_marker, .. } in
+        mcdc_degraded_branch_spans
+    {
+        writeln!(
+            w,
+            "{INDENT}coverage branch {{ true: {true_marker:?}, false: {false_marker:?} }} => {span:?}",
+        )?;
+        did_print = true;
+    }
+
+    for (
+        coverage::MCDCDecisionSpan { span, end_markers, decision_depth, num_conditions: _ },
+        conditions,
+    ) in mcdc_spans
+    {
+        let num_conditions = conditions.len();
+        writeln!(
+            w,
+            "{INDENT}coverage mcdc decision {{ num_conditions: {num_conditions:?}, end: {end_markers:?}, depth: {decision_depth:?} }} => {span:?}"
+        )?;
+        for coverage::MCDCBranchSpan { span, condition_info, true_marker, false_marker } in
+            conditions
+        {
+            writeln!(
+                w,
+                "{INDENT}coverage mcdc branch {{ condition_id: {:?}, true: {true_marker:?}, false: {false_marker:?} }} => {span:?}",
+                condition_info.condition_id
+            )?;
+        }
+        did_print = true;
+    }
+
     if did_print {
         writeln!(w)?;
     }
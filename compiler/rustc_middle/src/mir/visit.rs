@@ -365,7 +365,9 @@ macro_rules! make_mir_visitor {
                             coroutine_closure_def_id: _def_id,
                             receiver_by_ref: _,
                         }
-                        | ty::InstanceKind::DropGlue(_def_id, None) => {}
+                        | ty::InstanceKind::DropGlue(_def_id, None)
+                        | ty::InstanceKind::ColdPathShim(_def_id, _)
+                        | ty::InstanceKind::ConstSpecialized(_def_id, _) => {}
 
                         ty::InstanceKind::FnPtrShim(_def_id, ty)
                         | ty::InstanceKind::DropGlue(_def_id, Some(ty))
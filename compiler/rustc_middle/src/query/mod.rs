@@ -80,7 +80,7 @@ use rustc_errors::ErrorGuaranteed;
 use rustc_hir::attrs::StrippedCfgItem;
 use rustc_hir::def::{DefKind, DocLinkResMap};
 use rustc_hir::def_id::{
-    CrateNum, DefId, DefIdMap, LocalDefId, LocalDefIdMap, LocalDefIdSet, LocalModDefId,
+    CrateNum, DefId, DefIdMap, DefPathHash, LocalDefId, LocalDefIdMap, LocalDefIdSet, LocalModDefId,
 };
 use rustc_hir::lang_items::{LangItem, LanguageItems};
 use rustc_hir::{Crate, ItemLocalId, ItemLocalMap, PreciseCapturingArgKind, TraitCandidate};
@@ -97,6 +97,7 @@ use rustc_session::cstore::{
     CrateDepKind, CrateSource, ExternCrate, ForeignModule, LinkagePreference, NativeLib,
 };
 use rustc_session::lint::LintExpectationId;
+use rustc_session::mir_profile::Hotness;
 use rustc_span::def_id::LOCAL_CRATE;
 use rustc_span::source_map::Spanned;
 use rustc_span::{DUMMY_SP, Span, Symbol};
@@ -2384,6 +2385,14 @@ rustc_queries! {
         desc { "collect_and_partition_mono_items" }
     }
 
+    /// The MIR that is code generated for `key`. This is its `instance_mir`, except that the calls
+    /// that were redirected to an `InstanceKind::ConstSpecialized` call that clone instead.
+    query codegen_mir(key: ty::Instance<'tcx>) -> &'tcx mir::Body<'tcx> {
+        desc { "getting the MIR to codegen for `{}`", key }
+        // This is mostly the `instance_mir`, which is not worth hashing again.
+        no_hash
+    }
+
     query is_codegened_item(def_id: DefId) -> bool {
         desc { |tcx| "determining whether `{}` needs codegen", tcx.def_path_str(def_id) }
     }
@@ -2691,6 +2700,16 @@ rustc_queries! {
         separate_provide_extern
     }
 
+    /// The hotness of the functions in the profile given with `-Z mir-profile-use`.
+    ///
+    /// This is an input of the MIR optimizations: it is re-executed in every session, so that
+    /// changing the contents of the profile invalidates the optimized MIR that depends on it.
+    query mir_profile_hotness(_: ()) -> &'tcx UnordMap<DefPathHash, Hotness> {
+        arena_cache
+        eval_always
+        desc { "resolving the functions of the MIR profile" }
+    }
+
     /// Perform monomorphization-time checking on this item.
     /// This is used for lints/errors that can only be checked once the instance is fully
     /// monomorphized.
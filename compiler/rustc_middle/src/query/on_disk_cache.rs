@@ -7,6 +7,7 @@ use rustc_data_structures::memmap::Mmap;
 use rustc_data_structures::sync::{HashMapExt, Lock, RwLock};
 use rustc_data_structures::unhash::UnhashMap;
 use rustc_data_structures::unord::{UnordMap, UnordSet};
+use rustc_errors::DiagInner;
 use rustc_hir::def_id::{CrateNum, DefId, DefIndex, LOCAL_CRATE, LocalDefId, StableCrateId};
 use rustc_hir::definitions::DefPathHash;
 use rustc_index::{Idx, IndexVec};
@@ -72,6 +73,16 @@ pub struct OnDiskCache {
     // `serialized_data`.
     prev_side_effects_index: FxHashMap<SerializedDepNodeIndex, AbsoluteBytePos>,
 
+    // A map from dep-node to the rank of its `QuerySideEffect` in the order the
+    // side effects were emitted in during the previous compilation session.
+    prev_side_effects_order: FxHashMap<SerializedDepNodeIndex, usize>,
+
+    // Non-error diagnostics replayed from the previous compilation session, along with
+    // their rank in `prev_side_effects_order`. These are held back and emitted together
+    // by `emit_replayed_diagnostics`, so that a session where nothing changed prints its
+    // warnings in the same order as the session that originally produced them.
+    replayed_diagnostics: Lock<Vec<(usize, DepNodeIndex, DiagInner)>>,
+
     alloc_decoding_state: AllocDecodingState,
 
     // A map from syntax context ids to the position of their associated
@@ -116,6 +127,31 @@ struct Footer {
     foreign_expn_data: UnhashMap<ExpnHash, u32>,
 }
 
+impl Footer {
+    fn decode_from(data: &[u8], start_pos: usize) -> Result<Footer, ()> {
+        let mut decoder = MemDecoder::new(data, start_pos)?;
+
+        // Decode the *position* of the footer, which can be found in the
+        // last 8 bytes of the file.
+        let footer_pos = decoder
+            .with_position(decoder.len() - IntEncodedWithFixedSize::ENCODED_SIZE, |decoder| {
+                IntEncodedWithFixedSize::decode(decoder).0 as usize
+            });
+        // Decode the file footer, which contains all the lookup tables, etc.
+        Ok(decoder.with_position(footer_pos, |decoder| decode_tagged(decoder, TAG_FILE_FOOTER)))
+    }
+}
+
+/// The dep nodes of the previous compilation session that have data in an [`OnDiskCache`], as
+/// read by [`OnDiskCache::read_cached_dep_nodes`].
+#[derive(Debug, Default)]
+pub struct CachedDepNodes {
+    /// The nodes whose query result is cached.
+    pub query_results: Vec<SerializedDepNodeIndex>,
+    /// The nodes with side effects, like diagnostics, in the order they were emitted in.
+    pub side_effects: Vec<SerializedDepNodeIndex>,
+}
+
 pub type EncodedDepNodeIndex = Vec<(SerializedDepNodeIndex, AbsoluteBytePos)>;
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Encodable, Decodable)]
@@ -160,17 +196,7 @@ impl OnDiskCache {
     pub fn new(sess: &Session, data: Mmap, start_pos: usize) -> Result<Self, ()> {
         assert!(sess.opts.incremental.is_some());
 
-        let mut decoder = MemDecoder::new(&data, start_pos)?;
-
-        // Decode the *position* of the footer, which can be found in the
-        // last 8 bytes of the file.
-        let footer_pos = decoder
-            .with_position(decoder.len() - IntEncodedWithFixedSize::ENCODED_SIZE, |decoder| {
-                IntEncodedWithFixedSize::decode(decoder).0 as usize
-            });
-        // Decode the file footer, which contains all the lookup tables, etc.
-        let footer: Footer =
-            decoder.with_position(footer_pos, |decoder| decode_tagged(decoder, TAG_FILE_FOOTER));
+        let footer = Footer::decode_from(&data, start_pos)?;
 
         Ok(Self {
             serialized_data: RwLock::new(Some(data)),
@@ -178,7 +204,14 @@ impl OnDiskCache {
             file_index_to_file: Default::default(),
             current_side_effects: Default::default(),
             query_result_index: footer.query_result_index.into_iter().collect(),
+            prev_side_effects_order: footer
+                .side_effects_index
+                .iter()
+                .enumerate()
+                .map(|(rank, &(dep_node_index, _))| (dep_node_index, rank))
+                .collect(),
             prev_side_effects_index: footer.side_effects_index.into_iter().collect(),
+            replayed_diagnostics: Default::default(),
             alloc_decoding_state: AllocDecodingState::new(footer.interpret_alloc_index),
             syntax_contexts: footer.syntax_contexts,
             expn_data: footer.expn_data,
@@ -187,6 +220,17 @@ impl OnDiskCache {
         })
     }
 
+    /// Reads which dep nodes of the previous compilation session have a cached query result or
+    /// side effects in the serialized data in `data`, without a session. This is used by tools
+    /// that inspect incremental compilation directories.
+    pub fn read_cached_dep_nodes(data: &[u8], start_pos: usize) -> Result<CachedDepNodes, ()> {
+        let footer = Footer::decode_from(data, start_pos)?;
+        Ok(CachedDepNodes {
+            query_results: footer.query_result_index.into_iter().map(|(index, _)| index).collect(),
+            side_effects: footer.side_effects_index.into_iter().map(|(index, _)| index).collect(),
+        })
+    }
+
     pub fn new_empty() -> Self {
         Self {
             serialized_data: RwLock::new(None),
@@ -195,6 +239,8 @@ impl OnDiskCache {
             current_side_effects: Default::default(),
             query_result_index: Default::default(),
             prev_side_effects_index: Default::default(),
+            prev_side_effects_order: Default::default(),
+            replayed_diagnostics: Default::default(),
             alloc_decoding_state: AllocDecodingState::new(Vec::new()),
             syntax_contexts: FxHashMap::default(),
             expn_data: UnhashMap::default(),
@@ -221,6 +267,10 @@ impl OnDiskCache {
     }
 
     pub fn serialize(&self, tcx: TyCtxt<'_>, encoder: FileEncoder) -> FileEncodeResult {
+        // Replayed diagnostics are only stored as side effects of the current session once
+        // they have been emitted, so make sure that happened before we write them out.
+        self.emit_replayed_diagnostics(tcx);
+
         // Serializing the `DepGraph` should not modify it.
         tcx.dep_graph.with_ignore(|| {
             // Allocate `SourceFileIndex`es.
@@ -372,6 +422,55 @@ impl OnDiskCache {
         debug_assert!(prev.is_none());
     }
 
+    /// Replays a `QuerySideEffect` created during the previous compilation session, and
+    /// stores it again as the side effect of `dep_node_index` for the next one.
+    ///
+    /// Errors are emitted right away, and so are the diagnostics that fulfill a lint
+    /// expectation, as `check_expectations` runs before the held back diagnostics are emitted.
+    /// Other diagnostics are held back until `emit_replayed_diagnostics` so that they come out
+    /// in their original order, rather than in the order the dep-graph happens to be marked
+    /// green in.
+    pub fn replay_side_effect(
+        &self,
+        tcx: TyCtxt<'_>,
+        prev_dep_node_index: SerializedDepNodeIndex,
+        dep_node_index: DepNodeIndex,
+        side_effect: QuerySideEffect,
+    ) {
+        match side_effect {
+            QuerySideEffect::Diagnostic(diagnostic)
+                if !diagnostic.is_error() && diagnostic.lint_id.is_none() =>
+            {
+                let rank = self.prev_side_effects_order[&prev_dep_node_index];
+                self.replayed_diagnostics.lock().push((rank, dep_node_index, diagnostic));
+            }
+            QuerySideEffect::Diagnostic(diagnostic) => {
+                tcx.dcx().emit_diagnostic(diagnostic.clone());
+                self.store_side_effect(dep_node_index, QuerySideEffect::Diagnostic(diagnostic));
+            }
+            QuerySideEffect::MirRemark(remark) => {
+                tcx.sess.mir_remarks.record(remark.clone());
+                self.store_side_effect(dep_node_index, QuerySideEffect::MirRemark(remark));
+            }
+        }
+    }
+
+    /// Emits all diagnostics held back by `replay_side_effect`, in the order they were
+    /// emitted in during the previous compilation session.
+    pub fn emit_replayed_diagnostics(&self, tcx: TyCtxt<'_>) {
+        let mut replayed = mem::take(&mut *self.replayed_diagnostics.lock());
+        if replayed.is_empty() {
+            return;
+        }
+        replayed.sort_by_key(|&(rank, ..)| rank);
+        tcx.dep_graph.with_ignore(|| {
+            for (_, dep_node_index, diagnostic) in replayed {
+                tcx.dcx().emit_diagnostic(diagnostic.clone());
+                self.store_side_effect(dep_node_index, QuerySideEffect::Diagnostic(diagnostic));
+            }
+        })
+    }
+
     /// Return whether the cached query result can be decoded.
     #[inline]
     pub fn loadable_from_disk(&self, dep_node_index: SerializedDepNodeIndex) -> bool {
@@ -78,6 +78,7 @@ use crate::traits::solve::{
     QueryResult, inspect,
 };
 use crate::ty::predicate::ExistentialPredicateStableCmpExt as _;
+use crate::ty::print::with_no_trimmed_paths;
 use crate::ty::{
     self, AdtDef, AdtDefData, AdtKind, Binder, Clause, Clauses, Const, GenericArg, GenericArgs,
     GenericArgsRef, GenericParamDefKind, List, ListWithCachedTypeInfo, ParamConst, ParamTy,
@@ -2445,6 +2446,14 @@ impl<'tcx> TyCtxt<'tcx> {
         self.hir_expect_opaque_ty(def_id).origin
     }
 
+    /// Emits the diagnostics replayed from the previous incremental session that are still
+    /// held back, see `OnDiskCache::replay_side_effect`.
+    pub fn emit_replayed_diagnostics(self) {
+        if let Some(on_disk_cache) = &self.query_system.on_disk_cache {
+            on_disk_cache.emit_replayed_diagnostics(self);
+        }
+    }
+
     pub fn finish(self) {
         // We assume that no queries are run past here. If there are new queries
         // after this point, they'll show up as "<unknown>" in self-profiling data.
@@ -3269,8 +3278,7 @@ impl<'tcx> TyCtxt<'tcx> {
         span: impl Into<MultiSpan>,
         decorator: impl for<'a> LintDiagnostic<'a, ()>,
     ) {
-        let level = self.lint_level_at_node(lint, hir_id);
-        lint_level(self.sess, lint, level, Some(span.into()), |lint| {
+        self.lint_at_node(lint, hir_id, Some(span.into()), |lint| {
             decorator.decorate_lint(lint);
         })
     }
@@ -3286,9 +3294,38 @@ impl<'tcx> TyCtxt<'tcx> {
         hir_id: HirId,
         span: impl Into<MultiSpan>,
         decorate: impl for<'a, 'b> FnOnce(&'b mut Diag<'a, ()>),
+    ) {
+        self.lint_at_node(lint, hir_id, Some(span.into()), decorate);
+    }
+
+    /// Emits a lint at the level of a hir node, recording the item of the node for
+    /// `--diagnostic-baseline`. All the lints emitted at a hir node go through here.
+    #[rustc_lint_diagnostics]
+    #[track_caller]
+    fn lint_at_node(
+        self,
+        lint: &'static Lint,
+        hir_id: HirId,
+        span: Option<MultiSpan>,
+        decorate: impl for<'a, 'b> FnOnce(&'b mut Diag<'a, ()>),
     ) {
         let level = self.lint_level_at_node(lint, hir_id);
-        lint_level(self.sess, lint, level, Some(span.into()), decorate);
+        lint_level(self.sess, lint, level, span, |lint| {
+            if let Some(item) = self.lint_baseline_item(hir_id) {
+                lint.lint_item(item);
+            }
+            decorate(lint);
+        });
+    }
+
+    /// The path of the item a lint is emitted in, when `--diagnostic-baseline` needs it to
+    /// identify the lint.
+    fn lint_baseline_item(self, hir_id: HirId) -> Option<String> {
+        self.sess.opts.diagnostic_baseline.as_ref()?;
+        if hir_id.owner == hir::CRATE_OWNER_ID {
+            return Some("crate".to_owned());
+        }
+        Some(with_no_trimmed_paths!(self.def_path_str(hir_id.owner.to_def_id())))
     }
 
     /// Find the appropriate span where `use` and outer attributes can be inserted at.
@@ -3346,8 +3383,7 @@ impl<'tcx> TyCtxt<'tcx> {
         id: HirId,
         decorate: impl for<'a, 'b> FnOnce(&'b mut Diag<'a, ()>),
     ) {
-        let level = self.lint_level_at_node(lint, id);
-        lint_level(self.sess, lint, level, None, decorate);
+        self.lint_at_node(lint, id, None, decorate);
     }
 
     pub fn in_scope_traits(self, id: HirId) -> Option<&'tcx [TraitCandidate]> {
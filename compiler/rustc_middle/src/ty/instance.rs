@@ -182,6 +182,20 @@ pub enum InstanceKind<'tcx> {
     /// async_drop_in_place poll function implementation (for generated coroutine).
     /// `Ty` here is `async_drop_in_place<T>::{closure}` coroutine type, not just `T`
     AsyncDropGlue(DefId, Ty<'tcx>),
+
+    /// A cold path of a function, outlined by the `OutlineColdPaths` MIR pass.
+    ///
+    /// The `DefId` is for the function that the path was outlined from, and the `u32` is the
+    /// index of the path in the `cold_paths` of its optimized MIR.
+    ColdPathShim(DefId, u32),
+
+    /// A clone of a function in which some of the arguments are replaced by the constants that
+    /// its call sites pass, created during monomorphization at `-Z mir-opt-level=3`.
+    ///
+    /// The `DefId` is for the cloned function, and the `u32` is the index of the clone in the
+    /// `const_specializations` of `collect_and_partition_mono_items`. The `args` of the instance
+    /// are those of the cloned function.
+    ConstSpecialized(DefId, u32),
 }
 
 impl<'tcx> Instance<'tcx> {
@@ -260,7 +274,9 @@ impl<'tcx> InstanceKind<'tcx> {
             | InstanceKind::FnPtrAddrShim(def_id, _)
             | InstanceKind::FutureDropPollShim(def_id, _, _)
             | InstanceKind::AsyncDropGlue(def_id, _)
-            | InstanceKind::AsyncDropGlueCtorShim(def_id, _) => def_id,
+            | InstanceKind::AsyncDropGlueCtorShim(def_id, _)
+            | InstanceKind::ColdPathShim(def_id, _)
+            | InstanceKind::ConstSpecialized(def_id, _) => def_id,
         }
     }
 
@@ -282,7 +298,9 @@ impl<'tcx> InstanceKind<'tcx> {
             | ty::InstanceKind::ConstructCoroutineInClosureShim { .. }
             | InstanceKind::DropGlue(..)
             | InstanceKind::CloneShim(..)
-            | InstanceKind::FnPtrAddrShim(..) => None,
+            | InstanceKind::FnPtrAddrShim(..)
+            | InstanceKind::ColdPathShim(..)
+            | InstanceKind::ConstSpecialized(..) => None,
         }
     }
 
@@ -309,6 +327,8 @@ impl<'tcx> InstanceKind<'tcx> {
             ty::InstanceKind::FutureDropPollShim(_, _, _) => return false,
             ty::InstanceKind::AsyncDropGlue(_, _) => return false,
             ty::InstanceKind::ThreadLocalShim(_) => return false,
+            ty::InstanceKind::ColdPathShim(..) => return false,
+            ty::InstanceKind::ConstSpecialized(..) => return false,
             _ => return true,
         };
         matches!(
@@ -338,6 +358,8 @@ impl<'tcx> InstanceKind<'tcx> {
             InstanceKind::CloneShim(..)
             | InstanceKind::ThreadLocalShim(..)
             | InstanceKind::FnPtrAddrShim(..)
+            | InstanceKind::ColdPathShim(..)
+            | InstanceKind::ConstSpecialized(..)
             | InstanceKind::FnPtrShim(..)
             | InstanceKind::DropGlue(_, Some(_))
             | InstanceKind::FutureDropPollShim(..)
@@ -1705,7 +1705,9 @@ impl<'tcx> TyCtxt<'tcx> {
             | ty::InstanceKind::ThreadLocalShim(..)
             | ty::InstanceKind::FnPtrAddrShim(..)
             | ty::InstanceKind::AsyncDropGlueCtorShim(..)
-            | ty::InstanceKind::AsyncDropGlue(..) => self.mir_shims(instance),
+            | ty::InstanceKind::AsyncDropGlue(..)
+            | ty::InstanceKind::ColdPathShim(..)
+            | ty::InstanceKind::ConstSpecialized(..) => self.mir_shims(instance),
         }
     }
 
@@ -372,6 +372,12 @@ impl<'tcx, P: Printer<'tcx> + std::fmt::Write> Print<'tcx, P> for ty::Instance<'
             }
             ty::InstanceKind::CloneShim(_, ty) => cx.write_str(&format!(" - shim({ty})"))?,
             ty::InstanceKind::FnPtrAddrShim(_, ty) => cx.write_str(&format!(" - shim({ty})"))?,
+            ty::InstanceKind::ColdPathShim(_, index) => {
+                cx.write_str(&format!(" - shim(cold#{index})"))?
+            }
+            ty::InstanceKind::ConstSpecialized(_, index) => {
+                cx.write_str(&format!(" - shim(const#{index})"))?
+            }
             ty::InstanceKind::FutureDropPollShim(_, proxy_ty, impl_ty) => {
                 cx.write_str(&format!(" - dropshim({proxy_ty}-{impl_ty})"))?
             }
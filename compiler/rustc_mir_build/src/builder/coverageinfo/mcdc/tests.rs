@@ -0,0 +1,102 @@
+use rustc_span::{DUMMY_SP, create_default_session_globals_then};
+
+use super::*;
+
+fn marker(index: u32) -> BlockMarkerId {
+    BlockMarkerId::from_u32(index)
+}
+
+/// Returns the IDs of a condition and of the conditions evaluated after it when it is true and
+/// when it is false.
+fn ids(branch: &MCDCBranchSpan) -> (u32, Option<u32>, Option<u32>) {
+    let ConditionInfo { condition_id, true_next_id, false_next_id } = branch.condition_info;
+    (condition_id.as_u32(), true_next_id.map(|id| id.as_u32()), false_next_id.map(|id| id.as_u32()))
+}
+
+/// Evaluates the next condition of the decision being built, with the block markers `2 * n` and
+/// `2 * n + 1` for its outcomes.
+fn evaluate(
+    state: &mut MCDCState,
+    n: u32,
+    degraded: &mut Vec<MCDCBranchSpan>,
+) -> Option<(MCDCDecisionSpan, Vec<MCDCBranchSpan>)> {
+    state.try_finish_decision(DUMMY_SP, marker(2 * n), marker(2 * n + 1), degraded)
+}
+
+#[test]
+fn single_condition_is_not_a_decision() {
+    create_default_session_globals_then(|| {
+        // `if a {}` has no logical operator, so `a` is an ordinary branch.
+        let mut state = MCDCState::new();
+        let mut degraded = vec![];
+        assert!(evaluate(&mut state, 0, &mut degraded).is_none());
+        assert_eq!(degraded.len(), 1);
+        assert_eq!(ids(&degraded[0]), (0, None, None));
+    })
+}
+
+#[test]
+fn and_or_decision() {
+    create_default_session_globals_then(|| {
+        // `(a && b) || c`
+        let mut state = MCDCState::new();
+        let mut degraded = vec![];
+        state.record_conditions(LogicalOp::Or, DUMMY_SP);
+        state.record_conditions(LogicalOp::And, DUMMY_SP);
+        assert!(evaluate(&mut state, 0, &mut degraded).is_none());
+        assert!(evaluate(&mut state, 1, &mut degraded).is_none());
+        let (decision, conditions) = evaluate(&mut state, 2, &mut degraded).unwrap();
+
+        assert!(degraded.is_empty());
+        assert_eq!(decision.num_conditions, 3);
+        assert_eq!(decision.decision_depth, 0);
+        let condition_ids = conditions.iter().map(ids).collect::<Vec<_>>();
+        assert_eq!(condition_ids, [(0, Some(2), Some(1)), (2, None, Some(1)), (1, None, None)]);
+        // The decision ends when `b` is true, or when `c` is evaluated.
+        assert_eq!(decision.end_markers, [marker(2), marker(4), marker(5)]);
+    })
+}
+
+#[test]
+fn nested_decision() {
+    create_default_session_globals_then(|| {
+        // `a && if b || c { d } else { e }`, where `b || c` is a decision of its own.
+        let mut state = MCDCState::new();
+        let mut degraded = vec![];
+        state.record_conditions(LogicalOp::And, DUMMY_SP);
+        assert!(evaluate(&mut state, 0, &mut degraded).is_none());
+
+        state.decision_ctx_stack.push(MCDCDecisionCtx::default());
+        assert_eq!(state.decision_depth(), 1);
+        state.record_conditions(LogicalOp::Or, DUMMY_SP);
+        assert!(evaluate(&mut state, 1, &mut degraded).is_none());
+        let (inner, inner_conditions) = evaluate(&mut state, 2, &mut degraded).unwrap();
+        state.decision_ctx_stack.pop();
+        assert_eq!(state.decision_depth(), 0);
+
+        let (outer, outer_conditions) = evaluate(&mut state, 3, &mut degraded).unwrap();
+
+        assert!(degraded.is_empty());
+        assert_eq!((inner.decision_depth, inner.num_conditions), (1, 2));
+        let inner_ids = inner_conditions.iter().map(ids).collect::<Vec<_>>();
+        assert_eq!(inner_ids, [(0, None, Some(1)), (1, None, None)]);
+        assert_eq!((outer.decision_depth, outer.num_conditions), (0, 2));
+        let outer_ids = outer_conditions.iter().map(ids).collect::<Vec<_>>();
+        assert_eq!(outer_ids, [(0, Some(1), None), (1, None, None)]);
+    })
+}
+
+#[test]
+fn max_decision_depth() {
+    let mut state = MCDCState::new();
+    state.decision_ctx_stack.resize_with(usize::from(u16::MAX) + 1, Default::default);
+    assert_eq!(state.decision_depth(), u16::MAX);
+}
+
+#[test]
+#[should_panic = "decision depth did not fit in u16"]
+fn decision_depth_overflow() {
+    let mut state = MCDCState::new();
+    state.decision_ctx_stack.resize_with(usize::from(u16::MAX) + 2, Default::default);
+    state.decision_depth();
+}
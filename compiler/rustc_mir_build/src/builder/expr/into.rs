@@ -159,6 +159,8 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
                 let condition_scope = this.local_scope();
                 let source_info = this.source_info(expr.span);
 
+                this.visit_coverage_branch_operation(op, expr.span);
+
                 // We first evaluate the left-hand side of the predicate ...
                 let (then_block, else_block) =
                     this.in_if_then_scope(condition_scope, expr.span, |this| {
@@ -192,8 +194,25 @@ impl<'a, 'tcx> Builder<'a, 'tcx> {
                         const_: Const::from_bool(this.tcx, constant),
                     },
                 );
+                // Unless the RHS is itself a lazy logical op that continues the current
+                // MC/DC decision, any boolean expressions nested within it belong to
+                // separate decisions.
+                let mut rhs_inner = rhs;
+                while let ExprKind::Use { source: inner } | ExprKind::Scope { value: inner, .. } =
+                    this.thir[rhs_inner].kind
+                {
+                    rhs_inner = inner;
+                }
+                let rhs_is_condition =
+                    !matches!(this.thir[rhs_inner].kind, ExprKind::LogicalOp { .. });
+                if rhs_is_condition {
+                    this.mcdc_increment_depth_if_enabled();
+                }
                 let mut rhs_block =
                     this.expr_into_dest(destination, continuation, rhs).into_block();
+                if rhs_is_condition {
+                    this.mcdc_decrement_depth_if_enabled();
+                }
                 // Instrument the lowered RHS's value for condition coverage.
                 // (Does nothing if condition coverage is not enabled.)
                 this.visit_coverage_standalone_condition(rhs, destination, &mut rhs_block);
@@ -136,6 +136,19 @@ impl<V: Clone + HasBottom> State<V> {
         matches!(self, State::Reachable(_))
     }
 
+    /// Applies `f` to the value of every place that is not `⊥`.
+    ///
+    /// This is meant for analyses whose values refer to other places, and which need to update
+    /// those references when a place is overwritten. `f` must not turn a value into `⊥`.
+    pub fn for_each_value_mut(&mut self, mut f: impl FnMut(&mut V)) {
+        let State::Reachable(values) = self else { return };
+        #[allow(rustc::potential_query_instability)]
+        for value in values.map.values_mut() {
+            f(value);
+            debug_assert!(!value.is_bottom());
+        }
+    }
+
     /// Assign `value` to all places that are contained in `place` or may alias one.
     pub fn flood_with(&mut self, place: PlaceRef<'_>, map: &Map<'_>, value: V) {
         self.flood_with_tail_elem(place, None, map, value)
@@ -0,0 +1,947 @@
+//! Removes bounds checks that are known to always pass.
+//!
+//! Indexing a slice or an array is lowered to a comparison followed by an assertion:
+//!
+//! ```text
+//! _c = Lt(copy _index, copy _len);
+//! assert(move _c, "index out of bounds: ...") -> bb;
+//! ```
+//!
+//! This pass runs an interval analysis on top of [`value_analysis`], and replaces the `Assert` by
+//! a `Goto` when the comparison is known to always be true. Since this happens on MIR, every
+//! codegen backend benefits from it.
+//!
+//! Numeric ranges alone cannot handle the most common case, `for i in 0..s.len() { s[i] }`, since
+//! the length of `s` is not known. So in addition to its range, the value of a place may record
+//! that it is strictly less than, or equal to, the *current* value of another tracked place:
+//!
+//! ```text
+//! bb1: _7 = copy _4;                               // _7: [0, ∞], == _4
+//!      _8 = Lt(copy _7, copy _2);
+//!      switchInt(move _8) -> [0: bb3, otherwise: bb2];
+//! bb2: _9 = Lt(copy _7, copy _2);                  // _7: [0, ∞], < _2
+//!      assert(move _9, "index out of bounds: ...") -> bb4;
+//! ```
+//!
+//! Such a relation is forgotten as soon as the local it refers to is written to. As tracked places
+//! never have their address taken, a direct write is the only way for their value to change.
+//!
+//! [`value_analysis`]: rustc_mir_dataflow::value_analysis
+
+use std::fmt::Formatter;
+
+use rustc_abi::Integer;
+use rustc_data_structures::fx::FxHashSet;
+use rustc_hir::def::DefKind;
+use rustc_middle::bug;
+use rustc_middle::mir::*;
+use rustc_middle::ty::layout::IntegerExt;
+use rustc_middle::ty::{self, Ty, TyCtxt};
+use rustc_mir_dataflow::fmt::DebugWithContext;
+use rustc_mir_dataflow::lattice::{HasBottom, HasTop, JoinSemiLattice};
+use rustc_mir_dataflow::value_analysis::{
+    Map, PlaceIndex, State, TrackElem, ValueOrPlace, debug_with_context, excluded_locals,
+};
+use rustc_mir_dataflow::{Analysis, ResultsVisitor, visit_reachable_results};
+use tracing::{debug, debug_span, instrument};
+
+use crate::remarks::{RemarkKind, emit_remark};
+
+// These limits are the same as the ones of `DataflowConstProp`, which has the same complexity.
+// If `tcx.sess.mir_opt_level() >= 4`, we ignore the limits (this can become very expensive).
+const BLOCK_LIMIT: usize = 100;
+const PLACE_LIMIT: usize = 100;
+
+pub(super) struct BoundsCheckElimination;
+
+impl<'tcx> crate::MirPass<'tcx> for BoundsCheckElimination {
+    fn is_enabled(&self, sess: &rustc_session::Session) -> bool {
+        sess.mir_opt_level() >= 2
+    }
+
+    #[instrument(skip_all level = "debug")]
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        debug!(def_id = ?body.source.def_id());
+        if tcx.sess.mir_opt_level() < 4 && body.basic_blocks.len() > BLOCK_LIMIT {
+            debug!("aborted bounds check elimination due too many basic blocks");
+            return;
+        }
+
+        let candidates = find_bounds_checks(body);
+        if candidates.is_empty() {
+            return;
+        }
+
+        let redundant = {
+            let place_limit = if tcx.sess.mir_opt_level() < 4 { Some(PLACE_LIMIT) } else { None };
+            let map = Map::new(tcx, body, place_limit);
+
+            let mut intervals = debug_span!("analyze").in_scope(|| {
+                IntervalAnalysis::new(tcx, body, map).iterate_to_fixpoint(tcx, body, None)
+            });
+
+            let mut collector = Collector { candidates, redundant: Vec::new() };
+            debug_span!("collect").in_scope(|| {
+                visit_reachable_results(
+                    body,
+                    &mut intervals.analysis,
+                    &intervals.results,
+                    &mut collector,
+                )
+            });
+            collector.redundant
+        };
+
+        let def_id = body.source.def_id();
+        for block in redundant {
+            let terminator = body.basic_blocks_mut()[block].terminator_mut();
+            let TerminatorKind::Assert { target, .. } = terminator.kind else {
+                bug!("bounds check candidate is not an assertion");
+            };
+            debug!(?block, "removing bounds check");
+            terminator.kind = TerminatorKind::Goto { target };
+            let span = terminator.source_info.span;
+            emit_remark(tcx, "BoundsCheckElimination", def_id, span, RemarkKind::Passed, || {
+                "removed a bounds check, as the index is always in bounds".to_owned()
+            });
+        }
+    }
+
+    fn is_required(&self) -> bool {
+        false
+    }
+}
+
+/// Finds the comparisons whose result is checked by a bounds check, i.e. the `_c = Lt(_, _)`
+/// statements such that the block ends with `assert(move _c, "index out of bounds: ...")`.
+fn find_bounds_checks(body: &Body<'_>) -> FxHashSet<Location> {
+    let excluded = excluded_locals(body);
+    let mut candidates = FxHashSet::default();
+
+    for (block, data) in body.basic_blocks.iter_enumerated() {
+        let TerminatorKind::Assert { cond, expected: true, msg, .. } = &data.terminator().kind
+        else {
+            continue;
+        };
+        if !matches!(**msg, AssertKind::BoundsCheck { .. }) {
+            continue;
+        }
+        // If the condition had its address taken, it could be modified behind our back.
+        let Some(cond) = cond.place().and_then(|place| place.as_local()) else { continue };
+        if excluded.contains(cond) {
+            continue;
+        }
+
+        let last_write = data
+            .statements
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, statement)| writes_to_local(statement, cond));
+        if let Some((statement_index, statement)) = last_write
+            && let StatementKind::Assign(box (place, Rvalue::BinaryOp(BinOp::Lt, _))) =
+                &statement.kind
+            && place.as_local() == Some(cond)
+        {
+            candidates.insert(Location { block, statement_index });
+        }
+    }
+
+    candidates
+}
+
+/// Whether `statement` may directly modify the value of `local`.
+fn writes_to_local(statement: &Statement<'_>, local: Local) -> bool {
+    match &statement.kind {
+        StatementKind::Assign(box (place, _))
+        | StatementKind::SetDiscriminant { box place, .. }
+        | StatementKind::Deinit(box place) => {
+            place.local == local && !place.is_indirect_first_projection()
+        }
+        StatementKind::StorageLive(l) | StatementKind::StorageDead(l) => *l == local,
+        _ => false,
+    }
+}
+
+/// Returns the operand that `operand`, read by `statements[index]`, was copied from in the same
+/// block, if that operand still holds the same value. A comparison is usually done on such a
+/// temporary, which dies before the branch on its result:
+///
+/// ```text
+/// _5 = copy _4;
+/// _6 = Lt(move _5, copy _3);
+/// StorageDead(_5);
+/// switchInt(move _6) -> [0: bb2, otherwise: bb4];
+/// ```
+///
+/// Only the value of `_4` is still known on the edges of the branch.
+fn copy_source<'a, 'tcx>(
+    statements: &'a [Statement<'tcx>],
+    index: usize,
+    operand: &'a Operand<'tcx>,
+) -> &'a Operand<'tcx> {
+    let Some(local) = operand.place().and_then(|place| place.as_local()) else { return operand };
+    let Some((copy_index, copy)) =
+        statements[..index].iter().enumerate().rev().find(|(_, s)| writes_to_local(s, local))
+    else {
+        return operand;
+    };
+    if let StatementKind::Assign(box (place, Rvalue::Use(source @ Operand::Copy(source_place)))) =
+        &copy.kind
+        && place.as_local() == Some(local)
+        && !source_place.is_indirect()
+        && !statements[copy_index + 1..index].iter().any(|s| writes_to_local(s, source_place.local))
+    {
+        source
+    } else {
+        operand
+    }
+}
+
+/// A tracked place whose current value bounds the value of another place.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct Bound {
+    /// The local of `place`, so that the bound can be forgotten when the local is written to.
+    local: Local,
+    place: PlaceIndex,
+}
+
+/// What is known about the value of an unsigned integer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct Bounds {
+    min: u128,
+    max: u128,
+    /// The value is strictly less than the current value of this place.
+    less_than: Option<Bound>,
+    /// The value is equal to the current value of this place.
+    equal_to: Option<Bound>,
+}
+
+impl Bounds {
+    const FULL: Bounds = Bounds::new(0, u128::MAX);
+
+    const fn new(min: u128, max: u128) -> Bounds {
+        Bounds { min, max, less_than: None, equal_to: None }
+    }
+
+    /// Forgets every relation to the value of a place of `local`.
+    fn forget(&mut self, local: Local) {
+        if self.less_than.is_some_and(|bound| bound.local == local) {
+            self.less_than = None;
+        }
+        if self.equal_to.is_some_and(|bound| bound.local == local) {
+            self.equal_to = None;
+        }
+    }
+}
+
+/// The lattice of values used by [`IntervalAnalysis`].
+///
+/// Places that do not have an unsigned integer type only ever hold `⊥` or relations to other
+/// places, with the full numeric range.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Interval {
+    /// The place is uninitialized.
+    Bottom,
+    Known(Bounds),
+}
+
+impl HasBottom for Interval {
+    const BOTTOM: Self = Interval::Bottom;
+
+    fn is_bottom(&self) -> bool {
+        matches!(self, Interval::Bottom)
+    }
+}
+
+impl HasTop for Interval {
+    const TOP: Self = Interval::Known(Bounds::FULL);
+}
+
+impl JoinSemiLattice for Interval {
+    fn join(&mut self, other: &Self) -> bool {
+        match (&mut *self, other) {
+            (_, Interval::Bottom) => false,
+            (Interval::Bottom, Interval::Known(_)) => {
+                *self = *other;
+                true
+            }
+            (Interval::Known(this), Interval::Known(other)) => {
+                // A bound that grows is widened straight away, which keeps the height of the
+                // lattice small. This is what makes loop counters converge. The precision lost
+                // here is usually recovered by the loop condition.
+                let joined = Bounds {
+                    min: if other.min < this.min { 0 } else { this.min },
+                    max: if other.max > this.max { u128::MAX } else { this.max },
+                    less_than: if this.less_than == other.less_than {
+                        this.less_than
+                    } else {
+                        None
+                    },
+                    equal_to: if this.equal_to == other.equal_to { this.equal_to } else { None },
+                };
+                let changed = joined != *this;
+                *this = joined;
+                changed
+            }
+        }
+    }
+}
+
+/// A comparison whose result is branched on by a `SwitchInt` terminator.
+struct Comparison<'a, 'tcx> {
+    op: BinOp,
+    left: &'a Operand<'tcx>,
+    right: &'a Operand<'tcx>,
+}
+
+// Note: like in `DataflowConstProp`, places that have their reference taken are not tracked. So
+// the only way to change the value behind a tracked place is by direct assignment.
+struct IntervalAnalysis<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    body: &'a Body<'tcx>,
+    map: Map<'tcx>,
+    typing_env: ty::TypingEnv<'tcx>,
+}
+
+impl<'a, 'tcx> Analysis<'tcx> for IntervalAnalysis<'a, 'tcx> {
+    type Domain = State<Interval>;
+
+    type SwitchIntData = Comparison<'a, 'tcx>;
+
+    const NAME: &'static str = "IntervalAnalysis";
+
+    fn bottom_value(&self, _body: &Body<'tcx>) -> Self::Domain {
+        State::Unreachable
+    }
+
+    fn initialize_start_block(&self, body: &Body<'tcx>, state: &mut Self::Domain) {
+        // The initial state maps all tracked places of argument projections to ⊤ and the rest to ⊥.
+        *state = State::new_reachable();
+        for arg in body.args_iter() {
+            state.flood(PlaceRef { local: arg, projection: &[] }, &self.map);
+        }
+    }
+
+    fn apply_primary_statement_effect(
+        &mut self,
+        state: &mut Self::Domain,
+        statement: &Statement<'tcx>,
+        _location: Location,
+    ) {
+        if state.is_reachable() {
+            self.handle_statement(statement, state);
+        }
+    }
+
+    fn apply_primary_terminator_effect<'mir>(
+        &mut self,
+        state: &mut Self::Domain,
+        terminator: &'mir Terminator<'tcx>,
+        _location: Location,
+    ) -> TerminatorEdges<'mir, 'tcx> {
+        if !state.is_reachable() {
+            return TerminatorEdges::None;
+        }
+        match &terminator.kind {
+            TerminatorKind::Drop { place, .. } => {
+                self.forget_relations_to(state, place.as_ref());
+                state.flood_with(place.as_ref(), &self.map, Interval::BOTTOM);
+            }
+            TerminatorKind::Yield { .. } => {
+                // They would have an effect, but are not allowed in this phase.
+                bug!("encountered disallowed terminator");
+            }
+            TerminatorKind::Call { .. }
+            | TerminatorKind::InlineAsm { .. }
+            | TerminatorKind::SwitchInt { .. }
+            | TerminatorKind::TailCall { .. }
+            | TerminatorKind::Goto { .. }
+            | TerminatorKind::UnwindResume
+            | TerminatorKind::UnwindTerminate(_)
+            | TerminatorKind::Return
+            | TerminatorKind::Unreachable
+            | TerminatorKind::Assert { .. }
+            | TerminatorKind::CoroutineDrop
+            | TerminatorKind::FalseEdge { .. }
+            | TerminatorKind::FalseUnwind { .. } => {
+                // Calls and switches have their effect applied on their edges, the other
+                // terminators have no effect on the analysis.
+            }
+        }
+        terminator.edges()
+    }
+
+    fn apply_call_return_effect(
+        &mut self,
+        state: &mut Self::Domain,
+        _block: BasicBlock,
+        return_places: CallReturnPlaces<'_, 'tcx>,
+    ) {
+        if state.is_reachable() {
+            return_places.for_each(|place| {
+                self.forget_relations_to(state, place.as_ref());
+                state.flood(place.as_ref(), &self.map);
+            })
+        }
+    }
+
+    fn get_switch_int_data(
+        &mut self,
+        block: BasicBlock,
+        discr: &Operand<'tcx>,
+    ) -> Option<Self::SwitchIntData> {
+        let discr = discr.place()?.as_local()?;
+        let body = self.body;
+        let statements = &body[block].statements;
+        let (index, statement) = statements
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, statement)| writes_to_local(statement, discr))?;
+
+        let StatementKind::Assign(box (place, Rvalue::BinaryOp(op, box (left, right)))) =
+            &statement.kind
+        else {
+            return None;
+        };
+        if place.as_local() != Some(discr)
+            || !matches!(op, BinOp::Lt | BinOp::Le | BinOp::Gt | BinOp::Ge | BinOp::Eq | BinOp::Ne)
+            || !self.is_unsigned(left.ty(self.body, self.tcx))
+        {
+            return None;
+        }
+
+        // The compared places must still hold the compared values when we branch.
+        let modified = |operand: &Operand<'_>| {
+            operand.place().is_some_and(|place| {
+                statements[index + 1..].iter().any(|s| writes_to_local(s, place.local))
+            })
+        };
+        let (left, right) =
+            (copy_source(statements, index, left), copy_source(statements, index, right));
+        if modified(left) || modified(right) {
+            return None;
+        }
+
+        Some(Comparison { op: *op, left, right })
+    }
+
+    fn apply_switch_int_edge_effect(
+        &mut self,
+        comparison: &mut Self::SwitchIntData,
+        state: &mut Self::Domain,
+        value: SwitchTargetValue,
+        targets: &SwitchTargets,
+    ) {
+        let holds = match value {
+            SwitchTargetValue::Normal(value) => value != 0,
+            // The otherwise branch is taken for `true` if only `false` has its own target.
+            SwitchTargetValue::Otherwise => match targets.all_values() {
+                [value] => value.get() == 0,
+                _ => return,
+            },
+        };
+        if state.is_reachable() {
+            self.assume(state, comparison, holds);
+        }
+    }
+}
+
+impl<'a, 'tcx> IntervalAnalysis<'a, 'tcx> {
+    fn new(tcx: TyCtxt<'tcx>, body: &'a Body<'tcx>, map: Map<'tcx>) -> Self {
+        Self { tcx, body, map, typing_env: body.typing_env(tcx) }
+    }
+
+    fn is_unsigned(&self, ty: Ty<'tcx>) -> bool {
+        matches!(ty.kind(), ty::Uint(_))
+    }
+
+    /// Returns the maximum value of `ty`, if it is an unsigned integer type.
+    fn unsigned_max(&self, ty: Ty<'tcx>) -> Option<u128> {
+        match ty.kind() {
+            ty::Uint(uint_ty) => {
+                Some(Integer::from_uint_ty(&self.tcx, *uint_ty).size().unsigned_int_max())
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the bound that designates the current value of `place`, if it is tracked.
+    fn place_bound(&self, place: PlaceRef<'_>) -> Option<Bound> {
+        let index = self.map.find(place)?;
+        Some(Bound { local: place.local, place: index })
+    }
+
+    /// Returns a bound that is equal to `operand`, whose value is `value`.
+    fn bound_of(&self, operand: &Operand<'tcx>, value: &Interval) -> Option<Bound> {
+        if let Interval::Known(Bounds { equal_to: Some(bound), .. }) = value {
+            return Some(*bound);
+        }
+        self.place_bound(operand.place()?.as_ref())
+    }
+
+    /// Must be called before `place` is written to, and before the written value is read from
+    /// the state.
+    fn forget_relations_to(&self, state: &mut State<Interval>, place: PlaceRef<'_>) {
+        // Writes through a pointer never reach a tracked place, and a local that has no tracked
+        // place cannot be referred to.
+        if place.is_indirect_first_projection() || self.map.find(place.local.into()).is_none() {
+            return;
+        }
+        state.for_each_value_mut(|value| {
+            if let Interval::Known(bounds) = value {
+                bounds.forget(place.local);
+            }
+        });
+    }
+
+    fn handle_statement(&self, statement: &Statement<'tcx>, state: &mut State<Interval>) {
+        match &statement.kind {
+            StatementKind::Assign(box (place, rvalue)) => {
+                self.handle_assign(*place, rvalue, state);
+            }
+            StatementKind::SetDiscriminant { box place, .. } => {
+                self.forget_relations_to(state, place.as_ref());
+                state.flood_discr(place.as_ref(), &self.map);
+            }
+            StatementKind::StorageLive(local) | StatementKind::StorageDead(local) => {
+                // StorageLive leaves the local in an uninitialized state.
+                // StorageDead makes it UB to access the local afterwards.
+                let place = Place::from(*local);
+                self.forget_relations_to(state, place.as_ref());
+                state.flood_with(place.as_ref(), &self.map, Interval::BOTTOM);
+            }
+            StatementKind::Deinit(box place) => {
+                // Deinit makes the place uninitialized.
+                self.forget_relations_to(state, place.as_ref());
+                state.flood_with(place.as_ref(), &self.map, Interval::BOTTOM);
+            }
+            StatementKind::Intrinsic(..) => {
+                // Intrinsics only write through pointers, so they cannot reach a tracked place.
+            }
+            StatementKind::Retag(..)
+            | StatementKind::ConstEvalCounter
+            | StatementKind::Nop
+            | StatementKind::FakeRead(..)
+            | StatementKind::PlaceMention(..)
+            | StatementKind::Coverage(..)
+            | StatementKind::BackwardIncompatibleDropHint { .. }
+            | StatementKind::AscribeUserType(..) => {}
+        }
+    }
+
+    fn handle_assign(
+        &self,
+        target: Place<'tcx>,
+        rvalue: &Rvalue<'tcx>,
+        state: &mut State<Interval>,
+    ) {
+        match rvalue {
+            Rvalue::Use(Operand::Copy(source) | Operand::Move(source))
+            | Rvalue::CopyForDeref(source) => {
+                self.forget_relations_to(state, target.as_ref());
+                state.flood(target.as_ref(), &self.map);
+                let Some(target_index) = self.map.find(target.as_ref()) else { return };
+                if let Some(source_index) = self.map.find(source.as_ref()) {
+                    state.insert_place_idx(target_index, source_index, &self.map);
+                }
+                // The target is now equal to the source, until either of them is modified.
+                if source.local != target.local
+                    && let Some(bound) = self.place_bound(source.as_ref())
+                    && let Interval::Known(mut bounds) = state.get_idx(target_index, &self.map)
+                    && bounds.equal_to.is_none()
+                {
+                    bounds.equal_to = Some(bound);
+                    state.insert_value_idx(target_index, Interval::Known(bounds), &self.map);
+                }
+            }
+            Rvalue::Aggregate(kind, operands) => {
+                // Evaluate the fields first, so that they can still refer to the previous value of
+                // the target.
+                let fields: Vec<_> = operands
+                    .iter_enumerated()
+                    .map(|(field_index, operand)| (field_index, self.eval_copy(operand, state)))
+                    .collect();
+
+                self.forget_relations_to(state, target.as_ref());
+                state.flood(target.as_ref(), &self.map);
+                let Some(target_index) = self.map.find(target.as_ref()) else { return };
+                let variant_index = match **kind {
+                    AggregateKind::Tuple => Some(target_index),
+                    AggregateKind::Adt(def_id, variant_index, ..) => {
+                        match self.tcx.def_kind(def_id) {
+                            DefKind::Struct => Some(target_index),
+                            DefKind::Enum => {
+                                self.map.apply(target_index, TrackElem::Variant(variant_index))
+                            }
+                            _ => return,
+                        }
+                    }
+                    _ => return,
+                };
+                let Some(variant_index) = variant_index else { return };
+                for (field_index, mut value) in fields {
+                    if let Some(field) =
+                        self.map.apply(variant_index, TrackElem::Field(field_index))
+                    {
+                        if let Interval::Known(bounds) = &mut value {
+                            bounds.forget(target.local);
+                        }
+                        state.insert_value_idx(field, value, &self.map);
+                    }
+                }
+            }
+            Rvalue::BinaryOp(op, box (left, right)) if op.is_overflowing() => {
+                // Only the wrapped result is tracked, the overflow flag is left as ⊤.
+                let op = op.overflowing_to_wrapping().unwrap();
+                let ty = left.ty(self.body, self.tcx);
+                let value = match self.unsigned_max(ty) {
+                    Some(type_max) => self.binary_op(op, left, right, type_max, state),
+                    None => Interval::TOP,
+                };
+                let field = target.project_deeper(&[PlaceElem::Field(0_u32.into(), ty)], self.tcx);
+                self.forget_relations_to(state, target.as_ref());
+                state.flood(target.as_ref(), &self.map);
+                self.assign_value(field, value, state);
+            }
+            _ => {
+                let value = self.handle_rvalue(rvalue, state);
+                self.forget_relations_to(state, target.as_ref());
+                self.assign_value(target, value, state);
+            }
+        }
+    }
+
+    /// Assigns `value` to `target`. The caller must have called `forget_relations_to`.
+    fn assign_value(&self, target: Place<'tcx>, mut value: Interval, state: &mut State<Interval>) {
+        if let Interval::Known(bounds) = &mut value {
+            bounds.forget(target.local);
+        }
+        state.assign(target.as_ref(), ValueOrPlace::Value(value), &self.map);
+    }
+
+    fn handle_rvalue(&self, rvalue: &Rvalue<'tcx>, state: &State<Interval>) -> Interval {
+        let Some(type_max) = self.unsigned_max(rvalue.ty(self.body, self.tcx)) else {
+            return Interval::TOP;
+        };
+        match rvalue {
+            Rvalue::Use(operand) => self.eval_operand(operand, state),
+            Rvalue::UnaryOp(UnOp::PtrMetadata, operand) => {
+                let Some(place) = operand.place() else { return Interval::TOP };
+                let Some(len) = self.map.find_len(place.as_ref()) else { return Interval::TOP };
+                let mut value = state.get_idx(len, &self.map);
+                if let Interval::Known(bounds) = &mut value
+                    && bounds.equal_to.is_none()
+                {
+                    bounds.equal_to = Some(Bound { local: place.local, place: len });
+                }
+                value
+            }
+            Rvalue::Cast(CastKind::IntToInt, operand, _) => {
+                if !self.is_unsigned(operand.ty(self.body, self.tcx)) {
+                    return Interval::TOP;
+                }
+                // The numeric value is preserved if it fits in the target type.
+                match self.eval_operand(operand, state) {
+                    Interval::Known(bounds) if bounds.max > type_max => Interval::TOP,
+                    value => value,
+                }
+            }
+            Rvalue::BinaryOp(op, box (left, right)) => {
+                self.binary_op(*op, left, right, type_max, state)
+            }
+            _ => Interval::TOP,
+        }
+    }
+
+    /// Computes `left op right`, whose type has the maximum value `type_max`.
+    fn binary_op(
+        &self,
+        op: BinOp,
+        left: &Operand<'tcx>,
+        right: &Operand<'tcx>,
+        type_max: u128,
+        state: &State<Interval>,
+    ) -> Interval {
+        let left_value = self.eval_operand(left, state);
+        let right_value = self.eval_operand(right, state);
+        let (Interval::Known(l), Interval::Known(r)) = (left_value, right_value) else {
+            return Interval::Bottom;
+        };
+
+        let bounds = match op {
+            BinOp::Add | BinOp::AddUnchecked | BinOp::Mul | BinOp::MulUnchecked => {
+                let (min, max) = if let BinOp::Add | BinOp::AddUnchecked = op {
+                    (l.min.checked_add(r.min), l.max.checked_add(r.max))
+                } else {
+                    (l.min.checked_mul(r.min), l.max.checked_mul(r.max))
+                };
+                match (min, max) {
+                    (Some(min), Some(max)) if max <= type_max => Bounds::new(min, max),
+                    // Overflowing is UB for the unchecked operations, so the minimum still holds.
+                    (Some(min), _)
+                        if matches!(op, BinOp::AddUnchecked | BinOp::MulUnchecked)
+                            && min <= type_max =>
+                    {
+                        Bounds::new(min, type_max)
+                    }
+                    _ => return Interval::TOP,
+                }
+            }
+            BinOp::Sub | BinOp::SubUnchecked => {
+                // Without underflow, the difference is at most `left`.
+                let less_than =
+                    if r.min > 0 { self.bound_of(left, &left_value) } else { l.less_than };
+                if l.min >= r.max {
+                    Bounds { min: l.min - r.max, max: l.max - r.min, less_than, equal_to: None }
+                } else if let BinOp::SubUnchecked = op {
+                    // Underflowing is UB for the unchecked operation.
+                    let max = l.max.saturating_sub(r.min);
+                    Bounds { min: 0, max, less_than, equal_to: None }
+                } else {
+                    return Interval::TOP;
+                }
+            }
+            // Dividing by zero is UB, so `right` is at least 1.
+            BinOp::Div => Bounds {
+                min: l.min / r.max.max(1),
+                max: l.max / r.min.max(1),
+                less_than: l.less_than,
+                equal_to: None,
+            },
+            BinOp::Rem => {
+                if l.max < r.min {
+                    return left_value;
+                }
+                // The remainder is less than both `left` and `right`, which is at least 1.
+                Bounds {
+                    min: 0,
+                    max: l.max.min(r.max.saturating_sub(1)),
+                    less_than: self.bound_of(right, &right_value).or(l.less_than),
+                    equal_to: None,
+                }
+            }
+            BinOp::BitAnd => Bounds {
+                min: 0,
+                max: l.max.min(r.max),
+                less_than: l.less_than.or(r.less_than),
+                equal_to: None,
+            },
+            BinOp::Shr | BinOp::ShrUnchecked => {
+                // An out-of-range shift amount is masked, so it only gives a bound when in range.
+                let bits = u128::from(type_max.count_ones());
+                let max = if r.max < bits { l.max >> r.min } else { l.max };
+                Bounds { min: 0, max, less_than: l.less_than, equal_to: None }
+            }
+            _ => return Interval::TOP,
+        };
+        Interval::Known(bounds)
+    }
+
+    fn eval_operand(&self, operand: &Operand<'tcx>, state: &State<Interval>) -> Interval {
+        match operand {
+            Operand::Constant(box constant) => {
+                if self.is_unsigned(constant.ty())
+                    && let Some(bits) = constant.const_.try_eval_bits(self.tcx, self.typing_env)
+                {
+                    Interval::Known(Bounds::new(bits, bits))
+                } else {
+                    Interval::TOP
+                }
+            }
+            Operand::Copy(place) | Operand::Move(place) => self
+                .map
+                .find(place.as_ref())
+                .map_or(Interval::TOP, |place| state.get_idx(place, &self.map)),
+        }
+    }
+
+    /// Evaluates `operand`, remembering that the result is equal to it.
+    fn eval_copy(&self, operand: &Operand<'tcx>, state: &State<Interval>) -> Interval {
+        let mut value = self.eval_operand(operand, state);
+        if let Interval::Known(bounds) = &mut value
+            && bounds.equal_to.is_none()
+            && let Some(place) = operand.place()
+        {
+            bounds.equal_to = self.place_bound(place.as_ref());
+        }
+        value
+    }
+
+    /// Refines `state` with the fact that `comparison` evaluated to `holds`.
+    fn assume(&self, state: &mut State<Interval>, comparison: &Comparison<'_, 'tcx>, holds: bool) {
+        let &Comparison { op, left, right } = comparison;
+        match (op, holds) {
+            (BinOp::Lt, true) | (BinOp::Ge, false) => self.assume_less(state, left, right, true),
+            (BinOp::Lt, false) | (BinOp::Ge, true) => self.assume_less(state, right, left, false),
+            (BinOp::Le, true) | (BinOp::Gt, false) => self.assume_less(state, left, right, false),
+            (BinOp::Le, false) | (BinOp::Gt, true) => self.assume_less(state, right, left, true),
+            (BinOp::Eq, true) | (BinOp::Ne, false) => {
+                self.assume_less(state, left, right, false);
+                self.assume_less(state, right, left, false);
+            }
+            (BinOp::Eq, false) | (BinOp::Ne, true) => {
+                self.assume_not_equal(state, left, right);
+                self.assume_not_equal(state, right, left);
+            }
+            _ => bug!("unexpected comparison {op:?}"),
+        }
+    }
+
+    /// Refines `state` with `small < large`, or `small <= large` if not `strict`.
+    fn assume_less(
+        &self,
+        state: &mut State<Interval>,
+        small: &Operand<'tcx>,
+        large: &Operand<'tcx>,
+        strict: bool,
+    ) {
+        let small_value = self.eval_operand(small, state);
+        let large_value = self.eval_operand(large, state);
+        let (Interval::Known(mut s), Interval::Known(mut l)) = (small_value, large_value) else {
+            return;
+        };
+
+        let offset = u128::from(strict);
+        let Some(max) = l.max.checked_sub(offset) else {
+            // Nothing is less than zero.
+            *state = State::Unreachable;
+            return;
+        };
+        s.max = s.max.min(max);
+        l.min = l.min.max(s.min.saturating_add(offset));
+        if s.min > s.max || l.min > l.max {
+            *state = State::Unreachable;
+            return;
+        }
+
+        if strict
+            && let Some(bound) = self.bound_of(large, &large_value)
+            && small.place().is_none_or(|place| bound.local != place.local)
+        {
+            s.less_than = Some(bound);
+        }
+        self.refine(state, small, Interval::Known(s));
+        self.refine(state, large, Interval::Known(l));
+    }
+
+    /// Refines `state` with `value != other`, when `other` is known exactly.
+    fn assume_not_equal(
+        &self,
+        state: &mut State<Interval>,
+        value: &Operand<'tcx>,
+        other: &Operand<'tcx>,
+    ) {
+        let (Interval::Known(mut v), Interval::Known(o)) =
+            (self.eval_operand(value, state), self.eval_operand(other, state))
+        else {
+            return;
+        };
+        if o.min != o.max {
+            return;
+        }
+        if v.min == o.min && v.max == o.min {
+            *state = State::Unreachable;
+            return;
+        }
+        if v.min == o.min {
+            v.min += 1;
+        } else if v.max == o.min {
+            v.max -= 1;
+        } else {
+            return;
+        }
+        self.refine(state, value, Interval::Known(v));
+    }
+
+    /// Stores the refined `value` of `operand`, if it is a tracked place.
+    fn refine(&self, state: &mut State<Interval>, operand: &Operand<'tcx>, value: Interval) {
+        if let Some(place) = operand.place()
+            && let Some(index) = self.map.find(place.as_ref())
+        {
+            state.insert_value_idx(index, value, &self.map);
+        }
+    }
+
+    /// Whether `left < right` always holds in `state`.
+    fn is_less_than(
+        &self,
+        state: &State<Interval>,
+        left: &Operand<'tcx>,
+        right: &Operand<'tcx>,
+    ) -> bool {
+        let left_value = self.eval_operand(left, state);
+        let right_value = self.eval_operand(right, state);
+        let (Interval::Known(l), Interval::Known(r)) = (left_value, right_value) else {
+            return false;
+        };
+        if l.max < r.min {
+            return true;
+        }
+
+        let Some(right_bound) = self.bound_of(right, &right_value) else { return false };
+        if l.less_than == Some(right_bound) {
+            return true;
+        }
+        // `left` may be a copy of a place that is known to be less than `right`.
+        if let Some(left_bound) = l.equal_to
+            && let Interval::Known(bounds) = state.get_idx(left_bound.place, &self.map)
+        {
+            return bounds.less_than == Some(right_bound);
+        }
+        false
+    }
+}
+
+/// This is used to visualize the dataflow analysis.
+impl<'tcx> DebugWithContext<IntervalAnalysis<'_, 'tcx>> for State<Interval> {
+    fn fmt_with(
+        &self,
+        ctxt: &IntervalAnalysis<'_, 'tcx>,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        match self {
+            State::Reachable(values) => debug_with_context(values, None, &ctxt.map, f),
+            State::Unreachable => write!(f, "unreachable"),
+        }
+    }
+
+    fn fmt_diff_with(
+        &self,
+        old: &Self,
+        ctxt: &IntervalAnalysis<'_, 'tcx>,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        match (self, old) {
+            (State::Reachable(this), State::Reachable(old)) => {
+                debug_with_context(this, Some(old), &ctxt.map, f)
+            }
+            _ => Ok(()), // Consider printing something here.
+        }
+    }
+}
+
+/// Collects the blocks whose bounds check always passes.
+struct Collector {
+    candidates: FxHashSet<Location>,
+    redundant: Vec<BasicBlock>,
+}
+
+impl<'tcx> ResultsVisitor<'tcx, IntervalAnalysis<'_, 'tcx>> for Collector {
+    fn visit_after_early_statement_effect(
+        &mut self,
+        analysis: &mut IntervalAnalysis<'_, 'tcx>,
+        state: &State<Interval>,
+        statement: &Statement<'tcx>,
+        location: Location,
+    ) {
+        if self.candidates.contains(&location)
+            && let StatementKind::Assign(box (_, Rvalue::BinaryOp(BinOp::Lt, box (index, len)))) =
+                &statement.kind
+            && analysis.is_less_than(state, index, len)
+        {
+            self.redundant.push(location.block);
+        }
+    }
+}
@@ -1,18 +1,56 @@
+use std::collections::BTreeSet;
+
+use rustc_data_structures::fx::FxIndexMap;
 use rustc_index::IndexVec;
 use rustc_middle::mir::coverage::{
-    BlockMarkerId, BranchSpan, CoverageInfoHi, CoverageKind, Mapping, MappingKind,
+    BasicCoverageBlock, BlockMarkerId, BranchSpan, ConditionId, ConditionInfo, CoverageInfoHi,
+    CoverageKind, MCDCBranchSpan, Mapping, MappingKind,
 };
 use rustc_middle::mir::{self, BasicBlock, StatementKind};
 use rustc_middle::ty::TyCtxt;
+use rustc_span::Span;
 
 use crate::coverage::graph::CoverageGraph;
 use crate::coverage::hir_info::ExtractedHirInfo;
 use crate::coverage::spans::extract_refined_covspans;
 use crate::coverage::unexpand::unexpand_into_body_span;
+use crate::errors::MCDCExceedsTestVectorLimit;
+
+/// Associates an MC/DC condition with the coverage graph nodes that are
+/// executed when it is true or false.
+#[derive(Debug)]
+pub(crate) struct MCDCBranch {
+    pub(crate) span: Span,
+    pub(crate) true_bcb: BasicCoverageBlock,
+    pub(crate) false_bcb: BasicCoverageBlock,
+    pub(crate) condition_info: ConditionInfo,
+    /// Offset added to the test vector index if this condition is true.
+    pub(crate) true_index: usize,
+    /// Offset added to the test vector index if this condition is false.
+    pub(crate) false_index: usize,
+}
+
+/// Associates an MC/DC decision with the coverage graph nodes that are
+/// executed once it has been fully evaluated.
+#[derive(Debug)]
+pub(crate) struct MCDCDecision {
+    pub(crate) span: Span,
+    pub(crate) end_bcbs: BTreeSet<BasicCoverageBlock>,
+    /// Index of the first bit of this decision in the function's test-vector bitmap.
+    pub(crate) bitmap_idx: usize,
+    pub(crate) num_test_vectors: usize,
+    pub(crate) decision_depth: u16,
+}
+
+/// LLVM stores the bitmap size of a function as an `i32`, so no function can
+/// have more test vectors than that in total.
+pub(super) const MCDC_MAX_BITMAP_SIZE: usize = i32::MAX as usize;
 
 #[derive(Default)]
 pub(crate) struct ExtractedMappings {
     pub(crate) mappings: Vec<Mapping>,
+    pub(crate) mcdc_bitmap_bits: usize,
+    pub(crate) mcdc_mappings: Vec<(MCDCDecision, Vec<MCDCBranch>)>,
 }
 
 /// Extracts coverage-relevant spans from MIR, and uses them to create
@@ -30,7 +68,19 @@ pub(crate) fn extract_mappings_from_mir<'tcx>(
 
     extract_branch_mappings(mir_body, hir_info, graph, &mut mappings);
 
-    ExtractedMappings { mappings }
+    let mut mcdc_bitmap_bits = 0;
+    let mut mcdc_mappings = vec![];
+    extract_mcdc_mappings(
+        mir_body,
+        tcx,
+        hir_info,
+        graph,
+        &mut mcdc_bitmap_bits,
+        &mut mappings,
+        &mut mcdc_mappings,
+    );
+
+    ExtractedMappings { mappings, mcdc_bitmap_bits, mcdc_mappings }
 }
 
 fn resolve_block_markers(
@@ -66,19 +116,212 @@ pub(super) fn extract_branch_mappings(
 
     mappings.extend(coverage_info_hi.branch_spans.iter().filter_map(
         |&BranchSpan { span: raw_span, true_marker, false_marker }| try {
-            // For now, ignore any branch span that was introduced by
-            // expansion. This makes things like assert macros less noisy.
-            if !raw_span.ctxt().outer_expn_data().is_root() {
-                return None;
-            }
-            let span = unexpand_into_body_span(raw_span, hir_info.body_span)?;
-
-            let bcb_from_marker = |marker: BlockMarkerId| graph.bcb_from_bb(block_markers[marker]?);
-
-            let true_bcb = bcb_from_marker(true_marker)?;
-            let false_bcb = bcb_from_marker(false_marker)?;
+            let (span, true_bcb, false_bcb) = check_branch_bcb(
+                raw_span,
+                true_marker,
+                false_marker,
+                hir_info,
+                graph,
+                &block_markers,
+            )?;
 
             Mapping { span, kind: MappingKind::Branch { true_bcb, false_bcb } }
         },
     ));
 }
+
+fn check_branch_bcb(
+    raw_span: Span,
+    true_marker: BlockMarkerId,
+    false_marker: BlockMarkerId,
+    hir_info: &ExtractedHirInfo,
+    graph: &CoverageGraph,
+    block_markers: &IndexVec<BlockMarkerId, Option<BasicBlock>>,
+) -> Option<(Span, BasicCoverageBlock, BasicCoverageBlock)> {
+    // For now, ignore any branch span that was introduced by
+    // expansion. This makes things like assert macros less noisy.
+    if !raw_span.ctxt().outer_expn_data().is_root() {
+        return None;
+    }
+    let span = unexpand_into_body_span(raw_span, hir_info.body_span)?;
+
+    let bcb_from_marker = |marker: BlockMarkerId| graph.bcb_from_bb(block_markers[marker]?);
+
+    let true_bcb = bcb_from_marker(true_marker)?;
+    let false_bcb = bcb_from_marker(false_marker)?;
+    Some((span, true_bcb, false_bcb))
+}
+
+/// Reserves `num_test_vectors` bits in the function's test-vector bitmap,
+/// returning the index of the first one, or `None` if the bitmap would become
+/// too large.
+pub(super) fn try_reserve_bitmap_bits(
+    mcdc_bitmap_bits: &mut usize,
+    num_test_vectors: usize,
+) -> Option<usize> {
+    let bitmap_idx = *mcdc_bitmap_bits;
+    let next_bitmap_bits = bitmap_idx.saturating_add(num_test_vectors);
+    (next_bitmap_bits <= MCDC_MAX_BITMAP_SIZE).then(|| {
+        *mcdc_bitmap_bits = next_bitmap_bits;
+        bitmap_idx
+    })
+}
+
+pub(super) fn extract_mcdc_mappings(
+    mir_body: &mir::Body<'_>,
+    tcx: TyCtxt<'_>,
+    hir_info: &ExtractedHirInfo,
+    graph: &CoverageGraph,
+    mcdc_bitmap_bits: &mut usize,
+    mappings: &mut Vec<Mapping>,
+    mcdc_mappings: &mut Vec<(MCDCDecision, Vec<MCDCBranch>)>,
+) {
+    let Some(coverage_info_hi) = mir_body.coverage_info_hi.as_deref() else { return };
+
+    let block_markers = resolve_block_markers(coverage_info_hi, mir_body);
+
+    let get_mapping_from_branch = |branch: &MCDCBranchSpan| {
+        let &MCDCBranchSpan { span: raw_span, condition_info, true_marker, false_marker } = branch;
+        let (span, true_bcb, false_bcb) =
+            check_branch_bcb(raw_span, true_marker, false_marker, hir_info, graph, &block_markers)?;
+        Some(MCDCBranch {
+            span,
+            true_bcb,
+            false_bcb,
+            condition_info,
+            true_index: usize::MAX,
+            false_index: usize::MAX,
+        })
+    };
+
+    // Conditions that could not be instrumented as part of a decision are
+    // still reported as ordinary branches.
+    let degrade = |branch: MCDCBranch| Mapping {
+        span: branch.span,
+        kind: MappingKind::Branch { true_bcb: branch.true_bcb, false_bcb: branch.false_bcb },
+    };
+
+    mappings.extend(
+        coverage_info_hi
+            .mcdc_degraded_branch_spans
+            .iter()
+            .filter_map(get_mapping_from_branch)
+            .map(degrade),
+    );
+
+    for (decision, branches) in &coverage_info_hi.mcdc_spans {
+        if branches.is_empty() {
+            continue;
+        }
+        let Some(decision_span) = unexpand_into_body_span(decision.span, hir_info.body_span) else {
+            continue;
+        };
+
+        let end_bcbs = decision
+            .end_markers
+            .iter()
+            .map(|&marker| graph.bcb_from_bb(block_markers[marker]?))
+            .collect::<Option<BTreeSet<_>>>();
+
+        let mut branch_mappings: Vec<_> =
+            branches.iter().filter_map(get_mapping_from_branch).collect();
+        let Some(end_bcbs) = end_bcbs.filter(|_| branch_mappings.len() == branches.len()) else {
+            mappings.extend(branch_mappings.into_iter().map(degrade));
+            continue;
+        };
+
+        let num_test_vectors = calc_test_vectors_index(&mut branch_mappings);
+        let Some(bitmap_idx) = try_reserve_bitmap_bits(mcdc_bitmap_bits, num_test_vectors) else {
+            tcx.dcx().emit_warn(MCDCExceedsTestVectorLimit {
+                span: decision_span,
+                max_num_test_vectors: MCDC_MAX_BITMAP_SIZE,
+            });
+            mappings.extend(branch_mappings.into_iter().map(degrade));
+            continue;
+        };
+
+        // LLVM requires the span of a decision to contain the spans of all of
+        // its conditions, which might not be the case if macros are involved.
+        let span = branch_mappings
+            .iter()
+            .map(|branch| branch.span)
+            .fold(decision_span, |decision_span, branch_span| decision_span.to(branch_span));
+
+        let decision = MCDCDecision {
+            span,
+            end_bcbs,
+            bitmap_idx,
+            num_test_vectors,
+            decision_depth: decision.decision_depth,
+        };
+        mcdc_mappings.push((decision, branch_mappings));
+    }
+}
+
+/// Assigns the test vector offsets of each condition in a decision, in the
+/// same way as LLVM's `TVIdxBuilder`, and returns the number of distinct test
+/// vectors that the decision can produce.
+///
+/// Each path through the decision's conditions is numbered, so that adding up
+/// the offsets of the outcomes taken along a path yields a unique index for
+/// that path's test vector.
+pub(super) fn calc_test_vectors_index(conditions: &mut Vec<MCDCBranch>) -> usize {
+    let mut indegree_stats = IndexVec::<ConditionId, usize>::from_elem_n(0, conditions.len());
+    // Number of distinct paths that reach each condition.
+    let mut num_paths_stats = IndexVec::<ConditionId, usize>::from_elem_n(0, conditions.len());
+    let mut next_conditions = conditions
+        .iter_mut()
+        .map(|branch| {
+            let ConditionInfo { condition_id, true_next_id, false_next_id } = branch.condition_info;
+            [true_next_id, false_next_id]
+                .into_iter()
+                .flatten()
+                .for_each(|next_id| indegree_stats[next_id] += 1);
+            (condition_id, branch)
+        })
+        .collect::<FxIndexMap<_, _>>();
+
+    let mut queue =
+        std::collections::VecDeque::from_iter(next_conditions.swap_remove(&ConditionId::START));
+    num_paths_stats[ConditionId::START] = 1;
+    let mut decision_end_nodes = Vec::new();
+    while let Some(branch) = queue.pop_front() {
+        let ConditionInfo { condition_id, true_next_id, false_next_id } = branch.condition_info;
+        let (false_index, true_index) = (&mut branch.false_index, &mut branch.true_index);
+        let this_paths_count = num_paths_stats[condition_id];
+        // Visit the false outcome first, so that conditions are numbered in
+        // the same order as `llvm-cov` expects.
+        for (next, index) in [(false_next_id, false_index), (true_next_id, true_index)] {
+            if let Some(next_id) = next {
+                let next_paths_count = &mut num_paths_stats[next_id];
+                *index = *next_paths_count;
+                *next_paths_count = next_paths_count.saturating_add(this_paths_count);
+                let next_indegree = &mut indegree_stats[next_id];
+                *next_indegree -= 1;
+                if *next_indegree == 0 {
+                    queue.push_back(next_conditions.swap_remove(&next_id).expect(
+                        "conditions with non-zero indegree before must be in next_conditions",
+                    ));
+                }
+            } else {
+                decision_end_nodes.push((this_paths_count, condition_id, index));
+            }
+        }
+    }
+    assert!(next_conditions.is_empty(), "the decision tree has untouched nodes");
+    let mut cur_idx = 0;
+    // LLVM expects the end nodes to be sorted in descending order of their
+    // path counts, which keeps the bitmap small for decisions shaped like
+    // `a && b && c && ...`.
+    decision_end_nodes.sort_by_key(|&(num_paths, _, _)| std::cmp::Reverse(num_paths));
+    for (num_paths, condition_id, index) in decision_end_nodes {
+        assert_eq!(
+            num_paths, num_paths_stats[condition_id],
+            "end nodes should not be updated since they were visited"
+        );
+        assert_eq!(*index, usize::MAX, "end nodes should not be assigned index before");
+        *index = cur_idx;
+        cur_idx = cur_idx.saturating_add(num_paths);
+    }
+    cur_idx
+}
@@ -1,11 +1,13 @@
-use rustc_middle::mir::coverage::{CoverageKind, FunctionCoverageInfo};
+use rustc_middle::mir::coverage::{
+    CoverageKind, DecisionInfo, FunctionCoverageInfo, Mapping, MappingKind,
+};
 use rustc_middle::mir::{self, BasicBlock, Statement, StatementKind, TerminatorKind};
 use rustc_middle::ty::TyCtxt;
 use tracing::{debug, debug_span, trace};
 
 use crate::coverage::counters::BcbCountersData;
 use crate::coverage::graph::CoverageGraph;
-use crate::coverage::mappings::ExtractedMappings;
+use crate::coverage::mappings::{ExtractedMappings, MCDCBranch, MCDCDecision};
 
 mod counters;
 mod expansion;
@@ -72,7 +74,7 @@ fn instrument_function_for_coverage<'tcx>(tcx: TyCtxt<'tcx>, mir_body: &mut mir:
 
     ////////////////////////////////////////////////////
     // Extract coverage spans and other mapping info from MIR.
-    let ExtractedMappings { mappings } =
+    let ExtractedMappings { mut mappings, mcdc_bitmap_bits, mcdc_mappings } =
         mappings::extract_mappings_from_mir(tcx, mir_body, &hir_info, &graph);
     if mappings.is_empty() {
         // No spans could be converted into valid mappings, so skip this function.
@@ -88,6 +90,32 @@ fn instrument_function_for_coverage<'tcx>(tcx: TyCtxt<'tcx>, mir_body: &mut mir:
 
     // Inject coverage statements into MIR.
     inject_coverage_statements(mir_body, &graph);
+    inject_mcdc_statements(mir_body, &graph, &mcdc_mappings);
+
+    let mcdc_num_condition_bitmaps = mcdc_mappings
+        .iter()
+        .map(|&(MCDCDecision { decision_depth, .. }, _)| decision_depth)
+        .max()
+        .map_or(0, |max| usize::from(max) + 1);
+
+    mappings.extend(mcdc_mappings.into_iter().flat_map(|(decision, branches)| {
+        let decision_mapping = Mapping {
+            span: decision.span,
+            kind: MappingKind::MCDCDecision(DecisionInfo {
+                // LLVM expects the decision region to record the index just
+                // past the end of the decision's test vectors.
+                bitmap_idx: (decision.bitmap_idx + decision.num_test_vectors) as u32,
+                num_conditions: branches.len() as u16,
+            }),
+        };
+        let branch_mappings = branches.into_iter().map(
+            |MCDCBranch { span, true_bcb, false_bcb, condition_info, .. }| Mapping {
+                span,
+                kind: MappingKind::MCDCBranch { true_bcb, false_bcb, mcdc_params: condition_info },
+            },
+        );
+        std::iter::once(decision_mapping).chain(branch_mappings)
+    }));
 
     mir_body.function_coverage_info = Some(Box::new(FunctionCoverageInfo {
         function_source_hash: hir_info.function_source_hash,
@@ -96,6 +124,9 @@ fn instrument_function_for_coverage<'tcx>(tcx: TyCtxt<'tcx>, mir_body: &mut mir:
         priority_list,
 
         mappings,
+
+        mcdc_bitmap_bits,
+        mcdc_num_condition_bitmaps,
     }));
 }
 
@@ -107,6 +138,44 @@ fn inject_coverage_statements<'tcx>(mir_body: &mut mir::Body<'tcx>, graph: &Cove
     }
 }
 
+/// For each conditions inject statements to update condition bitmap after it has been evaluated.
+/// For each decision inject statements to update test vector bitmap after it has been evaluated.
+fn inject_mcdc_statements<'tcx>(
+    mir_body: &mut mir::Body<'tcx>,
+    graph: &CoverageGraph,
+    mcdc_mappings: &[(MCDCDecision, Vec<MCDCBranch>)],
+) {
+    for (decision, conditions) in mcdc_mappings {
+        // Inject test vector update first because `inject_statement` always insert new statement at
+        // head.
+        for &end in &decision.end_bcbs {
+            let end_bb = graph[end].leader_bb();
+            inject_statement(
+                mir_body,
+                CoverageKind::TestVectorBitmapUpdate {
+                    bitmap_idx: decision.bitmap_idx as u32,
+                    decision_depth: decision.decision_depth,
+                },
+                end_bb,
+            );
+        }
+
+        for &MCDCBranch { true_bcb, false_bcb, true_index, false_index, .. } in conditions {
+            for (index, bcb) in [(false_index, false_bcb), (true_index, true_bcb)] {
+                let bb = graph[bcb].leader_bb();
+                inject_statement(
+                    mir_body,
+                    CoverageKind::CondBitmapUpdate {
+                        index: index as u32,
+                        decision_depth: decision.decision_depth,
+                    },
+                    bb,
+                );
+            }
+        }
+    }
+}
+
 fn inject_statement(mir_body: &mut mir::Body<'_>, counter_kind: CoverageKind, bb: BasicBlock) {
     debug!("  injecting statement {counter_kind:?} for {bb:?}");
     let data = &mut mir_body[bb];
@@ -107,10 +107,12 @@ fn coverage_ids_info<'tcx>(
             MappingKind::Code { bcb } => {
                 bcb_needs_counter.insert(bcb);
             }
-            MappingKind::Branch { true_bcb, false_bcb } => {
+            MappingKind::Branch { true_bcb, false_bcb }
+            | MappingKind::MCDCBranch { true_bcb, false_bcb, .. } => {
                 bcb_needs_counter.insert(true_bcb);
                 bcb_needs_counter.insert(false_bcb);
             }
+            MappingKind::MCDCDecision(_) => {}
         }
     }
 
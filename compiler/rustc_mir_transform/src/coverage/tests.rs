@@ -32,6 +32,9 @@ use rustc_middle::{bug, ty};
 use rustc_span::{BytePos, DUMMY_SP, Pos, Span};
 
 use super::graph::{self, BasicCoverageBlock};
+use super::mappings::{
+    MCDC_MAX_BITMAP_SIZE, MCDCBranch, calc_test_vectors_index, try_reserve_bitmap_bits,
+};
 
 fn bcb(index: u32) -> BasicCoverageBlock {
     BasicCoverageBlock::from_u32(index)
@@ -525,3 +528,55 @@ fn test_covgraph_switchint_loop_then_inner_loop_else_break() {
     assert_successors(&graph, bcb(5), &[bcb(1)]);
     assert_successors(&graph, bcb(6), &[bcb(4)]);
 }
+
+fn mcdc_branch(id: u32, true_next: Option<u32>, false_next: Option<u32>) -> MCDCBranch {
+    let condition_id = |id: u32| coverage::ConditionId::from_u32(id);
+    MCDCBranch {
+        span: DUMMY_SP,
+        true_bcb: bcb(0),
+        false_bcb: bcb(0),
+        condition_info: coverage::ConditionInfo {
+            condition_id: condition_id(id),
+            true_next_id: true_next.map(condition_id),
+            false_next_id: false_next.map(condition_id),
+        },
+        true_index: usize::MAX,
+        false_index: usize::MAX,
+    }
+}
+
+#[test]
+fn test_mcdc_test_vectors_index() {
+    // `a && b && c`: every condition except the last ends the decision when false.
+    let mut conditions = vec![
+        mcdc_branch(0, Some(1), None),
+        mcdc_branch(1, Some(2), None),
+        mcdc_branch(2, None, None),
+    ];
+    assert_eq!(calc_test_vectors_index(&mut conditions), 4);
+
+    // `(a && b) || c`: `c` can be reached along two different paths.
+    let mut conditions = vec![
+        mcdc_branch(0, Some(2), Some(1)),
+        mcdc_branch(2, None, Some(1)),
+        mcdc_branch(1, None, None),
+    ];
+    assert_eq!(calc_test_vectors_index(&mut conditions), 5);
+    let indices = conditions.iter().map(|b| (b.true_index, b.false_index)).collect::<Vec<_>>();
+    assert_eq!(indices, [(0, 0), (4, 1), (2, 0)]);
+}
+
+#[test]
+fn test_mcdc_bitmap_limit() {
+    // Each decision gets the bits after the ones of the previous decisions.
+    let mut bitmap_bits = 0;
+    assert_eq!(try_reserve_bitmap_bits(&mut bitmap_bits, 3), Some(0));
+    assert_eq!(try_reserve_bitmap_bits(&mut bitmap_bits, 5), Some(3));
+    assert_eq!(bitmap_bits, 8);
+
+    // A decision that doesn't fit isn't instrumented, and leaves room for the next ones.
+    assert_eq!(try_reserve_bitmap_bits(&mut bitmap_bits, MCDC_MAX_BITMAP_SIZE), None);
+    assert_eq!(bitmap_bits, 8);
+    assert_eq!(try_reserve_bitmap_bits(&mut bitmap_bits, MCDC_MAX_BITMAP_SIZE - 8), Some(8));
+    assert_eq!(try_reserve_bitmap_bits(&mut bitmap_bits, 1), None);
+}
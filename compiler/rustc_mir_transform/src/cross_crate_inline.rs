@@ -8,8 +8,13 @@ use rustc_middle::ty::TyCtxt;
 use rustc_session::config::{InliningThreshold, OptLevel};
 use rustc_span::sym;
 
+use crate::profile::{self, Hotness};
 use crate::{inline, pass_manager as pm};
 
+/// How much bigger than `-Z cross-crate-inline-threshold` the functions that are hot in
+/// `-Z mir-profile-use` may be.
+const HOT_THRESHOLD_MULTIPLIER: usize = 4;
+
 pub(super) fn provide(providers: &mut Providers) {
     providers.cross_crate_inlinable = cross_crate_inlinable;
 }
@@ -92,10 +97,22 @@ fn cross_crate_inlinable(tcx: TyCtxt<'_>, def_id: LocalDefId) -> bool {
         InliningThreshold::Never => return false,
     };
 
+    // With a profile, functions that were never entered are not worth a copy in every crate that
+    // calls them, while hot functions are worth one even if they are bigger or not leaves.
+    let hotness = profile::hotness(tcx, def_id.to_def_id());
+    if hotness == Hotness::Cold {
+        return false;
+    }
+
     let mir = tcx.optimized_mir(def_id);
     let mut checker =
         CostChecker { tcx, callee_body: mir, calls: 0, statements: 0, landing_pads: 0, resumes: 0 };
     checker.visit_body(mir);
+    if hotness == Hotness::Hot {
+        return checker.resumes == 0
+            && checker.landing_pads == 0
+            && checker.statements <= threshold * HOT_THRESHOLD_MULTIPLIER;
+    }
     checker.calls == 0
         && checker.resumes == 0
         && checker.landing_pads == 0
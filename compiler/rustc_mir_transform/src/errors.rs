@@ -101,12 +101,31 @@ pub(crate) struct UnalignedPackedRef {
     pub span: Span,
 }
 
+#[derive(Diagnostic)]
+#[diag(mir_transform_exceeds_mcdc_test_vector_limit)]
+pub(crate) struct MCDCExceedsTestVectorLimit {
+    #[primary_span]
+    pub(crate) span: Span,
+    pub(crate) max_num_test_vectors: usize,
+}
+
 #[derive(Diagnostic)]
 #[diag(mir_transform_unknown_pass_name)]
 pub(crate) struct UnknownPassName<'a> {
     pub(crate) name: &'a str,
 }
 
+#[derive(Diagnostic)]
+#[diag(mir_transform_remark)]
+pub(crate) struct MirRemark<'a> {
+    #[primary_span]
+    pub(crate) span: Span,
+    pub(crate) pass: &'a str,
+    pub(crate) kind: &'a str,
+    pub(crate) function: &'a str,
+    pub(crate) message: &'a str,
+}
+
 pub(crate) struct AssertLint<P> {
     pub span: Span,
     pub assert_kind: AssertKind<P>,
@@ -112,6 +112,7 @@ use rustc_span::DUMMY_SP;
 use smallvec::SmallVec;
 use tracing::{debug, instrument, trace};
 
+use crate::remarks::{RemarkKind, emit_remark, remarks_enabled};
 use crate::ssa::SsaLocals;
 
 pub(super) struct GVN;
@@ -148,7 +149,14 @@ impl<'tcx> crate::MirPass<'tcx> for GVN {
         // For each local that is reused (`y` above), we remove its storage statements do avoid any
         // difficulty. Those locals are SSA, so should be easy to optimize by LLVM without storage
         // statements.
+        let remarks = state.remarks.take();
         StorageRemover { tcx, reused_locals: state.reused_locals }.visit_body_preserves_cfg(body);
+
+        let def_id = body.source.def_id();
+        for (location, message) in remarks.into_iter().flatten() {
+            let span = body.source_info(location).span;
+            emit_remark(tcx, "GVN", def_id, span, RemarkKind::Passed, || message);
+        }
     }
 
     fn is_required(&self) -> bool {
@@ -371,6 +379,8 @@ struct VnState<'body, 'a, 'tcx> {
     dominators: Dominators<BasicBlock>,
     reused_locals: DenseBitSet<Local>,
     arena: &'a DroplessArena,
+    /// The assignments that we rewrote, if `-Z mir-remarks` is enabled for this pass.
+    remarks: Option<Vec<(Location, String)>>,
 }
 
 impl<'body, 'a, 'tcx> VnState<'body, 'a, 'tcx> {
@@ -404,6 +414,7 @@ impl<'body, 'a, 'tcx> VnState<'body, 'a, 'tcx> {
             dominators,
             reused_locals: DenseBitSet::new_empty(local_decls.len()),
             arena,
+            remarks: remarks_enabled(tcx, "GVN").then(Vec::new),
         }
     }
 
@@ -1879,6 +1890,7 @@ impl<'tcx> MutVisitor<'tcx> for VnState<'_, '_, 'tcx> {
     ) {
         self.simplify_place_projection(lhs, location);
 
+        let original = self.remarks.is_some().then(|| rvalue.clone());
         let value = self.simplify_rvalue(lhs, rvalue, location);
         if let Some(value) = value {
             if let Some(const_) = self.try_as_constant(value) {
@@ -1892,6 +1904,13 @@ impl<'tcx> MutVisitor<'tcx> for VnState<'_, '_, 'tcx> {
             }
         }
 
+        if let Some(original) = original
+            && original != *rvalue
+            && let Some(remarks) = &mut self.remarks
+        {
+            remarks.push((location, format!("replaced `{original:?}` with `{rvalue:?}`")));
+        }
+
         if lhs.is_indirect() {
             // Non-local mutation maybe invalidate deref.
             self.invalidate_derefs();
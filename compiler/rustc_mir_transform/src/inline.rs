@@ -21,6 +21,8 @@ use tracing::{debug, instrument, trace, trace_span};
 
 use crate::cost_checker::{CostChecker, is_call_like};
 use crate::deref_separator::deref_finder;
+use crate::profile::{self, Hotness};
+use crate::remarks::{RemarkKind, emit_remark};
 use crate::simplify::{UsedInStmtLocals, simplify_cfg};
 use crate::validate::validate_types;
 use crate::{check_inline, util};
@@ -48,6 +50,13 @@ impl<'tcx> crate::MirPass<'tcx> for Inline {
             return enabled;
         }
 
+        // FIXME: MC/DC condition bitmaps are allocated per function during
+        // codegen, so coverage statements inlined from another function would
+        // have no bitmap to update.
+        if sess.instrument_coverage_mcdc() {
+            return false;
+        }
+
         match sess.mir_opt_level() {
             0 | 1 => false,
             2 => {
@@ -287,6 +296,8 @@ struct NormalInliner<'tcx> {
     /// Indicates that the caller is #[inline] and just calls another function,
     /// and thus we can inline less into it as it'll be inlined itself.
     caller_is_inline_forwarder: bool,
+    /// The hotness of the caller in `-Z mir-profile-use`.
+    caller_hotness: Hotness,
 }
 
 impl<'tcx> NormalInliner<'tcx> {
@@ -311,6 +322,7 @@ impl<'tcx> Inliner<'tcx> for NormalInliner<'tcx> {
                 codegen_fn_attrs.inline,
                 InlineAttr::Hint | InlineAttr::Always | InlineAttr::Force { .. }
             ) && body_is_forwarder(body),
+            caller_hotness: profile::hotness(tcx, def_id),
         }
     }
 
@@ -377,8 +389,26 @@ impl<'tcx> Inliner<'tcx> for NormalInliner<'tcx> {
             return Err("Not inlining multi-block body as we're past a depth limit");
         }
 
+        // Inlining into or out of functions that were never entered while profiling only makes
+        // them bigger. `#[inline(always)]` is still respected, as LLVM would inline them anyway.
+        let callee_hotness = profile::hotness(tcx, callsite.callee.def_id());
+        if !matches!(callee_attrs.inline, InlineAttr::Always) {
+            if self.caller_hotness == Hotness::Cold {
+                return Err("caller is cold in the profile");
+            }
+            if callee_hotness == Hotness::Cold {
+                return Err("callee is cold in the profile");
+            }
+        }
+
+        // We only know how often functions are entered, so a call site is hot if both the caller
+        // and the callee are.
+        let hot_callsite = self.caller_hotness == Hotness::Hot && callee_hotness == Hotness::Hot;
+
         let mut threshold = if self.caller_is_inline_forwarder || self.past_depth_limit() {
             tcx.sess.opts.unstable_opts.inline_mir_forwarder_threshold.unwrap_or(30)
+        } else if hot_callsite {
+            tcx.sess.opts.unstable_opts.inline_mir_hot_threshold.unwrap_or(200)
         } else if tcx.cross_crate_inlinable(callsite.callee.def_id()) {
             tcx.sess.opts.unstable_opts.inline_mir_hint_threshold.unwrap_or(100)
         } else {
@@ -456,6 +486,20 @@ impl<'tcx> Inliner<'tcx> for NormalInliner<'tcx> {
         // generous) inlining threshold. Such functions are very poor MIR inlining candidates.
         // Always inlining #[inline(always)] functions in MIR, on net, slows down the compiler.
         let cost = checker.cost();
+        emit_remark(
+            tcx,
+            "Inline",
+            self.def_id,
+            callsite.source_info.span,
+            RemarkKind::Analysis,
+            || {
+                let hot = if hot_callsite { ", hot call site" } else { "" };
+                format!(
+                    "inlining cost of `{}` is {cost} (threshold {threshold}{hot})",
+                    callsite.callee
+                )
+            },
+        );
         if cost <= threshold {
             debug!("INLINING {:?} [cost={} <= threshold={}]", callsite, cost, threshold);
             Ok(())
@@ -473,6 +517,15 @@ impl<'tcx> Inliner<'tcx> for NormalInliner<'tcx> {
     ) {
         self.changed = true;
 
+        emit_remark(
+            self.tcx,
+            "Inline",
+            self.def_id,
+            callsite.source_info.span,
+            RemarkKind::Passed,
+            || format!("inlined `{}`", callsite.callee),
+        );
+
         let new_calls_count = new_blocks
             .clone()
             .filter(|&bb| is_call_like(caller_body.basic_blocks[bb].terminator()))
@@ -490,7 +543,16 @@ impl<'tcx> Inliner<'tcx> for NormalInliner<'tcx> {
         }
     }
 
-    fn on_inline_failure(&self, _: &CallSite<'tcx>, _: &'static str) {}
+    fn on_inline_failure(&self, callsite: &CallSite<'tcx>, reason: &'static str) {
+        emit_remark(
+            self.tcx,
+            "Inline",
+            self.def_id,
+            callsite.source_info.span,
+            RemarkKind::Missed,
+            || format!("`{}` not inlined: {reason}", callsite.callee),
+        );
+    }
 }
 
 fn inline<'tcx, T: Inliner<'tcx>>(tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) -> bool {
@@ -761,7 +823,9 @@ fn check_mir_is_available<'tcx, I: Inliner<'tcx>>(
         | InstanceKind::DropGlue(..)
         | InstanceKind::CloneShim(..)
         | InstanceKind::ThreadLocalShim(..)
-        | InstanceKind::FnPtrAddrShim(..) => return Ok(()),
+        | InstanceKind::FnPtrAddrShim(..)
+        | InstanceKind::ColdPathShim(..)
+        | InstanceKind::ConstSpecialized(..) => return Ok(()),
     }
 
     if inliner.tcx().is_constructor(callee_def_id) {
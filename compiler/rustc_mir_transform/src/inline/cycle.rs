@@ -37,6 +37,12 @@ fn should_recurse<'tcx>(tcx: TyCtxt<'tcx>, callee: ty::Instance<'tcx>) -> bool {
         // This shim does not call any other functions, thus there can be no recursion.
         InstanceKind::FnPtrAddrShim(..) => return false,
 
+        // Cold paths are only ever called through function pointers, which are not inlined.
+        InstanceKind::ColdPathShim(..) => return false,
+
+        // Clones for constant arguments only exist after all MIR was optimized.
+        InstanceKind::ConstSpecialized(..) => return false,
+
         // FIXME: A not fully instantiated drop shim can cause ICEs if one attempts to
         // have its MIR built. Likely oli-obk just screwed up the `ParamEnv`s, so this
         // needs some more analysis.
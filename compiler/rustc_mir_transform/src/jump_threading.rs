@@ -48,10 +48,11 @@ use rustc_middle::mir::*;
 use rustc_middle::ty::{self, ScalarInt, TyCtxt};
 use rustc_mir_dataflow::lattice::HasBottom;
 use rustc_mir_dataflow::value_analysis::{Map, PlaceIndex, State, TrackElem};
-use rustc_span::DUMMY_SP;
+use rustc_span::{DUMMY_SP, Span};
 use tracing::{debug, instrument, trace};
 
 use crate::cost_checker::CostChecker;
+use crate::remarks::{RemarkKind, emit_remark};
 
 pub(super) struct JumpThreading;
 
@@ -102,7 +103,15 @@ impl<'tcx> crate::MirPass<'tcx> for JumpThreading {
         for to in opportunities.iter() {
             assert!(to.chain.iter().all(|&block| !finder.maybe_loop_headers.contains(block)));
         }
-        OpportunitySet::new(body, opportunities).apply(body);
+        let mut opportunities = OpportunitySet::new(body, opportunities);
+        opportunities.apply(body);
+
+        for span in opportunities.threaded {
+            emit_remark(tcx, "JumpThreading", def_id, span, RemarkKind::Passed, || {
+                "threaded a jump over this branch, whose outcome is known on some incoming paths"
+                    .to_owned()
+            });
+        }
     }
 
     fn is_required(&self) -> bool {
@@ -692,6 +701,8 @@ struct OpportunitySet {
     involving_tos: IndexVec<BasicBlock, Vec<(usize, usize)>>,
     /// Cache the number of predecessors for each block, as we clear the basic block cache..
     predecessors: IndexVec<BasicBlock, usize>,
+    /// The spans of the branches that were replaced by a jump, for `-Z mir-remarks`.
+    threaded: Vec<Span>,
 }
 
 impl OpportunitySet {
@@ -704,7 +715,7 @@ impl OpportunitySet {
             involving_tos[to.target].push((index, to.chain.len()));
         }
         let predecessors = predecessor_count(body);
-        OpportunitySet { opportunities, involving_tos, predecessors }
+        OpportunitySet { opportunities, involving_tos, predecessors, threaded: Vec::new() }
     }
 
     /// Apply the opportunities on the graph.
@@ -801,6 +812,7 @@ impl OpportunitySet {
 
         let current = &mut basic_blocks[current];
         self.update_predecessor_count(current.terminator(), Update::Decr);
+        self.threaded.push(current.terminator().source_info.span);
         current.terminator_mut().kind = TerminatorKind::Goto { target: op_target };
         self.predecessors[op_target] += 1;
     }
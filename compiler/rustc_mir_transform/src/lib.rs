@@ -51,7 +51,10 @@ mod errors;
 mod ffi_unwind_calls;
 mod lint;
 mod lint_tail_expr_drop_order;
+mod loops;
 mod patch;
+mod profile;
+mod remarks;
 mod shim;
 mod ssa;
 
@@ -115,6 +118,7 @@ declare_passes! {
     mod add_moves_for_packed_drops : AddMovesForPackedDrops;
     mod add_retag : AddRetag;
     mod add_subtyping_projections : Subtyper;
+    mod bounds_check_elim : BoundsCheckElimination;
     mod check_inline : CheckForceInline;
     mod check_call_recursion : CheckCallRecursion, CheckDropRecursion;
     mod check_inline_always_target_features: CheckInlineAlwaysTargetFeature;
@@ -151,11 +155,13 @@ declare_passes! {
     mod jump_threading : JumpThreading;
     mod known_panics_lint : KnownPanicsLint;
     mod large_enums : EnumSizeOpt;
+    mod licm : LoopInvariantCodeMotion;
     mod lower_intrinsics : LowerIntrinsics;
     mod lower_slice_len : LowerSliceLenCalls;
     mod match_branches : MatchBranchSimplification;
     mod mentioned_items : MentionedItems;
     mod multiple_return_terminators : MultipleReturnTerminators;
+    mod outline_cold_paths : OutlineColdPaths;
     mod post_drop_elaboration : CheckLiveDrops;
     mod prettify : ReorderBasicBlocks, ReorderLocals;
     mod promote_consts : PromoteTemps;
@@ -222,8 +228,10 @@ pub fn provide(providers: &mut Providers) {
         promoted_mir,
         deduced_param_attrs: deduce_param_attrs::deduced_param_attrs,
         coroutine_by_move_body_def_id: coroutine::coroutine_by_move_body_def_id,
+        mir_profile_hotness: profile::mir_profile_hotness,
         ..providers.queries
     };
+    providers.hooks.validate_mir_body = validate::validate_external_body;
 }
 
 fn remap_mir_for_const_eval_select<'tcx>(
@@ -705,6 +713,7 @@ pub(crate) fn run_optimization_passes<'tcx>(tcx: TyCtxt<'tcx>, body: &mut Body<'
             &instsimplify::InstSimplify::AfterSimplifyCfg,
             &simplify::SimplifyLocals::BeforeConstProp,
             &dead_store_elimination::DeadStoreElimination::Initial,
+            &licm::LoopInvariantCodeMotion,
             &gvn::GVN,
             &simplify::SimplifyLocals::AfterGVN,
             &match_branches::MatchBranchSimplification,
@@ -712,9 +721,12 @@ pub(crate) fn run_optimization_passes<'tcx>(tcx: TyCtxt<'tcx>, body: &mut Body<'
             &single_use_consts::SingleUseConsts,
             &o1(simplify_branches::SimplifyConstCondition::AfterConstProp),
             &jump_threading::JumpThreading,
+            &bounds_check_elim::BoundsCheckElimination,
             &early_otherwise_branch::EarlyOtherwiseBranch,
             &simplify_comparison_integral::SimplifyComparisonIntegral,
             &o1(simplify_branches::SimplifyConstCondition::Final),
+            // Before the final SimplifyCfg, which removes the blocks of the outlined paths.
+            &outline_cold_paths::OutlineColdPaths,
             &o1(remove_noop_landing_pads::RemoveNoopLandingPads),
             &o1(simplify::SimplifyCfg::Final),
             // After the last SimplifyCfg, because this wants one-block functions.
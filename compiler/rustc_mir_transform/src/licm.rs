@@ -0,0 +1,390 @@
+//! Loop-invariant code motion.
+//!
+//! This pass moves assignments whose value is the same in every iteration of a loop to the
+//! preheader of that loop, i.e. to a block that jumps to the loop header from outside the loop:
+//! ```ignore (MIR)
+//! bb0: {
+//!     goto -> bb1;
+//! }
+//! bb1: {
+//!     // `_2` and `_3` are assigned outside of the loop.
+//!     _5 = Add(copy _2, copy _3);
+//!     _6 = Add(copy _4, copy _5);
+//!     switchInt(...) -> [0: bb2, otherwise: bb1];
+//! }
+//! ```
+//! becomes
+//! ```ignore (MIR)
+//! bb0: {
+//!     _5 = Add(copy _2, copy _3);
+//!     goto -> bb1;
+//! }
+//! bb1: {
+//!     _6 = Add(copy _4, copy _5);
+//!     switchInt(...) -> [0: bb2, otherwise: bb1];
+//! }
+//! ```
+//!
+//! An assignment can be hoisted when it assigns an SSA local, and its value is computed from SSA
+//! locals whose assignment dominates the loop, or from locals that have already been hoisted. We
+//! hoist it as far out of the loop nest as these operands allow.
+//!
+//! The hoisted assignment is executed even when the loop runs zero times, or when the original
+//! assignment sits on a path that the loop does not take. We therefore only consider rvalues that
+//! cannot have any effect besides computing a value: arithmetic that cannot overflow into UB,
+//! casts, and reads of the length of a slice or of the discriminant of a local.
+//!
+//! The hoisted locals and their operands have their storage statements removed, as they are now
+//! live across iterations of the loop.
+
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_index::IndexVec;
+use rustc_index::bit_set::DenseBitSet;
+use rustc_middle::mir::visit::{MutVisitor, PlaceContext, Visitor};
+use rustc_middle::mir::*;
+use rustc_middle::ty::{self, TyCtxt};
+use tracing::{debug, instrument, trace};
+
+use crate::loops::{LoopIdx, LoopNest};
+use crate::remarks::{RemarkKind, emit_remark};
+use crate::ssa::SsaLocals;
+
+pub(super) struct LoopInvariantCodeMotion;
+
+impl<'tcx> crate::MirPass<'tcx> for LoopInvariantCodeMotion {
+    fn is_enabled(&self, sess: &rustc_session::Session) -> bool {
+        sess.mir_opt_level() >= 2
+    }
+
+    #[instrument(level = "trace", skip(self, tcx, body))]
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        debug!(def_id = ?body.source.def_id());
+
+        let loop_nest = LoopNest::new(body);
+        if loop_nest.loops().is_empty() {
+            return;
+        }
+
+        let typing_env = body.typing_env(tcx);
+        let ssa = SsaLocals::new(tcx, body, typing_env);
+        let mut finder = HoistFinder {
+            tcx,
+            typing_env,
+            body,
+            ssa: &ssa,
+            loop_nest: &loop_nest,
+            hoisted_to: IndexVec::from_elem(None, &body.local_decls),
+            hoisted: Vec::new(),
+        };
+        for &bb in body.basic_blocks.reverse_postorder() {
+            finder.visit_block(bb);
+        }
+        let HoistFinder { hoisted, .. } = finder;
+        if hoisted.is_empty() {
+            return;
+        }
+
+        // Find or create the preheader of each loop we hoist to. This must be done before
+        // modifying the CFG, as `entering_blocks` relies on the cached predecessors.
+        let mut preheaders = FxIndexMap::default();
+        for &(loop_, _) in &hoisted {
+            preheaders.entry(loop_).or_insert_with(|| {
+                let entering: Vec<_> = loop_nest.entering_blocks(body, loop_).collect();
+                let header = loop_nest.loops()[loop_].header;
+                match entering[..] {
+                    [block]
+                        if matches!(
+                            body.basic_blocks[block].terminator().kind,
+                            TerminatorKind::Goto { .. }
+                        ) =>
+                    {
+                        Preheader::Existing(block)
+                    }
+                    _ => Preheader::New { header, entering },
+                }
+            });
+        }
+
+        let basic_blocks = body.basic_blocks.as_mut();
+        let preheaders: FxIndexMap<LoopIdx, BasicBlock> = preheaders
+            .into_iter()
+            .map(|(loop_, preheader)| {
+                let block = match preheader {
+                    Preheader::Existing(block) => block,
+                    Preheader::New { header, entering } => {
+                        let source_info = basic_blocks[header].terminator().source_info;
+                        let terminator = Terminator {
+                            source_info,
+                            kind: TerminatorKind::Goto { target: header },
+                        };
+                        let preheader =
+                            basic_blocks.push(BasicBlockData::new(Some(terminator), false));
+                        for block in entering {
+                            basic_blocks[block].terminator_mut().successors_mut(|target| {
+                                if *target == header {
+                                    *target = preheader;
+                                }
+                            });
+                        }
+                        preheader
+                    }
+                };
+                (loop_, block)
+            })
+            .collect();
+
+        // Remove all the statements first, so that moving a statement does not shift the location
+        // of another one.
+        let mut reused_locals = DenseBitSet::new_empty(body.local_decls.len());
+        let mut moved = Vec::with_capacity(hoisted.len());
+        for (loop_, location) in hoisted {
+            let statement = &mut basic_blocks[location.block].statements[location.statement_index];
+            // Debuginfo statements stay where they are, only the assignment moves.
+            let kind = std::mem::replace(&mut statement.kind, StatementKind::Nop);
+            let statement = Statement::new(statement.source_info, kind);
+            LocalCollector { locals: &mut reused_locals }.visit_statement(&statement, location);
+            moved.push((preheaders[&loop_], statement));
+        }
+        let def_id = body.source.def_id();
+        for (preheader, statement) in moved {
+            trace!(?preheader, ?statement, "hoisting");
+            let span = statement.source_info.span;
+            emit_remark(tcx, "LoopInvariantCodeMotion", def_id, span, RemarkKind::Passed, || {
+                format!("hoisted `{:?}` out of a loop", statement.kind)
+            });
+            basic_blocks[preheader].statements.push(statement);
+        }
+
+        StorageRemover { tcx, reused_locals }.visit_body_preserves_cfg(body);
+    }
+
+    fn is_required(&self) -> bool {
+        false
+    }
+}
+
+enum Preheader {
+    /// The only block that enters the loop already ends with a `goto` to the header.
+    Existing(BasicBlock),
+    /// We need to insert a new block on the edges from `entering` to `header`.
+    New { header: BasicBlock, entering: Vec<BasicBlock> },
+}
+
+struct HoistFinder<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    typing_env: ty::TypingEnv<'tcx>,
+    body: &'a Body<'tcx>,
+    ssa: &'a SsaLocals,
+    loop_nest: &'a LoopNest,
+    /// For each local that we decided to hoist, the loop to whose preheader it is hoisted.
+    hoisted_to: IndexVec<Local, Option<LoopIdx>>,
+    /// The assignments to hoist, in the order in which they must be executed.
+    hoisted: Vec<(LoopIdx, Location)>,
+}
+
+impl<'a, 'tcx> HoistFinder<'a, 'tcx> {
+    fn visit_block(&mut self, block: BasicBlock) {
+        let body = self.body;
+        let data = &body.basic_blocks[block];
+        if data.is_cleanup || self.loop_nest.innermost_loop(block).is_none() {
+            return;
+        }
+
+        for (statement_index, statement) in data.statements.iter().enumerate() {
+            let StatementKind::Assign(box (place, ref rvalue)) = statement.kind else { continue };
+            let Some(local) = place.as_local() else { continue };
+            if local == RETURN_PLACE
+                || !self.ssa.is_ssa(local)
+                || self.ssa.borrowed_locals().contains(local)
+                || !self.is_hoistable(rvalue)
+            {
+                continue;
+            }
+
+            let location = Location { block, statement_index };
+            if let Some(loop_) = self.outermost_invariant_loop(block, rvalue) {
+                debug!(?location, ?loop_, ?rvalue, "loop invariant");
+                self.hoisted_to[local] = Some(loop_);
+                self.hoisted.push((loop_, location));
+            }
+        }
+    }
+
+    /// Whether evaluating `rvalue` has no effect besides computing its value, so that it can be
+    /// evaluated at any point where its operands are available.
+    fn is_hoistable(&self, rvalue: &Rvalue<'tcx>) -> bool {
+        match *rvalue {
+            Rvalue::BinaryOp(op, box (ref lhs, ref rhs)) => {
+                let op_allowed = match op {
+                    BinOp::Add
+                    | BinOp::AddWithOverflow
+                    | BinOp::Sub
+                    | BinOp::SubWithOverflow
+                    | BinOp::Mul
+                    | BinOp::MulWithOverflow
+                    | BinOp::BitXor
+                    | BinOp::BitAnd
+                    | BinOp::BitOr
+                    | BinOp::Shl
+                    | BinOp::Shr
+                    | BinOp::Eq
+                    | BinOp::Lt
+                    | BinOp::Le
+                    | BinOp::Ne
+                    | BinOp::Ge
+                    | BinOp::Gt
+                    | BinOp::Cmp => true,
+                    // Integer division is UB on a zero divisor, and on overflow.
+                    BinOp::Div | BinOp::Rem => self.is_safe_divisor(lhs, rhs),
+                    // The unchecked operations and `Offset` have UB preconditions that may only
+                    // hold on the paths where they are evaluated.
+                    BinOp::AddUnchecked
+                    | BinOp::SubUnchecked
+                    | BinOp::MulUnchecked
+                    | BinOp::ShlUnchecked
+                    | BinOp::ShrUnchecked
+                    | BinOp::Offset => false,
+                };
+                op_allowed && is_hoistable_operand(lhs) && is_hoistable_operand(rhs)
+            }
+            Rvalue::UnaryOp(UnOp::Not | UnOp::Neg | UnOp::PtrMetadata, ref operand) => {
+                is_hoistable_operand(operand)
+            }
+            Rvalue::Cast(
+                CastKind::IntToInt
+                | CastKind::IntToFloat
+                | CastKind::FloatToInt
+                | CastKind::FloatToFloat
+                | CastKind::PtrToPtr,
+                ref operand,
+                _,
+            ) => is_hoistable_operand(operand),
+            // Reading the discriminant of a local, without going through a pointer.
+            Rvalue::Discriminant(place) => place.as_local().is_some(),
+            _ => false,
+        }
+    }
+
+    /// Whether `lhs / rhs` and `lhs % rhs` can never be UB.
+    fn is_safe_divisor(&self, lhs: &Operand<'tcx>, rhs: &Operand<'tcx>) -> bool {
+        let ty = lhs.ty(self.body, self.tcx);
+        if ty.is_floating_point() {
+            return true;
+        }
+        let Some(rhs) = rhs.constant() else { return false };
+        let Some(divisor) = rhs.const_.try_eval_scalar_int(self.tcx, self.typing_env) else {
+            return false;
+        };
+        let size = divisor.size();
+        let bits = divisor.to_bits(size);
+        // For signed integers, `MIN / -1` overflows.
+        bits != 0 && !(ty.is_signed() && bits == size.unsigned_int_max())
+    }
+
+    /// Returns the outermost loop that contains `block` and in which all the operands of `rvalue`
+    /// are invariant.
+    fn outermost_invariant_loop(
+        &self,
+        block: BasicBlock,
+        rvalue: &Rvalue<'tcx>,
+    ) -> Option<LoopIdx> {
+        let locals: Vec<Local> = match *rvalue {
+            Rvalue::BinaryOp(_, box (ref lhs, ref rhs)) => {
+                lhs.place().into_iter().chain(rhs.place()).map(|place| place.local).collect()
+            }
+            Rvalue::UnaryOp(_, ref operand) | Rvalue::Cast(_, ref operand, _) => {
+                operand.place().into_iter().map(|place| place.local).collect()
+            }
+            Rvalue::Discriminant(place) => vec![place.local],
+            _ => return None,
+        };
+
+        let loops = self.loop_nest.loops();
+        let dominators = self.body.basic_blocks.dominators();
+        let mut candidate = None;
+        let mut current = self.loop_nest.innermost_loop(block);
+        while let Some(loop_) = current {
+            let header = loops[loop_].header;
+            // We cannot add a preheader in front of the start block or of a cleanup block.
+            if header == START_BLOCK || self.body.basic_blocks[header].is_cleanup {
+                break;
+            }
+            let entry = Location { block: header, statement_index: 0 };
+            let invariant = locals.iter().all(|&local| {
+                self.ssa.assignment_dominates(dominators, local, entry)
+                    || self.hoisted_to[local].is_some_and(|to| self.is_nested_in(loop_, to))
+            });
+            if !invariant {
+                break;
+            }
+            candidate = Some(loop_);
+            current = loops[loop_].parent;
+        }
+        candidate
+    }
+
+    /// Whether `inner` is `outer` or one of the loops that it contains.
+    fn is_nested_in(&self, inner: LoopIdx, outer: LoopIdx) -> bool {
+        let loops = self.loop_nest.loops();
+        let mut current = Some(inner);
+        while let Some(loop_) = current {
+            if loop_ == outer {
+                return true;
+            }
+            current = loops[loop_].parent;
+        }
+        false
+    }
+}
+
+/// Hoisted operands must be read by copy, as the value is still used by the following iterations.
+fn is_hoistable_operand(operand: &Operand<'_>) -> bool {
+    match operand {
+        Operand::Copy(place) => place.as_local().is_some(),
+        Operand::Constant(_) => true,
+        Operand::Move(_) => false,
+    }
+}
+
+/// Collects the locals used by the hoisted statements.
+struct LocalCollector<'a> {
+    locals: &'a mut DenseBitSet<Local>,
+}
+
+impl<'tcx> Visitor<'tcx> for LocalCollector<'_> {
+    fn visit_local(&mut self, local: Local, _: PlaceContext, _: Location) {
+        self.locals.insert(local);
+    }
+}
+
+struct StorageRemover<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    reused_locals: DenseBitSet<Local>,
+}
+
+impl<'tcx> MutVisitor<'tcx> for StorageRemover<'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn visit_operand(&mut self, operand: &mut Operand<'tcx>, _: Location) {
+        if let Operand::Move(place) = *operand
+            && !place.is_indirect_first_projection()
+            && self.reused_locals.contains(place.local)
+        {
+            *operand = Operand::Copy(place);
+        }
+    }
+
+    fn visit_statement(&mut self, stmt: &mut Statement<'tcx>, loc: Location) {
+        match stmt.kind {
+            // When removing storage statements, we need to remove both (#107511).
+            StatementKind::StorageLive(l) | StatementKind::StorageDead(l)
+                if self.reused_locals.contains(l) =>
+            {
+                stmt.make_nop(true)
+            }
+            _ => self.super_statement(stmt, loc),
+        }
+    }
+}
@@ -0,0 +1,110 @@
+//! Loop nest analysis.
+//!
+//! We find the natural loops of a body from its dominator tree: an edge `tail -> header` such that
+//! `header` dominates `tail` is a back edge, and the loop it forms contains `header` and every
+//! block that can reach `tail` without going through `header`. Loops that share their header are
+//! merged. Two loops are then either disjoint or nested, which gives the loop nest.
+//!
+//! Irreducible cycles, i.e. cycles that can be entered through more than one block, have no back
+//! edge by this definition and are not reported as loops.
+
+use rustc_index::bit_set::DenseBitSet;
+use rustc_index::{IndexSlice, IndexVec};
+use rustc_middle::mir::{BasicBlock, Body, START_BLOCK};
+
+rustc_index::newtype_index! {
+    #[orderable]
+    #[debug_format = "loop{}"]
+    pub(super) struct LoopIdx {}
+}
+
+#[derive(Debug)]
+pub(super) struct Loop {
+    /// The only block of the loop that can be reached from outside of it. It dominates every
+    /// block of the loop.
+    pub header: BasicBlock,
+    /// All the blocks of the loop, including its header and the blocks of nested loops.
+    pub blocks: DenseBitSet<BasicBlock>,
+    /// The innermost loop that strictly contains this one.
+    pub parent: Option<LoopIdx>,
+}
+
+#[derive(Debug)]
+pub(super) struct LoopNest {
+    /// The loops, ordered so that a loop always comes before the loops that it contains.
+    loops: IndexVec<LoopIdx, Loop>,
+    /// The innermost loop that contains each block, if any.
+    innermost: IndexVec<BasicBlock, Option<LoopIdx>>,
+}
+
+impl LoopNest {
+    pub(super) fn new(body: &Body<'_>) -> LoopNest {
+        let basic_blocks = &body.basic_blocks;
+        let dominators = basic_blocks.dominators();
+        let predecessors = basic_blocks.predecessors();
+
+        // Find the back edges. Visiting headers in reverse postorder makes an outer loop come
+        // before the loops it contains, as its header dominates theirs.
+        let mut tails: IndexVec<BasicBlock, Vec<BasicBlock>> =
+            IndexVec::from_elem(Vec::new(), basic_blocks);
+        let mut headers = Vec::new();
+        for &block in basic_blocks.reverse_postorder() {
+            for successor in basic_blocks[block].terminator().successors() {
+                if dominators.dominates(successor, block) {
+                    tails[successor].push(block);
+                }
+            }
+        }
+        for &block in basic_blocks.reverse_postorder() {
+            if !tails[block].is_empty() {
+                headers.push(block);
+            }
+        }
+
+        let mut loops = IndexVec::with_capacity(headers.len());
+        let mut innermost = IndexVec::from_elem(None, basic_blocks);
+        for header in headers {
+            let mut blocks = DenseBitSet::new_empty(basic_blocks.len());
+            blocks.insert(header);
+            let mut stack = std::mem::take(&mut tails[header]);
+            while let Some(block) = stack.pop() {
+                // Unreachable blocks may jump into a loop, but are not part of it.
+                if dominators.is_reachable(block) && blocks.insert(block) {
+                    stack.extend(predecessors[block].iter().copied());
+                }
+            }
+
+            // Loops are visited from the outside in, so the innermost loop known so far to
+            // contain the header is the parent.
+            let parent = innermost[header];
+            let idx = loops.push(Loop { header, blocks, parent });
+            for block in loops[idx].blocks.iter() {
+                innermost[block] = Some(idx);
+            }
+        }
+
+        LoopNest { loops, innermost }
+    }
+
+    pub(super) fn loops(&self) -> &IndexSlice<LoopIdx, Loop> {
+        &self.loops
+    }
+
+    /// Returns the innermost loop that contains `block`, if any.
+    pub(super) fn innermost_loop(&self, block: BasicBlock) -> Option<LoopIdx> {
+        self.innermost[block]
+    }
+
+    /// Returns the blocks that jump to the header of `loop_` from outside of it. This is empty if
+    /// the header is the start block, as it is then entered from the caller.
+    pub(super) fn entering_blocks<'a>(
+        &'a self,
+        body: &'a Body<'_>,
+        loop_: LoopIdx,
+    ) -> impl Iterator<Item = BasicBlock> + 'a {
+        let Loop { header, ref blocks, .. } = self.loops[loop_];
+        let predecessors: &[BasicBlock] =
+            if header == START_BLOCK { &[] } else { &body.basic_blocks.predecessors()[header] };
+        predecessors.iter().copied().filter(move |&block| !blocks.contains(block))
+    }
+}
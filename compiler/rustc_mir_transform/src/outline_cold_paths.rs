@@ -0,0 +1,522 @@
+//! Outlining of cold paths.
+//!
+//! A cold path is a part of a body that can only end in a call to a diverging function, like the
+//! formatting of a panic message followed by the call to `panic_fmt`. These paths are rarely
+//! taken, but they can make up a large part of a small function, which makes the inliner
+//! overestimate the cost of inlining it. This pass moves each of them to a separate body, which
+//! is called through a function pointer to an `InstanceKind::ColdPathShim`:
+//! ```ignore (MIR)
+//! bb1: {
+//!     _4 = Arguments::<'_>::new_const::<1>(const "index out of range") -> [return: bb2, ...];
+//! }
+//! bb2: {
+//!     _3 = panic_fmt(move _4) -> unwind continue;
+//! }
+//! ```
+//! becomes
+//! ```ignore (MIR)
+//! bb1: {
+//!     _5 = const {fn ptr}() -> unwind continue;
+//! }
+//! ```
+//! The outlined bodies are stored in the `cold_paths` of the body they come from, and
+//! `mir_shims` hands them out from there.
+//!
+//! The locals that a path shares with the rest of the body are passed to the outlined body by
+//! pointer, so that the path still reads and writes the same memory. The locals that are only
+//! used on the path become locals of the outlined body.
+//!
+//! The call to the outlined body unwinds to the same cleanup block as the path did. We therefore
+//! only outline paths that unwind to at most one cleanup block.
+
+use std::borrow::Cow;
+
+use rustc_hir::def::DefKind;
+use rustc_index::IndexVec;
+use rustc_index::bit_set::DenseBitSet;
+use rustc_middle::bug;
+use rustc_middle::middle::codegen_fn_attrs::CodegenFnAttrFlags;
+use rustc_middle::mir::interpret::{CTFE_ALLOC_SALT, Pointer, Scalar};
+use rustc_middle::mir::visit::{
+    MutVisitor, NonMutatingUseContext, NonUseContext, PlaceContext, Visitor,
+};
+use rustc_middle::mir::*;
+use rustc_middle::ty::{self, Instance, InstanceKind, Ty, TyCtxt};
+use rustc_mir_dataflow::Analysis;
+use rustc_mir_dataflow::impls::{MaybeStorageDead, always_storage_live_locals};
+use rustc_span::source_map::Spanned;
+use tracing::{debug, instrument};
+
+use crate::remarks::{RemarkKind, emit_remark};
+
+pub(super) struct OutlineColdPaths;
+
+impl<'tcx> crate::MirPass<'tcx> for OutlineColdPaths {
+    fn is_enabled(&self, sess: &rustc_session::Session) -> bool {
+        sess.mir_opt_level() >= 3
+    }
+
+    #[instrument(level = "trace", skip(self, tcx, body))]
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        let def_id = body.source.def_id();
+        debug!(?def_id);
+
+        if !can_outline_from(tcx, body) {
+            return;
+        }
+
+        let cold = cold_blocks(body);
+        let paths: Vec<ColdPath> = body
+            .basic_blocks
+            .reverse_postorder()
+            .iter()
+            .filter_map(|&entry| ColdPath::new(body, &cold, entry))
+            .collect();
+        if paths.is_empty() {
+            return;
+        }
+
+        // Blocks that are not part of any path are the rest of the body.
+        let mut path_of = IndexVec::from_elem(None, &body.basic_blocks);
+        for (id, path) in paths.iter().enumerate() {
+            for &block in &path.blocks {
+                path_of[block] = Some(id);
+            }
+        }
+        // The arguments are initialized by the caller of the parent body, and the return place
+        // is read by it.
+        let mut uses = LocalUses {
+            path_of: &path_of,
+            uses: IndexVec::from_fn_n(
+                |local: Local| {
+                    if local.as_usize() <= body.arg_count {
+                        LocalUse::Shared
+                    } else {
+                        LocalUse::Unused
+                    }
+                },
+                body.local_decls.len(),
+            ),
+        };
+        for (block, data) in body.basic_blocks.iter_enumerated() {
+            uses.visit_basic_block_data(block, data);
+        }
+        let LocalUses { uses, .. } = uses;
+
+        let typing_env = body.typing_env(tcx);
+        let mut maybe_dead = MaybeStorageDead::new(Cow::Owned(always_storage_live_locals(body)))
+            .iterate_to_fixpoint(tcx, body, None)
+            .into_results_cursor(body);
+        let paths: Vec<(usize, ColdPath)> = paths
+            .into_iter()
+            .enumerate()
+            .filter_map(|(id, mut path)| {
+                let mut checker = SharedLocalChecker {
+                    uses: &uses,
+                    path: id,
+                    shared: IndexVec::from_elem(None, &body.local_decls),
+                    valid: true,
+                };
+                for &block in &path.blocks {
+                    checker.visit_basic_block_data(block, &body.basic_blocks[block]);
+                }
+                if !checker.valid {
+                    return None;
+                }
+                path.shared = checker
+                    .shared
+                    .iter_enumerated()
+                    .filter_map(|(local, &mutability)| Some((local, mutability?)))
+                    .collect();
+
+                // The pointers we pass must point to live and sized locals.
+                maybe_dead.seek_to_block_start(path.entry);
+                path.shared
+                    .iter()
+                    .all(|&(local, _)| {
+                        !maybe_dead.get().contains(local)
+                            && body.local_decls[local].ty.is_sized(tcx, typing_env)
+                    })
+                    .then_some((id, path))
+            })
+            .collect();
+
+        for (id, path) in paths {
+            outline(tcx, body, &uses, id, path);
+        }
+    }
+
+    fn is_required(&self) -> bool {
+        false
+    }
+}
+
+fn can_outline_from<'tcx>(tcx: TyCtxt<'tcx>, body: &Body<'tcx>) -> bool {
+    let def_id = body.source.def_id();
+    // The outlined bodies are found through the optimized MIR of the item they come from, and
+    // are not instantiated when they are called.
+    if !matches!(body.source.instance, InstanceKind::Item(_))
+        || body.source.promoted.is_some()
+        || !matches!(tcx.def_kind(def_id), DefKind::Fn | DefKind::AssocFn)
+        || tcx.generics_of(def_id).requires_monomorphization(tcx)
+        || body.coroutine.is_some()
+        // Coverage counters refer to the coverage info of the body they are in.
+        || body.function_coverage_info.is_some()
+    {
+        return false;
+    }
+
+    let attrs = tcx.codegen_fn_attrs(def_id);
+    // Outlined bodies take the symbol name of their parent as a prefix, and do not have its
+    // implicit caller location argument. There is nothing to gain for cold functions.
+    attrs.symbol_name.is_none()
+        && attrs.linkage.is_none()
+        && !attrs.flags.intersects(
+            CodegenFnAttrFlags::NO_MANGLE
+                | CodegenFnAttrFlags::RUSTC_STD_INTERNAL_SYMBOL
+                | CodegenFnAttrFlags::TRACK_CALLER
+                | CodegenFnAttrFlags::NAKED
+                | CodegenFnAttrFlags::COLD,
+        )
+}
+
+/// Returns the blocks from which every path leads to a diverging call.
+fn cold_blocks(body: &Body<'_>) -> DenseBitSet<BasicBlock> {
+    let mut cold = DenseBitSet::new_empty(body.basic_blocks.len());
+    // Visit the successors of a block before the block itself. This is not the case for back
+    // edges, but the blocks of a loop are never cold anyway: each of them has a successor in the
+    // loop, which would need to be cold first.
+    for &block in body.basic_blocks.reverse_postorder().iter().rev() {
+        let data = &body.basic_blocks[block];
+        if data.is_cleanup {
+            continue;
+        }
+        let is_cold = match data.terminator().kind {
+            TerminatorKind::Call { target: None, .. } => true,
+            TerminatorKind::Goto { .. }
+            | TerminatorKind::SwitchInt { .. }
+            | TerminatorKind::Call { .. }
+            | TerminatorKind::Assert { .. }
+            | TerminatorKind::Drop { .. } => data
+                .terminator()
+                .successors()
+                .filter(|&successor| !body.basic_blocks[successor].is_cleanup)
+                .all(|successor| cold.contains(successor)),
+            _ => false,
+        };
+        if is_cold {
+            cold.insert(block);
+        }
+    }
+    cold
+}
+
+#[derive(Debug)]
+struct ColdPath {
+    entry: BasicBlock,
+    /// The blocks of the path, starting with its entry.
+    blocks: Vec<BasicBlock>,
+    /// What the call to the outlined body does when it unwinds.
+    unwind: UnwindAction,
+    /// The locals that the path shares with the rest of the body, and how the path uses them.
+    shared: Vec<(Local, Mutability)>,
+}
+
+impl ColdPath {
+    /// Returns the path that starts at `entry`, if `entry` is the only block of that path which
+    /// is reached from the rest of the body, and it is worth outlining.
+    fn new(body: &Body<'_>, cold: &DenseBitSet<BasicBlock>, entry: BasicBlock) -> Option<ColdPath> {
+        let predecessors = body.basic_blocks.predecessors();
+        if entry == START_BLOCK
+            || !cold.contains(entry)
+            || predecessors[entry].iter().all(|&pred| cold.contains(pred))
+        {
+            return None;
+        }
+
+        let mut in_path = DenseBitSet::new_empty(body.basic_blocks.len());
+        let mut blocks = Vec::new();
+        let mut stack = vec![entry];
+        let mut unwind = None;
+        while let Some(block) = stack.pop() {
+            if !in_path.insert(block) {
+                continue;
+            }
+            blocks.push(block);
+
+            let terminator = body.basic_blocks[block].terminator();
+            match terminator.unwind() {
+                Some(&action @ (UnwindAction::Continue | UnwindAction::Cleanup(_))) => {
+                    if unwind.replace(action).is_some_and(|previous| previous != action) {
+                        return None;
+                    }
+                }
+                Some(UnwindAction::Unreachable | UnwindAction::Terminate(_)) | None => {}
+            }
+            stack.extend(
+                terminator
+                    .successors()
+                    .filter(|&successor| !body.basic_blocks[successor].is_cleanup),
+            );
+        }
+
+        // Only the entry may be reached from outside of the path. This keeps paths disjoint.
+        if blocks[1..]
+            .iter()
+            .any(|&block| predecessors[block].iter().any(|&pred| !in_path.contains(pred)))
+        {
+            return None;
+        }
+
+        // A single call to a diverging function is already as small as the call that would
+        // replace it.
+        if let [block] = blocks[..]
+            && body.basic_blocks[block].statements.is_empty()
+        {
+            return None;
+        }
+
+        let unwind = unwind.unwrap_or(UnwindAction::Unreachable);
+        Some(ColdPath { entry, blocks, unwind, shared: Vec::new() })
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum LocalUse {
+    Unused,
+    /// The local is only used in the path with that index.
+    Path(usize),
+    /// The local is used outside of any path, or in several of them.
+    Shared,
+}
+
+/// Finds which locals are only used by a single path.
+struct LocalUses<'a> {
+    path_of: &'a IndexVec<BasicBlock, Option<usize>>,
+    uses: IndexVec<Local, LocalUse>,
+}
+
+impl<'tcx> Visitor<'tcx> for LocalUses<'_> {
+    fn visit_local(&mut self, local: Local, context: PlaceContext, location: Location) {
+        if let PlaceContext::NonUse(NonUseContext::VarDebugInfo) = context {
+            return;
+        }
+        let local_use = &mut self.uses[local];
+        *local_use = match (*local_use, self.path_of[location.block]) {
+            (LocalUse::Unused, Some(path)) => LocalUse::Path(path),
+            (LocalUse::Path(previous), Some(path)) if previous == path => LocalUse::Path(path),
+            _ => LocalUse::Shared,
+        };
+    }
+}
+
+/// Checks how a path uses the locals it shares with the rest of the body, and that they can be
+/// accessed through a pointer instead.
+struct SharedLocalChecker<'a> {
+    uses: &'a IndexVec<Local, LocalUse>,
+    path: usize,
+    /// Whether the path writes or only reads each shared local.
+    shared: IndexVec<Local, Option<Mutability>>,
+    valid: bool,
+}
+
+impl<'tcx> Visitor<'tcx> for SharedLocalChecker<'_> {
+    fn visit_local(&mut self, local: Local, context: PlaceContext, _: Location) {
+        if self.uses[local] == LocalUse::Path(self.path) {
+            return;
+        }
+        match context {
+            PlaceContext::NonUse(NonUseContext::VarDebugInfo) => {}
+            // The shared local stays in the parent body, and so do its storage statements.
+            PlaceContext::NonUse(NonUseContext::StorageLive | NonUseContext::StorageDead) => {
+                self.valid = false;
+            }
+            _ if local == RETURN_PLACE => self.valid = false,
+            PlaceContext::MutatingUse(_)
+            | PlaceContext::NonMutatingUse(NonMutatingUseContext::Move) => {
+                self.shared[local] = Some(Mutability::Mut);
+            }
+            _ => {
+                self.shared[local].get_or_insert(Mutability::Not);
+            }
+        }
+    }
+
+    fn visit_projection_elem(
+        &mut self,
+        place_ref: PlaceRef<'tcx>,
+        elem: PlaceElem<'tcx>,
+        context: PlaceContext,
+        location: Location,
+    ) {
+        // Indices must be locals of the outlined body.
+        if let ProjectionElem::Index(index) = elem
+            && self.uses[index] != LocalUse::Path(self.path)
+        {
+            self.valid = false;
+        }
+        self.super_projection_elem(place_ref, elem, context, location);
+    }
+}
+
+/// Where a local of the parent body is found in the outlined body.
+#[derive(Copy, Clone, Debug)]
+enum Relocated {
+    Local(Local),
+    /// The local is behind the pointer passed in this argument.
+    Pointee(Local),
+}
+
+struct Relocator<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    locals: IndexVec<Local, Option<Relocated>>,
+}
+
+impl<'tcx> Relocator<'tcx> {
+    fn relocate_local(&self, local: Local) -> Local {
+        match self.locals[local] {
+            Some(Relocated::Local(local)) => local,
+            relocated => bug!("{local:?} cannot be used as a local, it is {relocated:?}"),
+        }
+    }
+}
+
+impl<'tcx> MutVisitor<'tcx> for Relocator<'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn visit_local(&mut self, local: &mut Local, _: PlaceContext, _: Location) {
+        *local = self.relocate_local(*local);
+    }
+
+    fn visit_place(&mut self, place: &mut Place<'tcx>, _: PlaceContext, _: Location) {
+        let mut projection = Vec::with_capacity(place.projection.len() + 1);
+        let local = match self.locals[place.local] {
+            Some(Relocated::Local(local)) => local,
+            Some(Relocated::Pointee(arg)) => {
+                projection.push(PlaceElem::Deref);
+                arg
+            }
+            None => bug!("{:?} is not used by the outlined path", place.local),
+        };
+        projection.extend(place.projection.iter().map(|elem| match elem {
+            ProjectionElem::Index(index) => ProjectionElem::Index(self.relocate_local(index)),
+            elem => elem,
+        }));
+        *place = Place { local, projection: self.tcx.mk_place_elems(&projection) };
+    }
+}
+
+fn outline<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &mut Body<'tcx>,
+    uses: &IndexVec<Local, LocalUse>,
+    id: usize,
+    path: ColdPath,
+) {
+    let def_id = body.source.def_id();
+    let index = body.cold_paths.len();
+    let entry_source_info = body.basic_blocks[path.entry].terminator().source_info;
+    let span = entry_source_info.span;
+    debug!(?path);
+
+    // The outlined body takes a pointer to each shared local, followed by the locals that only the
+    // path uses.
+    let mut local_decls = IndexVec::with_capacity(1 + path.shared.len());
+    local_decls.push(LocalDecl::new(tcx.types.never, span));
+    let mut locals = IndexVec::from_elem(None, &body.local_decls);
+    for &(local, mutability) in &path.shared {
+        let ty = Ty::new_ptr(tcx, body.local_decls[local].ty, mutability);
+        let arg = local_decls.push(LocalDecl::new(ty, span));
+        locals[local] = Some(Relocated::Pointee(arg));
+    }
+    for (local, &local_use) in uses.iter_enumerated() {
+        if local_use == LocalUse::Path(id) {
+            let decl = body.local_decls[local].clone();
+            locals[local] = Some(Relocated::Local(local_decls.push(decl)));
+        }
+    }
+
+    let mut block_map = IndexVec::from_elem(None, &body.basic_blocks);
+    for (new_block, &block) in path.blocks.iter().enumerate() {
+        block_map[block] = Some(BasicBlock::from_usize(new_block));
+    }
+    let mut blocks: IndexVec<BasicBlock, BasicBlockData<'tcx>> =
+        path.blocks.iter().map(|&block| body.basic_blocks[block].clone()).collect();
+    for data in &mut blocks {
+        // The outlined body has no debuginfo of its own.
+        data.drop_debuginfo();
+        let terminator = data.terminator_mut();
+        if let Some(unwind) = terminator.unwind_mut()
+            && let UnwindAction::Cleanup(_) = unwind
+        {
+            *unwind = UnwindAction::Continue;
+        }
+        terminator.successors_mut(|target| *target = block_map[*target].unwrap());
+    }
+
+    let mut outlined = Body::new(
+        MirSource::from_instance(InstanceKind::ColdPathShim(def_id, index as u32)),
+        blocks,
+        body.source_scopes.clone(),
+        local_decls,
+        IndexVec::new(),
+        path.shared.len(),
+        Vec::new(),
+        body.span,
+        None,
+        None,
+    );
+    outlined.phase = body.phase;
+    outlined.required_consts = body.required_consts.clone();
+    outlined.mentioned_items = body.mentioned_items.clone();
+    let mut relocator = Relocator { tcx, locals };
+    for (block, data) in outlined.basic_blocks.as_mut_preserves_cfg().iter_enumerated_mut() {
+        relocator.visit_basic_block_data(block, data);
+    }
+
+    // Replace the entry of the path with a call to the outlined body.
+    let fn_ptr_ty = Ty::new_fn_ptr(tcx, ty::Binder::dummy(outlined.cold_path_fn_sig(tcx)));
+    let instance = Instance {
+        def: InstanceKind::ColdPathShim(def_id, index as u32),
+        args: ty::GenericArgs::empty(),
+    };
+    let fn_ptr = Pointer::from(tcx.reserve_and_set_fn_alloc(instance, CTFE_ALLOC_SALT));
+    let func = Operand::Constant(Box::new(ConstOperand {
+        span,
+        user_ty: None,
+        const_: Const::from_scalar(tcx, Scalar::from_pointer(fn_ptr, &tcx), fn_ptr_ty),
+    }));
+    body.cold_paths.push(outlined);
+
+    let mut statements = Vec::with_capacity(path.shared.len());
+    let mut args = Vec::with_capacity(path.shared.len());
+    for &(local, mutability) in &path.shared {
+        let ty = Ty::new_ptr(tcx, body.local_decls[local].ty, mutability);
+        let ptr = body.local_decls.push(LocalDecl::new(ty, span));
+        let rvalue = Rvalue::RawPtr(RawPtrKind::from(mutability), local.into());
+        statements.push(Statement::new(
+            entry_source_info,
+            StatementKind::Assign(Box::new((ptr.into(), rvalue))),
+        ));
+        args.push(Spanned { node: Operand::Move(ptr.into()), span });
+    }
+    let destination = body.local_decls.push(LocalDecl::new(tcx.types.never, span));
+    let entry = &mut body.basic_blocks_mut()[path.entry];
+    entry.statements = statements;
+    entry.drop_debuginfo();
+    entry.terminator_mut().kind = TerminatorKind::Call {
+        func,
+        args: args.into_boxed_slice(),
+        destination: destination.into(),
+        target: None,
+        unwind: path.unwind,
+        call_source: CallSource::Misc,
+        fn_span: span,
+    };
+
+    emit_remark(tcx, "OutlineColdPaths", def_id, span, RemarkKind::Passed, || {
+        format!("outlined the cold path starting at {:?}", path.entry)
+    });
+}
@@ -0,0 +1,49 @@
+//! Function hotness from the entry counts given with `-Z mir-profile-use`, used to guide inlining.
+
+use rustc_data_structures::unord::UnordMap;
+use rustc_hir::def_id::{DefId, DefIndex, DefPathHash, LOCAL_CRATE};
+use rustc_middle::ty::TyCtxt;
+pub(crate) use rustc_session::mir_profile::Hotness;
+
+/// The hotness of `def_id` in the profile, or `Hotness::Neutral` if there is no profile.
+pub(crate) fn hotness(tcx: TyCtxt<'_>, def_id: DefId) -> Hotness {
+    if tcx.sess.mir_profile().is_none() {
+        return Hotness::Neutral;
+    }
+    tcx.mir_profile_hotness(()).get(&tcx.def_path_hash(def_id)).copied().unwrap_or(Hotness::Neutral)
+}
+
+/// Resolves the paths of the profile to the definitions they name. The paths are only built for
+/// the definitions of the crates that the profile mentions, once per session.
+pub(crate) fn mir_profile_hotness(tcx: TyCtxt<'_>, (): ()) -> UnordMap<DefPathHash, Hotness> {
+    let mut hotness = UnordMap::default();
+    let Some(profile) = tcx.sess.mir_profile() else {
+        return hotness;
+    };
+
+    let mut record = |def_id: DefId, crate_name: &str| {
+        let path = format!("{crate_name}{}", tcx.def_path(def_id).to_string_no_crate_verbose());
+        match profile.hotness(&path) {
+            Hotness::Neutral => {}
+            function_hotness => {
+                hotness.insert(tcx.def_path_hash(def_id), function_hotness);
+            }
+        }
+    };
+    for &krate in [LOCAL_CRATE].iter().chain(tcx.crates(())) {
+        let crate_name = tcx.crate_name(krate);
+        if !profile.mentions_crate(crate_name.as_str()) {
+            continue;
+        }
+        if krate == LOCAL_CRATE {
+            for def_id in tcx.iter_local_def_id() {
+                record(def_id.to_def_id(), crate_name.as_str());
+            }
+        } else {
+            for index in 0..tcx.num_extern_def_ids(krate) {
+                record(DefId { krate, index: DefIndex::from_usize(index) }, crate_name.as_str());
+            }
+        }
+    }
+    hotness
+}
@@ -0,0 +1,58 @@
+//! Optimization remarks for MIR passes, enabled with `-Z mir-remarks`.
+//!
+//! Remarks explain the decisions of a pass at a given location in the source, e.g. why a call was
+//! not inlined. They are keyed by the name of the pass, as accepted by `-Z mir-enable-passes`, and
+//! by the body that the pass was running on. See `rustc_session::mir_remarks` for how they are
+//! reported.
+
+use rustc_hir::def_id::DefId;
+use rustc_middle::ty::TyCtxt;
+pub(crate) use rustc_session::mir_remarks::RemarkKind;
+use rustc_session::mir_remarks::{MirRemark, RemarkLocation};
+use rustc_span::Span;
+
+use crate::errors;
+
+/// Whether remarks are enabled for `pass`. Passes should check this before doing work that is only
+/// needed to build a remark.
+pub(crate) fn remarks_enabled(tcx: TyCtxt<'_>, pass: &str) -> bool {
+    tcx.sess.opts.unstable_opts.mir_remarks.contains(pass)
+}
+
+/// Emits a remark for `pass`, which was running on the body of `def_id`. The message is only built
+/// if remarks are enabled for `pass`.
+pub(crate) fn emit_remark(
+    tcx: TyCtxt<'_>,
+    pass: &'static str,
+    def_id: DefId,
+    span: Span,
+    kind: RemarkKind,
+    message: impl FnOnce() -> String,
+) {
+    if !remarks_enabled(tcx, pass) {
+        return;
+    }
+
+    let function = tcx.def_path_str(def_id);
+    let message = message();
+    if tcx.sess.opts.unstable_opts.remark_dir.is_some() {
+        let location = (!span.is_dummy()).then(|| {
+            let loc = tcx.sess.source_map().lookup_char_pos(span.lo());
+            RemarkLocation {
+                file: loc.file.name.prefer_remapped_unconditionally().to_string(),
+                line: loc.line,
+                column: loc.col_display + 1,
+            }
+        });
+        let pass = pass.to_owned();
+        tcx.record_mir_remark(MirRemark { function, location, pass, kind, message });
+    } else {
+        tcx.dcx().emit_note(errors::MirRemark {
+            span,
+            pass,
+            kind: kind.as_str(),
+            function: &function,
+            message: &message,
+        });
+    }
+}
@@ -6,6 +6,7 @@ use rustc_hir as hir;
 use rustc_hir::def_id::DefId;
 use rustc_hir::lang_items::LangItem;
 use rustc_index::{Idx, IndexVec};
+use rustc_middle::mir::mono::ConstSpecialization;
 use rustc_middle::mir::visit::{MutVisitor, PlaceContext};
 use rustc_middle::mir::*;
 use rustc_middle::query::Providers;
@@ -20,9 +21,10 @@ use tracing::{debug, instrument};
 use crate::elaborate_drop::{DropElaborator, DropFlagMode, DropStyle, Unwind, elaborate_drop};
 use crate::patch::MirPatch;
 use crate::{
-    abort_unwinding_calls, add_call_guards, add_moves_for_packed_drops, deref_separator, inline,
-    instsimplify, mentioned_items, pass_manager as pm, remove_noop_landing_pads,
-    run_optimization_passes, simplify,
+    abort_unwinding_calls, add_call_guards, add_moves_for_packed_drops, copy_prop,
+    dataflow_const_prop, dead_store_elimination, deref_separator, gvn, inline, instsimplify,
+    jump_threading, mentioned_items, pass_manager as pm, remove_noop_landing_pads,
+    run_optimization_passes, simplify, simplify_branches,
 };
 
 mod async_destructor_ctor;
@@ -65,6 +67,22 @@ impl<'tcx> MutVisitor<'tcx> for FixProxyFutureDropVisitor<'tcx> {
     }
 }
 
+/// Replaces locals according to `map`.
+struct LocalRenamer<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    map: IndexVec<Local, Local>,
+}
+
+impl<'tcx> MutVisitor<'tcx> for LocalRenamer<'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn visit_local(&mut self, local: &mut Local, _: PlaceContext, _: Location) {
+        *local = self.map[*local];
+    }
+}
+
 fn make_shim<'tcx>(tcx: TyCtxt<'tcx>, instance: ty::InstanceKind<'tcx>) -> Body<'tcx> {
     debug!("make_shim({:?})", instance);
 
@@ -213,6 +231,27 @@ fn make_shim<'tcx>(tcx: TyCtxt<'tcx>, instance: ty::InstanceKind<'tcx>) -> Body<
             debug!("make_shim({:?}) = {:?}", instance, body);
             return body;
         }
+        ty::InstanceKind::ColdPathShim(def_id, index) => {
+            // The body was already optimized along with the body it was outlined from, up to the
+            // point where it was outlined.
+            let mut body = tcx.optimized_mir(def_id).cold_paths[index as usize].clone();
+            pm::run_passes(
+                tcx,
+                &mut body,
+                &[&add_call_guards::CriticalCallEdges],
+                Some(MirPhase::Runtime(RuntimePhase::Optimized)),
+                pm::Optimizations::Allowed,
+            );
+            debug!("make_shim({:?}) = {:?}", instance, body);
+            return body;
+        }
+        ty::InstanceKind::ConstSpecialized(def_id, index) => {
+            let mono_items = tcx.collect_and_partition_mono_items(());
+            let specialization = &mono_items.const_specializations.specializations[index as usize];
+            let body = build_const_specialization(tcx, def_id, index, specialization);
+            debug!("make_shim({:?}) = {:?}", instance, body);
+            return body;
+        }
         ty::InstanceKind::Virtual(..) => {
             bug!("InstanceKind::Virtual ({:?}) is for direct calls only", instance)
         }
@@ -1251,3 +1290,69 @@ fn build_construct_coroutine_by_move_shim<'tcx>(
 
     body
 }
+
+/// Builds the body of a clone of `specialization.instance` in which some arguments are constants.
+///
+/// Each of these arguments is replaced by a fresh local, which is only assigned the constant, in a
+/// new entry block since the old one may be the target of a loop. The body is then optimized
+/// again, which propagates the constants.
+fn build_const_specialization<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def_id: DefId,
+    index: u32,
+    specialization: &ConstSpecialization<'tcx>,
+) -> Body<'tcx> {
+    let instance = specialization.instance;
+    let mut body = instance.instantiate_mir_and_normalize_erasing_regions(
+        tcx,
+        ty::TypingEnv::fully_monomorphized(),
+        EarlyBinder::bind(tcx.instance_mir(instance.def).clone()),
+    );
+    body.source = MirSource::from_instance(ty::InstanceKind::ConstSpecialized(def_id, index));
+
+    let source_info = SourceInfo::outermost(body.span);
+    let mut map: IndexVec<Local, Local> = body.local_decls.indices().collect();
+    let mut statements = Vec::with_capacity(specialization.args.len());
+    for &(arg, const_) in &specialization.args {
+        let decl = LocalDecl::new(body.local_decls[arg].ty, body.local_decls[arg].source_info.span);
+        let local = body.local_decls.push(decl);
+        map[arg] = local;
+        let constant = ConstOperand { span: body.span, user_ty: None, const_ };
+        statements.push(Statement::new(
+            source_info,
+            StatementKind::Assign(Box::new((
+                local.into(),
+                Rvalue::Use(Operand::Constant(Box::new(constant))),
+            ))),
+        ));
+    }
+    LocalRenamer { tcx, map }.visit_body_preserves_cfg(&mut body);
+
+    let goto = Terminator { source_info, kind: TerminatorKind::Goto { target: START_BLOCK } };
+    body.basic_blocks_mut().raw.insert(0, BasicBlockData::new_stmts(statements, Some(goto), false));
+    for data in body.basic_blocks_mut().iter_mut() {
+        data.terminator_mut().successors_mut(|target| *target += 1);
+    }
+
+    body.phase = MirPhase::Runtime(RuntimePhase::PostCleanup);
+    pm::run_passes(
+        tcx,
+        &mut body,
+        &[
+            &gvn::GVN,
+            &simplify::SimplifyLocals::AfterGVN,
+            &dataflow_const_prop::DataflowConstProp,
+            &simplify_branches::SimplifyConstCondition::AfterConstProp,
+            &jump_threading::JumpThreading,
+            &simplify_branches::SimplifyConstCondition::Final,
+            &simplify::SimplifyCfg::Final,
+            &copy_prop::CopyProp,
+            &dead_store_elimination::DeadStoreElimination::Final,
+            &simplify::SimplifyLocals::Final,
+            &add_call_guards::CriticalCallEdges,
+        ],
+        Some(MirPhase::Runtime(RuntimePhase::Optimized)),
+        pm::Optimizations::Allowed,
+    );
+    body
+}
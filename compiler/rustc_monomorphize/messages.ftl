@@ -39,6 +39,9 @@ monomorphize_abi_required_target_feature =
 monomorphize_couldnt_dump_mono_stats =
     unexpected error occurred while dumping monomorphization stats: {$error}
 
+monomorphize_couldnt_dump_partitioning =
+    failed to write the partitioning report to `{$path}`: {$error}
+
 monomorphize_encountered_error_while_instantiating =
     the above error was encountered while instantiating `{$kind} {$instance}`
 
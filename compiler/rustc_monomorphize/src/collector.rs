@@ -220,7 +220,7 @@ use rustc_hir::lang_items::LangItem;
 use rustc_hir::limit::Limit;
 use rustc_middle::middle::codegen_fn_attrs::CodegenFnAttrFlags;
 use rustc_middle::mir::interpret::{AllocId, ErrorHandled, GlobalAlloc, Scalar};
-use rustc_middle::mir::mono::{CollectionMode, InstantiationMode, MonoItem};
+use rustc_middle::mir::mono::{CollectionMode, InstantiationMode, MonoCallGraph, MonoItem};
 use rustc_middle::mir::visit::Visitor as MirVisitor;
 use rustc_middle::mir::{self, Location, MentionedItem, traversal};
 use rustc_middle::query::TyCtxtAt;
@@ -284,6 +284,40 @@ impl<'tcx> UsageMap<'tcx> {
         assert!(self.used_map.insert(user_item, used_items.items().collect()).is_none());
     }
 
+    /// Records a clone of `original` that is used by `callers` instead of the original. The clone
+    /// uses the same items as the original.
+    pub(crate) fn record_clone(
+        &mut self,
+        original: MonoItem<'tcx>,
+        clone: MonoItem<'tcx>,
+        callers: &[MonoItem<'tcx>],
+    ) {
+        let used_items = self.used_map[&original].clone();
+        for &used_item in &used_items {
+            self.user_map.entry(used_item).or_default().push(clone);
+        }
+        assert!(self.used_map.insert(clone, used_items).is_none());
+
+        for &caller in callers {
+            self.user_map.entry(clone).or_default().push(caller);
+            self.used_map.get_mut(&caller).unwrap().push(clone);
+        }
+    }
+
+    /// Returns the functions used by each of the functions in `items`.
+    pub(crate) fn call_graph(&self, items: &[MonoItem<'tcx>]) -> MonoCallGraph<'tcx> {
+        items
+            .iter()
+            .filter_map(|item| {
+                let MonoItem::Fn(caller) = *item else { return None };
+                let callees = self.used_map.get(item).into_iter().flatten().filter_map(|used| {
+                    if let MonoItem::Fn(callee) = *used { Some(callee) } else { None }
+                });
+                Some((caller, callees.collect()))
+            })
+            .collect()
+    }
+
     pub(crate) fn get_user_items(&self, item: MonoItem<'tcx>) -> &[MonoItem<'tcx>] {
         self.user_map.get(&item).map(|items| items.as_slice()).unwrap_or(&[])
     }
@@ -995,7 +1029,9 @@ fn visit_instance_use<'tcx>(
         | ty::InstanceKind::Item(..)
         | ty::InstanceKind::FnPtrShim(..)
         | ty::InstanceKind::CloneShim(..)
-        | ty::InstanceKind::FnPtrAddrShim(..) => {
+        | ty::InstanceKind::FnPtrAddrShim(..)
+        | ty::InstanceKind::ColdPathShim(..)
+        | ty::InstanceKind::ConstSpecialized(..) => {
             output.push(create_fn_mono_item(tcx, instance, source));
         }
     }
@@ -0,0 +1,245 @@
+//! Clones of functions for the constant arguments that their callers pass.
+//!
+//! MIR optimizations only see one body at a time, so a function that is not inlined cannot make
+//! use of the constants that its callers pass, e.g. the fields of a configuration that is decided
+//! once and then passed down. Once all the mono items of the crate are collected, all the direct
+//! calls to a function are known. When all of them pass a constant for some of its arguments, and
+//! there are only a few different sets of such constants, the function is cloned for each set as an
+//! `InstanceKind::ConstSpecialized`, whose body is optimized again with these arguments replaced by
+//! the constants. The calls are redirected to the clones in the MIR that is code generated for
+//! their callers, see `codegen_mir`.
+//!
+//! The original function is kept, as it may also be used through a function pointer or from
+//! another crate. When it is not, the backend removes it.
+//!
+//! This is only done at `-Z mir-opt-level=3`, and not with incremental compilation, which does not
+//! track that the MIR of a caller depends on the calls in the rest of the crate.
+
+use rustc_abi::ExternAbi;
+use rustc_data_structures::fx::{FxHashSet, FxIndexMap};
+use rustc_hir::def::DefKind;
+use rustc_middle::bug;
+use rustc_middle::middle::codegen_fn_attrs::CodegenFnAttrFlags;
+use rustc_middle::mir::interpret::{CTFE_ALLOC_SALT, Pointer, Scalar};
+use rustc_middle::mir::mono::{ConstSpecialization, ConstSpecializations, MonoItem};
+use rustc_middle::mir::{
+    BasicBlock, Body, Const, ConstOperand, Local, Operand, Terminator, TerminatorKind, traversal,
+};
+use rustc_middle::ty::{self, Instance, InstanceKind, ScalarInt, Ty, TyCtxt, TypeFoldable};
+use rustc_middle::util::Providers;
+
+use crate::collector::UsageMap;
+
+/// The most clones of a single function.
+const MAX_CLONES: usize = 4;
+
+/// Functions whose `size_estimate` is above this are not cloned.
+const MAX_SIZE_ESTIMATE: usize = 400;
+
+/// The type and value of a constant argument.
+type ConstArg<'tcx> = (Ty<'tcx>, ScalarInt);
+
+struct CallSite<'tcx> {
+    caller: Instance<'tcx>,
+    block: BasicBlock,
+    /// The arguments of the call that are constants.
+    args: Vec<Option<ConstArg<'tcx>>>,
+}
+
+/// Clones the functions in `items` that are always called with some constant arguments, adding
+/// the clones to `items` and `usage_map`.
+pub(crate) fn specialize_for_const_args<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    items: &mut Vec<MonoItem<'tcx>>,
+    usage_map: &mut UsageMap<'tcx>,
+) -> ConstSpecializations<'tcx> {
+    let mut specializations = ConstSpecializations::default();
+    if tcx.sess.mir_opt_level() < 3 || tcx.sess.opts.incremental.is_some() {
+        return specializations;
+    }
+
+    let _prof_timer = tcx.prof.generic_activity("monomorphization_const_specialization");
+
+    let local_fns: FxHashSet<Instance<'tcx>> = items
+        .iter()
+        .filter_map(|item| if let MonoItem::Fn(instance) = *item { Some(instance) } else { None })
+        .collect();
+
+    // `items` are sorted, so this is deterministic.
+    let mut calls: FxIndexMap<Instance<'tcx>, Vec<CallSite<'tcx>>> = FxIndexMap::default();
+    for item in items.iter() {
+        let MonoItem::Fn(caller) = *item else { continue };
+        // Only the MIR of items is redirected, see `codegen_mir`.
+        if !matches!(caller.def, InstanceKind::Item(_)) {
+            continue;
+        }
+        let body = tcx.instance_mir(caller.def);
+        for (block, data) in traversal::mono_reachable(body, tcx, caller) {
+            if let Some((callee, args)) = direct_call(tcx, caller, body, data.terminator())
+                && local_fns.contains(&callee)
+            {
+                calls.entry(callee).or_default().push(CallSite { caller, block, args });
+            }
+        }
+    }
+
+    for (callee, sites) in calls {
+        if !can_clone(tcx, callee) {
+            continue;
+        }
+
+        // The arguments that are constants in every call.
+        let constant_args: Vec<usize> = (0..sites[0].args.len())
+            .filter(|&i| sites.iter().all(|site| site.args[i].is_some()))
+            .collect();
+        if constant_args.is_empty() {
+            continue;
+        }
+
+        let mut clones: FxIndexMap<Vec<ConstArg<'tcx>>, Vec<&CallSite<'tcx>>> =
+            FxIndexMap::default();
+        for site in &sites {
+            let values = constant_args.iter().map(|&i| site.args[i].unwrap()).collect();
+            clones.entry(values).or_default().push(site);
+        }
+        if clones.len() > MAX_CLONES {
+            continue;
+        }
+
+        for (values, sites) in clones {
+            let index = specializations.specializations.len() as u32;
+            let args = std::iter::zip(&constant_args, values)
+                .map(|(&i, (ty, value))| {
+                    (Local::from_usize(i + 1), Const::from_scalar(tcx, Scalar::Int(value), ty))
+                })
+                .collect();
+            specializations.specializations.push(ConstSpecialization { instance: callee, args });
+
+            let mut callers = Vec::with_capacity(sites.len());
+            for site in sites {
+                specializations.redirects.entry(site.caller).or_default().push((site.block, index));
+                callers.push(MonoItem::Fn(site.caller));
+            }
+            callers.dedup();
+
+            let clone = MonoItem::Fn(clone_instance(callee, index));
+            usage_map.record_clone(MonoItem::Fn(callee), clone, &callers);
+            items.push(clone);
+        }
+    }
+
+    specializations
+}
+
+fn clone_instance<'tcx>(callee: Instance<'tcx>, index: u32) -> Instance<'tcx> {
+    Instance { def: InstanceKind::ConstSpecialized(callee.def_id(), index), args: callee.args }
+}
+
+fn monomorphize<'tcx, T: TypeFoldable<TyCtxt<'tcx>>>(
+    tcx: TyCtxt<'tcx>,
+    instance: Instance<'tcx>,
+    value: T,
+) -> T {
+    instance.instantiate_mir_and_normalize_erasing_regions(
+        tcx,
+        ty::TypingEnv::fully_monomorphized(),
+        ty::EarlyBinder::bind(value),
+    )
+}
+
+/// If `terminator` is a direct call, returns the callee and the constant arguments.
+fn direct_call<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    caller: Instance<'tcx>,
+    body: &Body<'tcx>,
+    terminator: &Terminator<'tcx>,
+) -> Option<(Instance<'tcx>, Vec<Option<ConstArg<'tcx>>>)> {
+    let TerminatorKind::Call { func, args, .. } = &terminator.kind else { return None };
+    let ty::FnDef(def_id, fn_args) = *monomorphize(tcx, caller, func.ty(body, tcx)).kind() else {
+        return None;
+    };
+    let callee = Instance::expect_resolve(
+        tcx,
+        ty::TypingEnv::fully_monomorphized(),
+        def_id,
+        fn_args,
+        terminator.source_info.span,
+    );
+    let args = args.iter().map(|arg| const_arg(tcx, caller, &arg.node)).collect();
+    Some((callee, args))
+}
+
+fn const_arg<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    caller: Instance<'tcx>,
+    operand: &Operand<'tcx>,
+) -> Option<ConstArg<'tcx>> {
+    let Operand::Constant(constant) = operand else { return None };
+    let const_ = monomorphize(tcx, caller, constant.const_);
+    let ty = const_.ty();
+    if !(ty.is_bool() || ty.is_char() || ty.is_integral()) {
+        return None;
+    }
+    let value = const_.try_eval_scalar_int(tcx, ty::TypingEnv::fully_monomorphized())?;
+    Some((ty, value))
+}
+
+fn can_clone<'tcx>(tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> bool {
+    let InstanceKind::Item(def_id) = instance.def else { return false };
+    if !matches!(tcx.def_kind(def_id), DefKind::Fn | DefKind::AssocFn) {
+        return false;
+    }
+
+    // The calls are redirected through a function pointer, which must have the same ABI as the
+    // function.
+    let sig = tcx.fn_sig(def_id).skip_binder();
+    if sig.abi() != ExternAbi::Rust || sig.c_variadic() {
+        return false;
+    }
+
+    let attrs = tcx.codegen_fn_attrs(def_id);
+    if attrs.contains_extern_indicator()
+        || attrs.flags.intersects(CodegenFnAttrFlags::TRACK_CALLER | CodegenFnAttrFlags::NAKED)
+        || tcx.intrinsic(def_id).is_some()
+    {
+        return false;
+    }
+
+    let body = tcx.instance_mir(instance.def);
+    body.coroutine.is_none()
+        && body.function_coverage_info.is_none()
+        && tcx.size_estimate(instance) <= MAX_SIZE_ESTIMATE
+}
+
+pub(crate) fn provide(providers: &mut Providers) {
+    providers.codegen_mir = codegen_mir;
+}
+
+fn codegen_mir<'tcx>(tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> &'tcx Body<'tcx> {
+    let body = tcx.instance_mir(instance.def);
+    let specializations = tcx.collect_and_partition_mono_items(()).const_specializations;
+    let Some(redirects) = specializations.redirects.get(&instance) else {
+        return body;
+    };
+
+    let mut body = body.clone();
+    for &(block, index) in redirects {
+        let callee = specializations.specializations[index as usize].instance;
+        let clone = clone_instance(callee, index);
+        let fn_ptr_ty =
+            Ty::new_fn_ptr(tcx, callee.ty(tcx, ty::TypingEnv::fully_monomorphized()).fn_sig(tcx));
+        let fn_ptr = Pointer::from(tcx.reserve_and_set_fn_alloc(clone, CTFE_ALLOC_SALT));
+
+        let terminator = body.basic_blocks.as_mut_preserves_cfg()[block].terminator_mut();
+        let span = terminator.source_info.span;
+        let TerminatorKind::Call { func, .. } = &mut terminator.kind else {
+            bug!("redirected block {block:?} of `{instance}` does not end in a call");
+        };
+        *func = Operand::Constant(Box::new(ConstOperand {
+            span,
+            user_ty: None,
+            const_: Const::from_scalar(tcx, Scalar::from_pointer(fn_ptr, &tcx), fn_ptr_ty),
+        }));
+    }
+    tcx.arena.alloc(body)
+}
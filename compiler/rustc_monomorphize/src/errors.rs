@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use rustc_macros::{Diagnostic, LintDiagnostic};
 use rustc_middle::ty::{Instance, Ty};
 use rustc_span::{Span, Symbol};
@@ -46,6 +48,13 @@ pub(crate) struct CouldntDumpMonoStats {
     pub error: String,
 }
 
+#[derive(Diagnostic)]
+#[diag(monomorphize_couldnt_dump_partitioning)]
+pub(crate) struct CouldntDumpPartitioning {
+    pub path: PathBuf,
+    pub error: String,
+}
+
 #[derive(Diagnostic)]
 #[diag(monomorphize_encountered_error_while_instantiating)]
 pub(crate) struct EncounteredErrorWhileInstantiating<'tcx> {
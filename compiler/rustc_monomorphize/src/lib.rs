@@ -15,6 +15,7 @@ use rustc_middle::{bug, traits};
 use rustc_span::ErrorGuaranteed;
 
 mod collector;
+mod const_specialization;
 mod errors;
 mod mono_checks;
 mod partitioning;
@@ -51,5 +52,6 @@ fn custom_coerce_unsize_info<'tcx>(
 
 pub fn provide(providers: &mut Providers) {
     partitioning::provide(providers);
+    const_specialization::provide(providers);
     mono_checks::provide(providers);
 }
@@ -92,6 +92,9 @@
 //! source-level module, functions from the same module will be available for
 //! inlining, even when they are not marked `#[inline]`.
 
+mod report;
+
+use std::cell::RefCell;
 use std::cmp;
 use std::collections::hash_map::Entry;
 use std::fs::{self, File};
@@ -114,7 +117,7 @@ use rustc_middle::mir::mono::{
     MonoItemPartitions, Visibility,
 };
 use rustc_middle::ty::print::{characteristic_def_id_of_type, with_no_trimmed_paths};
-use rustc_middle::ty::{self, InstanceKind, TyCtxt};
+use rustc_middle::ty::{self, Instance, InstanceKind, TyCtxt};
 use rustc_middle::util::Providers;
 use rustc_session::CodegenUnits;
 use rustc_session::config::{DumpMonoStatsFormat, SwitchWithOptPath};
@@ -122,12 +125,24 @@ use rustc_span::Symbol;
 use rustc_target::spec::SymbolVisibility;
 use tracing::debug;
 
+use self::report::{MergeReason, PartitioningReport, RootPlacement};
 use crate::collector::{self, MonoItemCollectionStrategy, UsageMap};
+use crate::const_specialization;
 use crate::errors::{CouldntDumpMonoStats, SymbolAlreadyDefined};
 
 struct PartitioningCx<'a, 'tcx> {
     tcx: TyCtxt<'tcx>,
     usage_map: &'a UsageMap<'tcx>,
+    /// The report of `-Z dump-partitioning`, if requested.
+    report: Option<RefCell<PartitioningReport<'tcx>>>,
+}
+
+impl<'tcx> PartitioningCx<'_, 'tcx> {
+    fn report(&self, f: impl FnOnce(&mut PartitioningReport<'tcx>)) {
+        if let Some(report) = &self.report {
+            f(&mut report.borrow_mut());
+        }
+    }
 }
 
 struct PlacedMonoItems<'tcx> {
@@ -148,7 +163,8 @@ where
 {
     let _prof_timer = tcx.prof.generic_activity("cgu_partitioning");
 
-    let cx = &PartitioningCx { tcx, usage_map };
+    let report = tcx.sess.opts.unstable_opts.dump_partitioning.map(|_| Default::default());
+    let cx = &PartitioningCx { tcx, usage_map, report };
 
     // Place all mono items into a codegen unit. `place_mono_items` is
     // responsible for initializing the CGU size estimates.
@@ -193,6 +209,12 @@ where
         bug!("unsorted CGUs:\n{names}");
     }
 
+    if let Some(format) = tcx.sess.opts.unstable_opts.dump_partitioning
+        && let Some(report) = &cx.report
+    {
+        report.borrow().write(tcx, format, &codegen_units);
+    }
+
     codegen_units
 }
 
@@ -254,6 +276,16 @@ where
         }
         let size_estimate = mono_item.size_estimate(cx.tcx);
 
+        cx.report(|report| {
+            let placement = RootPlacement {
+                initial_cgu: cgu_name,
+                characteristic_def_id,
+                is_volatile,
+                is_exported_generic: mono_item.is_generic_fn() && !can_be_internalized,
+            };
+            report.place_root(mono_item, placement);
+        });
+
         cgu.items_mut()
             .insert(mono_item, MonoItemData { inlined: false, linkage, visibility, size_estimate });
 
@@ -268,6 +300,8 @@ where
         // from multiple root items within a CGU, which is fine, it just means
         // the `insert` will be a no-op.
         for inlined_item in reachable_inlined_items {
+            cx.report(|report| report.place_inlined(cgu_name, inlined_item, mono_item));
+
             // This is a CGU-private copy.
             cgu.items_mut().entry(inlined_item).or_insert_with(|| MonoItemData {
                 inlined: true,
@@ -293,6 +327,8 @@ where
         cgu.compute_size_estimate();
     }
 
+    cx.report(|report| report.add_initial_cgus(&codegen_units));
+
     return PlacedMonoItems { codegen_units, internalization_candidates };
 
     fn get_reachable_inlined_items<'tcx>(
@@ -372,6 +408,9 @@ fn merge_codegen_units<'tcx>(
         // unaffected. Recalculate size estimates afterwards.
         cgu_dst.items_mut().append(cgu_src.items_mut());
         cgu_dst.compute_size_estimate();
+        cx.report(|report| {
+            report.merge(MergeReason::MaxCodegenUnits, &cgu_src, cgu_dst, Some(max_overlap))
+        });
 
         // Record that `cgu_dst` now contains all the stuff that was in
         // `cgu_src` before.
@@ -411,6 +450,7 @@ fn merge_codegen_units<'tcx>(
         // unaffected. Recalculate size estimates afterwards.
         second_smallest.items_mut().append(smallest.items_mut());
         second_smallest.compute_size_estimate();
+        cx.report(|report| report.merge(MergeReason::MinCguSize, &smallest, second_smallest, None));
 
         // Don't update `cgu_contents`, that's only for incremental builds.
     }
@@ -453,6 +493,7 @@ fn merge_codegen_units<'tcx>(
                     // instead.
                     Symbol::intern(&CodegenUnit::mangle_name(new_cgu_name))
                 };
+                cx.report(|report| report.rename(cgu.name(), new_cgu_name));
                 cgu.set_name(new_cgu_name);
             }
         }
@@ -488,6 +529,7 @@ fn merge_codegen_units<'tcx>(
             let suffix = format!("{index:0num_digits$}");
             let numbered_codegen_unit_name =
                 cgu_name_builder.build_cgu_name_no_mangle(LOCAL_CRATE, &["cgu"], Some(suffix));
+            cx.report(|report| report.rename(cgu.name(), numbered_codegen_unit_name));
             cgu.set_name(numbered_codegen_unit_name);
         }
     }
@@ -584,6 +626,7 @@ fn internalize_symbols<'tcx>(
             // it's fine to make this monomorphization internal.
             data.linkage = Linkage::Internal;
             data.visibility = Visibility::Default;
+            cx.report(|report| report.internalize(*item));
         }
     }
 }
@@ -617,7 +660,7 @@ fn characteristic_def_id_of_mono_item<'tcx>(
     match mono_item {
         MonoItem::Fn(instance) => {
             let def_id = match instance.def {
-                ty::InstanceKind::Item(def) => def,
+                ty::InstanceKind::Item(def) | ty::InstanceKind::ConstSpecialized(def, _) => def,
                 ty::InstanceKind::VTableShim(..)
                 | ty::InstanceKind::ReifyShim(..)
                 | ty::InstanceKind::FnPtrShim(..)
@@ -631,7 +674,8 @@ fn characteristic_def_id_of_mono_item<'tcx>(
                 | ty::InstanceKind::FnPtrAddrShim(..)
                 | ty::InstanceKind::FutureDropPollShim(..)
                 | ty::InstanceKind::AsyncDropGlue(..)
-                | ty::InstanceKind::AsyncDropGlueCtorShim(..) => return None,
+                | ty::InstanceKind::AsyncDropGlueCtorShim(..)
+                | ty::InstanceKind::ColdPathShim(..) => return None,
             };
 
             // If this is a method, we want to put it into the same module as
@@ -803,7 +847,9 @@ fn mono_item_visibility<'tcx>(
         | InstanceKind::ConstructCoroutineInClosureShim { .. }
         | InstanceKind::DropGlue(..)
         | InstanceKind::CloneShim(..)
-        | InstanceKind::FnPtrAddrShim(..) => return Visibility::Hidden,
+        | InstanceKind::FnPtrAddrShim(..)
+        | InstanceKind::ColdPathShim(..)
+        | InstanceKind::ConstSpecialized(..) => return Visibility::Hidden,
     };
 
     // Both the `start_fn` lang item and `main` itself should not be exported,
@@ -1116,13 +1162,22 @@ fn collect_and_partition_mono_items(tcx: TyCtxt<'_>, (): ()) -> MonoItemPartitio
         MonoItemCollectionStrategy::Lazy
     };
 
-    let (items, usage_map) = collector::collect_crate_mono_items(tcx, collection_strategy);
+    let (mut items, mut usage_map) = collector::collect_crate_mono_items(tcx, collection_strategy);
 
     // If there was an error during collection (e.g. from one of the constants we evaluated),
     // then we stop here. This way codegen does not have to worry about failing constants.
     // (codegen relies on this and ICEs will happen if this is violated.)
     tcx.dcx().abort_if_errors();
 
+    let const_specializations =
+        const_specialization::specialize_for_const_args(tcx, &mut items, &mut usage_map);
+
+    let call_graph = if tcx.sess.opts.prints_stack_sizes() {
+        usage_map.call_graph(&items)
+    } else {
+        Default::default()
+    };
+
     let (codegen_units, _) = tcx.sess.time("partition_and_assert_distinct_symbols", || {
         sync::join(
             || {
@@ -1211,7 +1266,12 @@ fn collect_and_partition_mono_items(tcx: TyCtxt<'_>, (): ()) -> MonoItemPartitio
         }
     }
 
-    MonoItemPartitions { all_mono_items: tcx.arena.alloc(mono_items), codegen_units }
+    MonoItemPartitions {
+        all_mono_items: tcx.arena.alloc(mono_items),
+        codegen_units,
+        const_specializations: tcx.arena.alloc(const_specializations),
+        call_graph: tcx.arena.alloc(call_graph),
+    }
 }
 
 /// Outputs stats about instantiation counts and estimated size, per `MonoItem`'s
@@ -1311,10 +1371,16 @@ pub(crate) fn provide(providers: &mut Providers) {
             // statements, plus one for the terminator.
             InstanceKind::Item(..)
             | InstanceKind::DropGlue(..)
-            | InstanceKind::AsyncDropGlueCtorShim(..) => {
+            | InstanceKind::AsyncDropGlueCtorShim(..)
+            | InstanceKind::ColdPathShim(..) => {
                 let mir = tcx.instance_mir(instance.def);
                 mir.basic_blocks.iter().map(|bb| bb.statements.len() + 1).sum()
             }
+            // The body of a clone is only built for codegen, after partitioning, so estimate it
+            // with the body it was cloned from.
+            InstanceKind::ConstSpecialized(def_id, _) => {
+                tcx.size_estimate(Instance::new_raw(def_id, instance.args))
+            }
             // Other compiler-generated shims size estimate: 1
             _ => 1,
         }
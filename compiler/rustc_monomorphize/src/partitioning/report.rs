@@ -0,0 +1,247 @@
+//! The report of `-Z dump-partitioning`, which explains why each mono item ended up in its codegen
+//! unit.
+//!
+//! The partitioning records its decisions here as it goes: where each root item was placed and
+//! why, which root items each inlined item was copied for, which codegen units were merged and
+//! how they were renamed afterwards, and which items were internalized.
+
+use std::fs::File;
+use std::io::{self, BufWriter};
+
+use rustc_data_structures::fx::{FxHashMap, FxHashSet, FxIndexSet};
+use rustc_hir::def_id::DefId;
+use rustc_middle::mir::mono::{CodegenUnit, MonoItem};
+use rustc_middle::ty::TyCtxt;
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_session::config::DumpPartitioningFormat;
+use rustc_span::Symbol;
+use serde::Serialize;
+
+use crate::errors::CouldntDumpPartitioning;
+
+/// Why a root item was placed in its initial codegen unit.
+pub(super) struct RootPlacement {
+    pub initial_cgu: Symbol,
+    /// The definition whose module gives the codegen unit its name, if there is one.
+    pub characteristic_def_id: Option<DefId>,
+    /// Whether this is a generic instance, which is placed in the volatile codegen unit of the
+    /// module in incremental builds.
+    pub is_volatile: bool,
+    /// Whether this is a generic instance that is exported for downstream crates, as with
+    /// `-Z share-generics`.
+    pub is_exported_generic: bool,
+}
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(super) enum MergeReason {
+    /// Merged to stay within the maximum number of codegen units.
+    MaxCodegenUnits,
+    /// Merged because one of the codegen units was below the minimum size of non-incremental
+    /// builds.
+    MinCguSize,
+}
+
+#[derive(Serialize)]
+struct Merge {
+    reason: MergeReason,
+    from: String,
+    into: String,
+    /// The size of the inlined items that both codegen units contain.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    inlined_overlap: Option<usize>,
+    size_estimate: usize,
+}
+
+#[derive(Default)]
+pub(super) struct PartitioningReport<'tcx> {
+    roots: FxHashMap<MonoItem<'tcx>, RootPlacement>,
+    /// The root items that each inlined item was copied for, by initial codegen unit.
+    inlined_for: FxHashMap<(Symbol, MonoItem<'tcx>), FxIndexSet<MonoItem<'tcx>>>,
+    /// The initial codegen units that each codegen unit consists of, by current name.
+    initial_cgus: FxHashMap<Symbol, Vec<Symbol>>,
+    merges: Vec<Merge>,
+    internalized: FxHashSet<MonoItem<'tcx>>,
+}
+
+impl<'tcx> PartitioningReport<'tcx> {
+    pub(super) fn place_root(&mut self, item: MonoItem<'tcx>, placement: RootPlacement) {
+        self.roots.insert(item, placement);
+    }
+
+    pub(super) fn place_inlined(
+        &mut self,
+        cgu: Symbol,
+        item: MonoItem<'tcx>,
+        root: MonoItem<'tcx>,
+    ) {
+        self.inlined_for.entry((cgu, item)).or_default().insert(root);
+    }
+
+    pub(super) fn add_initial_cgus(&mut self, codegen_units: &[CodegenUnit<'tcx>]) {
+        self.initial_cgus.extend(codegen_units.iter().map(|cgu| (cgu.name(), vec![cgu.name()])));
+    }
+
+    pub(super) fn merge(
+        &mut self,
+        reason: MergeReason,
+        src: &CodegenUnit<'tcx>,
+        dst: &CodegenUnit<'tcx>,
+        inlined_overlap: Option<usize>,
+    ) {
+        let mut consumed = self.initial_cgus.remove(&src.name()).unwrap_or_default();
+        self.initial_cgus.entry(dst.name()).or_default().append(&mut consumed);
+        self.merges.push(Merge {
+            reason,
+            from: src.name().to_string(),
+            into: dst.name().to_string(),
+            inlined_overlap,
+            size_estimate: dst.size_estimate(),
+        });
+    }
+
+    pub(super) fn rename(&mut self, old_name: Symbol, new_name: Symbol) {
+        if let Some(initial_cgus) = self.initial_cgus.remove(&old_name) {
+            self.initial_cgus.insert(new_name, initial_cgus);
+        }
+    }
+
+    pub(super) fn internalize(&mut self, item: MonoItem<'tcx>) {
+        self.internalized.insert(item);
+    }
+
+    /// Writes the report on the final codegen units next to the outputs of the crate.
+    pub(super) fn write(
+        &self,
+        tcx: TyCtxt<'tcx>,
+        format: DumpPartitioningFormat,
+        codegen_units: &[CodegenUnit<'tcx>],
+    ) {
+        let report = self.build(tcx, codegen_units);
+        let ext = format!("partitioning.{}", format.extension());
+        let path = tcx.output_filenames(()).with_extension(&ext);
+        let result = File::create(&path).map(BufWriter::new).and_then(|file| match format {
+            DumpPartitioningFormat::Json => {
+                serde_json::to_writer_pretty(file, &report).map_err(io::Error::from)
+            }
+        });
+        if let Err(error) = result {
+            tcx.dcx().emit_fatal(CouldntDumpPartitioning { path, error: error.to_string() });
+        }
+    }
+
+    fn build(&self, tcx: TyCtxt<'tcx>, codegen_units: &[CodegenUnit<'tcx>]) -> Report<'_> {
+        let codegen_units = codegen_units
+            .iter()
+            .map(|cgu| {
+                let initial_cgus =
+                    self.initial_cgus.get(&cgu.name()).map_or(&[][..], Vec::as_slice);
+                let items = cgu
+                    .items_in_deterministic_order(tcx)
+                    .into_iter()
+                    .map(|(item, data)| ItemReport {
+                        item: with_no_trimmed_paths!(item.to_string()),
+                        symbol: item.symbol_name(tcx).name.to_string(),
+                        size_estimate: data.size_estimate,
+                        linkage: format!("{:?}", data.linkage),
+                        visibility: format!("{:?}", data.visibility),
+                        placement: self.placement(tcx, initial_cgus, item, data.inlined),
+                    })
+                    .collect();
+                CodegenUnitReport {
+                    name: cgu.name().to_string(),
+                    size_estimate: cgu.size_estimate(),
+                    initial_cgus: initial_cgus.iter().map(|name| name.to_string()).collect(),
+                    items,
+                }
+            })
+            .collect();
+
+        Report {
+            incremental: tcx.sess.opts.incremental.is_some(),
+            share_generics: tcx.sess.opts.share_generics(),
+            max_codegen_units: tcx.sess.codegen_units().as_usize(),
+            merges: &self.merges,
+            codegen_units,
+        }
+    }
+
+    fn placement(
+        &self,
+        tcx: TyCtxt<'tcx>,
+        initial_cgus: &[Symbol],
+        item: MonoItem<'tcx>,
+        inlined: bool,
+    ) -> Placement {
+        if !inlined {
+            let root = &self.roots[&item];
+            return Placement::Root {
+                initial_cgu: root.initial_cgu.to_string(),
+                characteristic_def: root
+                    .characteristic_def_id
+                    .map(|def_id| with_no_trimmed_paths!(tcx.def_path_str(def_id))),
+                volatile: root.is_volatile,
+                exported_generic: root.is_exported_generic,
+                internalized: self.internalized.contains(&item),
+            };
+        }
+
+        // The copy is shared by the root items of all of the initial codegen units that were
+        // merged into this one.
+        let mut inlined_for: Vec<_> = initial_cgus
+            .iter()
+            .filter_map(|&initial_cgu| self.inlined_for.get(&(initial_cgu, item)))
+            .flatten()
+            .map(|root| with_no_trimmed_paths!(root.to_string()))
+            .collect();
+        inlined_for.sort();
+        inlined_for.dedup();
+        Placement::Inlined { inlined_for }
+    }
+}
+
+#[derive(Serialize)]
+struct Report<'a> {
+    incremental: bool,
+    share_generics: bool,
+    max_codegen_units: usize,
+    /// The merges of codegen units, in order, with the names that they had before the renaming.
+    merges: &'a [Merge],
+    codegen_units: Vec<CodegenUnitReport>,
+}
+
+#[derive(Serialize)]
+struct CodegenUnitReport {
+    name: String,
+    size_estimate: usize,
+    /// The codegen units that were merged into this one, with their names before the renaming.
+    initial_cgus: Vec<String>,
+    items: Vec<ItemReport>,
+}
+
+#[derive(Serialize)]
+struct ItemReport {
+    item: String,
+    symbol: String,
+    size_estimate: usize,
+    linkage: String,
+    visibility: String,
+    placement: Placement,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum Placement {
+    /// A root item, which is placed in the codegen unit of the module of its characteristic
+    /// definition, or in a fallback codegen unit if it doesn't have one.
+    Root {
+        initial_cgu: String,
+        characteristic_def: Option<String>,
+        volatile: bool,
+        exported_generic: bool,
+        internalized: bool,
+    },
+    /// A local copy of an item, which is placed in every codegen unit with a root item that uses
+    /// it, directly or through other inlined items.
+    Inlined { inlined_for: Vec<String> },
+}
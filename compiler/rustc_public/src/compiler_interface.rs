@@ -17,10 +17,11 @@ use crate::mir::mono::{Instance, InstanceDef, StaticDef};
 use crate::mir::{BinOp, Body, Place, UnOp};
 use crate::target::{MachineInfo, MachineSize};
 use crate::ty::{
-    AdtDef, AdtKind, Allocation, ClosureDef, ClosureKind, CoroutineDef, Discr, FieldDef, FnDef,
-    ForeignDef, ForeignItemKind, ForeignModule, ForeignModuleDef, GenericArgs, GenericPredicates,
-    Generics, ImplDef, ImplTrait, IntrinsicDef, LineInfo, MirConst, PolyFnSig, RigidTy, Span,
-    TraitDecl, TraitDef, Ty, TyConst, TyConstId, TyKind, UintTy, VariantDef, VariantIdx,
+    AdtDef, AdtKind, Allocation, AssocDef, ClosureDef, ClosureKind, CoroutineDef, Discr, FieldDef,
+    FnDef, ForeignDef, ForeignItemKind, ForeignModule, ForeignModuleDef, GenericArgs,
+    GenericPredicates, Generics, ImplDef, ImplTrait, IntrinsicDef, LineInfo, MirConst, PolyFnSig,
+    RigidTy, Span, TraitDecl, TraitDef, TraitEvaluation, TraitRef, Ty, TyConst, TyConstId, TyKind,
+    TypingEnv, UintTy, VariantDef, VariantIdx,
 };
 use crate::unstable::{RustcInternal, Stable, new_item_kind};
 use crate::{
@@ -209,6 +210,9 @@ pub(crate) trait CompilerInterface {
     /// Create a new box type, `Box<T>`, for the given inner type `T`.
     fn new_box_ty(&self, ty: Ty) -> Ty;
 
+    /// Create a projection type for an associated type of a trait.
+    fn new_projection_ty(&self, def: AssocDef, args: &GenericArgs) -> Result<Ty, Error>;
+
     /// Returns the type of given crate item.
     fn def_ty(&self, item: DefId) -> Ty;
 
@@ -274,6 +278,12 @@ pub(crate) trait CompilerInterface {
         kind: ClosureKind,
     ) -> Option<Instance>;
 
+    /// Evaluate whether a trait reference holds in the given typing environment.
+    fn evaluate_trait_ref(&self, trait_ref: &TraitRef, typing_env: TypingEnv) -> TraitEvaluation;
+
+    /// Normalize the aliases in a type in the given typing environment.
+    fn normalize_ty(&self, ty: Ty, typing_env: TypingEnv) -> Result<Ty, Error>;
+
     /// Evaluate a static's initializer.
     fn eval_static_initializer(&self, def: StaticDef) -> Result<Allocation, Error>;
 
@@ -761,6 +771,15 @@ impl<'tcx> CompilerInterface for Container<'tcx, BridgeTys> {
         cx.new_box_ty(inner).stable(&mut *tables, cx)
     }
 
+    /// Create a projection type for an associated type of a trait.
+    fn new_projection_ty(&self, def: AssocDef, args: &GenericArgs) -> Result<Ty, Error> {
+        let mut tables = self.tables.borrow_mut();
+        let cx = &*self.cx.borrow();
+        let def_id = def.0.internal(&mut *tables, cx.tcx);
+        let args_ref = args.internal(&mut *tables, cx.tcx);
+        cx.new_projection_ty(def_id, args_ref).map(|ty| ty.stable(&mut *tables, cx))
+    }
+
     /// Returns the type of given crate item.
     fn def_ty(&self, item: DefId) -> Ty {
         let mut tables = self.tables.borrow_mut();
@@ -928,6 +947,24 @@ impl<'tcx> CompilerInterface for Container<'tcx, BridgeTys> {
         cx.resolve_closure(def_id, args_ref, closure_kind).map(|inst| inst.stable(&mut *tables, cx))
     }
 
+    /// Evaluate whether a trait reference holds in the given typing environment.
+    fn evaluate_trait_ref(&self, trait_ref: &TraitRef, typing_env: TypingEnv) -> TraitEvaluation {
+        let mut tables = self.tables.borrow_mut();
+        let cx = &*self.cx.borrow();
+        let trait_ref = trait_ref.internal(&mut *tables, cx.tcx);
+        let typing_env = typing_env.internal(&mut *tables, cx.tcx);
+        cx.evaluate_trait_ref(trait_ref, typing_env).stable(&mut *tables, cx)
+    }
+
+    /// Normalize the aliases in a type in the given typing environment.
+    fn normalize_ty(&self, ty: Ty, typing_env: TypingEnv) -> Result<Ty, Error> {
+        let mut tables = self.tables.borrow_mut();
+        let cx = &*self.cx.borrow();
+        let ty = ty.internal(&mut *tables, cx.tcx);
+        let typing_env = typing_env.internal(&mut *tables, cx.tcx);
+        cx.normalize_ty(ty, typing_env).map(|ty| ty.stable(&mut *tables, cx))
+    }
+
     /// Evaluate a static's initializer.
     fn eval_static_initializer(&self, def: StaticDef) -> Result<Allocation, Error> {
         let mut tables = self.tables.borrow_mut();
@@ -1069,11 +1106,20 @@ where
     if TLV.is_set() {
         Err(Error::from("rustc_public already running"))
     } else {
-        let ptr: *const () = (&raw const interface) as _;
-        TLV.set(&Cell::new(ptr), || Ok(f()))
+        Ok(run_nested(interface, f))
     }
 }
 
+/// Like [`run`], but also when rustc_public is already running, e.g. when the compiler calls
+/// into a tool while it computes a query. The current interface is restored afterwards.
+pub(crate) fn run_nested<F, T>(interface: &dyn CompilerInterface, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    let ptr: *const () = (&raw const interface) as _;
+    TLV.set(&Cell::new(ptr), f)
+}
+
 /// Execute the given function with access the [`CompilerInterface`].
 ///
 /// I.e., This function will load the current interface and calls a function with it.
@@ -1,4 +1,4 @@
-use std::io;
+use std::{io, mem};
 
 use serde::Serialize;
 
@@ -119,6 +119,80 @@ impl Body {
     pub fn spread_arg(&self) -> Option<Local> {
         self.spread_arg
     }
+
+    /// Add a new local to this body, e.g. for a temporary that instrumentation code uses.
+    pub fn new_local(&mut self, ty: Ty, span: Span, mutability: Mutability) -> Local {
+        self.locals.push(LocalDecl { ty, span, mutability });
+        self.locals.len() - 1
+    }
+
+    /// Split a basic block before the statement at `statement_index`.
+    ///
+    /// The statements from `statement_index` on and the terminator are moved to a new block,
+    /// which is returned. The original block then ends with a `Goto` to the new block, which can
+    /// be replaced to run code between the two halves.
+    pub fn split_block(&mut self, bb: BasicBlockIdx, statement_index: usize) -> BasicBlockIdx {
+        let new_bb = self.blocks.len();
+        let block = &mut self.blocks[bb];
+        let statements = block.statements.split_off(statement_index);
+        let goto = Terminator {
+            kind: TerminatorKind::Goto { target: new_bb },
+            span: block.terminator.span,
+        };
+        let terminator = mem::replace(&mut block.terminator, goto);
+        self.blocks.push(BasicBlock { statements, terminator });
+        new_bb
+    }
+
+    /// Insert a call before the statement at `statement_index` of the given block, or before its
+    /// terminator if `statement_index` is the number of statements.
+    ///
+    /// The call has the span of that statement, and returns to a new block with the rest of the
+    /// original block, which is returned. It must not unwind with `UnwindAction::Continue` from a
+    /// cleanup block.
+    pub fn insert_call(
+        &mut self,
+        bb: BasicBlockIdx,
+        statement_index: usize,
+        func: Operand,
+        args: Vec<Operand>,
+        destination: Place,
+        unwind: UnwindAction,
+    ) -> BasicBlockIdx {
+        let span = self.span_at(bb, statement_index);
+        let target = self.split_block(bb, statement_index);
+        self.blocks[bb].terminator = Terminator {
+            kind: TerminatorKind::Call { func, args, destination, target: Some(target), unwind },
+            span,
+        };
+        target
+    }
+
+    /// Insert an assertion that panics with `msg` unless `cond` is `expected`, like
+    /// [`Body::insert_call`] inserts a call.
+    pub fn insert_assert(
+        &mut self,
+        bb: BasicBlockIdx,
+        statement_index: usize,
+        cond: Operand,
+        expected: bool,
+        msg: AssertMessage,
+        unwind: UnwindAction,
+    ) -> BasicBlockIdx {
+        let span = self.span_at(bb, statement_index);
+        let target = self.split_block(bb, statement_index);
+        self.blocks[bb].terminator = Terminator {
+            kind: TerminatorKind::Assert { cond, expected, msg, target, unwind },
+            span,
+        };
+        target
+    }
+
+    /// The span of the statement at `statement_index` of the given block, or of its terminator.
+    fn span_at(&self, bb: BasicBlockIdx, statement_index: usize) -> Span {
+        let block = &self.blocks[bb];
+        block.statements.get(statement_index).map_or(block.terminator.span, |stmt| stmt.span)
+    }
 }
 
 type LocalDecls = Vec<LocalDecl>;
@@ -15,6 +15,12 @@ use crate::Error;
 use crate::unstable::{RustcInternal, Stable};
 
 pub mod pretty;
+pub mod snapshot;
+mod transform;
+
+pub use transform::{
+    MirTransform, OptimizedMirProvider, override_queries, transform_optimized_mir,
+};
 
 /// Convert an internal Rust compiler item into its stable counterpart, if one exists.
 ///
@@ -65,6 +71,14 @@ where
     F: FnOnce() -> T,
 {
     assert!(!TLV.is_set());
+    init_nested(container, f)
+}
+
+/// Like [`init`], but also when a context is already set, which is restored afterwards.
+pub(crate) fn init_nested<'tcx, F, T, B: Bridge>(container: &Container<'tcx, B>, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
     let ptr = container as *const _ as *const ();
     TLV.set(&Cell::new(ptr), || f())
 }
@@ -141,8 +155,18 @@ where
 /// #   assert_eq!(result, Err(CompilerError::Skipped))
 /// # }
 /// ```
+///
+/// A [`MirTransform`] that replaces the optimized MIR of the local items can be given after the
+/// callback, as `run!(args, callback, mir_transform = transform)`. The transform must be a path to
+/// a function, or a closure that captures nothing.
 #[macro_export]
 macro_rules! run {
+    ($args:expr, $callback_fn:ident, mir_transform = $transform:expr) => {
+        $crate::run_driver!($args, || $callback_fn(), mir_transform = Some($transform))
+    };
+    ($args:expr, $callback:expr, mir_transform = $transform:expr) => {
+        $crate::run_driver!($args, $callback, mir_transform = Some($transform))
+    };
     ($args:expr, $callback_fn:ident) => {
         $crate::run_driver!($args, || $callback_fn())
     };
@@ -157,6 +181,17 @@ macro_rules! run {
 /// which can be used to invoke internal APIs.
 #[macro_export]
 macro_rules! run_with_tcx {
+    ($args:expr, $callback_fn:ident, mir_transform = $transform:expr) => {
+        $crate::run_driver!(
+            $args,
+            |tcx| $callback_fn(tcx),
+            mir_transform = Some($transform),
+            with_tcx
+        )
+    };
+    ($args:expr, $callback:expr, mir_transform = $transform:expr) => {
+        $crate::run_driver!($args, $callback, mir_transform = Some($transform), with_tcx)
+    };
     ($args:expr, $callback_fn:ident) => {
         $crate::run_driver!($args, |tcx| $callback_fn(tcx), with_tcx)
     };
@@ -179,11 +214,12 @@ macro_rules! optional {
 /// This macro implements the instantiation of a rustc_public driver, and it will invoke
 /// the given callback after the compiler analyses.
 ///
-/// The third argument determines whether the callback requires `tcx` as an argument.
+/// The `mir_transform` argument is an optional [`MirTransform`], and the last argument determines
+/// whether the callback requires `tcx` as an argument.
 #[macro_export]
 #[doc(hidden)]
 macro_rules! run_driver {
-    ($args:expr, $callback:expr $(, $with_tcx:ident)?) => {{
+    ($args:expr, $callback:expr, mir_transform = $transform:expr $(, $with_tcx:ident)?) => {{
         use rustc_driver::{Callbacks, Compilation, run_compiler};
         use rustc_middle::ty::TyCtxt;
         use rustc_interface::interface;
@@ -242,6 +278,23 @@ macro_rules! run_driver {
             C: Send,
             F: FnOnce($($crate::optional!($with_tcx TyCtxt))?) -> ControlFlow<B, C> + Send,
         {
+            /// Installs the MIR transform given to the macro, if any.
+            fn config(&mut self, config: &mut interface::Config) {
+                // The providers are function pointers, so the transform is reached through
+                // constants rather than stored in the driver.
+                const MIR_TRANSFORM: Option<rustc_internal::MirTransform> = $transform;
+                const OPTIMIZED_MIR: rustc_internal::OptimizedMirProvider = |tcx, def_id| {
+                    let original = rustc_interface::DEFAULT_QUERY_PROVIDERS.queries.optimized_mir;
+                    let transform = MIR_TRANSFORM.unwrap();
+                    rustc_internal::transform_optimized_mir(tcx, def_id, original, transform)
+                };
+                if MIR_TRANSFORM.is_some() {
+                    config.override_queries = Some(|sess, providers| {
+                        rustc_internal::override_queries(sess, providers, OPTIMIZED_MIR)
+                    });
+                }
+            }
+
             /// Called after analysis. Return value instructs the compiler whether to
             /// continue the compilation afterwards (defaults to `Compilation::Continue`)
             fn after_analysis<'tcx>(
@@ -267,4 +320,7 @@ macro_rules! run_driver {
 
         RustcPublic::new($callback).run($args)
     }};
+    ($args:expr, $callback:expr $(, $with_tcx:ident)?) => {
+        $crate::run_driver!($args, $callback, mir_transform = None $(, $with_tcx)?)
+    };
 }
@@ -0,0 +1,315 @@
+//! Snapshots of the rustc_public view of a crate, for `-Z emit=public-mir`.
+//!
+//! The snapshot is built from the public API, like any other tool would, and serialized with the
+//! root types of the `rustc_public_mir` crate that reads the snapshots, with the data types of
+//! this crate as their [`Payload`].
+
+use std::collections::BTreeMap;
+use std::io;
+use std::ops::ControlFlow;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_public_mir::Payload;
+pub use rustc_public_mir::{Error as SnapshotError, Format as SnapshotFormat};
+
+use super::run;
+use crate::abi::{LayoutShape, ReprOptions};
+use crate::mir::alloc::{AllocId, GlobalAlloc};
+use crate::mir::mono::{Instance, InstanceDef};
+use crate::mir::visit::Location;
+use crate::mir::{AggregateKind, Body, MirVisitor, Rvalue};
+use crate::target::MachineInfo;
+use crate::ty::{
+    AdtDef, AdtKind, AliasDef, AliasTy, Allocation, ClosureDef, ConstantKind, CoroutineClosureDef,
+    CoroutineDef, CoroutineWitnessDef, ExistentialPredicate, FnDef, ForeignDef, GenericArgs,
+    ImplDef, LineInfo, MirConst, RigidTy, Span, TraitDecl, TraitDef, TraitRef, Ty, TyKind,
+};
+use crate::visitor::{Visitable, Visitor};
+use crate::{CrateDef, CrateItem, DefId, IndexedVal, ItemKind};
+
+/// Writes a snapshot of the local crate, which must have been analysed without errors.
+pub fn write_snapshot<W: io::Write>(
+    tcx: TyCtxt<'_>,
+    format: SnapshotFormat,
+    writer: W,
+) -> Result<(), SnapshotError> {
+    run(tcx, || rustc_public_mir::write(writer, format, &collect()))
+        .map_err(|error| SnapshotError::Io(io::Error::other(error)))?
+}
+
+/// The payload of the snapshots that the compiler writes.
+#[derive(Clone)]
+struct Public;
+
+impl Payload for Public {
+    type MachineInfo = MachineInfo;
+    type DefId = DefId;
+    type CrateItem = CrateItem;
+    type ItemKind = ItemKind;
+    type Span = Span;
+    type LineInfo = LineInfo;
+    type Ty = Ty;
+    type TyKind = TyKind;
+    type GenericArgs = GenericArgs;
+    type LayoutShape = LayoutShape;
+    type Body = Body;
+    type TraitDecl = TraitDecl;
+    type TraitRef = TraitRef;
+    type ImplDef = ImplDef;
+    type AdtDef = AdtDef;
+    type AdtKind = AdtKind;
+    type ReprOptions = ReprOptions;
+    type AllocId = AllocId;
+    type GlobalAlloc = GlobalAlloc;
+    type InstanceDef = InstanceDef;
+}
+
+type Snapshot = rustc_public_mir::Snapshot<Public>;
+type Item = rustc_public_mir::Item<Public>;
+type TraitImpl = rustc_public_mir::TraitImpl<Public>;
+type Def = rustc_public_mir::Def<Public>;
+type TyInfo = rustc_public_mir::TyInfo<Public>;
+type Adt = rustc_public_mir::Adt<Public>;
+type Variant = rustc_public_mir::Variant<Public>;
+type Field = rustc_public_mir::Field<Public>;
+type SpanInfo = rustc_public_mir::SpanInfo<Public>;
+type Alloc = rustc_public_mir::Alloc<Public>;
+type InstanceInfo = rustc_public_mir::InstanceInfo<Public>;
+
+fn collect() -> Snapshot {
+    let mut tables = Tables::default();
+    let krate = crate::local_crate();
+
+    let mut items: Vec<_> = crate::all_local_items()
+        .into_iter()
+        .map(|item| {
+            let item = Item {
+                def: item,
+                name: item.name(),
+                kind: item.kind(),
+                span: item.span(),
+                ty: item.ty(),
+                body: item.body(),
+            };
+            tables.add_def(item.def.0);
+            tables.add_span(item.span);
+            tables.add_ty(item.ty);
+            if let Some(body) = &item.body {
+                BodyVisitor(&mut tables).visit_body(body);
+            }
+            item
+        })
+        .collect();
+    items.sort_by_key(|item| item.def.0.to_index());
+
+    let trait_decls = krate
+        .trait_decls()
+        .iter()
+        .map(|def| {
+            tables.add_def(def.0);
+            TraitDef::declaration(def)
+        })
+        .collect();
+    let trait_impls = krate
+        .trait_impls()
+        .into_iter()
+        .map(|def| {
+            let trait_ref = def.trait_impl().value;
+            tables.add_def(def.0);
+            tables.add_def(trait_ref.def_id.0);
+            let _ = trait_ref.args().visit(&mut tables);
+            TraitImpl { def, trait_ref }
+        })
+        .collect();
+
+    Snapshot {
+        format_version: rustc_public_mir::FORMAT_VERSION,
+        crate_name: krate.name,
+        machine: MachineInfo::target(),
+        items,
+        trait_decls,
+        trait_impls,
+        defs: tables.defs.into_values().collect(),
+        types: tables.types.into_values().collect(),
+        adts: tables.adts.into_values().collect(),
+        spans: tables.spans.into_values().collect(),
+        allocs: tables.allocs.into_values().collect(),
+        instances: tables.instances.into_values().collect(),
+    }
+}
+
+/// The entries of the snapshot that the items refer to, by the index of their id.
+#[derive(Default)]
+struct Tables {
+    defs: BTreeMap<usize, Def>,
+    types: BTreeMap<usize, TyInfo>,
+    adts: BTreeMap<usize, Adt>,
+    spans: BTreeMap<usize, SpanInfo>,
+    allocs: BTreeMap<usize, Alloc>,
+    instances: BTreeMap<usize, InstanceInfo>,
+    /// The number of binders around the type that is visited. The layout of a type in a binder is
+    /// not computed, as it may refer to the bound variables.
+    binders: usize,
+}
+
+impl Tables {
+    fn add_def(&mut self, def_id: DefId) {
+        self.defs.entry(def_id.to_index()).or_insert_with(|| Def { def_id, name: def_id.name() });
+    }
+
+    fn add_span(&mut self, span: Span) {
+        self.spans.entry(span.to_index()).or_insert_with(|| SpanInfo {
+            span,
+            file: span.get_filename(),
+            lines: span.get_lines(),
+        });
+    }
+
+    fn add_ty(&mut self, ty: Ty) {
+        let _ = ty.visit(self);
+    }
+
+    fn add_adt(&mut self, def: AdtDef) {
+        if self.adts.contains_key(&def.0.to_index()) {
+            return;
+        }
+        self.add_def(def.0);
+        let variants: Vec<_> = def
+            .variants_iter()
+            .map(|variant| Variant {
+                name: variant.name(),
+                fields: variant
+                    .fields()
+                    .into_iter()
+                    .map(|field| Field { name: field.name.clone(), ty: field.ty() })
+                    .collect(),
+            })
+            .collect();
+        let field_tys: Vec<_> =
+            variants.iter().flat_map(|variant| &variant.fields).map(|field| field.ty).collect();
+        self.adts
+            .insert(def.0.to_index(), Adt { def, kind: def.kind(), repr: def.repr(), variants });
+        for ty in field_tys {
+            self.add_ty(ty);
+        }
+    }
+
+    fn add_allocation(&mut self, allocation: &Allocation) {
+        for (_, prov) in &allocation.provenance.ptrs {
+            self.add_alloc(prov.0);
+        }
+    }
+
+    fn add_alloc(&mut self, id: AllocId) {
+        if self.allocs.contains_key(&id.to_index()) {
+            return;
+        }
+        let alloc = GlobalAlloc::from(id);
+        self.allocs.insert(id.to_index(), Alloc { id, alloc: alloc.clone() });
+        match alloc {
+            GlobalAlloc::Function(instance) => self.add_instance(instance),
+            GlobalAlloc::VTable(ty, trait_ref) => {
+                self.add_ty(ty);
+                if let Some(trait_ref) = trait_ref {
+                    self.add_def(trait_ref.value.def_id.0);
+                }
+            }
+            GlobalAlloc::Static(def) => self.add_def(def.0),
+            GlobalAlloc::Memory(allocation) => self.add_allocation(&allocation),
+            GlobalAlloc::TypeId { ty } => self.add_ty(ty),
+        }
+    }
+
+    fn add_instance(&mut self, instance: Instance) {
+        if self.instances.contains_key(&instance.def.to_index()) {
+            return;
+        }
+        let info = InstanceInfo {
+            def: instance.def,
+            name: instance.name(),
+            mangled_name: instance.mangled_name(),
+            ty: instance.ty(),
+            args: instance.args(),
+        };
+        self.add_def(instance.def.def_id());
+        self.add_ty(info.ty);
+        let _ = info.args.visit(self);
+        self.instances.insert(instance.def.to_index(), info);
+    }
+}
+
+impl Visitor for Tables {
+    type Break = ();
+
+    fn visit_ty(&mut self, ty: &Ty) -> ControlFlow<()> {
+        if self.types.contains_key(&ty.to_index()) {
+            return ControlFlow::Continue(());
+        }
+        let kind = ty.kind();
+        let layout =
+            if self.binders == 0 { ty.layout().ok().map(|layout| layout.shape()) } else { None };
+        self.types.insert(ty.to_index(), TyInfo { ty: *ty, kind: kind.clone(), layout });
+
+        match &kind {
+            TyKind::RigidTy(RigidTy::Adt(def, _)) => self.add_adt(*def),
+            TyKind::RigidTy(
+                RigidTy::Foreign(ForeignDef(def_id))
+                | RigidTy::FnDef(FnDef(def_id), _)
+                | RigidTy::Closure(ClosureDef(def_id), _)
+                | RigidTy::Coroutine(CoroutineDef(def_id), _)
+                | RigidTy::CoroutineClosure(CoroutineClosureDef(def_id), _)
+                | RigidTy::CoroutineWitness(CoroutineWitnessDef(def_id), _),
+            )
+            | TyKind::Alias(_, AliasTy { def_id: AliasDef(def_id), .. }) => self.add_def(*def_id),
+            TyKind::RigidTy(RigidTy::Dynamic(predicates, _)) => {
+                for predicate in predicates {
+                    let trait_def = match &predicate.value {
+                        ExistentialPredicate::Trait(trait_ref) => trait_ref.def_id,
+                        ExistentialPredicate::Projection(projection) => projection.def_id,
+                        ExistentialPredicate::AutoTrait(def) => *def,
+                    };
+                    self.add_def(trait_def.0);
+                }
+            }
+            _ => {}
+        }
+
+        let binder = matches!(kind, TyKind::RigidTy(RigidTy::FnPtr(_) | RigidTy::Dynamic(..)));
+        self.binders += binder as usize;
+        let result = ty.super_visit(self);
+        self.binders -= binder as usize;
+        result
+    }
+}
+
+/// Collects the entries that a body refers to.
+struct BodyVisitor<'a>(&'a mut Tables);
+
+impl MirVisitor for BodyVisitor<'_> {
+    fn visit_ty(&mut self, ty: &Ty, _: Location) {
+        self.0.add_ty(*ty);
+    }
+
+    fn visit_span(&mut self, span: &Span) {
+        self.0.add_span(*span);
+    }
+
+    fn visit_mir_const(&mut self, constant: &MirConst, location: Location) {
+        if let ConstantKind::Allocated(allocation) = constant.kind() {
+            self.0.add_allocation(allocation);
+        }
+        self.super_mir_const(constant, location);
+    }
+
+    fn visit_rvalue(&mut self, rvalue: &Rvalue, location: Location) {
+        match rvalue {
+            Rvalue::ThreadLocalRef(item) => self.0.add_def(item.0),
+            Rvalue::Aggregate(AggregateKind::Adt(def, ..), _) => self.0.add_adt(*def),
+            Rvalue::Aggregate(AggregateKind::Closure(def, _), _) => self.0.add_def(def.0),
+            Rvalue::Aggregate(AggregateKind::Coroutine(def, _), _) => self.0.add_def(def.0),
+            Rvalue::Aggregate(AggregateKind::CoroutineClosure(def, _), _) => self.0.add_def(def.0),
+            _ => {}
+        }
+        self.super_rvalue(rvalue, location);
+    }
+}
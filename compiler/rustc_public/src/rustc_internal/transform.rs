@@ -0,0 +1,91 @@
+//! Replacement of the optimized MIR of local items by a rustc_public tool.
+//!
+//! The tool passes a [`MirTransform`] to [run!], which then overrides the `optimized_mir` query.
+//! Each body that the query computes is passed to the transform, and the body that it returns is
+//! converted back to internal MIR, checked by the MIR validator, and used for code generation and
+//! in the metadata of the crate.
+
+use std::cell::RefCell;
+
+use rustc_middle::mir;
+use rustc_middle::ty::TyCtxt;
+use rustc_middle::util::Providers;
+use rustc_public_bridge::context::CompilerCtxt;
+use rustc_public_bridge::{Container, Tables};
+use rustc_session::Session;
+use rustc_span::def_id::LocalDefId;
+
+use crate::CrateItem;
+use crate::mir::Body;
+use crate::unstable::{RustcInternal, Stable};
+
+/// A function that gets the optimized MIR of a local item, and returns the body that should
+/// replace it, if any.
+///
+/// The returned body cannot contain inline assembly, coverage statements, `Rvalue::Len`, or the
+/// statements that are removed before optimized MIR, like `FakeRead` and `AscribeUserType`.
+pub type MirTransform = fn(CrateItem, Body) -> Option<Body>;
+
+#[doc(hidden)]
+pub type OptimizedMirProvider = for<'tcx> fn(TyCtxt<'tcx>, LocalDefId) -> &'tcx mir::Body<'tcx>;
+
+/// Overrides the `optimized_mir` query with the provider that [run!] builds for its
+/// [`MirTransform`].
+///
+/// Transforms are not supported with incremental compilation, as the compiler would reuse bodies
+/// that it transformed before, so an error is emitted instead.
+#[doc(hidden)]
+pub fn override_queries(
+    sess: &Session,
+    providers: &mut Providers,
+    optimized_mir: OptimizedMirProvider,
+) {
+    if sess.opts.incremental.is_some() {
+        sess.dcx().err(
+            "the MIR transform of a rustc_public tool cannot be used with incremental compilation",
+        );
+        return;
+    }
+    providers.queries.optimized_mir = optimized_mir;
+}
+
+/// Computes the optimized MIR of `def_id` with the `original` provider, and replaces it with the
+/// body that `transform` returns, if any.
+///
+/// The transform is called while the compiler computes the MIR, which can be before or during the
+/// callback of [run!], so it should not depend on any state of the callback.
+#[doc(hidden)]
+pub fn transform_optimized_mir<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def_id: LocalDefId,
+    original: OptimizedMirProvider,
+    transform: MirTransform,
+) -> &'tcx mir::Body<'tcx> {
+    let original = original(tcx, def_id);
+
+    // The body may be computed while the tool is already running, e.g. for `Instance::body`, so
+    // the transform gets a context of its own.
+    let container = Container {
+        tables: RefCell::new(Tables::default()),
+        cx: RefCell::new(CompilerCtxt::new(tcx)),
+    };
+    let result = crate::compiler_interface::run_nested(&container, || {
+        super::init_nested(&container, || {
+            let (item, body) = super::with_container(|tables, cx| {
+                (tables.crate_item(def_id.to_def_id()), original.stable(tables, cx))
+            });
+            let Some(body) = transform(item, body) else { return Ok(None) };
+            let parts = super::with_container(|tables, _| body.internal(tables, tcx))?;
+            container.cx.borrow().replace_body(original, parts).map(Some)
+        })
+    });
+
+    match result {
+        Ok(Some(body)) => tcx.arena.alloc(body),
+        Ok(None) => original,
+        Err(err) => tcx.dcx().span_fatal(
+            tcx.def_span(def_id),
+            format!("cannot replace the MIR of `{}`: {err}", tcx.def_path_str(def_id)),
+        ),
+    }
+}
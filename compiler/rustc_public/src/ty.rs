@@ -94,10 +94,28 @@ impl Ty {
         Ty::from_rigid_kind(RigidTy::Uint(inner))
     }
 
+    /// Create a projection type, e.g. `<T as Iterator>::Item`, for the given associated type of
+    /// a trait.
+    ///
+    /// The generic arguments are the ones of the trait, starting with `Self`, followed by the ones
+    /// of the associated type.
+    pub fn try_new_projection(assoc: AssocDef, args: GenericArgs) -> Result<Ty, Error> {
+        with(|cx| cx.new_projection_ty(assoc, &args))
+    }
+
     /// Get a type layout.
     pub fn layout(self) -> Result<Layout, Error> {
         with(|cx| cx.ty_layout(self))
     }
+
+    /// Normalize the aliases in this type, e.g. projections like `<T as Iterator>::Item`, in the
+    /// given typing environment.
+    ///
+    /// This fails if an alias cannot be normalized, e.g. if the trait is not implemented, or if
+    /// the result depends on generic parameters that are not constrained by the environment.
+    pub fn normalize(self, typing_env: TypingEnv) -> Result<Ty, Error> {
+        with(|cx| cx.normalize_ty(self, typing_env))
+    }
 }
 
 impl Ty {
@@ -1445,6 +1463,44 @@ impl TraitRef {
         };
         self_ty
     }
+
+    /// Evaluate whether this trait reference holds, i.e. whether `Self` implements the trait, in
+    /// the given typing environment.
+    pub fn evaluate(&self, typing_env: TypingEnv) -> TraitEvaluation {
+        with(|cx| cx.evaluate_trait_ref(self, typing_env))
+    }
+
+    /// Whether this trait reference is known to hold in the given typing environment.
+    pub fn holds(&self, typing_env: TypingEnv) -> bool {
+        self.evaluate(typing_env) == TraitEvaluation::Holds
+    }
+}
+
+/// The environment in which trait references are evaluated and types are normalized, which
+/// determines the where clauses that can be used, and whether opaque types can be revealed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize)]
+pub enum TypingEnv {
+    /// For types without generic parameters, e.g. in monomorphized code. Opaque types are
+    /// revealed.
+    FullyMonomorphized,
+    /// The where clauses of the given item, with opaque types that are not revealed, as during
+    /// type checking.
+    NonBodyAnalysis(DefId),
+    /// The where clauses of the given item, with opaque types that are revealed, as in the
+    /// optimized MIR of the item.
+    PostAnalysis(DefId),
+}
+
+/// The result of the evaluation of a trait reference.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize)]
+pub enum TraitEvaluation {
+    /// The trait reference holds, ignoring lifetimes.
+    Holds,
+    /// The trait reference does not hold.
+    DoesNotHold,
+    /// The trait reference may hold, e.g. if it depends on generic parameters or inference
+    /// variables, or on the hidden type of an opaque type.
+    Ambiguous,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize)]
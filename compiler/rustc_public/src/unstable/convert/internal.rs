@@ -5,22 +5,33 @@
 
 // Prefer importing rustc_public over internal rustc constructs to make this file more readable.
 
+use rustc_middle::mir as rustc_mir;
 use rustc_middle::ty::{self as rustc_ty, Const as InternalConst, Ty as InternalTy};
 use rustc_public_bridge::Tables;
+use rustc_public_bridge::builder::BodyParts;
+use rustc_span::source_map::Spanned;
 
 use crate::abi::Layout;
 use crate::compiler_interface::BridgeTys;
 use crate::mir::alloc::AllocId;
 use crate::mir::mono::{Instance, MonoItem, StaticDef};
-use crate::mir::{BinOp, Mutability, Place, ProjectionElem, RawPtrKind, Safety, UnOp};
+use crate::mir::visit::{Location, PlaceContext};
+use crate::mir::{
+    AggregateKind, AssertMessage, BasicBlock, BinOp, Body, BorrowKind, CastKind, ConstOperand,
+    CoroutineDesugaring, CoroutineKind, CoroutineSource, FakeBorrowKind, Local, LocalDecl,
+    MirVisitor, MutBorrowKind, Mutability, NonDivergingIntrinsic, NullOp, Operand, Place,
+    PointerCoercion, ProjectionElem, RawPtrKind, RetagKind, Rvalue, Safety, Statement,
+    StatementKind, SwitchTargets, Terminator, TerminatorKind, UnOp, UnwindAction, VarDebugInfo,
+    VarDebugInfoContents, VarDebugInfoFragment,
+};
 use crate::ty::{
     Abi, AdtDef, Binder, BoundRegionKind, BoundTyKind, BoundVariableKind, ClosureKind,
     ExistentialPredicate, ExistentialProjection, ExistentialTraitRef, FloatTy, FnSig,
     GenericArgKind, GenericArgs, IntTy, MirConst, Movability, Pattern, Region, RigidTy, Span,
-    TermKind, TraitRef, Ty, TyConst, UintTy, VariantDef, VariantIdx,
+    TermKind, TraitRef, Ty, TyConst, TypingEnv, UintTy, VariantDef, VariantIdx,
 };
 use crate::unstable::{InternalCx, RustcInternal};
-use crate::{CrateItem, CrateNum, DefId, IndexedVal};
+use crate::{CrateItem, CrateNum, DefId, Error, IndexedVal, error};
 
 impl RustcInternal for CrateItem {
     type T<'tcx> = rustc_span::def_id::DefId;
@@ -542,6 +553,26 @@ impl RustcInternal for TraitRef {
     }
 }
 
+impl RustcInternal for TypingEnv {
+    type T<'tcx> = rustc_ty::TypingEnv<'tcx>;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        match self {
+            TypingEnv::FullyMonomorphized => rustc_ty::TypingEnv::fully_monomorphized(),
+            TypingEnv::NonBodyAnalysis(def_id) => {
+                rustc_ty::TypingEnv::non_body_analysis(tcx.tcx(), def_id.internal(tables, tcx))
+            }
+            TypingEnv::PostAnalysis(def_id) => {
+                rustc_ty::TypingEnv::post_analysis(tcx.tcx(), def_id.internal(tables, tcx))
+            }
+        }
+    }
+}
+
 impl RustcInternal for AllocId {
     type T<'tcx> = rustc_middle::mir::interpret::AllocId;
     fn internal<'tcx>(
@@ -759,6 +790,701 @@ impl RustcInternal for UnOp {
     }
 }
 
+/// Converts a body back to internal MIR, e.g. to replace the body that it was built from.
+///
+/// Inline assembly, coverage statements, `Rvalue::Len`, and the statements that are removed
+/// before optimized MIR, like `FakeRead` and `AscribeUserType`, cannot be converted back, as
+/// parts of them are opaque. The conversion of a body that contains them returns an error.
+impl RustcInternal for Body {
+    type T<'tcx> = Result<BodyParts<'tcx>, Error>;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        check_indices(self)?;
+        Ok(BodyParts {
+            basic_blocks: self
+                .blocks
+                .iter()
+                .map(|bb| bb.internal(tables, tcx))
+                .collect::<Result<_, _>>()?,
+            local_decls: self.locals().iter().map(|decl| decl.internal(tables, tcx)).collect(),
+            arg_count: self.arg_locals().len(),
+            var_debug_info: self.var_debug_info.internal(tables, tcx),
+            spread_arg: self.spread_arg().map(rustc_mir::Local::from_usize),
+            span: self.span.internal(tables, tcx),
+        })
+    }
+}
+
+/// Checks that the blocks and locals that `body` refers to are in range. The internal MIR is
+/// indexed without bounds checks, so the compiler would panic on them.
+fn check_indices(body: &Body) -> Result<(), Error> {
+    let blocks = body.blocks.len();
+    for (bb, block) in body.blocks.iter().enumerate() {
+        if let Some(target) = block.terminator.successors().into_iter().find(|&bb| bb >= blocks) {
+            return Err(error!("`bb{bb}` jumps to `bb{target}`, but the body has {blocks} blocks"));
+        }
+    }
+
+    let locals = body.locals().len();
+    let out_of_range = body.spread_arg().filter(|&local| local >= locals);
+    let mut checker = LocalChecker { locals, out_of_range };
+    checker.visit_body(body);
+    match checker.out_of_range {
+        Some(local) => Err(error!("`_{local}` is out of range, the body has {locals} locals")),
+        None => Ok(()),
+    }
+}
+
+struct LocalChecker {
+    locals: usize,
+    out_of_range: Option<Local>,
+}
+
+impl MirVisitor for LocalChecker {
+    fn visit_local(&mut self, local: &Local, _: PlaceContext, _: Location) {
+        if *local >= self.locals {
+            self.out_of_range.get_or_insert(*local);
+        }
+    }
+}
+
+impl RustcInternal for LocalDecl {
+    type T<'tcx> = rustc_mir::LocalDecl<'tcx>;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        let mut decl = rustc_mir::LocalDecl::new(
+            self.ty.internal(tables, tcx),
+            self.span.internal(tables, tcx),
+        );
+        decl.mutability = self.mutability.internal(tables, tcx);
+        decl
+    }
+}
+
+impl RustcInternal for BasicBlock {
+    type T<'tcx> = Result<rustc_mir::BasicBlockData<'tcx>, Error>;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        // Whether this is a cleanup block depends on the other blocks, so it is determined when
+        // the body is built.
+        Ok(rustc_mir::BasicBlockData::new_stmts(
+            self.statements
+                .iter()
+                .map(|statement| statement.internal(tables, tcx))
+                .collect::<Result<_, _>>()?,
+            Some(self.terminator.internal(tables, tcx)?),
+            false,
+        ))
+    }
+}
+
+impl RustcInternal for Statement {
+    type T<'tcx> = Result<rustc_mir::Statement<'tcx>, Error>;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        Ok(rustc_mir::Statement::new(
+            rustc_mir::SourceInfo::outermost(self.span.internal(tables, tcx)),
+            self.kind.internal(tables, tcx)?,
+        ))
+    }
+}
+
+impl RustcInternal for StatementKind {
+    type T<'tcx> = Result<rustc_mir::StatementKind<'tcx>, Error>;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        let kind = match self {
+            StatementKind::Assign(place, rvalue) => rustc_mir::StatementKind::Assign(Box::new((
+                place.internal(tables, tcx),
+                rvalue.internal(tables, tcx)?,
+            ))),
+            StatementKind::SetDiscriminant { place, variant_index } => {
+                rustc_mir::StatementKind::SetDiscriminant {
+                    place: Box::new(place.internal(tables, tcx)),
+                    variant_index: variant_index.internal(tables, tcx),
+                }
+            }
+            StatementKind::Deinit(place) => {
+                rustc_mir::StatementKind::Deinit(Box::new(place.internal(tables, tcx)))
+            }
+            StatementKind::StorageLive(local) => {
+                rustc_mir::StatementKind::StorageLive(rustc_mir::Local::from_usize(*local))
+            }
+            StatementKind::StorageDead(local) => {
+                rustc_mir::StatementKind::StorageDead(rustc_mir::Local::from_usize(*local))
+            }
+            StatementKind::Retag(kind, place) => rustc_mir::StatementKind::Retag(
+                kind.internal(tables, tcx),
+                Box::new(place.internal(tables, tcx)),
+            ),
+            StatementKind::PlaceMention(place) => {
+                rustc_mir::StatementKind::PlaceMention(Box::new(place.internal(tables, tcx)))
+            }
+            StatementKind::Intrinsic(intrinsic) => {
+                rustc_mir::StatementKind::Intrinsic(Box::new(intrinsic.internal(tables, tcx)))
+            }
+            StatementKind::ConstEvalCounter => rustc_mir::StatementKind::ConstEvalCounter,
+            StatementKind::Nop => rustc_mir::StatementKind::Nop,
+            StatementKind::FakeRead(..) => {
+                return Err(error!("cannot convert a `FakeRead` statement back to internal MIR"));
+            }
+            StatementKind::AscribeUserType { .. } => {
+                return Err(error!(
+                    "cannot convert an `AscribeUserType` statement back to internal MIR"
+                ));
+            }
+            StatementKind::Coverage(_) => {
+                return Err(error!("cannot convert a coverage statement back to internal MIR"));
+            }
+        };
+        Ok(kind)
+    }
+}
+
+impl RustcInternal for RetagKind {
+    type T<'tcx> = rustc_mir::RetagKind;
+
+    fn internal<'tcx>(
+        &self,
+        _tables: &mut Tables<'_, BridgeTys>,
+        _tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        match self {
+            RetagKind::FnEntry => rustc_mir::RetagKind::FnEntry,
+            RetagKind::TwoPhase => rustc_mir::RetagKind::TwoPhase,
+            RetagKind::Raw => rustc_mir::RetagKind::Raw,
+            RetagKind::Default => rustc_mir::RetagKind::Default,
+        }
+    }
+}
+
+impl RustcInternal for NonDivergingIntrinsic {
+    type T<'tcx> = rustc_mir::NonDivergingIntrinsic<'tcx>;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        match self {
+            NonDivergingIntrinsic::Assume(op) => {
+                rustc_mir::NonDivergingIntrinsic::Assume(op.internal(tables, tcx))
+            }
+            NonDivergingIntrinsic::CopyNonOverlapping(copy) => {
+                rustc_mir::NonDivergingIntrinsic::CopyNonOverlapping(
+                    rustc_mir::CopyNonOverlapping {
+                        src: copy.src.internal(tables, tcx),
+                        dst: copy.dst.internal(tables, tcx),
+                        count: copy.count.internal(tables, tcx),
+                    },
+                )
+            }
+        }
+    }
+}
+
+impl RustcInternal for Rvalue {
+    type T<'tcx> = Result<rustc_mir::Rvalue<'tcx>, Error>;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        let rvalue = match self {
+            Rvalue::AddressOf(kind, place) => {
+                rustc_mir::Rvalue::RawPtr(kind.internal(tables, tcx), place.internal(tables, tcx))
+            }
+            Rvalue::Aggregate(kind, operands) => rustc_mir::Rvalue::Aggregate(
+                Box::new(kind.internal(tables, tcx)),
+                operands.iter().map(|op| op.internal(tables, tcx)).collect(),
+            ),
+            Rvalue::BinaryOp(bin_op, lhs, rhs) => rustc_mir::Rvalue::BinaryOp(
+                bin_op.internal(tables, tcx),
+                Box::new((lhs.internal(tables, tcx), rhs.internal(tables, tcx))),
+            ),
+            Rvalue::Cast(kind, op, ty) => rustc_mir::Rvalue::Cast(
+                kind.internal(tables, tcx),
+                op.internal(tables, tcx),
+                ty.internal(tables, tcx),
+            ),
+            Rvalue::CheckedBinaryOp(bin_op, lhs, rhs) => rustc_mir::Rvalue::BinaryOp(
+                bin_op.internal(tables, tcx).wrapping_to_overflowing().ok_or_else(|| {
+                    error!("`{bin_op:?}` cannot overflow, so it cannot be a checked operation")
+                })?,
+                Box::new((lhs.internal(tables, tcx), rhs.internal(tables, tcx))),
+            ),
+            Rvalue::CopyForDeref(place) => {
+                rustc_mir::Rvalue::CopyForDeref(place.internal(tables, tcx))
+            }
+            Rvalue::Discriminant(place) => {
+                rustc_mir::Rvalue::Discriminant(place.internal(tables, tcx))
+            }
+            Rvalue::Ref(region, kind, place) => rustc_mir::Rvalue::Ref(
+                region.internal(tables, tcx),
+                kind.internal(tables, tcx),
+                place.internal(tables, tcx),
+            ),
+            Rvalue::Repeat(op, count) => {
+                rustc_mir::Rvalue::Repeat(op.internal(tables, tcx), count.internal(tables, tcx))
+            }
+            Rvalue::ShallowInitBox(op, ty) => rustc_mir::Rvalue::ShallowInitBox(
+                op.internal(tables, tcx),
+                ty.internal(tables, tcx),
+            ),
+            Rvalue::ThreadLocalRef(item) => {
+                rustc_mir::Rvalue::ThreadLocalRef(item.internal(tables, tcx))
+            }
+            Rvalue::NullaryOp(null_op, ty) => rustc_mir::Rvalue::NullaryOp(
+                null_op.internal(tables, tcx),
+                ty.internal(tables, tcx),
+            ),
+            Rvalue::UnaryOp(un_op, op) => {
+                rustc_mir::Rvalue::UnaryOp(un_op.internal(tables, tcx), op.internal(tables, tcx))
+            }
+            Rvalue::Use(op) => rustc_mir::Rvalue::Use(op.internal(tables, tcx)),
+            Rvalue::Len(_) => {
+                return Err(error!("cannot convert an `Rvalue::Len` back to internal MIR"));
+            }
+        };
+        Ok(rvalue)
+    }
+}
+
+impl RustcInternal for AggregateKind {
+    type T<'tcx> = rustc_mir::AggregateKind<'tcx>;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        match self {
+            AggregateKind::Array(ty) => rustc_mir::AggregateKind::Array(ty.internal(tables, tcx)),
+            AggregateKind::Tuple => rustc_mir::AggregateKind::Tuple,
+            AggregateKind::Adt(def, variant, args, user_ty, active_field) => {
+                rustc_mir::AggregateKind::Adt(
+                    def.internal(tables, tcx).did(),
+                    variant.internal(tables, tcx),
+                    args.internal(tables, tcx),
+                    user_ty.map(rustc_ty::UserTypeAnnotationIndex::from_usize),
+                    active_field.map(rustc_abi::FieldIdx::from_usize),
+                )
+            }
+            AggregateKind::Closure(def, args) => rustc_mir::AggregateKind::Closure(
+                def.0.internal(tables, tcx),
+                args.internal(tables, tcx),
+            ),
+            AggregateKind::Coroutine(def, args) => rustc_mir::AggregateKind::Coroutine(
+                def.0.internal(tables, tcx),
+                args.internal(tables, tcx),
+            ),
+            AggregateKind::CoroutineClosure(def, args) => {
+                rustc_mir::AggregateKind::CoroutineClosure(
+                    def.0.internal(tables, tcx),
+                    args.internal(tables, tcx),
+                )
+            }
+            AggregateKind::RawPtr(ty, mutability) => rustc_mir::AggregateKind::RawPtr(
+                ty.internal(tables, tcx),
+                mutability.internal(tables, tcx),
+            ),
+        }
+    }
+}
+
+impl RustcInternal for BorrowKind {
+    type T<'tcx> = rustc_mir::BorrowKind;
+
+    fn internal<'tcx>(
+        &self,
+        _tables: &mut Tables<'_, BridgeTys>,
+        _tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        match self {
+            BorrowKind::Shared => rustc_mir::BorrowKind::Shared,
+            BorrowKind::Fake(FakeBorrowKind::Deep) => {
+                rustc_mir::BorrowKind::Fake(rustc_mir::FakeBorrowKind::Deep)
+            }
+            BorrowKind::Fake(FakeBorrowKind::Shallow) => {
+                rustc_mir::BorrowKind::Fake(rustc_mir::FakeBorrowKind::Shallow)
+            }
+            BorrowKind::Mut { kind } => rustc_mir::BorrowKind::Mut {
+                kind: match kind {
+                    MutBorrowKind::Default => rustc_mir::MutBorrowKind::Default,
+                    MutBorrowKind::TwoPhaseBorrow => rustc_mir::MutBorrowKind::TwoPhaseBorrow,
+                    MutBorrowKind::ClosureCapture => rustc_mir::MutBorrowKind::ClosureCapture,
+                },
+            },
+        }
+    }
+}
+
+impl RustcInternal for CastKind {
+    type T<'tcx> = rustc_mir::CastKind;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        match self {
+            CastKind::PointerExposeAddress => rustc_mir::CastKind::PointerExposeProvenance,
+            CastKind::PointerWithExposedProvenance => {
+                rustc_mir::CastKind::PointerWithExposedProvenance
+            }
+            // Where the coercion comes from is only used for diagnostics.
+            CastKind::PointerCoercion(coercion) => rustc_mir::CastKind::PointerCoercion(
+                coercion.internal(tables, tcx),
+                rustc_mir::CoercionSource::Implicit,
+            ),
+            CastKind::IntToInt => rustc_mir::CastKind::IntToInt,
+            CastKind::FloatToInt => rustc_mir::CastKind::FloatToInt,
+            CastKind::FloatToFloat => rustc_mir::CastKind::FloatToFloat,
+            CastKind::IntToFloat => rustc_mir::CastKind::IntToFloat,
+            CastKind::PtrToPtr => rustc_mir::CastKind::PtrToPtr,
+            CastKind::FnPtrToPtr => rustc_mir::CastKind::FnPtrToPtr,
+            CastKind::Transmute => rustc_mir::CastKind::Transmute,
+            CastKind::Subtype => rustc_mir::CastKind::Subtype,
+        }
+    }
+}
+
+impl RustcInternal for PointerCoercion {
+    type T<'tcx> = rustc_ty::adjustment::PointerCoercion;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        use rustc_ty::adjustment::PointerCoercion as InternalCoercion;
+        match self {
+            PointerCoercion::ReifyFnPointer => InternalCoercion::ReifyFnPointer,
+            PointerCoercion::UnsafeFnPointer => InternalCoercion::UnsafeFnPointer,
+            PointerCoercion::ClosureFnPointer(safety) => {
+                InternalCoercion::ClosureFnPointer(safety.internal(tables, tcx))
+            }
+            PointerCoercion::MutToConstPointer => InternalCoercion::MutToConstPointer,
+            PointerCoercion::ArrayToPointer => InternalCoercion::ArrayToPointer,
+            PointerCoercion::Unsize => InternalCoercion::Unsize,
+        }
+    }
+}
+
+impl RustcInternal for NullOp {
+    type T<'tcx> = rustc_mir::NullOp<'tcx>;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        match self {
+            NullOp::SizeOf => rustc_mir::NullOp::SizeOf,
+            NullOp::AlignOf => rustc_mir::NullOp::AlignOf,
+            NullOp::OffsetOf(indices) => rustc_mir::NullOp::OffsetOf(
+                tcx.tcx().mk_offset_of_from_iter(indices.iter().map(|(variant, field)| {
+                    (variant.internal(tables, tcx), rustc_abi::FieldIdx::from_usize(*field))
+                })),
+            ),
+            NullOp::UbChecks => rustc_mir::NullOp::UbChecks,
+            NullOp::ContractChecks => rustc_mir::NullOp::ContractChecks,
+        }
+    }
+}
+
+impl RustcInternal for Operand {
+    type T<'tcx> = rustc_mir::Operand<'tcx>;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        match self {
+            Operand::Copy(place) => rustc_mir::Operand::Copy(place.internal(tables, tcx)),
+            Operand::Move(place) => rustc_mir::Operand::Move(place.internal(tables, tcx)),
+            Operand::Constant(constant) => {
+                rustc_mir::Operand::Constant(Box::new(constant.internal(tables, tcx)))
+            }
+        }
+    }
+}
+
+impl RustcInternal for ConstOperand {
+    type T<'tcx> = rustc_mir::ConstOperand<'tcx>;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        rustc_mir::ConstOperand {
+            span: self.span.internal(tables, tcx),
+            user_ty: self.user_ty.map(rustc_ty::UserTypeAnnotationIndex::from_usize),
+            const_: self.const_.internal(tables, tcx),
+        }
+    }
+}
+
+impl RustcInternal for Terminator {
+    type T<'tcx> = Result<rustc_mir::Terminator<'tcx>, Error>;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        let span = self.span.internal(tables, tcx);
+        let block = rustc_mir::BasicBlock::from_usize;
+        let kind = match &self.kind {
+            TerminatorKind::Goto { target } => {
+                rustc_mir::TerminatorKind::Goto { target: block(*target) }
+            }
+            TerminatorKind::SwitchInt { discr, targets } => rustc_mir::TerminatorKind::SwitchInt {
+                discr: discr.internal(tables, tcx),
+                targets: targets.internal(tables, tcx),
+            },
+            TerminatorKind::Resume => rustc_mir::TerminatorKind::UnwindResume,
+            TerminatorKind::Abort => {
+                rustc_mir::TerminatorKind::UnwindTerminate(rustc_mir::UnwindTerminateReason::Abi)
+            }
+            TerminatorKind::Return => rustc_mir::TerminatorKind::Return,
+            TerminatorKind::Unreachable => rustc_mir::TerminatorKind::Unreachable,
+            TerminatorKind::Drop { place, target, unwind } => rustc_mir::TerminatorKind::Drop {
+                place: place.internal(tables, tcx),
+                target: block(*target),
+                unwind: unwind.internal(tables, tcx),
+                replace: false,
+                drop: None,
+                async_fut: None,
+            },
+            TerminatorKind::Call { func, args, destination, target, unwind } => {
+                rustc_mir::TerminatorKind::Call {
+                    func: func.internal(tables, tcx),
+                    args: args
+                        .iter()
+                        .map(|arg| Spanned { node: arg.internal(tables, tcx), span })
+                        .collect(),
+                    destination: destination.internal(tables, tcx),
+                    target: target.map(block),
+                    unwind: unwind.internal(tables, tcx),
+                    call_source: rustc_mir::CallSource::Normal,
+                    fn_span: span,
+                }
+            }
+            TerminatorKind::Assert { cond, expected, msg, target, unwind } => {
+                rustc_mir::TerminatorKind::Assert {
+                    cond: cond.internal(tables, tcx),
+                    expected: *expected,
+                    msg: Box::new(msg.internal(tables, tcx)),
+                    target: block(*target),
+                    unwind: unwind.internal(tables, tcx),
+                }
+            }
+            TerminatorKind::InlineAsm { .. } => {
+                return Err(error!("cannot convert inline assembly back to internal MIR"));
+            }
+        };
+        Ok(rustc_mir::Terminator { source_info: rustc_mir::SourceInfo::outermost(span), kind })
+    }
+}
+
+impl RustcInternal for SwitchTargets {
+    type T<'tcx> = rustc_mir::SwitchTargets;
+
+    fn internal<'tcx>(
+        &self,
+        _tables: &mut Tables<'_, BridgeTys>,
+        _tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        rustc_mir::SwitchTargets::new(
+            self.branches()
+                .map(|(value, target)| (value, rustc_mir::BasicBlock::from_usize(target))),
+            rustc_mir::BasicBlock::from_usize(self.otherwise()),
+        )
+    }
+}
+
+impl RustcInternal for UnwindAction {
+    type T<'tcx> = rustc_mir::UnwindAction;
+
+    fn internal<'tcx>(
+        &self,
+        _tables: &mut Tables<'_, BridgeTys>,
+        _tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        match self {
+            UnwindAction::Continue => rustc_mir::UnwindAction::Continue,
+            UnwindAction::Unreachable => rustc_mir::UnwindAction::Unreachable,
+            // The reason only changes the message when the process is terminated.
+            UnwindAction::Terminate => {
+                rustc_mir::UnwindAction::Terminate(rustc_mir::UnwindTerminateReason::Abi)
+            }
+            UnwindAction::Cleanup(target) => {
+                rustc_mir::UnwindAction::Cleanup(rustc_mir::BasicBlock::from_usize(*target))
+            }
+        }
+    }
+}
+
+impl RustcInternal for AssertMessage {
+    type T<'tcx> = rustc_mir::AssertMessage<'tcx>;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        use rustc_mir::AssertKind;
+        match self {
+            AssertMessage::BoundsCheck { len, index } => AssertKind::BoundsCheck {
+                len: len.internal(tables, tcx),
+                index: index.internal(tables, tcx),
+            },
+            AssertMessage::Overflow(bin_op, lhs, rhs) => AssertKind::Overflow(
+                bin_op.internal(tables, tcx),
+                lhs.internal(tables, tcx),
+                rhs.internal(tables, tcx),
+            ),
+            AssertMessage::OverflowNeg(op) => AssertKind::OverflowNeg(op.internal(tables, tcx)),
+            AssertMessage::DivisionByZero(op) => {
+                AssertKind::DivisionByZero(op.internal(tables, tcx))
+            }
+            AssertMessage::RemainderByZero(op) => {
+                AssertKind::RemainderByZero(op.internal(tables, tcx))
+            }
+            AssertMessage::ResumedAfterReturn(kind) => {
+                AssertKind::ResumedAfterReturn(kind.internal(tables, tcx))
+            }
+            AssertMessage::ResumedAfterPanic(kind) => {
+                AssertKind::ResumedAfterPanic(kind.internal(tables, tcx))
+            }
+            AssertMessage::ResumedAfterDrop(kind) => {
+                AssertKind::ResumedAfterDrop(kind.internal(tables, tcx))
+            }
+            AssertMessage::MisalignedPointerDereference { required, found } => {
+                AssertKind::MisalignedPointerDereference {
+                    required: required.internal(tables, tcx),
+                    found: found.internal(tables, tcx),
+                }
+            }
+            AssertMessage::NullPointerDereference => AssertKind::NullPointerDereference,
+            AssertMessage::InvalidEnumConstruction(op) => {
+                AssertKind::InvalidEnumConstruction(op.internal(tables, tcx))
+            }
+        }
+    }
+}
+
+impl RustcInternal for CoroutineKind {
+    type T<'tcx> = rustc_hir::CoroutineKind;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        match self {
+            CoroutineKind::Desugared(desugaring, source) => {
+                let desugaring = match desugaring {
+                    CoroutineDesugaring::Async => rustc_hir::CoroutineDesugaring::Async,
+                    CoroutineDesugaring::Gen => rustc_hir::CoroutineDesugaring::Gen,
+                    CoroutineDesugaring::AsyncGen => rustc_hir::CoroutineDesugaring::AsyncGen,
+                };
+                let source = match source {
+                    CoroutineSource::Block => rustc_hir::CoroutineSource::Block,
+                    CoroutineSource::Closure => rustc_hir::CoroutineSource::Closure,
+                    CoroutineSource::Fn => rustc_hir::CoroutineSource::Fn,
+                };
+                rustc_hir::CoroutineKind::Desugared(desugaring, source)
+            }
+            CoroutineKind::Coroutine(movability) => {
+                rustc_hir::CoroutineKind::Coroutine(movability.internal(tables, tcx))
+            }
+        }
+    }
+}
+
+impl RustcInternal for VarDebugInfo {
+    type T<'tcx> = rustc_mir::VarDebugInfo<'tcx>;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        rustc_mir::VarDebugInfo {
+            name: rustc_span::Symbol::intern(&self.name),
+            source_info: rustc_mir::SourceInfo {
+                span: self.source_info.span.internal(tables, tcx),
+                scope: rustc_mir::SourceScope::from_u32(self.source_info.scope),
+            },
+            composite: self
+                .composite
+                .as_ref()
+                .map(|composite| Box::new(composite.internal(tables, tcx))),
+            value: self.value.internal(tables, tcx),
+            argument_index: self.argument_index,
+        }
+    }
+}
+
+impl RustcInternal for VarDebugInfoFragment {
+    type T<'tcx> = rustc_mir::VarDebugInfoFragment<'tcx>;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        rustc_mir::VarDebugInfoFragment {
+            ty: self.ty.internal(tables, tcx),
+            projection: self.projection.internal(tables, tcx),
+        }
+    }
+}
+
+impl RustcInternal for VarDebugInfoContents {
+    type T<'tcx> = rustc_mir::VarDebugInfoContents<'tcx>;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        match self {
+            VarDebugInfoContents::Place(place) => {
+                rustc_mir::VarDebugInfoContents::Place(place.internal(tables, tcx))
+            }
+            VarDebugInfoContents::Const(constant) => {
+                rustc_mir::VarDebugInfoContents::Const(constant.internal(tables, tcx))
+            }
+        }
+    }
+}
+
 impl<T> RustcInternal for &T
 where
     T: RustcInternal,
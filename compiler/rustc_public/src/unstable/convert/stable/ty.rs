@@ -613,6 +613,22 @@ impl<'tcx> Stable<'tcx> for ty::TraitRef<'tcx> {
     }
 }
 
+impl<'tcx> Stable<'tcx> for rustc_middle::traits::EvaluationResult {
+    type T = crate::ty::TraitEvaluation;
+
+    fn stable(&self, _: &mut Tables<'_, BridgeTys>, _: &CompilerCtxt<'_, BridgeTys>) -> Self::T {
+        use crate::ty::TraitEvaluation;
+
+        if self.must_apply_modulo_regions() {
+            TraitEvaluation::Holds
+        } else if self.may_apply() {
+            TraitEvaluation::Ambiguous
+        } else {
+            TraitEvaluation::DoesNotHold
+        }
+    }
+}
+
 impl<'tcx> Stable<'tcx> for ty::Generics {
     type T = crate::ty::Generics;
 
@@ -957,7 +973,9 @@ impl<'tcx> Stable<'tcx> for ty::Instance<'tcx> {
             | ty::InstanceKind::FnPtrShim(..)
             | ty::InstanceKind::FutureDropPollShim(..)
             | ty::InstanceKind::AsyncDropGlue(..)
-            | ty::InstanceKind::AsyncDropGlueCtorShim(..) => crate::mir::mono::InstanceKind::Shim,
+            | ty::InstanceKind::AsyncDropGlueCtorShim(..)
+            | ty::InstanceKind::ColdPathShim(..)
+            | ty::InstanceKind::ConstSpecialized(..) => crate::mir::mono::InstanceKind::Shim,
         };
         crate::mir::mono::Instance { def, kind }
     }
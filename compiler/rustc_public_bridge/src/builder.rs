@@ -1,12 +1,16 @@
-//! Logic required to produce a monomorphic body.
+//! Logic required to produce a monomorphic body, and to build a body from the parts that a
+//! rustc_public tool provides.
 //!
 //! We retrieve and monomorphize the rustc body representation, i.e., we generate a
 //! monomorphic body using internal representation.
 
+use rustc_data_structures::fx::FxHashMap;
 use rustc_hir::def::DefKind;
+use rustc_index::IndexVec;
 use rustc_middle::mir;
-use rustc_middle::mir::visit::MutVisitor;
+use rustc_middle::mir::visit::{MutVisitor, Visitor};
 use rustc_middle::ty::{self, TyCtxt};
+use rustc_span::Span;
 
 /// Builds a monomorphic body for a given instance.
 pub(crate) struct BodyBuilder<'tcx> {
@@ -74,3 +78,102 @@ impl<'tcx> MutVisitor<'tcx> for BodyBuilder<'tcx> {
         self.tcx
     }
 }
+
+/// The parts of a body that a rustc_public tool can change. The blocks and locals that they refer
+/// to must be in range.
+pub struct BodyParts<'tcx> {
+    pub basic_blocks: IndexVec<mir::BasicBlock, mir::BasicBlockData<'tcx>>,
+    pub local_decls: IndexVec<mir::Local, mir::LocalDecl<'tcx>>,
+    pub arg_count: usize,
+    pub var_debug_info: Vec<mir::VarDebugInfo<'tcx>>,
+    pub spread_arg: Option<mir::Local>,
+    pub span: Span,
+}
+
+/// Builds the body that replaces `original` from the given parts.
+///
+/// Everything that the parts do not cover, such as the source scopes, the phase and the coroutine
+/// layout, is kept from the original body, and the cleanup blocks are determined from the unwind
+/// edges. The statements, terminators and locals get the source scope that their span has in the
+/// original body, or the outermost scope if their span does not appear there, e.g. for code that
+/// the tool added.
+pub(crate) fn replace_body<'tcx>(
+    original: &mir::Body<'tcx>,
+    parts: BodyParts<'tcx>,
+) -> mir::Body<'tcx> {
+    let mut scopes = FxHashMap::default();
+    for block in original.basic_blocks.iter() {
+        for statement in &block.statements {
+            scopes.entry(statement.source_info.span).or_insert(statement.source_info.scope);
+        }
+        let source_info = block.terminator().source_info;
+        scopes.entry(source_info.span).or_insert(source_info.scope);
+    }
+    for decl in &original.local_decls {
+        scopes.entry(decl.source_info.span).or_insert(decl.source_info.scope);
+    }
+    let scope_of = |span: Span| scopes.get(&span).copied().unwrap_or(mir::OUTERMOST_SOURCE_SCOPE);
+
+    let BodyParts {
+        mut basic_blocks,
+        mut local_decls,
+        arg_count,
+        var_debug_info,
+        spread_arg,
+        span,
+    } = parts;
+    for block in basic_blocks.iter_mut() {
+        for statement in &mut block.statements {
+            statement.source_info.scope = scope_of(statement.source_info.span);
+        }
+        let source_info = &mut block.terminator_mut().source_info;
+        source_info.scope = scope_of(source_info.span);
+    }
+    for decl in local_decls.iter_mut() {
+        decl.source_info.scope = scope_of(decl.source_info.span);
+    }
+
+    // Cleanup blocks are the targets of unwind edges, and the blocks that they branch to.
+    let mut worklist: Vec<_> = basic_blocks
+        .iter()
+        .filter_map(|block| match block.terminator().unwind() {
+            Some(mir::UnwindAction::Cleanup(target)) => Some(*target),
+            _ => None,
+        })
+        .collect();
+    while let Some(bb) = worklist.pop() {
+        // The jump targets are in range, see `BodyParts`.
+        let block = &mut basic_blocks[bb];
+        if !block.is_cleanup {
+            block.is_cleanup = true;
+            worklist.extend(block.terminator().successors());
+        }
+    }
+
+    let mut body = original.clone();
+    body.basic_blocks = mir::BasicBlocks::new(basic_blocks);
+    body.local_decls = local_decls;
+    body.arg_count = arg_count;
+    body.var_debug_info = var_debug_info;
+    body.spread_arg = spread_arg;
+    body.span = span;
+
+    // The constants that have to be evaluated for the body to be codegen'd may have changed. The
+    // mentioned items are kept, as the items that the new body uses are collected from it anyway.
+    let mut required_consts = RequiredConsts(Vec::new());
+    for (bb, data) in mir::traversal::reverse_postorder(&body) {
+        required_consts.visit_basic_block_data(bb, data);
+    }
+    body.required_consts = Some(required_consts.0);
+    body
+}
+
+struct RequiredConsts<'tcx>(Vec<mir::ConstOperand<'tcx>>);
+
+impl<'tcx> Visitor<'tcx> for RequiredConsts<'tcx> {
+    fn visit_const_operand(&mut self, constant: &mir::ConstOperand<'tcx>, _: mir::Location) {
+        if constant.const_.is_required_const() {
+            self.0.push(*constant);
+        }
+    }
+}
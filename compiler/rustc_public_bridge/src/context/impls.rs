@@ -9,6 +9,7 @@ use rustc_hir::def::DefKind;
 use rustc_hir::{Attribute, LangItem};
 use rustc_middle::mir::interpret::{AllocId, ConstAllocation, ErrorHandled, GlobalAlloc, Scalar};
 use rustc_middle::mir::{BinOp, Body, Const as MirConst, ConstValue, UnOp};
+use rustc_middle::traits::{EvaluationResult, ObligationCause};
 use rustc_middle::ty::layout::{FnAbiOf, LayoutOf};
 use rustc_middle::ty::print::{with_forced_trimmed_paths, with_no_trimmed_paths};
 use rustc_middle::ty::util::Discr;
@@ -23,9 +24,12 @@ use rustc_session::cstore::ForeignModule;
 use rustc_span::def_id::{CrateNum, DefId, LOCAL_CRATE};
 use rustc_span::{FileNameDisplayPreference, Span, Symbol};
 use rustc_target::callconv::FnAbi;
+use rustc_trait_selection::infer::TyCtxtInferExt;
+use rustc_trait_selection::traits::query::evaluate_obligation::InferCtxtExt;
+use rustc_trait_selection::traits::{Obligation, ObligationCtxt};
 
 use super::{AllocRangeHelpers, CompilerCtxt, TyHelpers, TypingEnvHelpers};
-use crate::builder::BodyBuilder;
+use crate::builder::{self, BodyBuilder, BodyParts};
 use crate::{Bridge, Error, Tables, filter_def_ids};
 
 impl<'tcx, B: Bridge> TyHelpers<'tcx> for CompilerCtxt<'tcx, B> {
@@ -535,6 +539,30 @@ impl<'tcx, B: Bridge> CompilerCtxt<'tcx, B> {
         ty::Ty::new_box(self.tcx, ty)
     }
 
+    /// Create a projection type for an associated type of a trait.
+    pub fn new_projection_ty(
+        &self,
+        def_id: DefId,
+        args: GenericArgsRef<'tcx>,
+    ) -> Result<Ty<'tcx>, B::Error> {
+        let tcx = self.tcx;
+        if tcx.def_kind(def_id) != DefKind::AssocTy
+            || tcx.def_kind(tcx.parent(def_id)) != DefKind::Trait
+        {
+            return Err(B::Error::new(format!(
+                "Cannot create a projection: `{}` is not an associated type of a trait",
+                tcx.def_path_str(def_id)
+            )));
+        }
+        if !tcx.check_args_compatible(def_id, args) {
+            return Err(B::Error::new(format!(
+                "Cannot create a projection: `{args:?}` are not the generic arguments of `{}`",
+                tcx.def_path_str(def_id)
+            )));
+        }
+        Ok(Ty::new_projection_from_args(tcx, def_id, args))
+    }
+
     /// Returns the type of given crate item.
     pub fn def_ty(&self, item: DefId) -> Ty<'tcx> {
         self.tcx.type_of(item).instantiate_identity()
@@ -580,6 +608,26 @@ impl<'tcx, B: Bridge> CompilerCtxt<'tcx, B> {
         self.instance_has_body(instance).then(|| BodyBuilder::new(self.tcx, instance).build())
     }
 
+    /// Build the body that replaces `original` from the given parts, and check that it is valid.
+    pub fn replace_body(
+        &self,
+        original: &Body<'tcx>,
+        parts: BodyParts<'tcx>,
+    ) -> Result<Body<'tcx>, B::Error> {
+        let body = builder::replace_body(original, parts);
+        let failures = self.tcx.validate_mir_body(&body, "replaced by a rustc_public tool");
+        if failures.is_empty() {
+            return Ok(body);
+        }
+        let failures: Vec<_> =
+            failures.iter().map(|(location, msg)| format!("{location:?}: {msg}")).collect();
+        Err(B::Error::new(format!(
+            "Invalid body for `{}`:\n{}",
+            self.tcx.def_path_str(body.source.def_id()),
+            failures.join("\n")
+        )))
+    }
+
     /// Get the instance type with generic instantiations applied and lifetimes erased.
     pub fn instance_ty(&self, instance: ty::Instance<'tcx>) -> Ty<'tcx> {
         assert!(!instance.has_non_region_param(), "{instance:?} needs further instantiation");
@@ -674,6 +722,40 @@ impl<'tcx, B: Bridge> CompilerCtxt<'tcx, B> {
         Some(Instance::resolve_closure(self.tcx, def_id, args_ref, closure_kind))
     }
 
+    /// Evaluate whether a trait reference holds in the given typing environment.
+    pub fn evaluate_trait_ref(
+        &self,
+        trait_ref: TraitRef<'tcx>,
+        typing_env: ty::TypingEnv<'tcx>,
+    ) -> EvaluationResult {
+        let (infcx, param_env) = self.tcx.infer_ctxt().build_with_typing_env(typing_env);
+        let obligation = Obligation::new(self.tcx, ObligationCause::dummy(), param_env, trait_ref);
+        infcx.evaluate_obligation_no_overflow(&obligation)
+    }
+
+    /// Normalize the aliases in a type in the given typing environment, and erase its regions.
+    pub fn normalize_ty(
+        &self,
+        ty: Ty<'tcx>,
+        typing_env: ty::TypingEnv<'tcx>,
+    ) -> Result<Ty<'tcx>, B::Error> {
+        let (infcx, param_env) = self.tcx.infer_ctxt().build_with_typing_env(typing_env);
+        let ocx = ObligationCtxt::new(&infcx);
+        let normalized = ocx.deeply_normalize(&ObligationCause::dummy(), param_env, ty);
+        match normalized {
+            Ok(normalized) if ocx.select_all_or_error().is_empty() => {
+                let normalized = infcx.resolve_vars_if_possible(normalized);
+                if normalized.has_non_region_infer() {
+                    return Err(B::Error::new(format!(
+                        "Cannot normalize `{ty}`: the result `{normalized}` is ambiguous"
+                    )));
+                }
+                Ok(self.tcx.erase_and_anonymize_regions(normalized))
+            }
+            _ => Err(B::Error::new(format!("Cannot normalize `{ty}` in `{typing_env:?}`"))),
+        }
+    }
+
     /// Try to evaluate an instance into a constant.
     pub fn eval_instance(&self, instance: ty::Instance<'tcx>) -> Result<ConstValue, ErrorHandled> {
         self.tcx.const_eval_instance(
@@ -39,7 +39,7 @@ use rustc_span::def_id::{CrateNum, DefId, LOCAL_CRATE};
 
 pub mod alloc;
 pub mod bridge;
-mod builder;
+pub mod builder;
 pub mod context;
 
 #[deprecated(note = "please use `rustc_public::rustc_internal` instead")]
@@ -0,0 +1,199 @@
+//! The format of the snapshots of `-Z emit=public-mir`, and a reader for them.
+//!
+//! A snapshot contains the `rustc_public` view of a crate after analysis: the MIR bodies of its
+//! items, its traits and trait impls, and the types, ADTs, spans and allocations that they refer
+//! to. The types of this crate mirror the data types of `rustc_public` and have the same names
+//! and fields, but they don't depend on the compiler, so that the snapshots can be analysed after
+//! the compilation, in another process. The root types of the snapshot, like [`Snapshot`] and
+//! [`Item`], are generic over their [`Payload`], so that the compiler writes them with the data
+//! types of `rustc_public` directly.
+//!
+//! The ids in a snapshot, like [`Ty`](ty::Ty), [`Span`](ty::Span) and [`DefId`], are only
+//! meaningful within that snapshot. Their data is in the tables of the [`Snapshot`], which can be
+//! looked up with its methods.
+//!
+//! A snapshot is either JSON or a compact binary encoding. Both formats contain
+//! [`FORMAT_VERSION`], and [`read`] rejects snapshots of any other version.
+
+// The variants have the same sizes as in `rustc_public`.
+#![allow(clippy::large_enum_variant)]
+
+use std::fmt;
+use std::io::{self, Read, Write};
+
+use serde::{Deserialize, Serialize};
+
+pub mod abi;
+pub mod mir;
+mod snapshot;
+pub mod target;
+pub mod ty;
+
+pub use snapshot::*;
+
+#[cfg(test)]
+mod tests;
+
+/// The version of the snapshot format that this crate represents.
+///
+/// This integer is incremented with every change to the types of the snapshot, and is stored
+/// with it as [`Snapshot::format_version`].
+pub const FORMAT_VERSION: u32 = 1;
+
+/// The first bytes of a binary snapshot.
+const BINARY_MAGIC: &[u8; 4] = b"rpmr";
+
+/// The encoding of a snapshot.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Format {
+    Json,
+    /// A `bincode` encoding, after a header with a magic number and the format version.
+    Binary,
+}
+
+impl Format {
+    /// The extension of the files that `-Z emit=public-mir` writes in this format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Json => "public-mir.json",
+            Format::Binary => "public-mir.bin",
+        }
+    }
+}
+
+pub type Symbol = String;
+
+/// A definition of the crate or of one of its dependencies, whose name is in
+/// [`Snapshot::defs`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct DefId(pub usize);
+
+/// An item of the crate with a MIR body, in [`Snapshot::items`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct CrateItem(pub DefId);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ItemKind {
+    Fn,
+    Static,
+    Const,
+    Ctor(CtorKind),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CtorKind {
+    Const,
+    Fn,
+}
+
+/// The textual representation of a compiler construct that `rustc_public` doesn't model.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Opaque(pub String);
+
+impl fmt::Display for Opaque {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    Json(serde_json::Error),
+    Binary(bincode::Error),
+    /// The input is not a binary snapshot.
+    NotASnapshot,
+    /// The snapshot was written with another version of the format.
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(error) => error.fmt(f),
+            Error::Json(error) => error.fmt(f),
+            Error::Binary(error) => error.fmt(f),
+            Error::NotASnapshot => f.write_str("the input is not a binary public MIR snapshot"),
+            Error::UnsupportedVersion(version) => write!(
+                f,
+                "the snapshot has format version {version}, but only version {FORMAT_VERSION} \
+                 is supported"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Error::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(error: serde_json::Error) -> Self {
+        Error::Json(error)
+    }
+}
+
+impl From<bincode::Error> for Error {
+    fn from(error: bincode::Error) -> Self {
+        Error::Binary(error)
+    }
+}
+
+/// Writes a snapshot in the given format.
+///
+/// This is generic over the [`Payload`] so that the compiler can write its own data types, which
+/// have the same serialized form as the types of this crate.
+pub fn write<W: Write, P: Payload>(
+    mut writer: W,
+    format: Format,
+    snapshot: &Snapshot<P>,
+) -> Result<(), Error> {
+    match format {
+        Format::Json => serde_json::to_writer(&mut writer, snapshot)?,
+        Format::Binary => {
+            writer.write_all(BINARY_MAGIC)?;
+            writer.write_all(&FORMAT_VERSION.to_le_bytes())?;
+            bincode::serialize_into(&mut writer, snapshot)?;
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads a snapshot in the given format.
+pub fn read<R: Read>(mut reader: R, format: Format) -> Result<Snapshot, Error> {
+    match format {
+        Format::Json => {
+            // Check the version first, as the rest of the snapshot may not be readable.
+            #[derive(Deserialize)]
+            struct Header {
+                format_version: u32,
+            }
+
+            let mut json = Vec::new();
+            reader.read_to_end(&mut json)?;
+            let header: Header = serde_json::from_slice(&json)?;
+            if header.format_version != FORMAT_VERSION {
+                return Err(Error::UnsupportedVersion(header.format_version));
+            }
+            Ok(serde_json::from_slice(&json)?)
+        }
+        Format::Binary => {
+            let mut header = [0; 8];
+            reader.read_exact(&mut header).map_err(|_| Error::NotASnapshot)?;
+            let (magic, version) = header.split_at(4);
+            if magic != BINARY_MAGIC {
+                return Err(Error::NotASnapshot);
+            }
+            let version = u32::from_le_bytes(version.try_into().unwrap());
+            if version != FORMAT_VERSION {
+                return Err(Error::UnsupportedVersion(version));
+            }
+            Ok(bincode::deserialize_from(io::BufReader::new(reader))?)
+        }
+    }
+}
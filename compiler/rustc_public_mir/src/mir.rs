@@ -0,0 +1,7 @@
+//! The types of `rustc_public::mir`.
+
+pub mod alloc;
+mod body;
+pub mod mono;
+
+pub use body::*;
@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+use crate::mir::mono::{Instance, StaticDef};
+use crate::ty::{Allocation, Binder, ExistentialTraitRef, Ty, id};
+
+id! {
+    /// A global allocation, which is in [`Snapshot::allocs`](crate::Snapshot::allocs).
+    AllocId
+}
+
+/// An allocation in the rustc_public's IR global memory can be either a function pointer,
+/// a static, or a "real" allocation with some data in it.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GlobalAlloc {
+    /// The alloc ID is used as a function pointer.
+    Function(Instance),
+    /// This alloc ID points to a symbolic (not-reified) vtable.
+    /// The `None` trait ref is used to represent auto traits.
+    VTable(Ty, Option<Binder<ExistentialTraitRef>>),
+    /// The alloc ID points to a "lazy" static variable that did not get computed (yet).
+    Static(StaticDef),
+    /// The alloc ID points to memory.
+    Memory(Allocation),
+    /// The first pointer-sized segment of a type id.
+    TypeId { ty: Ty },
+}
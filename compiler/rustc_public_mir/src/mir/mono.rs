@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+use crate::ty::{def, id};
+
+id! {
+    /// An instance, which is in [`Snapshot::instances`](crate::Snapshot::instances).
+    InstanceDef
+}
+
+def!(StaticDef);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Instance {
+    /// The type of instance.
+    pub kind: InstanceKind,
+    /// The entry of the instance in [`Snapshot::instances`](crate::Snapshot::instances).
+    pub def: InstanceDef,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum InstanceKind {
+    /// A user defined item.
+    Item,
+    /// A compiler intrinsic function.
+    Intrinsic,
+    /// A virtual function definition stored in a VTable.
+    /// The `idx` field indicates the position in the VTable for this instance.
+    Virtual { idx: usize },
+    /// A compiler generated shim.
+    Shim,
+}
@@ -0,0 +1,265 @@
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::abi::{LayoutShape, ReprOptions};
+use crate::mir::Body;
+use crate::mir::alloc::{AllocId, GlobalAlloc};
+use crate::mir::mono::InstanceDef;
+use crate::target::MachineInfo;
+use crate::ty::{
+    AdtDef, AdtKind, GenericArgs, ImplDef, LineInfo, Span, TraitDecl, TraitRef, Ty, TyKind,
+};
+use crate::{CrateItem, DefId, ItemKind, Symbol};
+
+macro_rules! payload {
+    ($($name:ident),* $(,)?) => {
+        /// The types of the data of a snapshot.
+        ///
+        /// The snapshots are read with the types of this crate, see [`Offline`]. The compiler
+        /// writes them with the types of `rustc_public` instead, which have the same serialized
+        /// form and the same names, so that the root types of the snapshot are shared by the
+        /// writer and the reader.
+        pub trait Payload {
+            $(type $name: Clone + Serialize;)*
+        }
+
+        /// A payload whose types can be deserialized.
+        pub trait DeserializePayload: Payload<$($name: DeserializeOwned),*> {}
+
+        impl<P: Payload<$($name: DeserializeOwned),*>> DeserializePayload for P {}
+
+        impl Payload for Offline {
+            $(type $name = $name;)*
+        }
+    };
+}
+
+payload! {
+    MachineInfo,
+    DefId,
+    CrateItem,
+    ItemKind,
+    Span,
+    LineInfo,
+    Ty,
+    TyKind,
+    GenericArgs,
+    LayoutShape,
+    Body,
+    TraitDecl,
+    TraitRef,
+    ImplDef,
+    AdtDef,
+    AdtKind,
+    ReprOptions,
+    AllocId,
+    GlobalAlloc,
+    InstanceDef,
+}
+
+/// The payload of the snapshots that are read, which is made of the types of this crate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Offline;
+
+/// The root of a snapshot.
+///
+/// The tables of the ids are sorted by id, so that they can be searched with the methods of the
+/// snapshot. They contain the ids that the items refer to, directly or through other entries.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = "P: DeserializePayload"))]
+pub struct Snapshot<P: Payload = Offline> {
+    /// The version of the format, which is [`FORMAT_VERSION`](crate::FORMAT_VERSION).
+    pub format_version: u32,
+    pub crate_name: Symbol,
+    pub machine: P::MachineInfo,
+    /// The local items of the crate, which are the ones with MIR bodies.
+    pub items: Vec<Item<P>>,
+    /// The traits that are declared in the crate.
+    pub trait_decls: Vec<P::TraitDecl>,
+    /// The trait impls of the crate.
+    pub trait_impls: Vec<TraitImpl<P>>,
+    pub defs: Vec<Def<P>>,
+    pub types: Vec<TyInfo<P>>,
+    pub adts: Vec<Adt<P>>,
+    pub spans: Vec<SpanInfo<P>>,
+    pub allocs: Vec<Alloc<P>>,
+    pub instances: Vec<InstanceInfo<P>>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = "P: DeserializePayload"))]
+pub struct Item<P: Payload = Offline> {
+    pub def: P::CrateItem,
+    pub name: Symbol,
+    pub kind: P::ItemKind,
+    pub span: P::Span,
+    pub ty: P::Ty,
+    /// The optimized MIR of the item, if it has one.
+    pub body: Option<P::Body>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = "P: DeserializePayload"))]
+pub struct TraitImpl<P: Payload = Offline> {
+    pub def: P::ImplDef,
+    pub trait_ref: P::TraitRef,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = "P: DeserializePayload"))]
+pub struct Def<P: Payload = Offline> {
+    pub def_id: P::DefId,
+    /// The path of the definition.
+    pub name: Symbol,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = "P: DeserializePayload"))]
+pub struct TyInfo<P: Payload = Offline> {
+    pub ty: P::Ty,
+    pub kind: P::TyKind,
+    /// The layout of the type, which is `None` if it cannot be computed, e.g. because the type
+    /// depends on generic parameters.
+    pub layout: Option<P::LayoutShape>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = "P: DeserializePayload"))]
+pub struct Adt<P: Payload = Offline> {
+    pub def: P::AdtDef,
+    pub kind: P::AdtKind,
+    pub repr: P::ReprOptions,
+    pub variants: Vec<Variant<P>>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = "P: DeserializePayload"))]
+pub struct Variant<P: Payload = Offline> {
+    pub name: Symbol,
+    pub fields: Vec<Field<P>>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = "P: DeserializePayload"))]
+pub struct Field<P: Payload = Offline> {
+    pub name: Symbol,
+    /// The declared type of the field, which may refer to the generic parameters of the ADT.
+    pub ty: P::Ty,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = "P: DeserializePayload"))]
+pub struct SpanInfo<P: Payload = Offline> {
+    pub span: P::Span,
+    pub file: Symbol,
+    pub lines: P::LineInfo,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = "P: DeserializePayload"))]
+pub struct Alloc<P: Payload = Offline> {
+    pub id: P::AllocId,
+    pub alloc: P::GlobalAlloc,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = "P: DeserializePayload"))]
+pub struct InstanceInfo<P: Payload = Offline> {
+    pub def: P::InstanceDef,
+    pub name: Symbol,
+    pub mangled_name: Symbol,
+    pub ty: P::Ty,
+    pub args: P::GenericArgs,
+}
+
+// The derives would require these traits of every payload, but only the types of this crate
+// implement them.
+macro_rules! impl_offline_traits {
+    ($($ty:ident { $($field:ident),* })*) => {$(
+        impl fmt::Debug for $ty {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_struct(stringify!($ty))$(.field(stringify!($field), &self.$field))*.finish()
+            }
+        }
+
+        impl PartialEq for $ty {
+            fn eq(&self, other: &Self) -> bool {
+                $(self.$field == other.$field)&&*
+            }
+        }
+
+        impl Eq for $ty {}
+
+        impl Hash for $ty {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                $(self.$field.hash(state);)*
+            }
+        }
+    )*};
+}
+
+impl_offline_traits! {
+    Snapshot {
+        format_version, crate_name, machine, items, trait_decls, trait_impls, defs, types, adts,
+        spans, allocs, instances
+    }
+    Item { def, name, kind, span, ty, body }
+    TraitImpl { def, trait_ref }
+    Def { def_id, name }
+    TyInfo { ty, kind, layout }
+    Adt { def, kind, repr, variants }
+    Variant { name, fields }
+    Field { name, ty }
+    SpanInfo { span, file, lines }
+    Alloc { id, alloc }
+    InstanceInfo { def, name, mangled_name, ty, args }
+}
+
+macro_rules! lookup {
+    ($(#[$attr:meta])* $name:ident($id:ident: $ty:ty) -> $entry:ty { $table:ident.$key:ident }) => {
+        $(#[$attr])*
+        pub fn $name(&self, $id: $ty) -> Option<&$entry> {
+            let index = self.$table.binary_search_by_key(&$id, |entry| entry.$key).ok()?;
+            Some(&self.$table[index])
+        }
+    };
+}
+
+impl Snapshot {
+    lookup! {
+        /// Finds the item of a local definition.
+        item(def: CrateItem) -> Item { items.def }
+    }
+    lookup! {
+        /// Finds the path of a definition.
+        def(def_id: DefId) -> Def { defs.def_id }
+    }
+    lookup! {
+        /// Finds the kind and layout of a type.
+        ty(ty: Ty) -> TyInfo { types.ty }
+    }
+    lookup! {
+        /// Finds the variants and representation of an ADT.
+        adt(def: AdtDef) -> Adt { adts.def }
+    }
+    lookup! {
+        /// Finds the location of a span.
+        span(span: Span) -> SpanInfo { spans.span }
+    }
+    lookup! {
+        /// Finds a global allocation.
+        alloc(id: AllocId) -> Alloc { allocs.id }
+    }
+    lookup! {
+        /// Finds the name and type of an instance.
+        instance(def: InstanceDef) -> InstanceInfo { instances.def }
+    }
+
+    /// Finds the item with the given name.
+    pub fn item_by_name(&self, name: &str) -> Option<&Item> {
+        self.items.iter().find(|item| item.name == name)
+    }
+}
@@ -0,0 +1,150 @@
+use super::*;
+use crate::abi::{
+    FieldsShape, IntegerLength, LayoutShape, Primitive, ReprFlags, ReprOptions, Scalar, ValueAbi,
+    VariantsShape, WrappingRange,
+};
+use crate::mir::{
+    BasicBlock, Body, ConstOperand, LocalDecl, Mutability, Operand, Place, Rvalue, Statement,
+    StatementKind, Terminator, TerminatorKind,
+};
+use crate::target::{Endian, MachineInfo, MachineSize};
+use crate::ty::{
+    ConstantKind, LineInfo, MirConst, MirConstId, RigidTy, Span, Ty, TyKind, UintTy, VariantIdx,
+};
+
+fn snapshot() -> Snapshot {
+    let u128_ty = Ty(0);
+    let span = Span(0);
+    let constant = MirConst { kind: ConstantKind::ZeroSized, ty: u128_ty, id: MirConstId(0) };
+    let body = Body {
+        blocks: vec![BasicBlock {
+            statements: vec![Statement {
+                kind: StatementKind::Assign(
+                    Place { local: 0, projection: vec![] },
+                    Rvalue::Use(Operand::Constant(ConstOperand {
+                        span,
+                        user_ty: None,
+                        const_: constant,
+                    })),
+                ),
+                span,
+            }],
+            terminator: Terminator { kind: TerminatorKind::Return, span },
+        }],
+        locals: vec![LocalDecl { ty: u128_ty, span, mutability: Mutability::Mut }],
+        arg_count: 0,
+        var_debug_info: vec![],
+        spread_arg: None,
+        span,
+    };
+    let scalar = Scalar::Initialized {
+        value: Primitive::Int { length: IntegerLength::I128, signed: false },
+        valid_range: WrappingRange { start: 0, end: u128::MAX },
+    };
+    Snapshot {
+        format_version: FORMAT_VERSION,
+        crate_name: "input".to_string(),
+        machine: MachineInfo {
+            endian: Endian::Little,
+            pointer_width: MachineSize { num_bits: 64 },
+        },
+        items: vec![Item {
+            def: CrateItem(DefId(3)),
+            name: "max".to_string(),
+            kind: ItemKind::Fn,
+            span,
+            ty: Ty(1),
+            body: Some(body),
+        }],
+        trait_decls: vec![],
+        trait_impls: vec![],
+        defs: vec![Def { def_id: DefId(3), name: "max".to_string() }],
+        types: vec![TyInfo {
+            ty: u128_ty,
+            kind: TyKind::RigidTy(RigidTy::Uint(UintTy::U128)),
+            layout: Some(LayoutShape {
+                fields: FieldsShape::Primitive,
+                variants: VariantsShape::Single { index: VariantIdx(0) },
+                abi: ValueAbi::Scalar(scalar),
+                abi_align: 16,
+                size: MachineSize { num_bits: 128 },
+            }),
+        }],
+        adts: vec![Adt {
+            def: crate::ty::AdtDef(DefId(4)),
+            kind: crate::ty::AdtKind::Struct,
+            repr: ReprOptions {
+                int: None,
+                align: None,
+                pack: None,
+                flags: ReprFlags {
+                    is_simd: false,
+                    is_c: true,
+                    is_transparent: false,
+                    is_linear: false,
+                },
+            },
+            variants: vec![],
+        }],
+        spans: vec![SpanInfo {
+            span,
+            file: "input.rs".to_string(),
+            lines: LineInfo { start_line: 1, start_col: 1, end_line: 1, end_col: 10 },
+        }],
+        allocs: vec![],
+        instances: vec![],
+    }
+}
+
+#[test]
+fn test_json_roundtrip() {
+    let snapshot = snapshot();
+    let mut json = Vec::new();
+    write(&mut json, Format::Json, &snapshot).unwrap();
+    assert_eq!(read(&json[..], Format::Json).unwrap(), snapshot);
+}
+
+#[test]
+fn test_binary_roundtrip() {
+    let snapshot = snapshot();
+    let mut binary = Vec::new();
+    write(&mut binary, Format::Binary, &snapshot).unwrap();
+    assert!(binary.starts_with(BINARY_MAGIC));
+    assert_eq!(read(&binary[..], Format::Binary).unwrap(), snapshot);
+}
+
+#[test]
+fn test_lookup() {
+    let snapshot = snapshot();
+    let item = snapshot.item_by_name("max").unwrap();
+    assert_eq!(snapshot.item(item.def), Some(item));
+    assert_eq!(snapshot.def(item.def.0).unwrap().name, "max");
+    let body = item.body.as_ref().unwrap();
+    let ty = snapshot.ty(body.locals[0].ty).unwrap();
+    assert_eq!(ty.kind, TyKind::RigidTy(RigidTy::Uint(UintTy::U128)));
+    assert_eq!(snapshot.span(body.span).unwrap().file, "input.rs");
+    assert!(snapshot.ty(item.ty).is_none());
+}
+
+#[test]
+fn test_unsupported_version() {
+    let mut snapshot = snapshot();
+    snapshot.format_version = FORMAT_VERSION + 1;
+    let mut json = Vec::new();
+    write(&mut json, Format::Json, &snapshot).unwrap();
+    let error = read(&json[..], Format::Json).unwrap_err();
+    assert!(matches!(error, Error::UnsupportedVersion(v) if v == FORMAT_VERSION + 1));
+
+    let mut binary = BINARY_MAGIC.to_vec();
+    binary.extend((FORMAT_VERSION + 1).to_le_bytes());
+    let error = read(&binary[..], Format::Binary).unwrap_err();
+    assert!(matches!(error, Error::UnsupportedVersion(v) if v == FORMAT_VERSION + 1));
+}
+
+#[test]
+fn test_not_a_snapshot() {
+    let mut json = Vec::new();
+    write(&mut json, Format::Json, &snapshot()).unwrap();
+    assert!(matches!(read(&json[..], Format::Binary), Err(Error::NotASnapshot)));
+    assert!(matches!(read(&b"rp"[..], Format::Binary), Err(Error::NotASnapshot)));
+}
@@ -30,7 +30,9 @@ use rustc_query_system::query::{
 use rustc_query_system::{HandleCycleError, Value};
 use rustc_span::{ErrorGuaranteed, Span};
 
-use crate::plumbing::{__rust_begin_short_backtrace, encode_all_query_results, try_mark_green};
+use crate::plumbing::{
+    __rust_begin_short_backtrace, encode_all_query_results, record_mir_remark, try_mark_green,
+};
 use crate::profiling_support::QueryKeyStringCache;
 
 #[macro_use]
@@ -238,4 +240,5 @@ rustc_middle::rustc_with_all_queries! { define_queries! }
 pub fn provide(providers: &mut rustc_middle::util::Providers) {
     providers.hooks.alloc_self_profile_query_strings = alloc_self_profile_query_strings;
     providers.hooks.query_key_hash_verify_all = query_key_hash_verify_all;
+    providers.hooks.record_mir_remark = record_mir_remark;
 }
@@ -32,6 +32,7 @@ use rustc_query_system::query::{
 };
 use rustc_query_system::{QueryOverflow, QueryOverflowNote};
 use rustc_serialize::{Decodable, Encodable};
+use rustc_session::mir_remarks::MirRemark;
 use rustc_span::def_id::LOCAL_CRATE;
 
 use crate::QueryConfigRestored;
@@ -132,6 +133,19 @@ impl<'tcx> QueryContext for QueryCtxt<'tcx> {
         }
     }
 
+    #[inline(never)]
+    #[cold]
+    fn replay_side_effect(
+        self,
+        prev_dep_node_index: SerializedDepNodeIndex,
+        dep_node_index: DepNodeIndex,
+        side_effect: QuerySideEffect,
+    ) {
+        if let Some(c) = self.query_system.on_disk_cache.as_ref() {
+            c.replay_side_effect(self.tcx, prev_dep_node_index, dep_node_index, side_effect)
+        }
+    }
+
     /// Executes a job by changing the `ImplicitCtxt` to point to the
     /// new query job while it executes.
     #[inline(always)]
@@ -201,6 +215,12 @@ pub(super) fn encode_all_query_results<'tcx>(
     }
 }
 
+pub(crate) fn record_mir_remark(tcx: TyCtxt<'_>, remark: MirRemark) {
+    tcx.dep_graph
+        .record_side_effect(QueryCtxt::new(tcx), || QuerySideEffect::MirRemark(remark.clone()));
+    tcx.sess.mir_remarks.record(remark);
+}
+
 pub fn query_key_hash_verify_all<'tcx>(tcx: TyCtxt<'tcx>) {
     if tcx.sess().opts.unstable_opts.incremental_verify_ich || cfg!(debug_assertions) {
         tcx.sess.time("query_key_hash_verify_all", || {
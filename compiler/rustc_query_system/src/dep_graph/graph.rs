@@ -518,17 +518,29 @@ impl<D: Deps> DepGraph<D> {
     /// `diagnostic` with it, for use in the next session.
     #[inline]
     pub fn record_diagnostic<Qcx: QueryContext>(&self, qcx: Qcx, diagnostic: &DiagInner) {
+        self.record_side_effect(qcx, || QuerySideEffect::Diagnostic(diagnostic.clone()));
+    }
+
+    /// Like `record_diagnostic`, for any kind of side effect. `side_effect` is only called if
+    /// the side effect needs to be recorded.
+    #[inline]
+    pub fn record_side_effect<Qcx: QueryContext>(
+        &self,
+        qcx: Qcx,
+        side_effect: impl FnOnce() -> QuerySideEffect,
+    ) {
         if let Some(ref data) = self.data {
             D::read_deps(|task_deps| match task_deps {
                 TaskDepsRef::EvalAlways | TaskDepsRef::Ignore => return,
                 TaskDepsRef::Forbid | TaskDepsRef::Allow(..) => {
-                    self.read_index(data.encode_diagnostic(qcx, diagnostic));
+                    self.read_index(data.encode_side_effect(qcx, side_effect()));
                 }
             })
         }
     }
+
     /// This forces a diagnostic node green by running its side effect. `prev_index` would
-    /// refer to a node created used `encode_diagnostic` in the previous session.
+    /// refer to a node created used `encode_side_effect` in the previous session.
     #[inline]
     pub fn force_diagnostic_node<Qcx: QueryContext>(
         &self,
@@ -664,13 +676,13 @@ impl<D: Deps> DepGraphData<D> {
         self.debug_loaded_from_disk.lock().insert(dep_node);
     }
 
-    /// This encodes a diagnostic by creating a node with an unique index and assoicating
-    /// `diagnostic` with it, for use in the next session.
+    /// This encodes a side effect by creating a node with an unique index and assoicating
+    /// `side_effect` with it, for use in the next session.
     #[inline]
-    fn encode_diagnostic<Qcx: QueryContext>(
+    fn encode_side_effect<Qcx: QueryContext>(
         &self,
         qcx: Qcx,
-        diagnostic: &DiagInner,
+        side_effect: QuerySideEffect,
     ) -> DepNodeIndex {
         // Use `send_new` so we get an unique index, even though the dep node is not.
         let dep_node_index = self.current.encoder.send_new(
@@ -683,13 +695,12 @@ impl<D: Deps> DepGraphData<D> {
             // diagnostic.
             std::iter::once(DepNodeIndex::FOREVER_RED_NODE).collect(),
         );
-        let side_effect = QuerySideEffect::Diagnostic(diagnostic.clone());
         qcx.store_side_effect(dep_node_index, side_effect);
         dep_node_index
     }
 
     /// This forces a diagnostic node green by running its side effect. `prev_index` would
-    /// refer to a node created used `encode_diagnostic` in the previous session.
+    /// refer to a node created used `encode_side_effect` in the previous session.
     #[inline]
     fn force_diagnostic_node<Qcx: QueryContext>(
         &self,
@@ -699,12 +710,6 @@ impl<D: Deps> DepGraphData<D> {
         D::with_deps(TaskDepsRef::Ignore, || {
             let side_effect = qcx.load_side_effect(prev_index).unwrap();
 
-            match &side_effect {
-                QuerySideEffect::Diagnostic(diagnostic) => {
-                    qcx.dep_context().sess().dcx().emit_diagnostic(diagnostic.clone());
-                }
-            }
-
             // Use `send_and_color` as `promote_node_and_deps_to_current` expects all
             // green dependencies. `send_and_color` will also prevent multiple nodes
             // being encoded for concurrent calls.
@@ -719,8 +724,9 @@ impl<D: Deps> DepGraphData<D> {
                 std::iter::once(DepNodeIndex::FOREVER_RED_NODE).collect(),
                 true,
             );
-            // This will just overwrite the same value for concurrent calls.
-            qcx.store_side_effect(dep_node_index, side_effect);
+            // This emits the diagnostic, possibly deferred to keep the original order of
+            // diagnostics, and stores it again for the next session.
+            qcx.replay_side_effect(prev_index, dep_node_index, side_effect);
         })
     }
 
@@ -22,6 +22,7 @@ use rustc_errors::DiagInner;
 use rustc_hashes::Hash64;
 use rustc_hir::def::DefKind;
 use rustc_macros::{Decodable, Encodable};
+use rustc_session::mir_remarks::MirRemark;
 use rustc_span::Span;
 use rustc_span::def_id::DefId;
 
@@ -147,6 +148,9 @@ pub enum QuerySideEffect {
     /// the query as green, as that query will have the side
     /// effect dep node as a dependency.
     Diagnostic(DiagInner),
+    /// Stores a remark of a MIR pass that is written to `-Z remark-dir` at the end of the
+    /// session. It will be recorded again if we mark the query as green.
+    MirRemark(MirRemark),
 }
 
 pub trait QueryContext: HasDepContext {
@@ -174,6 +178,15 @@ pub trait QueryContext: HasDepContext {
     /// Register a side effect for the given node, for use in next session.
     fn store_side_effect(self, dep_node_index: DepNodeIndex, side_effect: QuerySideEffect);
 
+    /// Replay a side effect loaded from the previous session for `prev_dep_node_index`, and
+    /// register it for `dep_node_index`, for use in next session.
+    fn replay_side_effect(
+        self,
+        prev_dep_node_index: SerializedDepNodeIndex,
+        dep_node_index: DepNodeIndex,
+        side_effect: QuerySideEffect,
+    );
+
     /// Executes a job by changing the `ImplicitCtxt` to point to the
     /// new query job while it executes.
     fn start_query<R>(self, token: QueryJobId, depth_limit: bool, compute: impl FnOnce() -> R)
@@ -17,6 +17,7 @@ use std::{cmp, fmt, fs, iter};
 use externs::{ExternOpt, split_extern_opt};
 use rustc_data_structures::fx::{FxHashSet, FxIndexMap};
 use rustc_data_structures::stable_hasher::{StableHasher, StableOrd, ToStableHashKey};
+use rustc_errors::baseline::BaselineMode;
 use rustc_errors::emitter::HumanReadableErrorType;
 use rustc_errors::{ColorConfig, DiagArgValue, DiagCtxtFlags, IntoDiagArg};
 use rustc_feature::UnstableFeatures;
@@ -64,6 +65,8 @@ pub const PRINT_KINDS: &[(&str, PrintKind)] = &[
     ("relocation-models", PrintKind::RelocationModels),
     ("split-debuginfo", PrintKind::SplitDebuginfo),
     ("stack-protector-strategies", PrintKind::StackProtectorStrategies),
+    ("stack-sizes", PrintKind::StackSizes),
+    ("stack-sizes-json", PrintKind::StackSizesJson),
     ("supported-crate-types", PrintKind::SupportedCrateTypes),
     ("sysroot", PrintKind::Sysroot),
     ("target-cpus", PrintKind::TargetCPUs),
@@ -214,9 +217,15 @@ pub enum CoverageLevel {
     /// ```
     ///
     /// This level is mainly intended to be a stepping-stone towards full MC/DC
-    /// instrumentation, so it might be removed in the future when MC/DC is
-    /// sufficiently complete, or if it is making MC/DC changes difficult.
+    /// instrumentation, so it might be removed in the future if it is making
+    /// MC/DC changes difficult.
     Condition,
+    /// Instrument for MC/DC (modified condition/decision coverage). Mostly a
+    /// superset of condition coverage, and additionally records which
+    /// combinations of conditions were observed for each decision, so that
+    /// `llvm-cov` can report which conditions were shown to independently
+    /// affect the outcome of their decision.
+    Mcdc,
 }
 
 // The different settings that the `-Z offload` flag can have.
@@ -849,6 +858,8 @@ pub enum ErrorOutputType {
         json_rendered: HumanReadableErrorType,
         color_config: ColorConfig,
     },
+    /// A single SARIF 2.1.0 log for the whole session, consumed by code-scanning tools.
+    Sarif,
 }
 
 #[derive(Clone, Hash, Debug)]
@@ -1040,6 +1051,8 @@ pub enum PrintKind {
     RelocationModels,
     SplitDebuginfo,
     StackProtectorStrategies,
+    StackSizes,
+    StackSizesJson,
     SupportedCrateTypes,
     Sysroot,
     TargetCPUs,
@@ -1480,10 +1493,12 @@ impl Default for Options {
             json_timings: false,
             json_unused_externs: JsonUnusedExterns::No,
             json_future_incompat: false,
+            json_cause_tree: false,
             pretty: None,
             working_dir: RealFileName::LocalPath(std::env::current_dir().unwrap()),
             color: ColorConfig::Auto,
             logical_env: FxIndexMap::default(),
+            diagnostic_baseline: None,
             verbose: false,
             target_modifiers: BTreeMap::default(),
         }
@@ -1527,6 +1542,14 @@ impl Options {
     pub fn autodiff_enabled(&self) -> bool {
         self.unstable_opts.autodiff.contains(&AutoDiff::Enable)
     }
+
+    /// Whether `--print stack-sizes` or `--print stack-sizes-json` was requested, which needs
+    /// the backend to emit the stack sizes, and the call graph of the crate.
+    pub fn prints_stack_sizes(&self) -> bool {
+        self.prints
+            .iter()
+            .any(|print| matches!(print.kind, PrintKind::StackSizes | PrintKind::StackSizesJson))
+    }
 }
 
 impl UnstableOptions {
@@ -1601,13 +1624,20 @@ pub enum Passes {
 }
 
 impl Passes {
-    fn is_empty(&self) -> bool {
+    pub fn is_empty(&self) -> bool {
         match *self {
             Passes::Some(ref v) => v.is_empty(),
             Passes::All => false,
         }
     }
 
+    pub fn contains(&self, pass: &str) -> bool {
+        match *self {
+            Passes::Some(ref v) => v.iter().any(|p| p == pass),
+            Passes::All => true,
+        }
+    }
+
     pub(crate) fn extend(&mut self, passes: impl IntoIterator<Item = String>) {
         match *self {
             Passes::Some(ref mut v) => v.extend(passes),
@@ -1868,7 +1898,7 @@ pub fn rustc_optgroups() -> Vec<RustcOptGroup> {
             Opt,
             "",
             "explain",
-            "Provide a detailed explanation of an error message",
+            "Provide a detailed explanation of an error or lint",
             "<OPT>",
         ),
         opt(Stable, Flag, "", "test", "Build a test harness", ""),
@@ -1941,6 +1971,22 @@ pub fn rustc_optgroups() -> Vec<RustcOptGroup> {
             "<FROM>=<TO>",
         ),
         opt(Unstable, Multi, "", "env-set", "Inject an environment variable", "<VAR>=<VALUE>"),
+        opt(
+            Unstable,
+            Opt,
+            "",
+            "search",
+            "Search the explanations of all errors and lints for a term",
+            "<TERM>",
+        ),
+        opt(
+            Unstable,
+            Opt,
+            "",
+            "diagnostic-baseline",
+            "Suppress the lints recorded in a baseline file, or record them with `record=`",
+            "[record=]<FILE>",
+        ),
     ];
     options.extend(verbose_only.into_iter().map(|mut opt| {
         opt.is_verbose_help_only = true;
@@ -2008,6 +2054,8 @@ pub struct JsonConfig {
     json_timings: bool,
     pub json_unused_externs: JsonUnusedExterns,
     json_future_incompat: bool,
+    /// Attach the obligation cause tree of trait errors to JSON diagnostics.
+    json_cause_tree: bool,
 }
 
 /// Report unused externs in event stream
@@ -2048,6 +2096,7 @@ pub fn parse_json(early_dcx: &EarlyDiagCtxt, matches: &getopts::Matches) -> Json
     let mut json_unused_externs = JsonUnusedExterns::No;
     let mut json_future_incompat = false;
     let mut json_timings = false;
+    let mut json_cause_tree = false;
     for option in matches.opt_strs("json") {
         // For now conservatively forbid `--color` with `--json` since `--json`
         // won't actually be emitting any colors and anything colorized is
@@ -2068,6 +2117,7 @@ pub fn parse_json(early_dcx: &EarlyDiagCtxt, matches: &getopts::Matches) -> Json
                 "unused-externs" => json_unused_externs = JsonUnusedExterns::Loud,
                 "unused-externs-silent" => json_unused_externs = JsonUnusedExterns::Silent,
                 "future-incompat" => json_future_incompat = true,
+                "cause-tree" => json_cause_tree = true,
                 s => early_dcx.early_fatal(format!("unknown `--json` option `{s}`")),
             }
         }
@@ -2080,6 +2130,7 @@ pub fn parse_json(early_dcx: &EarlyDiagCtxt, matches: &getopts::Matches) -> Json
         json_timings,
         json_unused_externs,
         json_future_incompat,
+        json_cause_tree,
     }
 }
 
@@ -2115,11 +2166,13 @@ pub fn parse_error_format(
                 kind: HumanReadableErrorType::Unicode,
                 color_config,
             },
+            Some("sarif") => ErrorOutputType::Sarif,
             Some(arg) => {
                 early_dcx.set_error_format(ErrorOutputType::HumanReadable { color_config, .. });
                 early_dcx.early_fatal(format!(
                     "argument for `--error-format` must be `human`, `human-annotate-rs`, \
-                    `human-unicode`, `json`, `pretty-json` or `short` (instead was `{arg}`)"
+                    `human-unicode`, `json`, `pretty-json`, `sarif` or `short` \
+                    (instead was `{arg}`)"
                 ))
             }
         }
@@ -2179,6 +2232,7 @@ fn check_error_format_stability(
     }
     let format = match format {
         ErrorOutputType::Json { pretty: true, .. } => "pretty-json",
+        ErrorOutputType::Sarif => "sarif",
         ErrorOutputType::HumanReadable { kind, .. } => match kind {
             HumanReadableErrorType::AnnotateSnippet => "human-annotate-rs",
             HumanReadableErrorType::Unicode => "human-unicode",
@@ -2343,6 +2397,8 @@ fn is_print_request_stable(print_kind: PrintKind) -> bool {
         PrintKind::AllTargetSpecsJson
         | PrintKind::CheckCfg
         | PrintKind::CrateRootLintLevels
+        | PrintKind::StackSizes
+        | PrintKind::StackSizesJson
         | PrintKind::SupportedCrateTypes
         | PrintKind::TargetSpecJson
         | PrintKind::TargetSpecJsonSchema => false,
@@ -2609,6 +2665,7 @@ pub fn build_session_options(early_dcx: &mut EarlyDiagCtxt, matches: &getopts::M
         json_timings,
         json_unused_externs,
         json_future_incompat,
+        json_cause_tree,
     } = parse_json(early_dcx, matches);
 
     let error_format = parse_error_format(early_dcx, matches, color, json_color, json_rendered);
@@ -2632,6 +2689,11 @@ pub fn build_session_options(early_dcx: &mut EarlyDiagCtxt, matches: &getopts::M
         early_dcx.early_fatal("--json=timings is unstable and requires using `-Zunstable-options`");
     }
 
+    if !unstable_opts.unstable_options && json_cause_tree {
+        early_dcx
+            .early_fatal("--json=cause-tree is unstable and requires using `-Zunstable-options`");
+    }
+
     check_error_format_stability(early_dcx, &unstable_opts, error_format);
 
     let output_types = parse_output_types(early_dcx, &unstable_opts, matches);
@@ -2830,7 +2892,10 @@ pub fn build_session_options(early_dcx: &mut EarlyDiagCtxt, matches: &getopts::M
         early_dcx.early_warn("-C remark requires \"-C debuginfo=n\" to show source locations");
     }
 
-    if cg.remark.is_empty() && unstable_opts.remark_dir.is_some() {
+    if cg.remark.is_empty()
+        && unstable_opts.mir_remarks.is_empty()
+        && unstable_opts.remark_dir.is_some()
+    {
         early_dcx
             .early_warn("using -Z remark-dir without enabling remarks using e.g. -C remark=all");
     }
@@ -2848,6 +2913,13 @@ pub fn build_session_options(early_dcx: &mut EarlyDiagCtxt, matches: &getopts::M
 
     let logical_env = parse_logical_env(early_dcx, matches);
 
+    let diagnostic_baseline = matches.opt_str("diagnostic-baseline").map(|arg| {
+        match arg.strip_prefix("record=") {
+            Some(path) => (BaselineMode::Record, PathBuf::from(path)),
+            None => (BaselineMode::Enforce, PathBuf::from(arg)),
+        }
+    });
+
     let sysroot = Sysroot::new(matches.opt_str("sysroot").map(PathBuf::from));
 
     let real_source_base_dir = |suffix: &str, confirm: &str| {
@@ -2945,10 +3017,12 @@ pub fn build_session_options(early_dcx: &mut EarlyDiagCtxt, matches: &getopts::M
         json_timings,
         json_unused_externs,
         json_future_incompat,
+        json_cause_tree,
         pretty,
         working_dir,
         color,
         logical_env,
+        diagnostic_baseline,
         verbose,
         target_modifiers,
     }
@@ -3344,6 +3418,7 @@ pub(crate) mod dep_tracking {
         OomStrategy,
         LanguageIdentifier,
         NextSolverConfig,
+        Passes,
         PatchableFunctionEntry,
         Polonius,
         InliningThreshold,
@@ -3522,6 +3597,30 @@ impl DumpMonoStatsFormat {
     }
 }
 
+/// Which format to use for `-Z dump-partitioning`
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum DumpPartitioningFormat {
+    /// Emit structured JSON
+    Json,
+}
+
+impl DumpPartitioningFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+        }
+    }
+}
+
+/// An output of `-Z emit`, for the outputs that are not available with `--emit`
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum UnstableOutputType {
+    /// The snapshot of the crate as seen by `rustc_public`, as JSON
+    PublicMir,
+    /// The snapshot of the crate as seen by `rustc_public`, in a compact binary encoding
+    PublicMirBinary,
+}
+
 /// `-Z patchable-function-entry` representation - how many nops to put before and after function
 /// entry.
 #[derive(Clone, Copy, PartialEq, Hash, Debug, Default)]
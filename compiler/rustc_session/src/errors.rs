@@ -102,6 +102,13 @@ pub(crate) struct ProfileSampleUseFileDoesNotExist<'a> {
     pub(crate) path: &'a std::path::Path,
 }
 
+#[derive(Diagnostic)]
+#[diag(session_mir_profile_use_invalid)]
+pub(crate) struct MirProfileUseInvalid<'a> {
+    pub(crate) path: &'a std::path::Path,
+    pub(crate) err: String,
+}
+
 #[derive(Diagnostic)]
 #[diag(session_target_requires_unwind_tables)]
 pub(crate) struct TargetRequiresUnwindTables;
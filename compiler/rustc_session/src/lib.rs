@@ -20,6 +20,8 @@ pub mod code_stats;
 pub mod config;
 pub mod cstore;
 pub mod filesearch;
+pub mod mir_profile;
+pub mod mir_remarks;
 mod options;
 pub mod search_paths;
 
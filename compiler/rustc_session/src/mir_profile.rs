@@ -0,0 +1,107 @@
+//! Function entry counts used by MIR optimizations, read from `-Z mir-profile-use`.
+//!
+//! `-C profile-use` only feeds LLVM, which leaves MIR inlining blind to which functions are hot.
+//! This is a much simpler, rustc-native profile summary: each non-empty line contains the number
+//! of times a function was entered, followed by its path, e.g.
+//!
+//! ```text
+//! # count path
+//! 1500000 my_crate::parser::{impl#0}::next_token
+//! 0 my_crate::report_error
+//! ```
+//!
+//! A path is the crate name followed by the verbose def path of the function, with impls and
+//! closures written as `{impl#N}` and `{closure#N}`. Functions that are not in the profile are
+//! treated as neither hot nor cold.
+
+use std::fs;
+use std::path::Path;
+
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_macros::HashStable_Generic;
+
+/// The percentage of all the counted function entries that is attributed to hot functions, like
+/// LLVM's default hot cutoff.
+const HOT_CUTOFF_PERCENT: u128 = 99;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, HashStable_Generic)]
+pub enum Hotness {
+    /// One of the functions that together account for most of the counted entries.
+    Hot,
+    /// The function was never entered while profiling.
+    Cold,
+    /// The function was entered, but is not hot, or is not in the profile at all.
+    Neutral,
+}
+
+#[derive(Debug)]
+pub struct MirProfile {
+    counts: FxHashMap<String, u64>,
+    /// The names of the crates that the paths of the profile start with.
+    crates: FxHashSet<String>,
+    /// Functions entered at least this many times are hot.
+    hot_threshold: u64,
+}
+
+impl MirProfile {
+    pub fn load(path: &Path) -> Result<MirProfile, String> {
+        let contents = fs::read_to_string(path).map_err(|err| err.to_string())?;
+        MirProfile::parse(&contents)
+    }
+
+    fn parse(contents: &str) -> Result<MirProfile, String> {
+        let mut counts = FxHashMap::default();
+        for (index, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let parsed = line
+                .split_once(char::is_whitespace)
+                .and_then(|(count, path)| Some((count.parse::<u64>().ok()?, path.trim())));
+            let Some((count, path)) = parsed else {
+                return Err(format!("line {}: expected `<count> <path>`", index + 1));
+            };
+            // Functions that are inlined or duplicated may show up several times.
+            let total: &mut u64 = counts.entry(path.to_owned()).or_default();
+            *total = total.saturating_add(count);
+        }
+
+        let crates = counts
+            .keys()
+            .map(|path| path.split_once("::").map_or(&path[..], |(krate, _)| krate).to_owned())
+            .collect();
+        Ok(MirProfile { hot_threshold: hot_threshold(&counts), counts, crates })
+    }
+
+    /// Whether the profile has counts for functions of the crate named `crate_name`.
+    pub fn mentions_crate(&self, crate_name: &str) -> bool {
+        self.crates.contains(crate_name)
+    }
+
+    pub fn hotness(&self, path: &str) -> Hotness {
+        match self.counts.get(path) {
+            Some(0) => Hotness::Cold,
+            Some(&count) if count >= self.hot_threshold => Hotness::Hot,
+            _ => Hotness::Neutral,
+        }
+    }
+}
+
+/// Returns the smallest count among the most entered functions that together account for
+/// `HOT_CUTOFF_PERCENT` of all the entries.
+fn hot_threshold(counts: &FxHashMap<String, u64>) -> u64 {
+    let mut sorted: Vec<u64> = counts.values().copied().filter(|&count| count > 0).collect();
+    sorted.sort_unstable_by(|a, b| b.cmp(a));
+
+    let total: u128 = sorted.iter().map(|&count| count as u128).sum();
+    let mut covered = 0;
+    for count in sorted {
+        covered += count as u128;
+        if covered * 100 >= total * HOT_CUTOFF_PERCENT {
+            return count;
+        }
+    }
+    // Nothing was entered, so nothing is hot.
+    u64::MAX
+}
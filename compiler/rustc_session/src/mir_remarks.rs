@@ -0,0 +1,104 @@
+//! Optimization remarks emitted by MIR passes, enabled with `-Z mir-remarks`.
+//!
+//! Without `-Z remark-dir`, remarks are reported as diagnostics as soon as they are emitted.
+//! Otherwise they are recorded here, and written as YAML once all MIR has been optimized, in the
+//! same format as the remarks LLVM writes to that directory. Like diagnostics, the remarks are
+//! side effects of the query that emitted them, so incremental compilation records them again
+//! when it reuses the result of that query.
+
+use std::fmt::Write as _;
+use std::path::Path;
+use std::{fs, io};
+
+use rustc_data_structures::sync::Lock;
+use rustc_macros::{Decodable, Encodable};
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Encodable, Decodable)]
+pub enum RemarkKind {
+    /// The optimization was applied.
+    Passed,
+    /// The optimization was considered, but not applied.
+    Missed,
+    /// Information that explains why an optimization was or was not applied.
+    Analysis,
+}
+
+impl RemarkKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RemarkKind::Passed => "passed",
+            RemarkKind::Missed => "missed",
+            RemarkKind::Analysis => "analysis",
+        }
+    }
+
+    fn yaml_tag(self) -> &'static str {
+        match self {
+            RemarkKind::Passed => "!Passed",
+            RemarkKind::Missed => "!Missed",
+            RemarkKind::Analysis => "!Analysis",
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Encodable, Decodable)]
+pub struct RemarkLocation {
+    pub file: String,
+    pub line: usize,
+    /// 1-based, like `line`.
+    pub column: usize,
+}
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Encodable, Decodable)]
+pub struct MirRemark {
+    /// The path of the body that the pass was running on.
+    pub function: String,
+    pub location: Option<RemarkLocation>,
+    /// The name of the pass, as accepted by `-Z mir-enable-passes`.
+    pub pass: String,
+    pub kind: RemarkKind,
+    pub message: String,
+}
+
+#[derive(Default)]
+pub struct MirRemarks {
+    remarks: Lock<Vec<MirRemark>>,
+}
+
+impl MirRemarks {
+    pub fn record(&self, remark: MirRemark) {
+        self.remarks.borrow_mut().push(remark);
+    }
+
+    /// Writes all the recorded remarks to `path`. Bodies are optimized in an unspecified order, so
+    /// the remarks are sorted by function and location to keep the output stable.
+    pub fn write_yaml(&self, path: &Path) -> io::Result<()> {
+        let mut remarks = self.remarks.borrow().clone();
+        remarks.sort();
+
+        let mut out = String::new();
+        for remark in &remarks {
+            let MirRemark { function, location, pass, kind, message } = remark;
+            writeln!(out, "--- {}", kind.yaml_tag()).unwrap();
+            writeln!(out, "Pass:            {pass}").unwrap();
+            if let Some(RemarkLocation { file, line, column }) = location {
+                writeln!(
+                    out,
+                    "DebugLoc:        {{ File: {}, Line: {line}, Column: {column} }}",
+                    yaml_string(file)
+                )
+                .unwrap();
+            }
+            writeln!(out, "Function:        {}", yaml_string(function)).unwrap();
+            writeln!(out, "Args:").unwrap();
+            writeln!(out, "  - String:          {}", yaml_string(message)).unwrap();
+            writeln!(out, "...").unwrap();
+        }
+        fs::write(path, out)
+    }
+}
+
+/// Quotes `s` as a single-quoted YAML scalar.
+fn yaml_string(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''").replace('\n', " "))
+}
@@ -7,6 +7,8 @@ use rustc_abi::Align;
 use rustc_data_structures::fx::FxIndexMap;
 use rustc_data_structures::profiling::TimePassesFormat;
 use rustc_data_structures::stable_hasher::StableHasher;
+use rustc_errors::baseline::BaselineMode;
+use rustc_errors::fix::FixMode;
 use rustc_errors::{ColorConfig, LanguageIdentifier, TerminalUrl};
 use rustc_feature::UnstableFeatures;
 use rustc_hashes::Hash64;
@@ -411,6 +413,9 @@ top_level_options!(
         /// Effective logical environment used by `env!`/`option_env!` macros
         logical_env: FxIndexMap<String, String> [TRACKED],
 
+        /// `--diagnostic-baseline`: the baseline file of lints to record or to suppress.
+        diagnostic_baseline: Option<(BaselineMode, PathBuf)> [UNTRACKED],
+
         test: bool [TRACKED],
         error_format: ErrorOutputType [UNTRACKED],
         diagnostic_width: Option<usize> [UNTRACKED],
@@ -489,6 +494,9 @@ top_level_options!(
         /// `true` if we're emitting a JSON job containing a future-incompat report for lints
         json_future_incompat: bool [TRACKED],
 
+        /// `true` if trait errors carry their obligation cause tree in JSON diagnostics
+        json_cause_tree: bool [TRACKED],
+
         pretty: Option<PpMode> [UNTRACKED],
 
         /// The (potentially remapped) working directory
@@ -821,8 +829,11 @@ mod desc {
     pub(crate) const parse_strip: &str = "either `none`, `debuginfo`, or `symbols`";
     pub(crate) const parse_linker_flavor: &str = ::rustc_target::spec::LinkerFlavorCli::one_of();
     pub(crate) const parse_dump_mono_stats: &str = "`markdown` (default) or `json`";
+    pub(crate) const parse_dump_partitioning: &str = "`json`";
+    pub(crate) const parse_unstable_output_types: &str =
+        "a comma-separated list of outputs: `public-mir` or `public-mir-binary`";
     pub(crate) const parse_instrument_coverage: &str = parse_bool;
-    pub(crate) const parse_coverage_options: &str = "`block` | `branch` | `condition`";
+    pub(crate) const parse_coverage_options: &str = "`block` | `branch` | `condition` | `mcdc`";
     pub(crate) const parse_instrument_xray: &str = "either a boolean (`yes`, `no`, `on`, `off`, etc), or a comma separated list of settings: `always` or `never` (mutually exclusive), `ignore-loops`, `instruction-threshold=N`, `skip-entry`, `skip-exit`";
     pub(crate) const parse_unpretty: &str = "`string` or `string=string`";
     pub(crate) const parse_treat_err_as_bug: &str = "either no value or a non-negative number";
@@ -834,6 +845,7 @@ mod desc {
         "either a boolean (`yes`, `no`, `on`, `off`, etc), or the path to the linker plugin";
     pub(crate) const parse_location_detail: &str = "either `none`, or a comma separated list of location details to track: `file`, `line`, or `column`";
     pub(crate) const parse_fmt_debug: &str = "either `full`, `shallow`, or `none`";
+    pub(crate) const parse_fix_mode: &str = "either no value, `apply`, `dry-run` or `recheck`";
     pub(crate) const parse_switch_with_opt_path: &str =
         "an optional path to the profiling data output directory";
     pub(crate) const parse_merge_functions: &str =
@@ -1039,6 +1051,16 @@ pub mod parse {
         true
     }
 
+    pub(crate) fn parse_fix_mode(slot: &mut Option<FixMode>, v: Option<&str>) -> bool {
+        *slot = match v {
+            None | Some("apply") => Some(FixMode::Apply),
+            Some("dry-run") => Some(FixMode::DryRun),
+            Some("recheck") => Some(FixMode::Recheck),
+            _ => return false,
+        };
+        true
+    }
+
     pub(crate) fn parse_location_detail(ld: &mut LocationDetail, v: Option<&str>) -> bool {
         if let Some(v) = v {
             ld.line = false;
@@ -1426,6 +1448,37 @@ pub mod parse {
         }
     }
 
+    pub(crate) fn parse_dump_partitioning(
+        slot: &mut Option<DumpPartitioningFormat>,
+        v: Option<&str>,
+    ) -> bool {
+        match v {
+            Some("json") => {
+                *slot = Some(DumpPartitioningFormat::Json);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub(crate) fn parse_unstable_output_types(
+        slot: &mut Vec<UnstableOutputType>,
+        v: Option<&str>,
+    ) -> bool {
+        let Some(v) = v else { return false };
+        for output in v.split(',') {
+            let output = match output {
+                "public-mir" => UnstableOutputType::PublicMir,
+                "public-mir-binary" => UnstableOutputType::PublicMirBinary,
+                _ => return false,
+            };
+            if !slot.contains(&output) {
+                slot.push(output);
+            }
+        }
+        true
+    }
+
     pub(crate) fn parse_offload(slot: &mut Vec<Offload>, v: Option<&str>) -> bool {
         let Some(v) = v else {
             *slot = vec![];
@@ -1528,6 +1581,7 @@ pub mod parse {
                 "block" => slot.level = CoverageLevel::Block,
                 "branch" => slot.level = CoverageLevel::Branch,
                 "condition" => slot.level = CoverageLevel::Condition,
+                "mcdc" => slot.level = CoverageLevel::Mcdc,
                 "discard-all-spans-in-codegen" => slot.discard_all_spans_in_codegen = true,
                 _ => return false,
             }
@@ -2236,6 +2290,9 @@ options! {
         "hash algorithm of source files used to check freshness in cargo (`blake3` or `sha256`)"),
     codegen_backend: Option<String> = (None, parse_opt_string, [TRACKED],
         "the backend to use"),
+    codegen_cache_dir: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "reuse the object files of codegen units from a directory shared between compilations, \
+        keyed by the contents of the codegen units"),
     codegen_source_order: bool = (false, parse_bool, [UNTRACKED],
         "emit mono items in the order of spans in source files (default: no)"),
     contract_checks: Option<bool> = (None, parse_opt_bool, [TRACKED],
@@ -2289,6 +2346,9 @@ options! {
         "output statistics about monomorphization collection"),
     dump_mono_stats_format: DumpMonoStatsFormat = (DumpMonoStatsFormat::Markdown, parse_dump_mono_stats, [UNTRACKED],
         "the format to use for -Z dump-mono-stats (`markdown` (default) or `json`)"),
+    dump_partitioning: Option<DumpPartitioningFormat> = (None, parse_dump_partitioning, [UNTRACKED],
+        "write a report of the codegen units of the crate, and of why each mono item was placed \
+        in them (`json`)"),
     #[rustc_lint_opt_deny_field_access("use `Session::dwarf_version` instead of this field")]
     dwarf_version: Option<u32> = (None, parse_opt_number, [TRACKED],
         "version of DWARF debug information to emit (default: 2 or 4, depending on platform)"),
@@ -2303,6 +2363,10 @@ options! {
         "embed metadata in rlibs and dylibs (default: yes)"),
     embed_source: bool = (false, parse_bool, [TRACKED],
         "embed source text in DWARF debug sections (default: no)"),
+    emit: Vec<UnstableOutputType> = (Vec::new(), parse_unstable_output_types, [UNTRACKED],
+        "additional outputs that are not available with `--emit`: `public-mir` and \
+        `public-mir-binary` write a snapshot of the MIR, types and items of the crate as seen by \
+        rustc_public, for analysis after the compilation"),
     emit_stack_sizes: bool = (false, parse_bool, [UNTRACKED],
         "emit a section containing stack size metadata (default: no)"),
     emit_thin_lto: bool = (true, parse_bool, [TRACKED],
@@ -2323,6 +2387,10 @@ options! {
     fewer_names: Option<bool> = (None, parse_opt_bool, [TRACKED],
         "reduce memory use by retaining fewer names within compilation artifacts (LLVM-IR) \
         (default: no)"),
+    fix: Option<FixMode> = (None, parse_fix_mode, [UNTRACKED],
+        "apply machine-applicable suggestions to the source files once compilation is done, \
+        and compile them again with `-Zfix=recheck`, or only print them as a unified diff with \
+        `-Zfix=dry-run`"),
     fixed_x18: bool = (false, parse_bool, [TRACKED TARGET_MODIFIER],
         "make the x18 register reserved on AArch64 (default: no)"),
     flatten_format_args: bool = (true, parse_bool, [TRACKED],
@@ -2376,6 +2444,8 @@ options! {
         "inlining threshold when the caller is a simple forwarding function (default: 30)"),
     inline_mir_hint_threshold: Option<usize> = (None, parse_opt_number, [TRACKED],
         "inlining threshold for functions with inline hint (default: 100)"),
+    inline_mir_hot_threshold: Option<usize> = (None, parse_opt_number, [TRACKED],
+        "inlining threshold for call sites that are hot in `-Z mir-profile-use` (default: 200)"),
     inline_mir_preserve_debug: Option<bool> = (None, parse_opt_bool, [TRACKED],
         "when MIR inlining, whether to preserve debug info for callee variables \
         (default: preserve for debuginfo != None, otherwise remove)"),
@@ -2407,6 +2477,10 @@ options! {
         "lint LLVM IR (default: no)"),
     lint_mir: bool = (false, parse_bool, [UNTRACKED],
         "lint MIR before and after each transformation"),
+    lint_stats: bool = (false, parse_bool, [UNTRACKED],
+        "print the time spent in each lint pass and the number of lints it emitted (default: no)"),
+    lint_stats_format: DumpMonoStatsFormat = (DumpMonoStatsFormat::Markdown, parse_dump_mono_stats, [UNTRACKED],
+        "the format to use for -Z lint-stats (`markdown` (default) or `json`)"),
     llvm_module_flag: Vec<(String, u32, String)> = (Vec::new(), parse_llvm_module_flag, [TRACKED],
         "a list of module flags to pass to LLVM (space separated)"),
     llvm_plugins: Vec<String> = (Vec::new(), parse_list, [TRACKED],
@@ -2453,6 +2527,10 @@ options! {
     mir_preserve_ub: bool = (false, parse_bool, [TRACKED],
         "keep place mention statements and reads in trivial SwitchInt terminators, which are interpreted \
         e.g., by miri; implies -Zmir-opt-level=0 (default: no)"),
+    mir_profile_use: Option<PathBuf> = (None, parse_opt_pathbuf, [TRACKED],
+        "use the function entry counts in the given file to guide MIR inlining"),
+    mir_remarks: Passes = (Passes::Some(Vec::new()), parse_passes, [TRACKED],
+        "output remarks for these MIR optimization passes (space separated, or \"all\")"),
     mir_strip_debuginfo: MirStripDebugInfo = (MirStripDebugInfo::None, parse_mir_strip_debuginfo, [TRACKED],
         "Whether to remove some of the MIR debug info from methods.  Default: None"),
     move_size_limit: Option<usize> = (None, parse_opt_number, [TRACKED],
@@ -2567,7 +2645,7 @@ options! {
         "remap paths under the current working directory to this path prefix"),
     remap_path_scope: RemapPathScopeComponents = (RemapPathScopeComponents::all(), parse_remap_path_scope, [TRACKED],
         "remap path scope (default: all)"),
-    remark_dir: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+    remark_dir: Option<PathBuf> = (None, parse_opt_pathbuf, [TRACKED],
         "directory into which to write optimization remarks (if not specified, they will be \
 written to standard error output)"),
     retpoline: bool = (false, parse_bool, [TRACKED TARGET_MODIFIER],
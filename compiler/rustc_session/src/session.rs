@@ -13,11 +13,14 @@ use rustc_data_structures::fx::{FxHashMap, FxIndexSet};
 use rustc_data_structures::profiling::{SelfProfiler, SelfProfilerRef};
 use rustc_data_structures::sync::{DynSend, DynSync, Lock, MappedReadGuard, ReadGuard, RwLock};
 use rustc_errors::annotate_snippet_emitter_writer::AnnotateSnippetEmitter;
+use rustc_errors::baseline::DiagnosticBaseline;
 use rustc_errors::codes::*;
 use rustc_errors::emitter::{
     DynEmitter, HumanEmitter, HumanReadableErrorType, OutputTheme, stderr_destination,
 };
+use rustc_errors::fix::FixEmitter;
 use rustc_errors::json::JsonEmitter;
+use rustc_errors::sarif::SarifEmitter;
 use rustc_errors::timings::TimingSectionHandler;
 use rustc_errors::translation::Translator;
 use rustc_errors::{
@@ -46,6 +49,8 @@ use crate::config::{
 };
 use crate::filesearch::FileSearch;
 use crate::lint::LintId;
+use crate::mir_profile::MirProfile;
+use crate::mir_remarks::MirRemarks;
 use crate::parse::{ParseSess, add_feature_diagnostics};
 use crate::search_paths::SearchPath;
 use crate::{errors, filesearch, lint};
@@ -110,6 +115,12 @@ pub struct Session {
     /// Data about code being compiled, gathered during compilation.
     pub code_stats: CodeStats,
 
+    /// Remarks emitted by MIR passes, when they are written to `-Z remark-dir`.
+    pub mir_remarks: MirRemarks,
+
+    /// The profile given with `-Z mir-profile-use`, which is read when the session is created.
+    mir_profile: Option<MirProfile>,
+
     /// This only ever stores a `LintStore` but we don't want a dependency on that type here.
     pub lint_store: Option<Arc<dyn DynLintStore>>,
 
@@ -249,6 +260,7 @@ impl Session {
         let mut guar = None;
         guar = guar.or(self.check_miri_unleashed_features());
         guar = guar.or(self.dcx().emit_stashed_diagnostics());
+        self.dcx().finish_diagnostic_baseline();
         self.dcx().print_error_count();
         if self.opts.json_future_incompat {
             self.dcx().emit_future_breakage_report();
@@ -321,6 +333,11 @@ impl Session {
             && self.opts.unstable_opts.coverage_options.level >= CoverageLevel::Condition
     }
 
+    pub fn instrument_coverage_mcdc(&self) -> bool {
+        self.instrument_coverage()
+            && self.opts.unstable_opts.coverage_options.level >= CoverageLevel::Mcdc
+    }
+
     /// Provides direct access to the `CoverageOptions` struct, so that
     /// individual flags for debugging/testing coverage instrumetation don't
     /// need separate accessors.
@@ -598,6 +615,11 @@ impl Session {
             .unwrap_or_else(|| if self.opts.optimize != OptLevel::No { 2 } else { 1 })
     }
 
+    /// The function entry counts given with `-Z mir-profile-use`, if they could be read.
+    pub fn mir_profile(&self) -> Option<&MirProfile> {
+        self.mir_profile.as_ref()
+    }
+
     /// Calculates the flavor of LTO to use for this compilation.
     pub fn lto(&self) -> config::Lto {
         // Autodiff currently requires fat-lto to have access to the llvm-ir of all (indirectly) used functions and types.
@@ -954,7 +976,7 @@ fn default_emitter(
 
     let source_map = if sopts.unstable_opts.link_only { None } else { Some(source_map) };
 
-    match sopts.error_format {
+    let emitter: Box<DynEmitter> = match sopts.error_format {
         config::ErrorOutputType::HumanReadable { kind, color_config } => {
             let short = kind.short();
 
@@ -999,6 +1021,16 @@ fn default_emitter(
             .track_diagnostics(track_diagnostics)
             .terminal_url(terminal_url),
         ),
+        config::ErrorOutputType::Sarif => Box::new(SarifEmitter::new(
+            Box::new(io::BufWriter::new(io::stderr())),
+            source_map,
+            translator,
+        )),
+    };
+
+    match sopts.unstable_opts.fix {
+        Some(mode) => Box::new(FixEmitter::new(emitter, mode)),
+        None => emitter,
     }
 }
 
@@ -1045,6 +1077,15 @@ pub fn build_session(
     if let Some(ice_file) = ice_file {
         dcx = dcx.with_ice_file(ice_file);
     }
+    if let Some((mode, path)) = &sopts.diagnostic_baseline {
+        match DiagnosticBaseline::new(*mode, path.clone()) {
+            Ok(baseline) => dcx = dcx.with_diagnostic_baseline(baseline),
+            Err(e) => dcx.handle().fatal(format!(
+                "failed to read the diagnostic baseline `{}`: {e}",
+                path.display()
+            )),
+        }
+    }
 
     let host_triple = TargetTuple::from_tuple(config::host_tuple());
     let (host, target_warnings) = Target::search(&host_triple, sopts.sysroot.path())
@@ -1077,6 +1118,14 @@ pub fn build_session(
     let mut psess = ParseSess::with_dcx(dcx, source_map);
     psess.assume_incomplete_release = sopts.unstable_opts.assume_incomplete_release;
 
+    // The profile is read once, so that an invalid file is reported even if no optimization ends
+    // up needing it.
+    let mir_profile = sopts.unstable_opts.mir_profile_use.as_deref().and_then(|path| {
+        MirProfile::load(path)
+            .map_err(|err| psess.dcx().emit_err(errors::MirProfileUseInvalid { path, err }))
+            .ok()
+    });
+
     let host_triple = config::host_tuple();
     let target_triple = sopts.target_triple.tuple();
     // FIXME use host sysroot?
@@ -1124,6 +1173,8 @@ pub fn build_session(
         prof,
         timings,
         code_stats: Default::default(),
+        mir_remarks: Default::default(),
+        mir_profile,
         lint_store: None,
         driver_lint_caps,
         ctfe_backtrace,
@@ -1511,6 +1562,14 @@ fn mk_emitter(output: ErrorOutputType) -> Box<DynEmitter> {
                 color_config,
             ))
         }
+        config::ErrorOutputType::Sarif => Box::new(
+            SarifEmitter::new(
+                Box::new(io::BufWriter::new(io::stderr())),
+                Some(Arc::new(SourceMap::new(FilePathMapping::empty()))),
+                translator,
+            )
+            .write_empty_log(false),
+        ),
     };
     emitter
 }
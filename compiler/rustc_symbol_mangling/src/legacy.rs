@@ -86,6 +86,12 @@ pub(super) fn mangle<'tcx>(
         ty::InstanceKind::VTableShim(..) => {
             p.write_str("{{vtable-shim}}").unwrap();
         }
+        ty::InstanceKind::ColdPathShim(_, index) => {
+            p.write_str(&format!("{{{{cold-path-shim-{index}}}}}")).unwrap();
+        }
+        ty::InstanceKind::ConstSpecialized(_, index) => {
+            p.write_str(&format!("{{{{const-specialization-{index}}}}}")).unwrap();
+        }
         ty::InstanceKind::ReifyShim(_, reason) => {
             p.write_str("{{reify-shim").unwrap();
             match reason {
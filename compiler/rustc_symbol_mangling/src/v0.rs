@@ -61,8 +61,17 @@ pub(super) fn mangle<'tcx>(
             Some("by_ref")
         }
         ty::InstanceKind::FutureDropPollShim(_, _, _) => Some("drop"),
+        ty::InstanceKind::ColdPathShim(..) => Some("cold"),
+        ty::InstanceKind::ConstSpecialized(..) => Some("const"),
         _ => None,
     };
+    // Each cold path or constant clone of a function gets its own disambiguator.
+    let shim_disambiguator = match instance.def {
+        ty::InstanceKind::ColdPathShim(_, index) | ty::InstanceKind::ConstSpecialized(_, index) => {
+            u64::from(index)
+        }
+        _ => 0,
+    };
 
     if let ty::InstanceKind::AsyncDropGlue(_, ty) = instance.def {
         let ty::Coroutine(_, cor_args) = ty.kind() else {
@@ -71,7 +80,8 @@ pub(super) fn mangle<'tcx>(
         let drop_ty = cor_args.first().unwrap().expect_ty();
         p.print_def_path(def_id, tcx.mk_args(&[GenericArg::from(drop_ty)])).unwrap()
     } else if let Some(shim_kind) = shim_kind {
-        p.path_append_ns(|p| p.print_def_path(def_id, args), 'S', 0, shim_kind).unwrap()
+        p.path_append_ns(|p| p.print_def_path(def_id, args), 'S', shim_disambiguator, shim_kind)
+            .unwrap()
     } else {
         p.print_def_path(def_id, args).unwrap()
     };
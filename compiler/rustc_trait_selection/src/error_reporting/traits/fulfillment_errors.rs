@@ -9,8 +9,8 @@ use rustc_data_structures::fx::FxHashMap;
 use rustc_data_structures::unord::UnordSet;
 use rustc_errors::codes::*;
 use rustc_errors::{
-    Applicability, Diag, ErrorGuaranteed, Level, MultiSpan, StashKey, StringPart, Suggestions,
-    pluralize, struct_span_code_err,
+    Applicability, CauseNode, Diag, ErrorGuaranteed, Level, MultiSpan, StashKey, StringPart,
+    Suggestions, pluralize, struct_span_code_err,
 };
 use rustc_hir::def_id::{DefId, LOCAL_CRATE, LocalDefId};
 use rustc_hir::intravisit::Visitor;
@@ -2607,6 +2607,9 @@ impl<'a, 'tcx> TypeErrCtxt<'a, 'tcx> {
         err: &mut Diag<'_>,
         obligation: &PredicateObligation<'tcx>,
     ) {
+        if self.tcx.sess.opts.json_cause_tree {
+            err.cause_tree(self.obligation_cause_tree(obligation));
+        }
         // First, attempt to add note to this error with an async-await-specific
         // message, and fall back to regular note otherwise.
         if !self.maybe_note_obligation_cause_for_async_await(err, obligation) {
@@ -2638,6 +2641,122 @@ impl<'a, 'tcx> TypeErrCtxt<'a, 'tcx> {
         }
     }
 
+    /// Builds the tree of obligations which led to `obligation` being required, for
+    /// `--json=cause-tree`. Each derived obligation, e.g. `Vec<T>: Clone` for `T: Clone`, is a
+    /// node whose children are the reasons it was required, like the bound of a function or the
+    /// obligation it was derived from in turn. The outermost obligation ends up as the deepest
+    /// derived node.
+    fn obligation_cause_tree(&self, obligation: &PredicateObligation<'tcx>) -> CauseNode {
+        let predicate = self.resolve_vars_if_possible(obligation.predicate);
+        let mut root = match predicate.as_trait_clause() {
+            Some(trait_pred) => {
+                let mut node = CauseNode::new(
+                    format!("the trait bound `{trait_pred}` is not satisfied"),
+                    obligation.cause.span,
+                );
+                node.types = self.cause_tree_types(trait_pred.skip_binder().trait_ref);
+                node
+            }
+            None => CauseNode::new(
+                format!("the predicate `{predicate}` is not satisfied"),
+                obligation.cause.span,
+            ),
+        };
+
+        let mut parent = &mut root;
+        let mut code = Some(obligation.cause.code());
+        while let Some(current) = code {
+            if let Some((node, derived)) = self.cause_tree_node(current) {
+                parent.children.push(node);
+                // The codes after a derived one are the causes of the derived obligation.
+                if derived {
+                    parent = parent.children.last_mut().unwrap();
+                }
+            }
+            code = current.parent();
+        }
+        root
+    }
+
+    /// The node of a cause code, and whether it is a derived obligation, which the next codes
+    /// are the children of.
+    fn cause_tree_node(&self, code: &ObligationCauseCode<'tcx>) -> Option<(CauseNode, bool)> {
+        let tcx = self.tcx;
+        let derived = |trait_ref: ty::TraitRef<'tcx>, constness: String, span: Span| {
+            let trait_ref = self.resolve_vars_if_possible(trait_ref);
+            let mut node = CauseNode::new(
+                format!(
+                    "required for `{}` to implement `{constness}{}`",
+                    trait_ref.self_ty(),
+                    trait_ref.print_only_trait_path(),
+                ),
+                span,
+            );
+            node.types = self.cause_tree_types(trait_ref);
+            node
+        };
+        // The obligation was derived from an impl, so the bound of the impl that introduced it
+        // is the first of its causes.
+        let impl_derived = |mut node: CauseNode, bound_span: Span| {
+            if !bound_span.is_dummy() {
+                let bound = CauseNode::new("unsatisfied trait bound introduced here", bound_span);
+                node.children.push(bound);
+            }
+            node
+        };
+        let node = match code {
+            ObligationCauseCode::BuiltinDerived(data)
+            | ObligationCauseCode::WellFormedDerived(data) => {
+                derived(data.parent_trait_pred.skip_binder().trait_ref, String::new(), DUMMY_SP)
+            }
+            ObligationCauseCode::ImplDerived(data) => impl_derived(
+                derived(
+                    data.derived.parent_trait_pred.skip_binder().trait_ref,
+                    String::new(),
+                    tcx.def_span(data.impl_or_alias_def_id),
+                ),
+                data.span,
+            ),
+            ObligationCauseCode::BuiltinDerivedHost(data) => {
+                let pred = data.parent_host_pred.skip_binder();
+                derived(pred.trait_ref, format!("{} ", pred.constness), DUMMY_SP)
+            }
+            ObligationCauseCode::ImplDerivedHost(data) => {
+                let pred = data.derived.parent_host_pred.skip_binder();
+                impl_derived(
+                    derived(
+                        pred.trait_ref,
+                        format!("{} ", pred.constness),
+                        tcx.def_span(data.impl_def_id),
+                    ),
+                    data.span,
+                )
+            }
+            ObligationCauseCode::FunctionArg { arg_hir_id, .. } => {
+                return Some((
+                    CauseNode::new("required by this argument", tcx.hir_span(*arg_hir_id)),
+                    false,
+                ));
+            }
+            ObligationCauseCode::WhereClause(def_id, span)
+            | ObligationCauseCode::WhereClauseInExpr(def_id, span, ..)
+            | ObligationCauseCode::HostEffectInExpr(def_id, span, ..) => {
+                let node = CauseNode::new(
+                    format!("required by a bound in `{}`", tcx.def_path_str(*def_id)),
+                    if span.is_dummy() { tcx.def_span(*def_id) } else { *span },
+                );
+                return Some((node, false));
+            }
+            _ => return None,
+        };
+        Some((node, true))
+    }
+
+    /// The self type of `trait_ref` followed by the rest of its type arguments.
+    fn cause_tree_types(&self, trait_ref: ty::TraitRef<'tcx>) -> Vec<String> {
+        self.resolve_vars_if_possible(trait_ref).args.types().map(|ty| ty.to_string()).collect()
+    }
+
     pub(super) fn is_recursive_obligation(
         &self,
         obligated_types: &mut Vec<Ty<'tcx>>,
@@ -43,6 +43,10 @@ fn fn_sig_for_fn_abi<'tcx>(
         );
     }
 
+    if let InstanceKind::ColdPathShim(..) = instance.def {
+        return tcx.instance_mir(instance.def).cold_path_fn_sig(tcx);
+    }
+
     let ty = instance.ty(tcx, typing_env);
     match *ty.kind() {
         ty::FnDef(def_id, args) => {
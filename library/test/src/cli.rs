@@ -3,6 +3,7 @@
 use std::env;
 use std::io::{self, IsTerminal, Write};
 use std::path::PathBuf;
+use std::time::Duration;
 
 use super::options::{ColorConfig, Options, OutputFormat, RunIgnored};
 use super::time::TestTimeOptions;
@@ -30,6 +31,10 @@ pub struct TestOpts {
     /// May run a few more tests due to threading, but will
     /// abort as soon as possible.
     pub fail_fast: bool,
+    /// Fail the tests which run for longer than this, killing their subprocess.
+    pub test_timeout: Option<Duration>,
+    /// How many times failing tests are run again, to tell flaky tests from failing ones.
+    pub retries: usize,
     pub options: Options,
 }
 
@@ -103,8 +108,11 @@ fn optgroups() -> getopts::Options {
             pretty = Print verbose output;
             terse  = Display one character per test;
             json   = Output a json document;
-            junit  = Output a JUnit document",
-            "pretty|terse|json|junit",
+            junit  = Output a JUnit document;
+            tap    = Output in the Test Anything Protocol, version 14;
+            github = Print verbose output, with GitHub Actions annotations
+                     for the failures",
+            "pretty|terse|json|junit|tap|github",
         )
         .optflag("", "show-output", "Show captured stdout of successful tests")
         .optopt(
@@ -149,6 +157,25 @@ fn optgroups() -> getopts::Options {
             "shuffle-seed",
             "Run tests in random order; seed the random number generator with SEED",
             "SEED",
+        )
+        .optopt(
+            "",
+            "test-timeout",
+            "Fail the tests which run for longer than DURATION, e.g. `90s`,
+            `500ms` or `2m` (seconds by default).
+
+            The tests are run in subprocesses so that they can be killed,
+            unless --force-run-in-process is given. Tests run in-process still
+            fail when they finish after the timeout, but they cannot be
+            interrupted.",
+            "DURATION",
+        )
+        .optopt(
+            "",
+            "retries",
+            "Run failing tests again up to N times. Tests which pass on a
+            retry are reported as flaky.",
+            "N",
         );
     opts
 }
@@ -265,6 +292,8 @@ fn parse_opts_impl(matches: getopts::Matches) -> OptRes {
     let time_options = get_time_options(&matches, allow_unstable)?;
     let shuffle = get_shuffle(&matches, allow_unstable)?;
     let shuffle_seed = get_shuffle_seed(&matches, allow_unstable)?;
+    let test_timeout = get_test_timeout(&matches, allow_unstable)?;
+    let retries = get_retries(&matches, allow_unstable)?;
 
     let include_ignored = matches.opt_present("include-ignored");
     let quiet = matches.opt_present("quiet");
@@ -309,6 +338,8 @@ fn parse_opts_impl(matches: getopts::Matches) -> OptRes {
         time_options,
         options,
         fail_fast,
+        test_timeout,
+        retries,
     };
 
     Ok(test_opts)
@@ -383,6 +414,43 @@ fn get_shuffle_seed(matches: &getopts::Matches, allow_unstable: bool) -> OptPart
     Ok(shuffle_seed)
 }
 
+fn get_test_timeout(
+    matches: &getopts::Matches,
+    allow_unstable: bool,
+) -> OptPartRes<Option<Duration>> {
+    let Some(timeout) = unstable_optopt!(matches, allow_unstable, "test-timeout") else {
+        return Ok(None);
+    };
+    let (number, unit) = match timeout.find(|c: char| !c.is_ascii_digit() && c != '.') {
+        Some(i) => timeout.split_at(i),
+        None => (&*timeout, "s"),
+    };
+    let secs = match (number.parse::<f64>(), unit) {
+        (Ok(n), "ms") => n / 1000.0,
+        (Ok(n), "s") => n,
+        (Ok(n), "m") => n * 60.0,
+        _ => {
+            return Err(format!(
+                "argument for --test-timeout must be a duration such as `90s`, `500ms` or `2m` \
+                 (was {timeout})"
+            ));
+        }
+    };
+    match Duration::try_from_secs_f64(secs) {
+        Ok(duration) if !duration.is_zero() => Ok(Some(duration)),
+        _ => Err(format!("argument for --test-timeout must be positive (was {timeout})")),
+    }
+}
+
+fn get_retries(matches: &getopts::Matches, allow_unstable: bool) -> OptPartRes<usize> {
+    match unstable_optopt!(matches, allow_unstable, "retries") {
+        Some(n_str) => n_str
+            .parse::<usize>()
+            .map_err(|e| format!("argument for --retries must be a number (error: {e})")),
+        None => Ok(0),
+    }
+}
+
 fn get_test_threads(matches: &getopts::Matches) -> OptPartRes<Option<usize>> {
     let test_threads = match matches.opt_str("test-threads") {
         Some(n_str) => match n_str.parse::<usize>() {
@@ -422,9 +490,11 @@ fn get_format(
             }
             OutputFormat::Junit
         }
+        Some("tap") => OutputFormat::Tap,
+        Some("github") => OutputFormat::Github,
         Some(v) => {
             return Err(format!(
-                "argument for --format must be pretty, terse, json or junit (was \
+                "argument for --format must be pretty, terse, json, junit, tap or github (was \
                  {v})"
             ));
         }
@@ -1,5 +1,6 @@
 //! Module providing interface for running tests in the console.
 
+use std::collections::HashMap;
 use std::fs::File;
 use std::io;
 use std::io::prelude::Write;
@@ -9,14 +10,15 @@ use super::bench::fmt_bench_samples;
 use super::cli::TestOpts;
 use super::event::{CompletedTest, TestEvent};
 use super::formatters::{
-    JsonFormatter, JunitFormatter, OutputFormatter, PrettyFormatter, TerseFormatter,
+    GithubFormatter, JsonFormatter, JunitFormatter, OutputFormatter, PrettyFormatter,
+    TapFormatter, TerseFormatter,
 };
 use super::helpers::concurrency::get_concurrency;
 use super::helpers::metrics::MetricMap;
 use super::options::{Options, OutputFormat};
 use super::test_result::TestResult;
 use super::time::{TestExecTime, TestSuiteExecTime};
-use super::types::{NamePadding, TestDesc, TestDescAndFn};
+use super::types::{NamePadding, TestDesc, TestDescAndFn, TestName};
 use super::{filter_tests, run_tests, term};
 
 /// Generic wrapper over stdout.
@@ -88,6 +90,10 @@ pub(crate) struct ConsoleTestState {
     pub not_failures: Vec<(TestDesc, Vec<u8>)>,
     pub ignores: Vec<(TestDesc, Vec<u8>)>,
     pub time_failures: Vec<(TestDesc, Vec<u8>)>,
+    /// How many times each test has been retried so far, with `--retries`.
+    pub retry_counts: HashMap<TestName, usize>,
+    /// The tests which passed after being retried.
+    pub flaky: Vec<TestDesc>,
     pub options: Options,
 }
 
@@ -112,6 +118,8 @@ impl ConsoleTestState {
             not_failures: Vec::new(),
             ignores: Vec::new(),
             time_failures: Vec::new(),
+            retry_counts: HashMap::new(),
+            flaky: Vec::new(),
             options: opts.options,
         })
     }
@@ -164,6 +172,11 @@ impl ConsoleTestState {
         self.write_log(|| "\n")
     }
 
+    /// How many times the test has been retried so far.
+    pub(crate) fn retry_count(&self, test: &TestDesc) -> usize {
+        self.retry_counts.get(&test.name).copied().unwrap_or(0)
+    }
+
     fn current_test_count(&self) -> usize {
         self.passed + self.failed + self.ignored + self.measured
     }
@@ -177,7 +190,7 @@ pub(crate) fn list_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) ->
     };
 
     let mut out: Box<dyn OutputFormatter> = match opts.format {
-        OutputFormat::Pretty | OutputFormat::Junit => {
+        OutputFormat::Pretty | OutputFormat::Junit | OutputFormat::Tap | OutputFormat::Github => {
             Box::new(PrettyFormatter::new(output, false, 0, false, None))
         }
         OutputFormat::Terse => Box::new(TerseFormatter::new(output, false, 0, false)),
@@ -218,6 +231,9 @@ fn handle_test_result(st: &mut ConsoleTestState, completed_test: CompletedTest)
     match completed_test.result {
         TestResult::TrOk => {
             st.passed += 1;
+            if st.retry_count(&test) > 0 {
+                st.flaky.push(test.clone());
+            }
             st.not_failures.push((test, stdout));
         }
         TestResult::TrIgnored => {
@@ -276,6 +292,14 @@ fn on_test_event(
             out.write_result(test, result, exec_time.as_ref(), stdout, st)?;
             handle_test_result(st, completed_test);
         }
+        TestEvent::TeRetry(completed_test) => {
+            let test = &completed_test.desc;
+            let retry = st.retry_count(test) + 1;
+            st.retry_counts.insert(test.name.clone(), retry);
+            st.write_log_result(test, &completed_test.result, completed_test.exec_time.as_ref())?;
+            st.write_log(|| format!("retrying {} (retry {retry})\n", test.name))?;
+            out.write_retry(test, &completed_test.result, &completed_test.stdout, st)?;
+        }
     }
 
     Ok(())
@@ -302,6 +326,14 @@ pub(crate) fn get_formatter(opts: &TestOpts, max_name_len: usize) -> Box<dyn Out
         }
         OutputFormat::Json => Box::new(JsonFormatter::new(output)),
         OutputFormat::Junit => Box::new(JunitFormatter::new(output)),
+        OutputFormat::Tap => Box::new(TapFormatter::new(output)),
+        OutputFormat::Github => Box::new(GithubFormatter::new(PrettyFormatter::new(
+            output,
+            opts.use_color(),
+            max_name_len,
+            is_multithreaded,
+            opts.time_options,
+        ))),
     }
 }
 
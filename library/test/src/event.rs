@@ -31,6 +31,8 @@ pub enum TestEvent {
     TeFiltered(usize, Option<u64>),
     TeWait(TestDesc),
     TeResult(CompletedTest),
+    /// The test failed and is run again, with `--retries`.
+    TeRetry(CompletedTest),
     TeTimeout(TestDesc),
     TeFilteredOut(usize),
 }
@@ -0,0 +1,159 @@
+use std::io;
+use std::io::prelude::Write;
+
+use super::{OutputFormatter, PrettyFormatter, find_panic};
+use crate::console::{ConsoleTestDiscoveryState, ConsoleTestState};
+use crate::test_result::TestResult;
+use crate::time;
+use crate::types::TestDesc;
+
+/// The pretty output, along with GitHub Actions workflow commands which annotate the source of
+/// the failing tests, e.g. `::error file=src/lib.rs,line=10,col=5,title=...::message`.
+///
+/// Failures are annotated where the test panicked, as found in its captured output, or else where
+/// the test is declared.
+pub(crate) struct GithubFormatter<T> {
+    pretty: PrettyFormatter<T>,
+}
+
+impl<T: Write> GithubFormatter<T> {
+    pub(crate) fn new(pretty: PrettyFormatter<T>) -> Self {
+        Self { pretty }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn pretty(&self) -> &PrettyFormatter<T> {
+        &self.pretty
+    }
+
+    fn write_annotation(
+        &mut self,
+        command: &str,
+        location: Option<(&str, u32, u32)>,
+        title: &str,
+        message: &str,
+    ) -> io::Result<()> {
+        let mut properties = String::new();
+        if let Some((file, line, col)) = location {
+            properties.push_str(&format!("file={},line={line},", escape_property(file)));
+            if col != 0 {
+                properties.push_str(&format!("col={col},"));
+            }
+        }
+        properties.push_str(&format!("title={}", escape_property(title)));
+        self.pretty.write_plain(format!("::{command} {properties}::{}\n", escape_data(message)))
+    }
+
+    fn write_failure(
+        &mut self,
+        desc: &TestDesc,
+        message: Option<&str>,
+        stdout: &[u8],
+    ) -> io::Result<()> {
+        let output = String::from_utf8_lossy(stdout);
+        let panic = find_panic(&output);
+        let location = match &panic {
+            Some(panic) => Some((panic.file, panic.line, panic.col)),
+            None => declaration(desc),
+        };
+        let message = message
+            .or(panic.as_ref().map(|panic| &*panic.message).filter(|m| !m.is_empty()))
+            .unwrap_or("test failed");
+        self.write_annotation("error", location, &format!("test {} failed", desc.name), message)
+    }
+}
+
+/// Where the test is declared, if known.
+fn declaration(desc: &TestDesc) -> Option<(&str, u32, u32)> {
+    if desc.source_file.is_empty() || desc.start_line == 0 {
+        return None;
+    }
+    Some((desc.source_file, desc.start_line as u32, desc.start_col as u32))
+}
+
+/// Escapes the message of a workflow command.
+fn escape_data(s: &str) -> String {
+    s.replace('%', "%25").replace('\r', "%0D").replace('\n', "%0A")
+}
+
+/// Escapes the value of a property of a workflow command.
+fn escape_property(s: &str) -> String {
+    escape_data(s).replace(':', "%3A").replace(',', "%2C")
+}
+
+impl<T: Write> OutputFormatter for GithubFormatter<T> {
+    fn write_discovery_start(&mut self) -> io::Result<()> {
+        self.pretty.write_discovery_start()
+    }
+
+    fn write_test_discovered(&mut self, desc: &TestDesc, test_type: &str) -> io::Result<()> {
+        self.pretty.write_test_discovered(desc, test_type)
+    }
+
+    fn write_discovery_finish(&mut self, state: &ConsoleTestDiscoveryState) -> io::Result<()> {
+        self.pretty.write_discovery_finish(state)
+    }
+
+    fn write_run_start(&mut self, test_count: usize, shuffle_seed: Option<u64>) -> io::Result<()> {
+        self.pretty.write_run_start(test_count, shuffle_seed)
+    }
+
+    fn write_test_start(&mut self, desc: &TestDesc) -> io::Result<()> {
+        self.pretty.write_test_start(desc)
+    }
+
+    fn write_timeout(&mut self, desc: &TestDesc) -> io::Result<()> {
+        self.pretty.write_timeout(desc)?;
+        let message =
+            format!("test has been running for over {} seconds", time::TEST_WARN_TIMEOUT_S);
+        let title = format!("test {} is slow", desc.name);
+        self.write_annotation("warning", declaration(desc), &title, &message)
+    }
+
+    fn write_result(
+        &mut self,
+        desc: &TestDesc,
+        result: &TestResult,
+        exec_time: Option<&time::TestExecTime>,
+        stdout: &[u8],
+        state: &ConsoleTestState,
+    ) -> io::Result<()> {
+        self.pretty.write_result(desc, result, exec_time, stdout, state)?;
+        match *result {
+            TestResult::TrFailed => self.write_failure(desc, None, stdout),
+            TestResult::TrFailedMsg(ref m) => self.write_failure(desc, Some(m), stdout),
+            TestResult::TrTimedFail => {
+                self.write_failure(desc, Some("time limit exceeded"), stdout)
+            }
+            TestResult::TrOk if state.retry_count(desc) > 0 => {
+                let message = format!("test passed after {} retries", state.retry_count(desc));
+                let title = format!("test {} is flaky", desc.name);
+                self.write_annotation("warning", declaration(desc), &title, &message)
+            }
+            TestResult::TrOk | TestResult::TrIgnored | TestResult::TrBench(_) => Ok(()),
+        }
+    }
+
+    fn write_retry(
+        &mut self,
+        desc: &TestDesc,
+        result: &TestResult,
+        stdout: &[u8],
+        state: &ConsoleTestState,
+    ) -> io::Result<()> {
+        // Only the final failure of a test is annotated.
+        self.pretty.write_retry(desc, result, stdout, state)
+    }
+
+    fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
+        self.pretty.write_run_finish(state)
+    }
+
+    fn write_merged_doctests_times(
+        &mut self,
+        total_time: f64,
+        compilation_time: f64,
+    ) -> io::Result<()> {
+        self.pretty.write_merged_doctests_times(total_time, compilation_time)
+    }
+}
@@ -128,14 +128,27 @@ impl<T: Write> OutputFormatter for JsonFormatter<T> {
         } else {
             None
         };
+        let retries = state.retry_count(desc);
         match *result {
-            TestResult::TrOk => {
-                self.write_event("test", desc.name.as_slice(), "ok", exec_time, stdout, None)
-            }
+            TestResult::TrOk => self.write_event(
+                "test",
+                desc.name.as_slice(),
+                "ok",
+                exec_time,
+                stdout,
+                (retries > 0)
+                    .then(|| format!(r#""flaky": true, "retries": {retries}"#))
+                    .as_deref(),
+            ),
 
-            TestResult::TrFailed => {
-                self.write_event("test", desc.name.as_slice(), "failed", exec_time, stdout, None)
-            }
+            TestResult::TrFailed => self.write_event(
+                "test",
+                desc.name.as_slice(),
+                "failed",
+                exec_time,
+                stdout,
+                with_retries(None, retries).as_deref(),
+            ),
 
             TestResult::TrTimedFail => self.write_event(
                 "test",
@@ -143,7 +156,7 @@ impl<T: Write> OutputFormatter for JsonFormatter<T> {
                 "failed",
                 exec_time,
                 stdout,
-                Some(r#""reason": "time limit exceeded""#),
+                with_retries(Some(r#""reason": "time limit exceeded""#), retries).as_deref(),
             ),
 
             TestResult::TrFailedMsg(ref m) => self.write_event(
@@ -152,7 +165,8 @@ impl<T: Write> OutputFormatter for JsonFormatter<T> {
                 "failed",
                 exec_time,
                 stdout,
-                Some(&*format!(r#""message": "{}""#, EscapedString(m))),
+                with_retries(Some(&format!(r#""message": "{}""#, EscapedString(m))), retries)
+                    .as_deref(),
             ),
 
             TestResult::TrIgnored => self.write_event(
@@ -187,6 +201,25 @@ impl<T: Write> OutputFormatter for JsonFormatter<T> {
         }
     }
 
+    fn write_retry(
+        &mut self,
+        desc: &TestDesc,
+        result: &TestResult,
+        stdout: &[u8],
+        state: &ConsoleTestState,
+    ) -> io::Result<()> {
+        let stdout = (!stdout.is_empty()).then(|| String::from_utf8_lossy(stdout));
+        let mut extra = format!(r#""retry": {}"#, state.retry_count(desc));
+        match *result {
+            TestResult::TrTimedFail => extra.push_str(r#", "reason": "time limit exceeded""#),
+            TestResult::TrFailedMsg(ref m) => {
+                extra.push_str(&format!(r#", "message": "{}""#, EscapedString(m)))
+            }
+            _ => {}
+        }
+        self.write_event("test", desc.name.as_slice(), "retry", None, stdout, Some(&extra))
+    }
+
     fn write_timeout(&mut self, desc: &TestDesc) -> io::Result<()> {
         let name = EscapedString(desc.name.as_slice());
         let newline = "\n";
@@ -228,9 +261,18 @@ impl<T: Write> OutputFormatter for JsonFormatter<T> {
     }
 }
 
+/// Adds how many times a failed test was retried to the extra fields of its result.
+fn with_retries(extra: Option<&str>, retries: usize) -> Option<String> {
+    match (extra, retries) {
+        (extra, 0) => extra.map(str::to_owned),
+        (Some(extra), _) => Some(format!(r#"{extra}, "retries": {retries}"#)),
+        (None, _) => Some(format!(r#""retries": {retries}"#)),
+    }
+}
+
 /// A formatting utility used to print strings with characters in need of escaping.
 /// Base code taken form `libserialize::json::escape_str`
-struct EscapedString<S: AsRef<str>>(S);
+pub(super) struct EscapedString<S: AsRef<str>>(S);
 
 impl<S: AsRef<str>> std::fmt::Display for EscapedString<S> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> ::std::fmt::Result {
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::io::prelude::Write;
 use std::io::{self};
 use std::time::Duration;
@@ -6,16 +7,23 @@ use super::OutputFormatter;
 use crate::console::{ConsoleTestDiscoveryState, ConsoleTestState, OutputLocation};
 use crate::test_result::TestResult;
 use crate::time;
-use crate::types::{TestDesc, TestType};
+use crate::types::{TestDesc, TestName, TestType};
 
 pub(crate) struct JunitFormatter<T> {
     out: OutputLocation<T>,
     results: Vec<(TestDesc, TestResult, Duration, Vec<u8>)>,
+    /// The failed attempts of the tests which were retried, with `--retries`.
+    retries: HashMap<TestName, Vec<(TestResult, Vec<u8>)>>,
 }
 
 impl<T: Write> JunitFormatter<T> {
     pub(crate) fn new(out: OutputLocation<T>) -> Self {
-        Self { out, results: Vec::new() }
+        Self { out, results: Vec::new(), retries: HashMap::new() }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn output_location(&self) -> &OutputLocation<T> {
+        &self.out
     }
 
     fn write_message(&mut self, s: &str) -> io::Result<()> {
@@ -23,6 +31,50 @@ impl<T: Write> JunitFormatter<T> {
 
         self.out.write_all(s.as_ref())
     }
+
+    /// Writes the failed attempts of a retried test, as `<flakyFailure>` elements when it passed
+    /// in the end, or `<rerunFailure>` elements when it didn't, like Maven Surefire does.
+    fn write_attempts(
+        &mut self,
+        element: &str,
+        attempts: Vec<(TestResult, Vec<u8>)>,
+    ) -> io::Result<()> {
+        for (result, stdout) in attempts {
+            let attributes = match result {
+                TestResult::TrTimedFail => "type=\"timeout\"".to_owned(),
+                TestResult::TrFailedMsg(ref m) => {
+                    format!("message=\"{}\" type=\"assert\"", escape_attribute(m))
+                }
+                _ => "type=\"assert\"".to_owned(),
+            };
+            if stdout.is_empty() {
+                self.write_message(&format!("<{element} {attributes}/>"))?;
+            } else {
+                self.write_message(&format!("<{element} {attributes}><system-out>"))?;
+                self.write_message(&str_to_cdata(&String::from_utf8_lossy(&stdout)))?;
+                self.write_message(&format!("</system-out></{element}>"))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Escapes a string for a double-quoted attribute value. Newlines are escaped too, to keep the
+/// element on one line.
+fn escape_attribute(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\n' => escaped.push_str("&#xA;"),
+            '\r' => escaped.push_str("&#xD;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
 }
 
 fn str_to_cdata(s: &str) -> String {
@@ -84,6 +136,19 @@ impl<T: Write> OutputFormatter for JunitFormatter<T> {
         self.results.push((desc.clone(), result.clone(), duration, stdout.to_vec()));
         Ok(())
     }
+
+    fn write_retry(
+        &mut self,
+        desc: &TestDesc,
+        result: &TestResult,
+        stdout: &[u8],
+        _state: &ConsoleTestState,
+    ) -> io::Result<()> {
+        let attempts = self.retries.entry(desc.name.clone()).or_default();
+        attempts.push((result.clone(), stdout.to_vec()));
+        Ok(())
+    }
+
     fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
         self.write_message("<testsuites>")?;
 
@@ -98,6 +163,7 @@ impl<T: Write> OutputFormatter for JunitFormatter<T> {
         ))?;
         for (desc, result, duration, stdout) in std::mem::take(&mut self.results) {
             let (class_name, test_name) = parse_class_name(&desc);
+            let attempts = self.retries.remove(&desc.name).unwrap_or_default();
             match result {
                 TestResult::TrIgnored => { /* no-op */ }
                 TestResult::TrFailed => {
@@ -109,6 +175,7 @@ impl<T: Write> OutputFormatter for JunitFormatter<T> {
                         duration.as_secs_f64()
                     ))?;
                     self.write_message("<failure type=\"assert\"/>")?;
+                    self.write_attempts("rerunFailure", attempts)?;
                     if !stdout.is_empty() {
                         self.write_message("<system-out>")?;
                         self.write_message(&str_to_cdata(&String::from_utf8_lossy(&stdout)))?;
@@ -126,6 +193,7 @@ impl<T: Write> OutputFormatter for JunitFormatter<T> {
                         duration.as_secs_f64()
                     ))?;
                     self.write_message(&format!("<failure message=\"{m}\" type=\"assert\"/>"))?;
+                    self.write_attempts("rerunFailure", attempts)?;
                     if !stdout.is_empty() {
                         self.write_message("<system-out>")?;
                         self.write_message(&str_to_cdata(&String::from_utf8_lossy(&stdout)))?;
@@ -143,6 +211,7 @@ impl<T: Write> OutputFormatter for JunitFormatter<T> {
                         duration.as_secs_f64()
                     ))?;
                     self.write_message("<failure type=\"timeout\"/>")?;
+                    self.write_attempts("rerunFailure", attempts)?;
                     self.write_message("</testcase>")?;
                 }
 
@@ -162,12 +231,17 @@ impl<T: Write> OutputFormatter for JunitFormatter<T> {
                         test_name,
                         duration.as_secs_f64()
                     ))?;
-                    if stdout.is_empty() || !state.options.display_output {
+                    let display_stdout = !stdout.is_empty() && state.options.display_output;
+                    if !display_stdout && attempts.is_empty() {
                         self.write_message("/>")?;
                     } else {
-                        self.write_message("><system-out>")?;
-                        self.write_message(&str_to_cdata(&String::from_utf8_lossy(&stdout)))?;
-                        self.write_message("</system-out>")?;
+                        self.write_message(">")?;
+                        self.write_attempts("flakyFailure", attempts)?;
+                        if display_stdout {
+                            self.write_message("<system-out>")?;
+                            self.write_message(&str_to_cdata(&String::from_utf8_lossy(&stdout)))?;
+                            self.write_message("</system-out>")?;
+                        }
                         self.write_message("</testcase>")?;
                     }
                 }
@@ -6,14 +6,18 @@ use crate::test_result::TestResult;
 use crate::time;
 use crate::types::{TestDesc, TestName};
 
+mod github;
 mod json;
 mod junit;
 mod pretty;
+mod tap;
 mod terse;
 
+pub(crate) use self::github::GithubFormatter;
 pub(crate) use self::json::JsonFormatter;
 pub(crate) use self::junit::JunitFormatter;
 pub(crate) use self::pretty::PrettyFormatter;
+pub(crate) use self::tap::TapFormatter;
 pub(crate) use self::terse::TerseFormatter;
 
 pub(crate) trait OutputFormatter {
@@ -32,6 +36,15 @@ pub(crate) trait OutputFormatter {
         stdout: &[u8],
         state: &ConsoleTestState,
     ) -> io::Result<()>;
+    /// Writes that the test failed and is run again, with `--retries`. The retry is already
+    /// counted in `state`.
+    fn write_retry(
+        &mut self,
+        desc: &TestDesc,
+        result: &TestResult,
+        stdout: &[u8],
+        state: &ConsoleTestState,
+    ) -> io::Result<()>;
     fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool>;
     fn write_merged_doctests_times(
         &mut self,
@@ -48,3 +61,32 @@ pub(crate) fn write_stderr_delimiter(test_output: &mut Vec<u8>, test_name: &Test
     }
     writeln!(test_output, "---- {test_name} stderr ----").unwrap();
 }
+
+/// The first panic in the captured output of a test, as printed by the default panic hook.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct TestPanic<'a> {
+    pub(crate) file: &'a str,
+    pub(crate) line: u32,
+    pub(crate) col: u32,
+    pub(crate) message: String,
+}
+
+/// Finds where the test panicked in its captured output, i.e. the location printed by the panic
+/// hook in `thread 'foo' (123) panicked at src/lib.rs:10:5:`, followed by the panic message.
+pub(crate) fn find_panic(output: &str) -> Option<TestPanic<'_>> {
+    let mut lines = output.lines();
+    let location = lines.find_map(|line| {
+        let (_, location) = line.strip_prefix("thread '")?.split_once(" panicked at ")?;
+        location.strip_suffix(':')
+    })?;
+    let mut parts = location.rsplitn(3, ':');
+    let col = parts.next()?.parse().ok()?;
+    let line = parts.next()?.parse().ok()?;
+    let file = parts.next()?;
+
+    let message = lines
+        .take_while(|line| !["note: ", "thread '", "---- "].iter().any(|p| line.starts_with(p)))
+        .collect::<Vec<_>>()
+        .join("\n");
+    Some(TestPanic { file, line, col, message: message.trim_end().to_owned() })
+}
@@ -166,6 +166,21 @@ impl<T: Write> PrettyFormatter<T> {
         self.write_results(&state.time_failures, "failures (time limit exceeded)")
     }
 
+    pub(crate) fn write_flaky(&mut self, state: &ConsoleTestState) -> io::Result<()> {
+        self.write_plain("\nflaky tests (passed after retrying):\n")?;
+        let mut flaky: Vec<_> = state
+            .flaky
+            .iter()
+            .map(|desc| (desc.name.to_string(), state.retry_count(desc)))
+            .collect();
+        flaky.sort();
+        for (name, retries) in flaky {
+            let noun = if retries != 1 { "retries" } else { "retry" };
+            self.write_plain(format!("    {name} ({retries} {noun})\n"))?;
+        }
+        Ok(())
+    }
+
     fn write_test_name(&mut self, desc: &TestDesc) -> io::Result<()> {
         let name = desc.padded_name(self.max_name_len, desc.name.padding());
         if let Some(test_mode) = desc.test_mode() {
@@ -255,6 +270,24 @@ impl<T: Write> OutputFormatter for PrettyFormatter<T> {
         self.write_plain("\n")
     }
 
+    fn write_retry(
+        &mut self,
+        desc: &TestDesc,
+        result: &TestResult,
+        _: &[u8],
+        state: &ConsoleTestState,
+    ) -> io::Result<()> {
+        if self.is_multithreaded {
+            self.write_test_name(desc)?;
+        }
+
+        match *result {
+            TestResult::TrTimedFail => self.write_time_failed()?,
+            _ => self.write_failed()?,
+        }
+        self.write_plain(format!(", retrying (retry {})\n", state.retry_count(desc)))
+    }
+
     fn write_timeout(&mut self, desc: &TestDesc) -> io::Result<()> {
         self.write_plain(format!(
             "test {} has been running for over {} seconds\n",
@@ -278,6 +311,10 @@ impl<T: Write> OutputFormatter for PrettyFormatter<T> {
             }
         }
 
+        if !state.flaky.is_empty() {
+            self.write_flaky(state)?;
+        }
+
         self.write_plain("\ntest result: ")?;
 
         if success {
@@ -0,0 +1,187 @@
+use std::io;
+use std::io::prelude::Write;
+
+use super::json::EscapedString;
+use super::{OutputFormatter, find_panic};
+use crate::bench::fmt_bench_samples;
+use crate::console::{ConsoleTestDiscoveryState, ConsoleTestState, OutputLocation};
+use crate::test_result::TestResult;
+use crate::time;
+use crate::types::TestDesc;
+
+/// Output in the Test Anything Protocol, version 14.
+///
+/// See <https://testanything.org/tap-version-14-specification.html>.
+pub(crate) struct TapFormatter<T> {
+    out: OutputLocation<T>,
+    /// The number of the last test point written.
+    test_number: usize,
+}
+
+impl<T: Write> TapFormatter<T> {
+    pub(crate) fn new(out: OutputLocation<T>) -> Self {
+        Self { out, test_number: 0 }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn output_location(&self) -> &OutputLocation<T> {
+        &self.out
+    }
+
+    fn write_message(&mut self, s: &str) -> io::Result<()> {
+        self.out.write_all(s.as_bytes())?;
+        self.out.flush()
+    }
+
+    fn write_test_point(&mut self, ok: bool, desc: &TestDesc, directive: &str) -> io::Result<()> {
+        self.test_number += 1;
+        // `#` starts a directive in the description of a test point, and `\` escapes it.
+        let name = desc.name.as_slice().replace('\\', "\\\\").replace('#', "\\#");
+        let status = if ok { "ok" } else { "not ok" };
+        self.write_message(&format!("{status} {} - {name}{directive}\n", self.test_number))
+    }
+
+    /// Writes the YAML diagnostic block of a test point, from its `(key, value)` pairs.
+    fn write_yaml_block(&mut self, fields: &[(&str, String)]) -> io::Result<()> {
+        let mut block = String::from("  ---\n");
+        for (key, value) in fields {
+            block.push_str(&format!("  {key}: {value}\n"));
+        }
+        block.push_str("  ...\n");
+        self.write_message(&block)
+    }
+
+    fn write_failure(
+        &mut self,
+        desc: &TestDesc,
+        message: Option<&str>,
+        exec_time: Option<&time::TestExecTime>,
+        stdout: &[u8],
+    ) -> io::Result<()> {
+        self.write_test_point(false, desc, "")?;
+
+        let output = String::from_utf8_lossy(stdout);
+        let panic = find_panic(&output);
+        let mut fields = vec![];
+        if let Some(message) = message.or(panic.as_ref().map(|panic| &*panic.message)) {
+            fields.push(("message", format!("\"{}\"", EscapedString(message))));
+        }
+        fields.push(("severity", "fail".to_owned()));
+        if let Some(panic) = &panic {
+            fields.push((
+                "at",
+                format!(
+                    "{{ file: \"{}\", line: {}, column: {} }}",
+                    EscapedString(panic.file),
+                    panic.line,
+                    panic.col
+                ),
+            ));
+        }
+        if let Some(exec_time) = exec_time {
+            fields.push(("duration_ms", format!("{:.3}", exec_time.0.as_secs_f64() * 1000.0)));
+        }
+        if !output.is_empty() {
+            fields.push(("output", format!("\"{}\"", EscapedString(&*output))));
+        }
+        self.write_yaml_block(&fields)
+    }
+}
+
+impl<T: Write> OutputFormatter for TapFormatter<T> {
+    fn write_discovery_start(&mut self) -> io::Result<()> {
+        Err(io::const_error!(io::ErrorKind::NotFound, "not yet implemented!"))
+    }
+
+    fn write_test_discovered(&mut self, _desc: &TestDesc, _test_type: &str) -> io::Result<()> {
+        Err(io::const_error!(io::ErrorKind::NotFound, "not yet implemented!"))
+    }
+
+    fn write_discovery_finish(&mut self, _state: &ConsoleTestDiscoveryState) -> io::Result<()> {
+        Err(io::const_error!(io::ErrorKind::NotFound, "not yet implemented!"))
+    }
+
+    fn write_run_start(&mut self, test_count: usize, shuffle_seed: Option<u64>) -> io::Result<()> {
+        self.write_message(&format!("TAP version 14\n1..{test_count}\n"))?;
+        if let Some(shuffle_seed) = shuffle_seed {
+            self.write_message(&format!("# shuffle seed: {shuffle_seed}\n"))?;
+        }
+        Ok(())
+    }
+
+    fn write_test_start(&mut self, _desc: &TestDesc) -> io::Result<()> {
+        // Test points are only written once the test has a result.
+        Ok(())
+    }
+
+    fn write_timeout(&mut self, desc: &TestDesc) -> io::Result<()> {
+        self.write_message(&format!(
+            "# test {} has been running for over {} seconds\n",
+            desc.name,
+            time::TEST_WARN_TIMEOUT_S
+        ))
+    }
+
+    fn write_result(
+        &mut self,
+        desc: &TestDesc,
+        result: &TestResult,
+        exec_time: Option<&time::TestExecTime>,
+        stdout: &[u8],
+        _state: &ConsoleTestState,
+    ) -> io::Result<()> {
+        match *result {
+            TestResult::TrOk => self.write_test_point(true, desc, ""),
+            TestResult::TrIgnored => {
+                let reason = desc.ignore_message.map(|m| format!(" {m}")).unwrap_or_default();
+                self.write_test_point(true, desc, &format!(" # SKIP{reason}"))
+            }
+            TestResult::TrBench(ref bs) => {
+                self.write_test_point(true, desc, "")?;
+                let samples = fmt_bench_samples(bs);
+                self.write_yaml_block(&[("bench", format!("\"{}\"", EscapedString(samples)))])
+            }
+            TestResult::TrFailed => self.write_failure(desc, None, exec_time, stdout),
+            TestResult::TrFailedMsg(ref m) => self.write_failure(desc, Some(m), exec_time, stdout),
+            TestResult::TrTimedFail => {
+                self.write_failure(desc, Some("time limit exceeded"), exec_time, stdout)
+            }
+        }
+    }
+
+    fn write_retry(
+        &mut self,
+        desc: &TestDesc,
+        _result: &TestResult,
+        _stdout: &[u8],
+        state: &ConsoleTestState,
+    ) -> io::Result<()> {
+        // Only the final result of a test is a test point, as the plan counts each test once.
+        let retry = state.retry_count(desc);
+        self.write_message(&format!("# test {} failed, retrying (retry {retry})\n", desc.name))
+    }
+
+    fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
+        let success = state.failed == 0;
+        self.write_message(&format!(
+            "# test result: {}. {} passed; {} failed; {} ignored; {} measured; {} filtered out\n",
+            if success { "ok" } else { "FAILED" },
+            state.passed,
+            state.failed,
+            state.ignored,
+            state.measured,
+            state.filtered_out,
+        ))?;
+        Ok(success)
+    }
+
+    fn write_merged_doctests_times(
+        &mut self,
+        total_time: f64,
+        compilation_time: f64,
+    ) -> io::Result<()> {
+        self.write_message(&format!(
+            "# all doctests ran in {total_time:.2}s; merged doctests compilation took {compilation_time:.2}s\n",
+        ))
+    }
+}
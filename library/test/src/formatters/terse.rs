@@ -244,6 +244,17 @@ impl<T: Write> OutputFormatter for TerseFormatter<T> {
         }
     }
 
+    fn write_retry(
+        &mut self,
+        _: &TestDesc,
+        _: &TestResult,
+        _: &[u8],
+        _: &ConsoleTestState,
+    ) -> io::Result<()> {
+        // Only the final result of a test is written.
+        Ok(())
+    }
+
     fn write_timeout(&mut self, desc: &TestDesc) -> io::Result<()> {
         self.write_plain(format!(
             "test {} has been running for over {} seconds\n",
@@ -54,7 +54,8 @@ pub mod test {
     pub use crate::{assert_test_result, filter_tests, run_test, test_main, test_main_static};
 }
 
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{BuildHasherDefault, DefaultHasher};
 use std::io::prelude::Write;
 use std::mem::ManuallyDrop;
 use std::panic::{self, AssertUnwindSafe, PanicHookInfo, catch_unwind};
@@ -107,6 +108,11 @@ pub fn test_main_with_exit_callback<F: FnOnce()>(
     options: Option<Options>,
     exit_callback: F,
 ) {
+    // If we're being run in SpawnedSecondary mode, run the test here. This exits the process.
+    if let Ok(name) = env::var(SECONDARY_TEST_INVOKER_VAR) {
+        run_spawned_secondary_test(&name, tests, options.is_some_and(|o| o.panic_abort));
+    }
+
     let mut opts = match cli::parse_opts(args) {
         Some(Ok(o)) => o,
         Some(Err(msg)) => {
@@ -192,44 +198,44 @@ pub fn test_main_static(tests: &[&TestDescAndFn]) {
 /// This is the entry point for the main function generated by `rustc --test`
 /// when panic=abort.
 pub fn test_main_static_abort(tests: &[&TestDescAndFn]) {
-    // If we're being run in SpawnedSecondary mode, run the test here. run_test
-    // will then exit the process.
-    if let Ok(name) = env::var(SECONDARY_TEST_INVOKER_VAR) {
+    let args = env::args().collect::<Vec<_>>();
+    let owned_tests: Vec<_> = tests.iter().map(make_owned_test).collect();
+    test_main(&args, owned_tests, Some(Options::new().panic_abort(true)))
+}
+
+/// Runs the test named `name` in this process, which was spawned to run it with panic=abort or
+/// `--test-timeout`, and exits the process.
+fn run_spawned_secondary_test(name: &str, tests: Vec<TestDescAndFn>, panic_abort: bool) -> ! {
+    unsafe {
+        env::remove_var(SECONDARY_TEST_INVOKER_VAR);
+    }
+
+    // Convert benchmarks to tests if we're not benchmarking.
+    let mut tests = tests;
+    if env::var(SECONDARY_TEST_BENCH_BENCHMARKS_VAR).is_ok() {
         unsafe {
-            env::remove_var(SECONDARY_TEST_INVOKER_VAR);
+            env::remove_var(SECONDARY_TEST_BENCH_BENCHMARKS_VAR);
         }
+    } else {
+        tests = convert_benchmarks_to_tests(tests);
+    };
 
-        // Convert benchmarks to tests if we're not benchmarking.
-        let mut tests = tests.iter().map(make_owned_test).collect::<Vec<_>>();
-        if env::var(SECONDARY_TEST_BENCH_BENCHMARKS_VAR).is_ok() {
-            unsafe {
-                env::remove_var(SECONDARY_TEST_BENCH_BENCHMARKS_VAR);
-            }
-        } else {
-            tests = convert_benchmarks_to_tests(tests);
-        };
-
-        let test = tests
-            .into_iter()
-            .find(|test| test.desc.name.as_slice() == name)
-            .unwrap_or_else(|| panic!("couldn't find a test with the provided name '{name}'"));
-        let TestDescAndFn { desc, testfn } = test;
-        match testfn.into_runnable() {
-            Runnable::Test(runnable_test) => {
-                if runnable_test.is_dynamic() {
-                    panic!("only static tests are supported");
-                }
-                run_test_in_spawned_subprocess(desc, runnable_test);
-            }
-            Runnable::Bench(_) => {
-                panic!("benchmarks should not be executed into child processes")
+    let test = tests
+        .into_iter()
+        .find(|test| test.desc.name.as_slice() == name)
+        .unwrap_or_else(|| panic!("couldn't find a test with the provided name '{name}'"));
+    let TestDescAndFn { desc, testfn } = test;
+    match testfn.into_runnable() {
+        Runnable::Test(runnable_test) => {
+            if runnable_test.is_dynamic() {
+                panic!("only static tests are supported");
             }
+            run_test_in_spawned_subprocess(desc, runnable_test, panic_abort);
+        }
+        Runnable::Bench(_) => {
+            panic!("benchmarks should not be executed into child processes")
         }
     }
-
-    let args = env::args().collect::<Vec<_>>();
-    let owned_tests: Vec<_> = tests.iter().map(make_owned_test).collect();
-    test_main(&args, owned_tests, Some(Options::new().panic_abort(true)))
 }
 
 /// Clones static values for putting into a dynamic vector, which test_main()
@@ -304,8 +310,6 @@ pub fn run_tests<F>(
 where
     F: FnMut(TestEvent) -> io::Result<()>,
 {
-    use std::collections::HashMap;
-    use std::hash::{BuildHasherDefault, DefaultHasher};
     use std::sync::mpsc::RecvTimeoutError;
 
     struct RunningTest {
@@ -378,11 +382,39 @@ where
     let mut pending = 0;
 
     let (tx, rx) = channel::<CompletedTest>();
-    let run_strategy = if opts.options.panic_abort && !opts.force_run_in_process {
+    // Tests are run in subprocesses with panic=abort, so that a failing test doesn't abort the
+    // whole run, and with `--test-timeout`, so that the tests which time out can be killed.
+    let supports_processes = !cfg!(target_os = "emscripten")
+        && !cfg!(target_family = "wasm")
+        && !cfg!(target_os = "zkvm");
+    let run_strategy = if (opts.options.panic_abort
+        || (opts.test_timeout.is_some() && supports_processes))
+        && !opts.force_run_in_process
+    {
         RunStrategy::SpawnPrimary
     } else {
         RunStrategy::InProcess
     };
+    // Dynamic tests can't run in a subprocess, so they couldn't be killed when they time out.
+    if matches!(run_strategy, RunStrategy::SpawnPrimary)
+        && !opts.options.panic_abort
+        && let Some((_, test)) = remaining
+            .iter()
+            .find(|(_, test)| matches!(test.testfn, DynTestFn(_) | DynBenchAsTestFn(_)))
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!(
+                "`--test-timeout` is not supported for the dynamic test `{}`, which can't run \
+                 in a subprocess; use `--force-run-in-process` to run it without a timeout",
+                test.desc.name
+            ),
+        ));
+    }
+
+    // With `--retries`, the failing tests are run again, which is only possible for static tests
+    // as dynamic ones are consumed by running them.
+    let mut retries = Retries { max: opts.retries, ..Retries::default() };
 
     let mut running_tests: TestMap = HashMap::default();
     let mut timeout_queue: VecDeque<TimeoutEntry> = VecDeque::new();
@@ -415,6 +447,7 @@ where
     if concurrency == 1 {
         while !remaining.is_empty() {
             let (id, test) = remaining.pop_front().unwrap();
+            retries.save(id, &test);
             let event = TestEvent::TeWait(test.desc.clone());
             notify_about_test_event(event)?;
             let join_handle = run_test(opts, !opts.run_tests, id, test, run_strategy, tx.clone());
@@ -422,6 +455,12 @@ where
             let mut completed_test = rx.recv().unwrap();
             RunningTest { join_handle }.join(&mut completed_test);
 
+            if let Some(test) = retries.take_retry(&completed_test) {
+                notify_about_test_event(TestEvent::TeRetry(completed_test))?;
+                remaining.push_front((id, test));
+                continue;
+            }
+
             let fail_fast = match completed_test.result {
                 TrIgnored | TrOk | TrBench(_) => false,
                 TrFailed | TrFailedMsg(_) | TrTimedFail => opts.fail_fast,
@@ -438,6 +477,7 @@ where
         while pending > 0 || !remaining.is_empty() {
             while pending < concurrency && !remaining.is_empty() {
                 let (id, test) = remaining.pop_front().unwrap();
+                retries.save(id, &test);
                 let timeout = time::get_default_test_timeout();
                 let desc = test.desc.clone();
 
@@ -477,6 +517,14 @@ where
             let mut completed_test = res.unwrap();
             let running_test = running_tests.remove(&completed_test.id).unwrap();
             running_test.join(&mut completed_test);
+            pending -= 1;
+
+            if let Some(test) = retries.take_retry(&completed_test) {
+                let id = completed_test.id;
+                notify_about_test_event(TestEvent::TeRetry(completed_test))?;
+                remaining.push_front((id, test));
+                continue;
+            }
 
             let fail_fast = match completed_test.result {
                 TrIgnored | TrOk | TrBench(_) => false,
@@ -485,7 +533,6 @@ where
 
             let event = TestEvent::TeResult(completed_test);
             notify_about_test_event(event)?;
-            pending -= 1;
 
             if fail_fast {
                 // Prevent remaining test threads from panicking
@@ -512,6 +559,41 @@ where
     Ok(())
 }
 
+/// The copies of the running tests needed to run them again if they fail, with `--retries`.
+#[derive(Default)]
+struct Retries {
+    /// How many times a test may be retried.
+    max: usize,
+    copies: HashMap<TestId, TestDescAndFn, BuildHasherDefault<DefaultHasher>>,
+    counts: HashMap<TestId, usize, BuildHasherDefault<DefaultHasher>>,
+}
+
+impl Retries {
+    /// Keeps a copy of a test about to run, if it may be retried.
+    fn save(&mut self, id: TestId, test: &TestDescAndFn) {
+        if self.max > self.counts.get(&id).copied().unwrap_or(0) {
+            let testfn = match test.testfn {
+                StaticTestFn(f) => StaticTestFn(f),
+                StaticBenchAsTestFn(f) => StaticBenchAsTestFn(f),
+                _ => return,
+            };
+            self.copies.insert(id, TestDescAndFn { desc: test.desc.clone(), testfn });
+        }
+    }
+
+    /// Returns the test to run again, if it failed and may be retried.
+    fn take_retry(&mut self, completed_test: &CompletedTest) -> Option<TestDescAndFn> {
+        let test = self.copies.remove(&completed_test.id)?;
+        match completed_test.result {
+            TrFailed | TrFailedMsg(_) | TrTimedFail => {
+                *self.counts.entry(completed_test.id).or_default() += 1;
+                Some(test)
+            }
+            TrIgnored | TrOk | TrBench(_) => None,
+        }
+    }
+}
+
 pub fn filter_tests(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> Vec<TestDescAndFn> {
     let mut filtered = tests;
     let matches_filter = |test: &TestDescAndFn, filter: &str| {
@@ -601,6 +683,7 @@ pub fn run_test(
             let name = desc.name.clone();
             let nocapture = opts.nocapture;
             let time_options = opts.time_options;
+            let test_timeout = opts.test_timeout;
             let bench_benchmarks = opts.bench_benchmarks;
 
             let runtest = move || match strategy {
@@ -612,6 +695,7 @@ pub fn run_test(
                     runnable_test,
                     monitor_ch,
                     time_options,
+                    test_timeout,
                 ),
                 RunStrategy::SpawnPrimary => spawn_test_subprocess(
                     id,
@@ -620,6 +704,7 @@ pub fn run_test(
                     time_options.is_some(),
                     monitor_ch,
                     time_options,
+                    test_timeout,
                     bench_benchmarks,
                 ),
             };
@@ -674,6 +759,7 @@ fn run_test_in_process(
     runnable_test: RunnableTest,
     monitor_ch: Sender<CompletedTest>,
     time_opts: Option<time::TestTimeOptions>,
+    test_timeout: Option<Duration>,
 ) {
     // Buffer for capturing standard I/O
     let data = Arc::new(Mutex::new(Vec::new()));
@@ -682,20 +768,22 @@ fn run_test_in_process(
         io::set_output_capture(Some(data.clone()));
     }
 
-    let start = report_time.then(Instant::now);
+    let start = (report_time || test_timeout.is_some()).then(Instant::now);
     let result = fold_err(catch_unwind(AssertUnwindSafe(|| runnable_test.run())));
-    let exec_time = start.map(|start| {
-        let duration = start.elapsed();
-        TestExecTime(duration)
-    });
+    let elapsed = start.map(|start| start.elapsed());
+    let exec_time = elapsed.filter(|_| report_time).map(TestExecTime);
 
     io::set_output_capture(None);
 
     // Determine whether the test passed or failed, by comparing its panic
     // payload (if any) with its `ShouldPanic` value, and by checking for
     // fatal timeout.
-    let test_result =
+    let mut test_result =
         calc_result(&desc, result.err().as_deref(), time_opts.as_ref(), exec_time.as_ref());
+    // Tests running in-process can't be killed, but they still fail if they exceed the timeout.
+    if test_result == TrOk && elapsed.zip(test_timeout).is_some_and(|(e, t)| e > t) {
+        test_result = TrTimedFail;
+    }
     let stdout = data.lock().unwrap_or_else(|e| e.into_inner()).to_vec();
     let message = CompletedTest::new(id, desc, test_result, exec_time, stdout);
     monitor_ch.send(message).unwrap();
@@ -721,6 +809,7 @@ fn spawn_test_subprocess(
     report_time: bool,
     monitor_ch: Sender<CompletedTest>,
     time_opts: Option<time::TestTimeOptions>,
+    test_timeout: Option<Duration>,
     bench_benchmarks: bool,
 ) {
     let (result, test_output, exec_time) = (|| {
@@ -735,10 +824,17 @@ fn spawn_test_subprocess(
         if nocapture {
             command.stdout(process::Stdio::inherit());
             command.stderr(process::Stdio::inherit());
+        } else {
+            command.stdout(process::Stdio::piped());
+            command.stderr(process::Stdio::piped());
         }
 
         let start = report_time.then(Instant::now);
-        let output = match command.output() {
+        let output = match test_timeout {
+            Some(timeout) => output_with_timeout(&mut command, timeout),
+            None => command.output().map(|output| (output, false)),
+        };
+        let (output, timed_out) = match output {
             Ok(out) => out,
             Err(e) => {
                 let err = format!("Failed to spawn {} as child for test: {:?}", args[0], e);
@@ -755,6 +851,12 @@ fn spawn_test_subprocess(
         formatters::write_stderr_delimiter(&mut test_output, &desc.name);
         test_output.extend_from_slice(&stderr);
 
+        if let Some(timeout) = test_timeout.filter(|_| timed_out) {
+            let note = format!("note: test killed after exceeding the timeout of {timeout:?}\n");
+            test_output.extend_from_slice(note.as_bytes());
+            return (TrTimedFail, test_output, exec_time);
+        }
+
         let result =
             get_result_from_exit_code(&desc, status, time_opts.as_ref(), exec_time.as_ref());
         (result, test_output, exec_time)
@@ -764,26 +866,70 @@ fn spawn_test_subprocess(
     monitor_ch.send(message).unwrap();
 }
 
-fn run_test_in_spawned_subprocess(desc: TestDesc, runnable_test: RunnableTest) -> ! {
+/// Runs the command to completion like `Command::output`, but kills it if it runs for longer than
+/// `timeout`. Also returns whether it was killed.
+fn output_with_timeout(
+    command: &mut Command,
+    timeout: Duration,
+) -> io::Result<(process::Output, bool)> {
+    fn read_pipe(pipe: Option<impl io::Read + Send + 'static>) -> thread::JoinHandle<Vec<u8>> {
+        thread::spawn(move || {
+            let mut buf = Vec::new();
+            if let Some(mut pipe) = pipe {
+                // The output read until an error is still worth reporting.
+                let _ = pipe.read_to_end(&mut buf);
+            }
+            buf
+        })
+    }
+
+    let start = Instant::now();
+    let mut child = command.spawn()?;
+    // The pipes are drained while waiting, so that the child doesn't block on a full pipe.
+    let stdout = read_pipe(child.stdout.take());
+    let stderr = read_pipe(child.stderr.take());
+
+    let mut timed_out = false;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        let elapsed = start.elapsed();
+        if elapsed >= timeout {
+            timed_out = true;
+            // The child may have exited in the meantime, in which case it can't be killed.
+            let _ = child.kill();
+            break child.wait()?;
+        }
+        thread::sleep((timeout - elapsed).min(Duration::from_millis(10)));
+    };
+
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+    Ok((process::Output { status, stdout, stderr }, timed_out))
+}
+
+fn run_test_in_spawned_subprocess(
+    desc: TestDesc,
+    runnable_test: RunnableTest,
+    panic_abort: bool,
+) -> ! {
+    // Only spawned for `--test-timeout` when panics unwind, so the test runs like it does
+    // in-process, where it may catch its own panics or the ones of the threads it joins.
+    if !panic_abort {
+        let result = fold_err(catch_unwind(AssertUnwindSafe(|| runnable_test.run())));
+        exit_with_result(calc_result(&desc, result.err().as_deref(), None, None));
+    }
+
     let builtin_panic_hook = panic::take_hook();
     let record_result = Arc::new(move |panic_info: Option<&'_ PanicHookInfo<'_>>| {
         let test_result = calc_result(&desc, panic_info.map(|info| info.payload()), None, None);
 
-        // We don't support serializing TrFailedMsg, so just
-        // print the message out to stderr.
-        if let TrFailedMsg(msg) = &test_result {
-            eprintln!("{msg}");
-        }
-
         if let Some(info) = panic_info {
             builtin_panic_hook(info);
         }
 
-        if let TrOk = test_result {
-            process::exit(test_result::TR_OK);
-        } else {
-            process::abort();
-        }
+        exit_with_result(test_result);
     });
     let record_result2 = record_result.clone();
     panic::set_hook(Box::new(move |info| record_result2(Some(info))));
@@ -793,3 +939,19 @@ fn run_test_in_spawned_subprocess(desc: TestDesc, runnable_test: RunnableTest) -
     record_result(None);
     unreachable!("panic=abort callback should have exited the process")
 }
+
+/// Exits the spawned subprocess of a test, with a status that `get_result_from_exit_code`
+/// turns back into the result.
+fn exit_with_result(test_result: TestResult) -> ! {
+    // We don't support serializing TrFailedMsg, so just
+    // print the message out to stderr.
+    if let TrFailedMsg(msg) = &test_result {
+        eprintln!("{msg}");
+    }
+
+    if let TrOk = test_result {
+        process::exit(test_result::TR_OK);
+    } else {
+        process::abort();
+    }
+}
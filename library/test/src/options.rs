@@ -36,6 +36,10 @@ pub enum OutputFormat {
     Json,
     /// JUnit output
     Junit,
+    /// Test Anything Protocol (version 14) output
+    Tap,
+    /// Pretty output, with GitHub Actions annotations for the failures
+    Github,
 }
 
 /// Whether ignored test should be run or not
@@ -1,7 +1,10 @@
 use super::*;
 use crate::{
     console::OutputLocation,
-    formatters::PrettyFormatter,
+    formatters::{
+        GithubFormatter, JunitFormatter, OutputFormatter, PrettyFormatter, TapFormatter,
+        TestPanic, find_panic,
+    },
     test::{
         MetricMap,
         // FIXME (introduced by #65251)
@@ -34,6 +37,8 @@ impl TestOpts {
             time_options: None,
             options: Options::new(),
             fail_fast: false,
+            test_timeout: None,
+            retries: 0,
         }
     }
 }
@@ -461,6 +466,26 @@ fn parse_show_output_flag() {
     assert!(opts.options.display_output);
 }
 
+#[test]
+fn parse_test_timeout_and_retries() {
+    let args: Vec<_> = ["progname", "-Zunstable-options", "--test-timeout=500ms", "--retries=2"]
+        .map(String::from)
+        .into();
+    let opts = parse_opts(&args).unwrap().unwrap();
+    assert_eq!(opts.test_timeout, Some(Duration::from_millis(500)));
+    assert_eq!(opts.retries, 2);
+
+    for (timeout, expected) in [("90", 90_000), ("1.5s", 1_500), ("2m", 120_000)] {
+        let args = ["progname", "-Zunstable-options", "--test-timeout", timeout].map(String::from);
+        let opts = parse_opts(&args).unwrap().unwrap();
+        assert_eq!(opts.test_timeout, Some(Duration::from_millis(expected)));
+    }
+    for timeout in ["0s", "fast", "10h"] {
+        let args = ["progname", "-Zunstable-options", "--test-timeout", timeout].map(String::from);
+        assert!(parse_opts(&args).unwrap().is_err());
+    }
+}
+
 #[test]
 fn parse_include_ignored_flag() {
     let args = vec!["progname".to_string(), "filter".to_string(), "--include-ignored".to_string()];
@@ -865,6 +890,8 @@ fn should_sort_failures_before_printing_them() {
         not_failures: Vec::new(),
         ignores: Vec::new(),
         time_failures: Vec::new(),
+        retry_counts: Default::default(),
+        flaky: Vec::new(),
     };
 
     out.write_failures(&st).unwrap();
@@ -912,3 +939,169 @@ fn test_dyn_bench_returning_err_fails_when_run_as_test() {
     let result = rx.recv().unwrap().result;
     assert_eq!(result, TrFailed);
 }
+
+fn failing_test_desc() -> TestDesc {
+    TestDesc {
+        name: StaticTestName("tests::b"),
+        ignore: false,
+        ignore_message: None,
+        source_file: "src/lib.rs",
+        start_line: 8,
+        start_col: 4,
+        end_line: 8,
+        end_col: 5,
+        should_panic: ShouldPanic::No,
+        compile_fail: false,
+        no_run: false,
+        test_type: TestType::UnitTest,
+    }
+}
+
+const FAILING_TEST_OUTPUT: &str = "print from failing test\n\
+    thread 'tests::b' (1234) panicked at src/lib.rs:10:5:\n\
+    assertion failed: x, y\n\
+    note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace\n";
+
+#[test]
+fn should_find_panic_in_output() {
+    assert_eq!(
+        find_panic(FAILING_TEST_OUTPUT),
+        Some(TestPanic {
+            file: "src/lib.rs",
+            line: 10,
+            col: 5,
+            message: "assertion failed: x, y".to_owned()
+        })
+    );
+    assert_eq!(find_panic("print from failing test\n"), None);
+}
+
+#[test]
+fn tap_formatter_output() {
+    let mut out = TapFormatter::new(OutputLocation::Raw(Vec::new()));
+    let st = console::ConsoleTestState::new(&TestOpts::new()).unwrap();
+    let desc = failing_test_desc();
+    out.write_run_start(2, None).unwrap();
+    let ignored = TestDesc { ignore_message: Some("slow"), ..desc.clone() };
+    out.write_result(&ignored, &TrIgnored, None, &[], &st).unwrap();
+    out.write_result(&desc, &TrFailed, None, FAILING_TEST_OUTPUT.as_bytes(), &st).unwrap();
+    let s = match out.output_location() {
+        OutputLocation::Raw(m) => String::from_utf8_lossy(&m[..]).into_owned(),
+        OutputLocation::Pretty(_) => unreachable!(),
+    };
+
+    let mut lines = s.lines();
+    assert_eq!(lines.next(), Some("TAP version 14"));
+    assert_eq!(lines.next(), Some("1..2"));
+    assert_eq!(lines.next(), Some("ok 1 - tests::b # SKIP slow"));
+    assert_eq!(lines.next(), Some("not ok 2 - tests::b"));
+    assert_eq!(lines.next(), Some("  ---"));
+    assert_eq!(lines.next(), Some("  message: \"assertion failed: x, y\""));
+    assert_eq!(lines.next(), Some("  severity: fail"));
+    assert_eq!(lines.next(), Some("  at: { file: \"src/lib.rs\", line: 10, column: 5 }"));
+}
+
+#[test]
+fn github_formatter_annotations() {
+    let mut out = GithubFormatter::new(PrettyFormatter::new(
+        OutputLocation::Raw(Vec::new()),
+        false,
+        10,
+        true,
+        None,
+    ));
+    let st = console::ConsoleTestState::new(&TestOpts::new()).unwrap();
+    let desc = failing_test_desc();
+    out.write_result(&desc, &TrOk, None, &[], &st).unwrap();
+    out.write_result(&desc, &TrFailed, None, FAILING_TEST_OUTPUT.as_bytes(), &st).unwrap();
+    out.write_result(&desc, &TrFailedMsg("50%\ndone".into()), None, &[], &st).unwrap();
+    let s = match out.pretty().output_location() {
+        OutputLocation::Raw(m) => String::from_utf8_lossy(&m[..]).into_owned(),
+        OutputLocation::Pretty(_) => unreachable!(),
+    };
+
+    let annotations: Vec<_> = s.lines().filter(|line| line.starts_with("::")).collect();
+    assert_eq!(
+        annotations,
+        [
+            "::error file=src/lib.rs,line=10,col=5,title=test tests%3A%3Ab failed::\
+             assertion failed: x, y",
+            "::error file=src/lib.rs,line=8,col=4,title=test tests%3A%3Ab failed::50%25%0Adone",
+        ]
+    );
+}
+
+#[test]
+fn retries_rerun_failing_static_test() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static RUNS: AtomicUsize = AtomicUsize::new(0);
+    fn f() -> Result<(), String> {
+        if RUNS.fetch_add(1, Ordering::Relaxed) < 2 { Err("flaky".into()) } else { Ok(()) }
+    }
+    let desc = TestDescAndFn { desc: failing_test_desc(), testfn: StaticTestFn(f) };
+    let (tx, rx) = channel();
+    let notify = move |event: TestEvent| {
+        match event {
+            TestEvent::TeRetry(test) => tx.send((true, test.result)).unwrap(),
+            TestEvent::TeResult(test) => tx.send((false, test.result)).unwrap(),
+            _ => {}
+        }
+        Ok(())
+    };
+    let opts = TestOpts { run_tests: true, retries: 3, test_threads: Some(1), ..TestOpts::new() };
+    run_tests(&opts, vec![desc], notify).unwrap();
+    let events: Vec<_> = rx.iter().collect();
+    assert_eq!(events, [(true, TrFailed), (true, TrFailed), (false, TrOk)]);
+}
+
+#[test]
+fn test_timeout_fails_slow_test_in_process() {
+    fn f() -> Result<(), String> {
+        thread::sleep(Duration::from_millis(50));
+        Ok(())
+    }
+    let desc = TestDescAndFn { desc: failing_test_desc(), testfn: StaticTestFn(f) };
+    let opts = TestOpts { test_timeout: Some(Duration::from_millis(1)), ..TestOpts::new() };
+    let (tx, rx) = channel();
+    run_test(&opts, false, TestId(0), desc, RunStrategy::InProcess, tx);
+    assert_eq!(rx.recv().unwrap().result, TrTimedFail);
+}
+
+#[test]
+fn test_timeout_rejects_dynamic_tests() {
+    let desc = TestDescAndFn {
+        desc: failing_test_desc(),
+        testfn: DynTestFn(Box::new(move || Ok(()))),
+    };
+    let opts = TestOpts {
+        run_tests: true,
+        test_timeout: Some(Duration::from_secs(1)),
+        ..TestOpts::new()
+    };
+    let err = run_tests(&opts, vec![desc], |_| Ok(())).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+}
+
+#[test]
+fn junit_escapes_retry_messages() {
+    let mut out = JunitFormatter::new(OutputLocation::Raw(Vec::new()));
+    let st = console::ConsoleTestState::new(&TestOpts::new()).unwrap();
+    let desc = failing_test_desc();
+    let result = TrFailedMsg("expected \"<a>\" & got\nb".into());
+    out.write_retry(&desc, &result, &[], &st).unwrap();
+    out.write_result(&desc, &TrOk, None, &[], &st).unwrap();
+    out.write_run_finish(&st).unwrap();
+    let s = match out.output_location() {
+        OutputLocation::Raw(m) => String::from_utf8_lossy(&m[..]).into_owned(),
+        OutputLocation::Pretty(_) => unreachable!(),
+    };
+
+    assert!(
+        s.contains(
+            "<flakyFailure message=\"expected &quot;&lt;a&gt;&quot; &amp; got&#xA;b\" \
+             type=\"assert\"/>"
+        ),
+        "{s}"
+    );
+}
@@ -834,6 +834,12 @@ tool_check_step!(Compiletest {
     default: false,
 });
 
+tool_check_step!(DepGraphExplorer {
+    path: "src/tools/dep-graph-explorer",
+    mode: |_builder| Mode::ToolRustcPrivate,
+    default: false
+});
+
 tool_check_step!(Linkchecker {
     path: "src/tools/linkchecker",
     mode: |_builder| Mode::ToolBootstrap,
@@ -1762,6 +1762,21 @@ fn copy_codegen_backends_to_sysroot(
     }
 }
 
+/// Copies the locale bundles of translated diagnostics from `src/locales` into
+/// `share/locale` of the given sysroot (or dist image), where `-Z translate-lang`
+/// looks for them.
+pub fn copy_locales_to_sysroot(builder: &Builder<'_>, sysroot: &Path) {
+    let src = builder.src.join("src/locales");
+    if builder.config.dry_run() || !src.exists() {
+        return;
+    }
+
+    let dst = sysroot.join("share/locale");
+    t!(fs::create_dir_all(&dst), dst);
+    // Only the Fluent resources are part of the bundles, not the READMEs.
+    builder.cp_link_filtered(&src, &dst, &|path| path.extension().is_none_or(|ext| ext == "ftl"));
+}
+
 /// Gets the path to a dynamic codegen backend library from its build stamp.
 pub fn get_codegen_backend_file(stamp: &BuildStamp) -> PathBuf {
     PathBuf::from(t!(fs::read_to_string(stamp.path())))
@@ -2306,6 +2321,8 @@ impl Step for Assemble {
             }
         }
 
+        copy_locales_to_sysroot(builder, &sysroot);
+
         if builder.config.lld_enabled {
             let lld_wrapper =
                 builder.ensure(crate::core::build_steps::tool::LldWrapper::for_use_by_compiler(
@@ -587,6 +587,9 @@ impl Step for Rustc {
                 t!(fs::copy(&page_src, &page_dst));
             }
 
+            // Locale bundles of translated diagnostics
+            compile::copy_locales_to_sysroot(builder, image);
+
             // Debugger scripts
             builder.ensure(DebuggerScripts { sysroot: image.to_owned(), target });
 
@@ -449,6 +449,52 @@ impl Step for CoverageDump {
     }
 }
 
+/// Step to run the dep-graph-explorer tool (`./x run dep-graph-explorer`).
+///
+/// The tool can only read the session directories written by the compiler that it links to,
+/// so it is built for the stage of the compiler whose incremental compilation is explored.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct DepGraphExplorer {
+    compilers: RustcPrivateCompilers,
+}
+
+impl Step for DepGraphExplorer {
+    type Output = ();
+
+    const DEFAULT: bool = false;
+    const IS_HOST: bool = true;
+
+    fn should_run(run: ShouldRun<'_>) -> ShouldRun<'_> {
+        run.path("src/tools/dep-graph-explorer")
+    }
+
+    fn make_run(run: RunConfig<'_>) {
+        let builder = run.builder;
+
+        // `x run` uses stage 0 by default, but the stage 0 compiler is not built in-tree.
+        // Change the stage to 1 if it's not set explicitly.
+        let stage = if builder.config.is_explicit_stage() || builder.top_stage >= 1 {
+            builder.top_stage
+        } else {
+            1
+        };
+
+        if stage == 0 {
+            eprintln!("ERROR: dep-graph-explorer cannot be run at stage 0");
+            exit!(1);
+        }
+
+        let compilers = RustcPrivateCompilers::new(builder, stage, builder.host_target);
+        run.builder.ensure(DepGraphExplorer { compilers });
+    }
+
+    fn run(self, builder: &Builder<'_>) {
+        let mut cmd = tool::DepGraphExplorer::command(builder, self.compilers);
+        cmd.args(&builder.config.free_args);
+        cmd.run(builder);
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Rustfmt;
 
@@ -1857,6 +1857,15 @@ NOTE: if you're sure you want to do this, please open an issue as to why. In the
 
                 cmd.arg("--cargo-path").arg(cargo_path);
             }
+
+            // The dep graph explorer can only read the incremental session directories of the
+            // compiler that it links to, so it is built for the compiler under test, which must
+            // be built in-tree.
+            if suite == "run-make" && test_compiler.stage > 0 {
+                let compilers = RustcPrivateCompilers::from_target_compiler(builder, test_compiler);
+                let explorer = builder.ensure(tool::DepGraphExplorer::from_compilers(compilers));
+                cmd.arg("--dep-graph-explorer-path").arg(explorer.tool_path);
+            }
         }
 
         // Avoid depending on rustdoc when we don't need it.
@@ -633,6 +633,71 @@ impl Step for ErrorIndex {
     }
 }
 
+/// The dep-graph-explorer tool, which reads the incremental compilation session directories
+/// written by the compiler that it links to.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct DepGraphExplorer {
+    compilers: RustcPrivateCompilers,
+}
+
+impl DepGraphExplorer {
+    pub fn from_compilers(compilers: RustcPrivateCompilers) -> Self {
+        Self { compilers }
+    }
+
+    pub fn command(builder: &Builder<'_>, compilers: RustcPrivateCompilers) -> BootstrapCommand {
+        // Like the error index generator, the tool needs `rustc_lib_paths` for rustc_private and
+        // libLLVM.so, and `sysroot_lib` for libstd, etc.
+        let mut cmd = command(builder.ensure(DepGraphExplorer { compilers }).tool_path);
+
+        let target_compiler = compilers.target_compiler();
+        let mut dylib_paths = builder.rustc_lib_paths(target_compiler);
+        dylib_paths.push(builder.sysroot_target_libdir(target_compiler, target_compiler.host));
+        add_dylib_path(dylib_paths, &mut cmd);
+        cmd
+    }
+}
+
+impl Step for DepGraphExplorer {
+    type Output = ToolBuildResult;
+
+    fn should_run(run: ShouldRun<'_>) -> ShouldRun<'_> {
+        run.path("src/tools/dep-graph-explorer")
+    }
+
+    fn make_run(run: RunConfig<'_>) {
+        run.builder.ensure(DepGraphExplorer {
+            compilers: RustcPrivateCompilers::new(
+                run.builder,
+                run.builder.top_stage,
+                run.builder.host_target,
+            ),
+        });
+    }
+
+    fn run(self, builder: &Builder<'_>) -> ToolBuildResult {
+        builder.ensure(ToolBuild {
+            build_compiler: self.compilers.build_compiler,
+            target: self.compilers.target(),
+            tool: "dep-graph-explorer",
+            mode: Mode::ToolRustcPrivate,
+            path: "src/tools/dep-graph-explorer",
+            source_type: SourceType::InTree,
+            extra_features: Vec::new(),
+            allow_features: "",
+            cargo_args: Vec::new(),
+            artifact_kind: ToolArtifactKind::Binary,
+        })
+    }
+
+    fn metadata(&self) -> Option<StepMetadata> {
+        Some(
+            StepMetadata::build("dep-graph-explorer", self.compilers.target())
+                .built_by(self.compilers.build_compiler),
+        )
+    }
+}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct RemoteTestServer {
     pub build_compiler: Compiler,
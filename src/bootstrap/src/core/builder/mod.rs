@@ -1010,6 +1010,7 @@ impl<'a> Builder<'a> {
                 tool::RustdocGUITest,
                 tool::OptimizedDist,
                 tool::CoverageDump,
+                tool::DepGraphExplorer,
                 tool::LlvmBitcodeLinker,
                 tool::RustcPerf,
                 tool::WasmComponentLd,
@@ -1061,6 +1062,7 @@ impl<'a> Builder<'a> {
                 check::Compiletest,
                 check::FeaturesStatusDump,
                 check::CoverageDump,
+                check::DepGraphExplorer,
                 check::Linkchecker,
                 check::BumpStage0,
                 // This has special staging logic, it may run on stage 1 while others run on stage 0.
@@ -1223,6 +1225,7 @@ impl<'a> Builder<'a> {
                 run::FeaturesStatusDump,
                 run::CyclicStep,
                 run::CoverageDump,
+                run::DepGraphExplorer,
                 run::Rustfmt,
             ),
             Kind::Setup => {
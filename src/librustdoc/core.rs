@@ -10,6 +10,7 @@ use rustc_errors::emitter::{
     DynEmitter, HumanEmitter, HumanReadableErrorType, OutputTheme, stderr_destination,
 };
 use rustc_errors::json::JsonEmitter;
+use rustc_errors::sarif::SarifEmitter;
 use rustc_feature::UnstableFeatures;
 use rustc_hir::def::Res;
 use rustc_hir::def_id::{DefId, DefIdMap, DefIdSet, LocalDefId};
@@ -187,6 +188,12 @@ pub(crate) fn new_dcx(
                 .terminal_url(TerminalUrl::No),
             )
         }
+        // The compiler session writes the SARIF log for the crate, so these auxiliary contexts
+        // only print one if they actually have something to report.
+        ErrorOutputType::Sarif => Box::new(
+            SarifEmitter::new(Box::new(io::BufWriter::new(io::stderr())), source_map, translator)
+                .write_empty_log(false),
+        ),
     };
 
     rustc_errors::DiagCtxt::new(emitter).with_flags(unstable_opts.dcx_flags(true))
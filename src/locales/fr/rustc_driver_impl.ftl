@@ -0,0 +1,6 @@
+driver_impl_ice = le compilateur a paniqué de manière inattendue. il s'agit d'un bogue.
+driver_impl_ice_bug_report = nous vous serions reconnaissants de signaler ce bogue : {$bug_report_url}
+driver_impl_ice_bug_report_update_note = veuillez vous assurer d'avoir mis à jour vers la dernière version nightly
+driver_impl_ice_flags = options du compilateur : {$flags}
+driver_impl_ice_path = veuillez joindre le fichier `{$path}` à votre signalement de bogue
+driver_impl_ice_version = rustc {$version} exécuté sur {$triple}
@@ -0,0 +1,48 @@
+errors_delayed_at_with_newline =
+    retardé à {$emitted_at}
+    {$note}
+
+errors_delayed_at_without_newline =
+    retardé à {$emitted_at} - {$note}
+
+errors_expected_lifetime_parameter =
+    {$count ->
+        [1] paramètre de durée de vie attendu
+        *[other] paramètres de durée de vie attendus
+    }
+
+errors_indicate_anonymous_lifetime =
+    indiquez {$count ->
+        [1] la durée de vie anonyme
+        *[other] les durées de vie anonymes
+    }
+
+errors_invalid_flushed_delayed_diagnostic_level =
+    `flushed_delayed` a reçu un diagnostic de niveau {$level}, au lieu du niveau `DelayedBug` attendu
+
+errors_target_inconsistent_architecture =
+    spécification de cible incohérente : « data-layout » indique une architecture {$dl}-endian, alors que « target-endian » vaut `{$target}`
+
+errors_target_inconsistent_pointer_width =
+    spécification de cible incohérente : « data-layout » indique des pointeurs de {$pointer_size} bits, alors que « target-pointer-width » vaut `{$target}`
+
+errors_target_invalid_address_space =
+    espace d'adressage `{$addr_space}` invalide pour `{$cause}` dans « data-layout » : {$err}
+
+errors_target_invalid_alignment =
+    alignement invalide pour `{$cause}` dans « data-layout » : `{$align}` {$err_kind ->
+        [not_power_of_two] n'est pas une puissance de 2
+        [too_large] est trop grand
+        *[other] {""}
+    }
+
+errors_target_invalid_bits =
+    {$kind} `{$bit}` invalide pour `{$cause}` dans « data-layout » : {$err}
+
+errors_target_invalid_bits_size = {$err}
+
+errors_target_invalid_datalayout_pointer_spec =
+    spécification de pointeur `{$err}` inconnue dans la chaîne de « datalayout »
+
+errors_target_missing_alignment =
+    alignement manquant pour `{$cause}` dans « data-layout »
@@ -0,0 +1,5 @@
+infer_opaque_hidden_type =
+    le type caché d'un type opaque ne peut pas être un autre type opaque de la même portée
+    .label = l'un des deux types opaques utilisés ici doit être en dehors de sa portée de définition
+    .opaque_type = type opaque dont le type caché est assigné
+    .hidden_type = type opaque utilisé comme type caché
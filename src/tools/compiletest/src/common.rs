@@ -291,6 +291,11 @@ pub struct Config {
     /// Path to the `src/tools/coverage-dump/` bootstrap tool executable.
     pub coverage_dump_path: Option<Utf8PathBuf>,
 
+    /// Path to the `src/tools/dep-graph-explorer/` tool executable, built for the
+    /// `rustc`-under-test so that it can read its incremental session directories. Only
+    /// available to `run-make` tests when that `rustc` is built in-tree.
+    pub dep_graph_explorer_path: Option<Utf8PathBuf>,
+
     /// Path to the Python 3 executable to use for LLDB and htmldocck.
     ///
     /// FIXME: the `lldb` setup currently requires I believe Python 3.10 **exactly**, it can't even
@@ -720,6 +725,7 @@ impl Config {
             query_rustc_path: Default::default(),
             rustdoc_path: Default::default(),
             coverage_dump_path: Default::default(),
+            dep_graph_explorer_path: Default::default(),
             python: Default::default(),
             jsondocck_path: Default::default(),
             jsondoclint_path: Default::default(),
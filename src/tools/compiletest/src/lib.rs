@@ -71,6 +71,12 @@ pub fn parse_config(args: Vec<String>) -> Config {
         )
         .optopt("", "rustdoc-path", "path to rustdoc to use for compiling", "PATH")
         .optopt("", "coverage-dump-path", "path to coverage-dump to use in tests", "PATH")
+        .optopt(
+            "",
+            "dep-graph-explorer-path",
+            "path to dep-graph-explorer to use in run-make tests",
+            "PATH",
+        )
         .reqopt("", "python", "path to python to use for doc tests", "PATH")
         .optopt("", "jsondocck-path", "path to jsondocck to use for doc tests", "PATH")
         .optopt("", "jsondoclint-path", "path to jsondoclint to use for doc tests", "PATH")
@@ -387,6 +393,7 @@ pub fn parse_config(args: Vec<String>) -> Config {
         query_rustc_path: matches.opt_str("query-rustc-path").map(Utf8PathBuf::from),
         rustdoc_path: matches.opt_str("rustdoc-path").map(Utf8PathBuf::from),
         coverage_dump_path: matches.opt_str("coverage-dump-path").map(Utf8PathBuf::from),
+        dep_graph_explorer_path: matches.opt_str("dep-graph-explorer-path").map(Utf8PathBuf::from),
         python: matches.opt_str("python").unwrap(),
         jsondocck_path: matches.opt_str("jsondocck-path"),
         jsondoclint_path: matches.opt_str("jsondoclint-path"),
@@ -738,6 +745,10 @@ fn common_inputs_stamp(config: &Config) -> Stamp {
         stamp.add_path(coverage_dump_path)
     }
 
+    if let Some(dep_graph_explorer_path) = &config.dep_graph_explorer_path {
+        stamp.add_path(dep_graph_explorer_path)
+    }
+
     stamp.add_dir(&src_root.join("src/tools/run-make-support"));
 
     // Compiletest itself.
@@ -124,6 +124,15 @@ impl<'test> TestCx<'test> {
             }
 
             cmd.args(&self.props.llvm_cov_flags);
+
+            // Tests that enable MC/DC instrumentation should always have their
+            // MC/DC report checked by the snapshot, so that changes to the
+            // recorded test vectors are noticed.
+            if self.uses_mcdc_coverage()
+                && !self.props.llvm_cov_flags.iter().any(|flag| flag == "--show-mcdc")
+            {
+                cmd.arg("--show-mcdc");
+            }
         });
         if !proc_res.status.success() {
             self.fatal_proc_rec("llvm-cov show failed!", &proc_res);
@@ -152,6 +161,17 @@ impl<'test> TestCx<'test> {
         }
     }
 
+    /// Returns true if this test's compiler flags enable MC/DC coverage
+    /// instrumentation, via `-Zcoverage-options=mcdc`.
+    fn uses_mcdc_coverage(&self) -> bool {
+        self.props
+            .compile_flags
+            .iter()
+            .filter_map(|flag| flag.split_once("coverage-options="))
+            .flat_map(|(_, options)| options.split(','))
+            .any(|option| option == "mcdc")
+    }
+
     /// Run any doctests embedded in this test file, and add any resulting
     /// `.profraw` files and doctest executables to the given vectors.
     fn run_doctests_for_coverage(
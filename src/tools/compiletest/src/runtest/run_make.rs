@@ -202,6 +202,10 @@ impl TestCx<'_> {
             cmd.env("RUSTDOC", rustdoc);
         }
 
+        if let Some(ref dep_graph_explorer) = self.config.dep_graph_explorer_path {
+            cmd.env("DEP_GRAPH_EXPLORER", dep_graph_explorer);
+        }
+
         if let Some(ref node) = self.config.nodejs {
             cmd.env("NODE", node);
         }
@@ -71,6 +71,7 @@ pub(crate) fn dump_covfun_mappings(
         }
 
         let mut max_counter = None;
+        let mut mcdc_checker = MCDCChecker::default();
         for i in 0..num_files {
             let num_mappings = parser.read_uleb128_u32()?;
             println!("Number of file {i} mappings: {num_mappings}");
@@ -78,6 +79,7 @@ pub(crate) fn dump_covfun_mappings(
             for _ in 0..num_mappings {
                 let (kind, region) = parser.read_mapping_kind_and_region()?;
                 println!("- {kind:?} at {region:?}");
+                mcdc_checker.visit(&kind)?;
                 kind.for_each_term(|term| {
                     if let CovTerm::Counter(n) = term {
                         max_counter = max_counter.max(Some(n));
@@ -103,6 +105,7 @@ pub(crate) fn dump_covfun_mappings(
         }
 
         parser.ensure_empty()?;
+        mcdc_checker.finish()?;
 
         // Printing the highest counter ID seen in the functions mappings makes
         // it easier to determine whether a change to coverage instrumentation
@@ -121,6 +124,55 @@ pub(crate) fn dump_covfun_mappings(
     Ok(())
 }
 
+/// Checks that a function's MC/DC decision and branch regions are consistent
+/// with each other, so that `llvm-cov` will be able to make sense of them.
+#[derive(Default)]
+struct MCDCChecker {
+    /// Total number of conditions declared by MC/DC decision regions.
+    num_decision_conditions: u32,
+    /// Largest number of conditions declared by a single decision region.
+    max_decision_conditions: u32,
+    num_branches: u32,
+}
+
+impl MCDCChecker {
+    fn visit(&mut self, kind: &MappingKind) -> anyhow::Result<()> {
+        match *kind {
+            MappingKind::MCDCDecision { conditions_num, .. } => {
+                ensure!(conditions_num > 0, "MC/DC decision has no conditions");
+                self.num_decision_conditions += conditions_num;
+                self.max_decision_conditions = self.max_decision_conditions.max(conditions_num);
+            }
+            MappingKind::MCDCBranch { condition_id, true_next_id, false_next_id, .. } => {
+                // Condition IDs are encoded as their real value plus 1, with 0
+                // meaning "no next condition", so a branch's own ID can't be 0.
+                ensure!(condition_id != 0, "MC/DC branch has no condition ID");
+                ensure!(
+                    condition_id != true_next_id && condition_id != false_next_id,
+                    "MC/DC branch {condition_id} refers to itself as a next condition"
+                );
+                self.num_branches += 1;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> anyhow::Result<()> {
+        let Self { num_decision_conditions, max_decision_conditions, num_branches } = self;
+        ensure!(
+            num_decision_conditions == num_branches,
+            "MC/DC decisions have {num_decision_conditions} conditions in total, \
+             but there are {num_branches} MC/DC branch regions"
+        );
+        if num_branches > 0 {
+            println!("MC/DC decision conditions: {num_decision_conditions}");
+            println!("Largest MC/DC decision: {max_decision_conditions} conditions");
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 struct CovfunLineData {
     is_used: bool,
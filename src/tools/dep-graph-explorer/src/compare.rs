@@ -0,0 +1,194 @@
+//! Comparing the dep graphs of two consecutive sessions, to find out what the red-green
+//! algorithm did with the nodes of the previous session.
+
+use std::cell::RefCell;
+use std::path::Path;
+
+use rustc_data_structures::fingerprint::Fingerprint;
+use rustc_data_structures::fx::FxHashMap;
+use rustc_query_system::dep_graph::SerializedDepNodeIndex;
+
+use crate::session::Session;
+
+/// What happened to a node of the previous session in the next session.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Status {
+    /// The node is not in the dep graph of the next session. Either it was not needed anymore,
+    /// or nothing tried to mark it green before the nodes that depended on it were re-executed.
+    Removed,
+    /// The node was re-executed and its result changed.
+    Red,
+    /// The node was re-executed, but its result is not hashed, like the results of the queries
+    /// marked `no_hash`. Whether it changed is unknown, so it is red like the compiler treats it.
+    Unhashed,
+    /// The node was re-executed because a dependency changed or because it is `eval_always`,
+    /// but its result didn't change.
+    Reexecuted,
+    /// The node was marked green without being re-executed.
+    Green,
+}
+
+impl Status {
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            Status::Removed => "removed",
+            Status::Red => "red",
+            Status::Unhashed => "red (unhashed)",
+            Status::Reexecuted => "green (re-executed)",
+            Status::Green => "green",
+        }
+    }
+}
+
+pub(crate) struct Comparison {
+    pub(crate) prev: Session,
+    pub(crate) next: Session,
+    /// Whether the nodes of the previous session changed, see [`Comparison::changed`].
+    changed: RefCell<FxHashMap<SerializedDepNodeIndex, bool>>,
+}
+
+impl Comparison {
+    pub(crate) fn load(prev: &Path, next: &Path) -> anyhow::Result<Comparison> {
+        let comparison = Comparison {
+            prev: Session::load(prev)?,
+            next: Session::load(next)?,
+            changed: Default::default(),
+        };
+        if comparison.prev.commandline_args_hash != comparison.next.commandline_args_hash {
+            eprintln!(
+                "warning: the sessions were compiled with different command-line arguments, so \
+                 the next session didn't start from the dep graph of the previous one"
+            );
+        } else if comparison.next.graph.session_count() != comparison.prev.graph.session_count() + 1
+        {
+            eprintln!(
+                "warning: the next session didn't directly follow the previous one, it is \
+                 session {} of the directory and the previous one is session {}",
+                comparison.next.graph.session_count(),
+                comparison.prev.graph.session_count(),
+            );
+        }
+        Ok(comparison)
+    }
+
+    /// Returns the index in the next session of a node of the previous session.
+    pub(crate) fn in_next(&self, prev: SerializedDepNodeIndex) -> Option<SerializedDepNodeIndex> {
+        self.next.graph.node_to_index_opt(&self.prev.node(prev))
+    }
+
+    /// Returns the index in the previous session of a node of the next session.
+    pub(crate) fn in_prev(&self, next: SerializedDepNodeIndex) -> Option<SerializedDepNodeIndex> {
+        self.prev.graph.node_to_index_opt(&self.next.node(next))
+    }
+
+    /// Returns whether the result of a node of the previous session is not hashed. Such nodes
+    /// have a zero fingerprint in both sessions.
+    fn unhashed(&self, prev: SerializedDepNodeIndex) -> bool {
+        self.prev.graph.fingerprint_by_index(prev) == Fingerprint::ZERO
+    }
+
+    /// Returns whether a node of the previous session is `eval_always`, so it was re-executed in
+    /// the next session regardless of its dependencies.
+    fn eval_always(&self, prev: SerializedDepNodeIndex) -> bool {
+        self.prev.is_eval_always(self.prev.node(prev))
+    }
+
+    /// Returns whether a node of the previous session is missing from the next session or has
+    /// another result there, which is what makes the nodes that depend on it re-executed.
+    ///
+    /// An unhashed node counts as changed whenever it was re-executed, like in the compiler.
+    /// Whether it was re-executed depends on its dependencies, and chains of them can be longer
+    /// than the stack allows to recurse, so they are visited with a worklist in post-order.
+    fn changed(&self, prev: SerializedDepNodeIndex) -> bool {
+        // The nodes being visited, with the number of their dependencies known to be unchanged.
+        let mut stack = vec![(prev, 0)];
+        while let Some((node, unchanged_deps)) = stack.last_mut() {
+            let node = *node;
+            if self.changed.borrow().contains_key(&node) {
+                stack.pop();
+                continue;
+            }
+            let changed = match self.in_next(node) {
+                Some(_) if self.unhashed(node) && self.eval_always(node) => true,
+                Some(_) if self.unhashed(node) => {
+                    // The dependencies are checked in order, up to the first changed one. One that
+                    // wasn't visited yet is visited first, and then this node again.
+                    let mut changed = false;
+                    let mut unvisited = None;
+                    for dep in self.prev.graph.edge_targets_from(node).skip(*unchanged_deps) {
+                        match self.changed.borrow().get(&dep) {
+                            Some(false) => *unchanged_deps += 1,
+                            Some(true) => {
+                                changed = true;
+                                break;
+                            }
+                            None => {
+                                unvisited = Some(dep);
+                                break;
+                            }
+                        }
+                    }
+                    if let Some(dep) = unvisited {
+                        stack.push((dep, 0));
+                        continue;
+                    }
+                    changed
+                }
+                Some(next) => {
+                    self.prev.graph.fingerprint_by_index(node)
+                        != self.next.graph.fingerprint_by_index(next)
+                }
+                None => true,
+            };
+            self.changed.borrow_mut().insert(node, changed);
+            stack.pop();
+        }
+        self.changed.borrow()[&prev]
+    }
+
+    pub(crate) fn status(&self, prev: SerializedDepNodeIndex) -> Status {
+        if self.in_next(prev).is_none() {
+            Status::Removed
+        } else if self.changed(prev) && self.unhashed(prev) {
+            Status::Unhashed
+        } else if self.changed(prev) {
+            Status::Red
+        } else if self.eval_always(prev) || self.first_changed_dep(prev).is_some() {
+            Status::Reexecuted
+        } else {
+            Status::Green
+        }
+    }
+
+    /// Returns the first dependency of a node of the previous session that changed in the next
+    /// session. The red-green algorithm checks the dependencies in order and stops at the first
+    /// changed one, so this is the dependency that made the node re-executed.
+    ///
+    /// A node that changed without a changed dependency is an input of the compilation, like
+    /// the source of an item, or a node that is executed in every session.
+    pub(crate) fn first_changed_dep(
+        &self,
+        prev: SerializedDepNodeIndex,
+    ) -> Option<SerializedDepNodeIndex> {
+        self.prev.graph.edge_targets_from(prev).find(|&dep| self.changed(dep))
+    }
+
+    /// Returns the path from a changed input to a node of the previous session, following the
+    /// first changed dependency of each node. The path starts with a node that was removed or
+    /// that changed without a changed dependency, and ends with the node.
+    pub(crate) fn path_from_input(
+        &self,
+        prev: SerializedDepNodeIndex,
+    ) -> Vec<SerializedDepNodeIndex> {
+        let mut path = vec![prev];
+        let mut node = prev;
+        while self.in_next(node).is_some()
+            && let Some(dep) = self.first_changed_dep(node)
+        {
+            path.push(dep);
+            node = dep;
+        }
+        path.reverse();
+        path
+    }
+}
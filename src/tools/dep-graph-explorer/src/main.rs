@@ -0,0 +1,196 @@
+//! A tool to explore the dep graphs that incremental compilation saves in its session
+//! directories, and to find out why a session re-executed queries of the previous one.
+
+#![feature(rustc_private)]
+
+extern crate rustc_data_structures;
+extern crate rustc_driver;
+extern crate rustc_feature;
+extern crate rustc_hashes;
+extern crate rustc_incremental;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate rustc_query_impl;
+extern crate rustc_query_system;
+extern crate rustc_serialize;
+
+mod compare;
+mod session;
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::bail;
+use rustc_query_system::dep_graph::SerializedDepNodeIndex;
+
+use crate::compare::{Comparison, Status};
+use crate::session::Session;
+
+const USAGE: &str = "\
+usage: dep-graph-explorer show <session-dir>
+       dep-graph-explorer diff <previous-session-dir> <next-session-dir>
+       dep-graph-explorer why <previous-session-dir> <next-session-dir> <node>
+
+<node> is a dep kind, like `typeck`, or the beginning of a node as printed by this tool,
+like `typeck(4e1`.";
+
+fn main() -> anyhow::Result<()> {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    match args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        ["show", dir] => show(&Session::load(Path::new(dir))?),
+        ["diff", prev, next] => diff(&Comparison::load(Path::new(prev), Path::new(next))?),
+        ["why", prev, next, node] => {
+            why(&Comparison::load(Path::new(prev), Path::new(next))?, node)
+        }
+        _ => bail!("{USAGE}"),
+    }
+}
+
+/// Prints the number of nodes of each dep kind in a session, and how many of them have a cached
+/// result or side effects.
+fn show(session: &Session) -> anyhow::Result<()> {
+    let mut kinds = BTreeMap::<&str, [usize; 3]>::new();
+    for index in session.nodes() {
+        let counts = kinds.entry(session.kind_name(session.node(index))).or_default();
+        counts[0] += 1;
+        counts[1] += session.cached_results.contains(&index) as usize;
+        counts[2] += session.side_effects.contains(&index) as usize;
+    }
+
+    println!("session {} of the directory", session.graph.session_count());
+    print_table(["nodes", "cached", "side effects"], kinds);
+    Ok(())
+}
+
+/// Prints what happened to the nodes of each dep kind in the next session, and the inputs that
+/// changed since the previous session.
+fn diff(comparison: &Comparison) -> anyhow::Result<()> {
+    let Comparison { prev, next, .. } = comparison;
+    let mut kinds = BTreeMap::<&str, [usize; 6]>::new();
+    let mut inputs = Vec::new();
+    for index in prev.nodes() {
+        let status = comparison.status(index);
+        let column = match status {
+            Status::Removed => 1,
+            Status::Red => 2,
+            Status::Unhashed => 3,
+            Status::Reexecuted => 4,
+            Status::Green => 5,
+        };
+        kinds.entry(prev.kind_name(prev.node(index))).or_default()[column] += 1;
+        if status == Status::Red && comparison.first_changed_dep(index).is_none() {
+            inputs.push(prev.display(prev.node(index)));
+        }
+    }
+    for index in next.nodes().filter(|&index| comparison.in_prev(index).is_none()) {
+        kinds.entry(next.kind_name(next.node(index))).or_default()[0] += 1;
+    }
+
+    print_table(
+        ["added", "removed", "red", "red (unhashed)", "green (re-executed)", "green"],
+        kinds,
+    );
+    if !inputs.is_empty() {
+        inputs.sort();
+        println!("\nchanged inputs:");
+        for input in inputs {
+            println!("    {input}");
+        }
+    }
+    Ok(())
+}
+
+/// Explains why the nodes that match `pattern` were executed in the next session or not.
+fn why(comparison: &Comparison, pattern: &str) -> anyhow::Result<()> {
+    let Comparison { prev, next, .. } = comparison;
+    // The nodes of the next session, and the ones of the previous session that it doesn't have.
+    let mut nodes: Vec<_> = next
+        .find(pattern)
+        .into_iter()
+        .map(|index| (next.display(next.node(index)), comparison.in_prev(index)))
+        .collect();
+    nodes.extend(
+        prev.find(pattern)
+            .into_iter()
+            .filter(|&index| comparison.in_next(index).is_none())
+            .map(|index| (prev.display(prev.node(index)), Some(index))),
+    );
+    if nodes.is_empty() {
+        bail!("no node matches `{pattern}` in the sessions");
+    }
+    nodes.sort();
+
+    for (i, (node, prev_index)) in nodes.into_iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        let Some(prev_index) = prev_index else {
+            println!("{node}: added");
+            println!("  it was not in the previous session, so it was executed");
+            continue;
+        };
+        let status = comparison.status(prev_index);
+        println!("{node}: {}", status.label());
+        match status {
+            Status::Removed => println!("  it was not needed by the next session"),
+            Status::Green if prev.cached_results.contains(&prev_index) => {
+                println!("  its result was loaded from the query result cache")
+            }
+            Status::Green => println!(
+                "  its result is not in the query result cache, so it was recomputed if it was \
+                 needed"
+            ),
+            Status::Unhashed => {
+                println!("  its result is not hashed, so it is red whenever it is re-executed");
+                print_path(comparison, prev_index)
+            }
+            Status::Reexecuted if prev.is_eval_always(prev.node(prev_index)) => {
+                println!("  it is `eval_always`, so it is re-executed in every session")
+            }
+            Status::Red | Status::Reexecuted => print_path(comparison, prev_index),
+        }
+    }
+    Ok(())
+}
+
+/// Prints the path from a changed input to a re-executed node of the previous session.
+fn print_path(comparison: &Comparison, prev_index: SerializedDepNodeIndex) {
+    let prev = &comparison.prev;
+    let path = comparison.path_from_input(prev_index);
+    if path.len() == 1 {
+        println!("  none of its dependencies changed, so it is an input or it is always executed");
+        return;
+    }
+    println!("  it was re-executed because of this path of first changed dependencies:");
+    for index in path {
+        let status = comparison.status(index);
+        println!("    {:<19}  {}", status.label(), prev.display(prev.node(index)));
+    }
+}
+
+/// Prints the counts of each dep kind and their totals, in columns named after `columns`.
+fn print_table<const N: usize>(columns: [&str; N], kinds: BTreeMap<&str, [usize; N]>) {
+    let mut total = [0; N];
+    for counts in kinds.values() {
+        for (total, count) in total.iter_mut().zip(counts) {
+            *total += count;
+        }
+    }
+    let rows = kinds.iter().map(|(kind, counts)| (*kind, counts)).chain([("total", &total)]);
+    let width = kinds.keys().map(|kind| kind.len()).max().unwrap_or(0).max("total".len());
+    let widths = columns.map(|column| column.len());
+    let widths = std::array::from_fn::<_, N, _>(|i| widths[i].max(total[i].to_string().len()));
+
+    print!("{:<width$}", "kind");
+    for (column, width) in columns.iter().zip(widths) {
+        print!("  {column:>width$}");
+    }
+    println!();
+    for (kind, counts) in rows {
+        print!("{kind:<width$}");
+        for (count, width) in counts.iter().zip(widths) {
+            print!("  {count:>width$}");
+        }
+        println!();
+    }
+}
@@ -0,0 +1,127 @@
+//! Loading the dep graph and the query result cache of an incremental session directory.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{Context, bail};
+use rustc_data_structures::fx::FxHashSet;
+use rustc_data_structures::memmap::Mmap;
+use rustc_feature::UnstableFeatures;
+use rustc_hashes::Hash64;
+use rustc_incremental::{DEP_GRAPH_FILENAME, QUERY_CACHE_FILENAME, read_incr_comp_file};
+use rustc_middle::arena::Arena;
+use rustc_middle::dep_graph::DepsType;
+use rustc_middle::query::on_disk_cache::OnDiskCache;
+use rustc_query_system::dep_graph::{DepNode, Deps, SerializedDepGraph, SerializedDepNodeIndex};
+use rustc_serialize::Decodable;
+use rustc_serialize::opaque::MemDecoder;
+
+/// The data that a compilation session left in its session directory.
+pub(crate) struct Session {
+    pub(crate) graph: Arc<SerializedDepGraph>,
+    deps: DepsType,
+    /// Whether the nodes of each dep kind are executed in every session, by dep kind.
+    eval_always: Vec<bool>,
+    /// The hash of the command-line arguments that affect the dep graph. A session only starts
+    /// from the dep graph of the previous one if they have the same hash.
+    pub(crate) commandline_args_hash: Hash64,
+    /// The nodes whose result is in the query result cache.
+    pub(crate) cached_results: FxHashSet<SerializedDepNodeIndex>,
+    /// The nodes that have side effects, like diagnostics, in the query result cache.
+    pub(crate) side_effects: FxHashSet<SerializedDepNodeIndex>,
+}
+
+impl Session {
+    /// Loads the files of a session directory, like `incremental/crate-xxx/s-xxx-xxx`, which
+    /// must have been written by the same compiler version as this tool.
+    pub(crate) fn load(dir: &Path) -> anyhow::Result<Session> {
+        let deps = DepsType { dep_names: rustc_query_impl::dep_kind_names() };
+        let arena = Arena::default();
+        let eval_always = rustc_query_impl::query_callbacks(&arena)
+            .iter()
+            .map(|kind| kind.is_eval_always)
+            .collect();
+
+        let (data, start_pos) = read_file(&dir.join(DEP_GRAPH_FILENAME))?
+            .with_context(|| format!("`{}` has no dep graph", dir.display()))?;
+        let Ok(mut decoder) = MemDecoder::new(&data, start_pos) else {
+            bail!("the dep graph of `{}` is truncated", dir.display());
+        };
+        let commandline_args_hash = Hash64::decode(&mut decoder);
+        let graph = SerializedDepGraph::decode::<DepsType>(&mut decoder, &deps);
+
+        // A session that failed before it saved the query result cache still has a dep graph.
+        let mut session = Session {
+            graph,
+            deps,
+            eval_always,
+            commandline_args_hash,
+            cached_results: Default::default(),
+            side_effects: Default::default(),
+        };
+        if let Some((data, start_pos)) = read_file(&dir.join(QUERY_CACHE_FILENAME))? {
+            let Ok(cached) = OnDiskCache::read_cached_dep_nodes(&data, start_pos) else {
+                bail!("the query result cache of `{}` is truncated", dir.display());
+            };
+            session.cached_results = cached.query_results.into_iter().collect();
+            session.side_effects = cached.side_effects.into_iter().collect();
+        }
+        Ok(session)
+    }
+
+    /// Returns the indices of the nodes of the graph, skipping the unused ones.
+    pub(crate) fn nodes(&self) -> impl Iterator<Item = SerializedDepNodeIndex> {
+        (0..self.graph.node_count())
+            .map(SerializedDepNodeIndex::from_usize)
+            .filter(|&index| self.graph.index_to_node(index).kind != DepsType::DEP_KIND_NULL)
+    }
+
+    pub(crate) fn node(&self, index: SerializedDepNodeIndex) -> DepNode {
+        self.graph.index_to_node(index)
+    }
+
+    pub(crate) fn kind_name(&self, node: DepNode) -> &'static str {
+        self.deps.name(node.kind)
+    }
+
+    /// Returns whether a node is of an `eval_always` kind, which is executed in every session
+    /// instead of being marked green.
+    pub(crate) fn is_eval_always(&self, node: DepNode) -> bool {
+        self.eval_always[node.kind.as_usize()]
+    }
+
+    /// Displays a node as `kind(hash)`, the form that [`Session::find`] accepts.
+    pub(crate) fn display(&self, node: DepNode) -> String {
+        format!("{}({})", self.kind_name(node), node.hash)
+    }
+
+    /// Returns the nodes whose kind is `pattern`, or whose displayed form starts with `pattern`,
+    /// so that `typeck` matches all the `typeck` nodes and `typeck(4e1` only some of them.
+    pub(crate) fn find(&self, pattern: &str) -> Vec<SerializedDepNodeIndex> {
+        self.nodes()
+            .filter(|&index| {
+                let node = self.node(index);
+                self.kind_name(node) == pattern
+                    || (pattern.contains('(') && self.display(node).starts_with(pattern))
+            })
+            .collect()
+    }
+}
+
+fn read_file(path: &Path) -> anyhow::Result<Option<(Mmap, usize)>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let is_nightly_build = UnstableFeatures::from_environment(None).is_nightly_build();
+    let version = rustc_interface::util::rustc_version_str().unwrap_or("unknown version");
+    let file = read_incr_comp_file(path, is_nightly_build, version)
+        .with_context(|| format!("couldn't read `{}`", path.display()))?;
+    if file.is_none() {
+        bail!(
+            "`{}` was not written by this compiler version ({version}); the tool must be built \
+             with the compiler that wrote the session directory",
+            path.display()
+        );
+    }
+    Ok(file)
+}
@@ -0,0 +1,18 @@
+use crate::command::Command;
+use crate::util::set_host_compiler_dylib_path;
+
+/// Returns a command that runs the in-tree `dep-graph-explorer`, which reads the incremental
+/// session directories of the `rustc` under test. It is provided by compiletest through the
+/// `DEP_GRAPH_EXPLORER` env var, and is only available if that `rustc` is built in-tree.
+#[track_caller]
+#[must_use]
+pub fn dep_graph_explorer() -> Command {
+    let explorer = std::env::var("DEP_GRAPH_EXPLORER").unwrap_or_else(|e| {
+        panic!("`dep-graph-explorer` is only available with an in-tree `rustc`: {e}")
+    });
+
+    let mut cmd = Command::new(explorer);
+    // The tool links to the `rustc_driver` of the `rustc` under test.
+    set_host_compiler_dylib_path(&mut cmd);
+    cmd
+}
@@ -5,6 +5,7 @@ pub mod c_build;
 pub mod c_cxx_compiler;
 pub mod cargo;
 pub mod clang;
+pub mod dep_graph_explorer;
 pub mod htmldocck;
 pub mod llvm;
 pub mod python;
@@ -36,6 +36,9 @@ pub mod rfs {
 // Re-exports of third-party library crates.
 pub use {bstr, gimli, libc, object, regex, serde_json, similar, wasmparser};
 
+// Re-export of the reader of the snapshots of `-Z emit=public-mir`.
+pub use rustc_public_mir;
+
 // Helpers for building names of output artifacts that are potentially target-specific.
 pub use crate::artifact_names::{
     bin_name, dynamic_lib_extension, dynamic_lib_name, msvc_import_dynamic_lib_name, rust_lib_name,
@@ -61,6 +64,7 @@ pub use crate::external_deps::c_cxx_compiler::{
 };
 pub use crate::external_deps::cargo::cargo;
 pub use crate::external_deps::clang::{Clang, clang};
+pub use crate::external_deps::dep_graph_explorer::dep_graph_explorer;
 pub use crate::external_deps::htmldocck::htmldocck;
 pub use crate::external_deps::llvm::{
     self, LlvmAr, LlvmBcanalyzer, LlvmDis, LlvmDwarfdump, LlvmFilecheck, LlvmNm, LlvmObjcopy,
@@ -332,6 +332,7 @@ const PERMITTED_RUSTC_DEPENDENCIES: &[&str] = &[
     "arrayref",
     "arrayvec",
     "autocfg",
+    "bincode",
     "bitflags",
     "blake3",
     "block-buffer",
@@ -0,0 +1,107 @@
+//! Checks the translations of the locale bundles in `src/locales` against the Fluent messages of
+//! the compiler.
+//!
+//! Each `src/locales/$locale/$crate.ftl` file translates the messages of
+//! `compiler/$crate/messages.ftl`. Messages and attributes which don't exist (anymore) in the
+//! compiler are stale, and are errors. Translations may be incomplete, as the missing messages fall
+//! back to English, so messages which haven't been translated yet are only reported.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+use fluent_syntax::ast::Entry;
+
+/// The message IDs of a Fluent resource, with the attributes of each message.
+fn messages(filename: &str, contents: &str, bad: &mut bool) -> BTreeMap<String, BTreeSet<String>> {
+    let parse = match fluent_syntax::parser::parse(contents) {
+        Ok(parse) => parse,
+        Err((parse, errs)) => {
+            for err in errs {
+                tidy_error!(bad, "{filename}: failed to parse message: {err}");
+            }
+            parse
+        }
+    };
+    parse
+        .body
+        .iter()
+        .filter_map(|entry| match entry {
+            Entry::Message(m) => Some((
+                m.id.name.to_owned(),
+                m.attributes.iter().map(|attr| attr.id.name.to_owned()).collect(),
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+fn check_locale(root_path: &Path, locale_path: &Path, verbose: bool, bad: &mut bool) {
+    let locale = locale_path.file_name().unwrap().to_string_lossy();
+    let mut translated = 0;
+    let mut missing = vec![];
+
+    for entry in t!(fs::read_dir(locale_path)) {
+        let path = t!(entry).path();
+        let filename = path.strip_prefix(root_path).unwrap().display().to_string();
+        if path.extension().is_none_or(|ext| ext != "ftl") {
+            // READMEs and such are fine, but anything else is likely a mistake.
+            if path.extension().is_none_or(|ext| ext != "md") {
+                tidy_error!(bad, "{filename}: locale bundles may only contain `.ftl` files");
+            }
+            continue;
+        }
+
+        let krate = path.file_stem().unwrap().to_string_lossy();
+        let source_path = root_path.join("compiler").join(&*krate).join("messages.ftl");
+        let Ok(source) = fs::read_to_string(&source_path) else {
+            tidy_error!(bad, "{filename}: `compiler/{krate}/messages.ftl` does not exist");
+            continue;
+        };
+
+        let source_messages = messages(&source_path.display().to_string(), &source, bad);
+        let locale_messages = messages(&filename, &t!(fs::read_to_string(&path)), bad);
+
+        for (id, attrs) in &locale_messages {
+            let Some(source_attrs) = source_messages.get(id) else {
+                tidy_error!(bad, "{filename}: message `{id}` is stale, it is not in `{krate}`");
+                continue;
+            };
+            for attr in attrs.difference(source_attrs) {
+                tidy_error!(bad, "{filename}: attribute `{id}.{attr}` is stale, not in `{krate}`");
+            }
+        }
+
+        for (id, source_attrs) in &source_messages {
+            let Some(attrs) = locale_messages.get(id) else {
+                missing.push(id.clone());
+                continue;
+            };
+            translated += 1;
+            missing.extend(source_attrs.difference(attrs).map(|attr| format!("{id}.{attr}")));
+        }
+    }
+
+    // Missing translations are expected, so only report them when asked to.
+    if verbose && !missing.is_empty() {
+        let missing_count = missing.len();
+        println!("locale `{locale}`: {translated} messages translated, {missing_count} missing");
+        for id in missing {
+            println!("  missing: `{id}`");
+        }
+    }
+}
+
+pub fn check(root_path: &Path, verbose: bool, bad: &mut bool) {
+    let locales_path = root_path.join("src").join("locales");
+    if !locales_path.exists() {
+        return;
+    }
+
+    for entry in t!(fs::read_dir(&locales_path)) {
+        let path = t!(entry).path();
+        if path.is_dir() {
+            check_locale(root_path, &path, verbose, bad);
+        }
+    }
+}
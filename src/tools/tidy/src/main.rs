@@ -122,6 +122,7 @@ fn main() {
         check!(fluent_alphabetical, &compiler_path, bless);
         check!(fluent_period, &compiler_path);
         check!(fluent_lowercase, &compiler_path);
+        check!(fluent_locales, &root_path, verbose);
         check!(target_policy, &root_path);
         check!(gcc_submodule, &root_path, &compiler_path);
 
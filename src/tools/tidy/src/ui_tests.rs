@@ -163,6 +163,7 @@ fn check_unexpected_extension(bad: &mut bool, file_path: &Path, ext: &str) {
         "tests/ui/proc-macro/auxiliary/included-file.txt", // more include
         "tests/ui/unpretty/auxiliary/data.txt", // more include
         "tests/ui/invalid/foo.natvis.xml", // sample debugger visualizer
+        "tests/ui/mir/mir-profile-use.txt", // function entry counts for `-Z mir-profile-use`
         "tests/ui/sanitizer/dataflow-abilist.txt", // dataflow sanitizer ABI list file
         "tests/ui/shell-argfiles/shell-argfiles.args", // passing args via a file
         "tests/ui/shell-argfiles/shell-argfiles-badquotes.args", // passing args via a file
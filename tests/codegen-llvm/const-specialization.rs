@@ -0,0 +1,19 @@
+//@ compile-flags: -Copt-level=3 -Zmir-opt-level=3
+
+// Check that a call whose arguments are constants is redirected to the clone of the callee for
+// these constants, whichever symbol mangling is used.
+
+#![crate_type = "lib"]
+
+#[inline(never)]
+fn scramble(x: u32, flip: bool) -> u32 {
+    if flip { !x } else { x ^ 0x5a5a }
+}
+
+// CHECK-LABEL: @caller(
+#[no_mangle]
+pub fn caller(x: u32) -> u32 {
+    // CHECK: call {{.*}}scramble{{.*}}const
+    // CHECK-NEXT: ret i32
+    scramble(x, true)
+}
@@ -0,0 +1,17 @@
+//@ compile-flags:-Zmir-opt-level=3
+//@ compile-flags:--crate-type=lib
+
+// Check that a function that is always called with the same constant argument gets a clone for
+// that constant, next to the original.
+
+//~ MONO_ITEM fn scramble
+//~ MONO_ITEM fn scramble - shim(const#0)
+#[inline(never)]
+fn scramble(x: u32, flip: bool) -> u32 {
+    if flip { !x } else { x ^ 0x5a5a }
+}
+
+//~ MONO_ITEM fn caller
+pub fn caller(x: u32) -> u32 {
+    scramble(x, true)
+}
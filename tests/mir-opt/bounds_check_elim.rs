@@ -0,0 +1,42 @@
+//@ test-mir-pass: BoundsCheckElimination
+// EMIT_MIR_FOR_EACH_PANIC_STRATEGY
+
+#![crate_type = "lib"]
+
+// EMIT_MIR bounds_check_elim.masked.BoundsCheckElimination.diff
+pub fn masked(a: &[u32; 16], i: usize) -> u32 {
+    // CHECK-LABEL: fn masked(
+    // CHECK: [[index:_.*]] = BitAnd({{.*}}, const 15_usize);
+    // CHECK: Lt(copy [[index]], const 16_usize);
+    // CHECK-NOT: assert(
+    // CHECK: goto -> bb1;
+    // CHECK: (*_1)[[[index]]];
+    a[i & 15]
+}
+
+// The index is masked, but then written to, so its range is lost.
+// EMIT_MIR bounds_check_elim.index_written.BoundsCheckElimination.diff
+pub fn index_written(a: &[u32; 16], i: usize, j: usize) -> u32 {
+    // CHECK-LABEL: fn index_written(
+    // CHECK: debug k => [[k:_.*]];
+    // CHECK: [[k]] = Add(
+    // CHECK: [[index:_.*]] = copy [[k]];
+    // CHECK: assert({{.*}}, "index out of bounds: {{.*}}", const 16_usize, copy [[index]])
+    let mut k = i & 15;
+    k += j;
+    a[k]
+}
+
+// The index is masked, but it may be modified through a reference to it, so it is not tracked.
+// EMIT_MIR bounds_check_elim.address_taken.BoundsCheckElimination.diff
+pub fn address_taken(a: &[u32; 16], i: usize, j: usize) -> u32 {
+    // CHECK-LABEL: fn address_taken(
+    // CHECK: debug k => [[k:_.*]];
+    // CHECK: (*{{_.*}}) = copy _3;
+    // CHECK: [[index:_.*]] = copy [[k]];
+    // CHECK: assert({{.*}}, "index out of bounds: {{.*}}", const 16_usize, copy [[index]])
+    let mut k = i & 15;
+    let p = &mut k;
+    *p = j;
+    a[k]
+}
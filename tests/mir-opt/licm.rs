@@ -0,0 +1,128 @@
+//@ test-mir-pass: LoopInvariantCodeMotion
+
+#![feature(custom_mir, core_intrinsics)]
+#![crate_type = "lib"]
+
+use std::intrinsics::mir::*;
+
+// The loop is entered from a `switchInt`, so a preheader is inserted in front of it.
+// EMIT_MIR licm.hoist.LoopInvariantCodeMotion.diff
+#[custom_mir(dialect = "runtime", phase = "post-cleanup")]
+pub fn hoist(a: u32, b: u32, n: u32) -> u32 {
+    // CHECK-LABEL: fn hoist(
+    // CHECK: bb0: {
+    // CHECK: switchInt(copy _3) -> [0: bb2, otherwise: [[preheader:bb[0-9]+]]];
+    // CHECK: bb1: {
+    // CHECK-NOT: Mul(
+    // CHECK: switchInt({{.*}}) -> [1: bb1, otherwise: bb2];
+    // CHECK: [[preheader]]: {
+    // CHECK-NEXT: [[x:_.*]] = Mul(copy _1, copy _2);
+    // CHECK-NEXT: goto -> bb1;
+    mir! {
+        let x: u32;
+        let again: bool;
+        {
+            RET = 0;
+            match n { 0 => bb2, _ => bb1 }
+        }
+        bb1 = {
+            x = a * b;
+            RET = RET + x;
+            again = RET < n;
+            match again { true => bb1, _ => bb2 }
+        }
+        bb2 = {
+            Return()
+        }
+    }
+}
+
+// The only block that enters the loop ends with a `goto`, so it is used as the preheader.
+// EMIT_MIR licm.existing_preheader.LoopInvariantCodeMotion.diff
+#[custom_mir(dialect = "runtime", phase = "post-cleanup")]
+pub fn existing_preheader(a: u32, b: u32, n: u32) -> u32 {
+    // CHECK-LABEL: fn existing_preheader(
+    // CHECK: bb0: {
+    // CHECK-NEXT: _0 = const 0_u32;
+    // CHECK-NEXT: [[x:_.*]] = Mul(copy _1, copy _2);
+    // CHECK-NEXT: goto -> bb1;
+    // CHECK: bb1: {
+    // CHECK-NOT: Mul(
+    // CHECK: Add(copy _0, copy [[x]]);
+    mir! {
+        let x: u32;
+        let again: bool;
+        {
+            RET = 0;
+            Goto(bb1)
+        }
+        bb1 = {
+            x = a * b;
+            RET = RET + x;
+            again = RET < n;
+            match again { true => bb1, _ => bb2 }
+        }
+        bb2 = {
+            Return()
+        }
+    }
+}
+
+// Dividing by zero is UB, so the division must stay on the paths where it is evaluated.
+// EMIT_MIR licm.divisor_may_be_zero.LoopInvariantCodeMotion.diff
+#[custom_mir(dialect = "runtime", phase = "post-cleanup")]
+pub fn divisor_may_be_zero(a: u32, b: u32, n: u32) -> u32 {
+    // CHECK-LABEL: fn divisor_may_be_zero(
+    // CHECK: bb0: {
+    // CHECK-NOT: Div(
+    // CHECK: bb1: {
+    // CHECK: Div(copy _1, copy _2);
+    mir! {
+        let x: u32;
+        let again: bool;
+        {
+            RET = 0;
+            Goto(bb1)
+        }
+        bb1 = {
+            x = a / b;
+            RET = RET + x;
+            again = RET < n;
+            match again { true => bb1, _ => bb2 }
+        }
+        bb2 = {
+            Return()
+        }
+    }
+}
+
+// `c` is written by the loop before it is read, so `c * b` is not invariant.
+// EMIT_MIR licm.read_after_write.LoopInvariantCodeMotion.diff
+#[custom_mir(dialect = "runtime", phase = "post-cleanup")]
+pub fn read_after_write(a: u32, b: u32, n: u32) -> u32 {
+    // CHECK-LABEL: fn read_after_write(
+    // CHECK: bb0: {
+    // CHECK-NOT: Mul(
+    // CHECK: bb1: {
+    // CHECK: [[c:_.*]] = Add(copy _0, copy _1);
+    // CHECK: Mul(copy [[c]], copy _2);
+    mir! {
+        let c: u32;
+        let x: u32;
+        let again: bool;
+        {
+            RET = 0;
+            Goto(bb1)
+        }
+        bb1 = {
+            c = RET + a;
+            x = c * b;
+            RET = RET + x;
+            again = RET < n;
+            match again { true => bb1, _ => bb2 }
+        }
+        bb2 = {
+            Return()
+        }
+    }
+}
@@ -0,0 +1,23 @@
+//@ test-mir-pass: OutlineColdPaths
+// EMIT_MIR_FOR_EACH_PANIC_STRATEGY
+
+#![crate_type = "lib"]
+
+#[inline(never)]
+fn fail(_code: u32) -> ! {
+    loop {}
+}
+
+// The path that ends in the call to `fail` is moved to a `ColdPathShim`, which reads `x` through
+// a pointer.
+// EMIT_MIR outline_cold_paths.checked.OutlineColdPaths.diff
+// EMIT_MIR outline_cold_paths.checked.cold0.AddCallGuards.after.mir
+pub fn checked(c: bool, x: u32) -> u32 {
+    // CHECK-LABEL: fn checked(
+    // CHECK: bb2: {
+    // CHECK-NOT: fail(
+    // CHECK: [[ptr:_.*]] = &raw const _2;
+    // CHECK-NEXT: {{_.*}} = const {checked as fn(*const u32) -> !}(move [[ptr]])
+    // CHECK: ALLOC{{[0-9]+}} (fn: checked - shim(cold#0))
+    if c { x } else { fail(x) }
+}
@@ -0,0 +1,19 @@
+mod a {
+    #[inline(never)]
+    pub fn sum(values: &[u32]) -> u32 {
+        values.iter().sum()
+    }
+}
+
+mod b {
+    #[inline(never)]
+    pub fn product(values: &[u32]) -> u32 {
+        values.iter().product()
+    }
+}
+
+fn main() {
+    let values = std::hint::black_box([1, 2, 3, 4]);
+    assert_eq!(a::sum(&values), 10);
+    assert_eq!(b::product(&values), 24);
+}
@@ -0,0 +1,75 @@
+// Check that `-Z codegen-cache-dir` shares the object files of codegen units between compilations
+// with different output directories, that a change of the options gives new entries, and that an
+// edit of one module keeps the entries of the codegen units of the other modules.
+
+//@ ignore-cross-compile
+
+use std::path::Path;
+
+use run_make_support::{Rustc, bin_name, rfs, run, rustc};
+
+fn entries(cache_dir: &Path) -> Vec<String> {
+    let mut entries: Vec<_> = rfs::read_dir(cache_dir)
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    entries.sort();
+    // Entries are only visible once they have been completely written.
+    assert!(entries.iter().all(|entry| !entry.starts_with('.')), "{entries:?}");
+    entries
+}
+
+fn compile(out_dir: &str, cache_dir: &str, opt_level: &str) -> Rustc {
+    let mut rustc = rustc();
+    rustc
+        .input("main.rs")
+        .out_dir(out_dir)
+        .opt_level(opt_level)
+        .codegen_units(4)
+        .arg(format!("-Zcodegen-cache-dir={cache_dir}"));
+    rustc
+}
+
+fn build(out_dir: &str, opt_level: &str) {
+    compile(out_dir, "cache", opt_level).run();
+    run(&Path::new(out_dir).join(bin_name("main")).to_string_lossy());
+}
+
+fn main() {
+    build("first", "1");
+    let first = entries(Path::new("cache"));
+    assert!(!first.is_empty());
+    for entry in &first {
+        assert!(Path::new("cache").join(entry).join("module.o").exists(), "{entry}");
+    }
+
+    // The second compilation reuses all of the entries.
+    build("second", "1");
+    assert_eq!(entries(Path::new("cache")), first);
+
+    // The object files are taken from the entries rather than compiled again: with a copy of the
+    // cache whose object files are empty, the compilation fails to link.
+    rfs::copy_dir_all("cache", "empty-cache");
+    for entry in &first {
+        rfs::write(Path::new("empty-cache").join(entry).join("module.o"), "");
+    }
+    compile("empty", "empty-cache", "1").run_fail().assert_stderr_contains("linking with");
+
+    // Another optimization level changes the object files, and so the keys.
+    build("third", "2");
+    let third = entries(Path::new("cache"));
+    assert!(third.len() > first.len(), "{third:?}");
+
+    // An edit of `b` only changes the key of its codegen unit, the others are reused.
+    rfs::write(
+        "main.rs",
+        rfs::read_to_string("main.rs").replace(".product()", ".fold(1, |a, b| a * b)"),
+    );
+    build("fourth", "1");
+    let fourth = entries(Path::new("cache"));
+    let added = fourth.len() - third.len();
+    assert!(
+        added >= 1 && added < first.len(),
+        "{added} new entries for {} codegen units",
+        first.len()
+    );
+}
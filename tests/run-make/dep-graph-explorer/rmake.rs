@@ -0,0 +1,64 @@
+// Check that `dep-graph-explorer` explains what the second session of an incremental build did
+// with the nodes of the first one, after an edit of one function.
+
+//@ needs-target-std
+//@ ignore-stage0 (the tool is built for an in-tree compiler)
+
+use std::path::PathBuf;
+
+use run_make_support::{dep_graph_explorer, rfs, rustc, shallow_find_directories};
+
+fn compile() {
+    rustc().input("lib.rs").crate_name("lib").crate_type("lib").incremental("incr").run();
+}
+
+/// Returns the finalized session directories in the incremental directory.
+fn session_dirs() -> Vec<PathBuf> {
+    let crate_dirs = shallow_find_directories("incr", |_| true);
+    assert_eq!(crate_dirs.len(), 1, "{crate_dirs:?}");
+    shallow_find_directories(&crate_dirs[0], |path| {
+        let name = path.file_name().unwrap().to_str().unwrap();
+        name.starts_with("s-") && !name.ends_with("-working")
+    })
+}
+
+fn main() {
+    compile();
+    let [first] = &session_dirs()[..] else { panic!("expected one session directory") };
+    // The next session deletes the directory of the previous one, so copy it aside.
+    rfs::copy_dir_all(first, "prev");
+
+    rfs::write("lib.rs", rfs::read_to_string("lib.rs").replace("2", "3"));
+    compile();
+    let next: Vec<_> = session_dirs().into_iter().filter(|dir| dir != first).collect();
+    let [next] = &next[..] else { panic!("expected a new session directory") };
+    rfs::copy_dir_all(next, "next");
+
+    // The edited body is an input that changed.
+    dep_graph_explorer()
+        .args(["diff", "prev", "next"])
+        .run()
+        .assert_stdout_contains("red (unhashed)")
+        .assert_stdout_contains("green (re-executed)")
+        .assert_stdout_contains("changed inputs:");
+
+    // `typeck` of `edited` was re-executed because of the edit, while `typeck` of `unchanged`
+    // was marked green.
+    dep_graph_explorer()
+        .args(["why", "prev", "next", "typeck"])
+        .run()
+        .assert_stdout_contains("it was re-executed because of this path of first changed")
+        .assert_stdout_contains(": green\n");
+
+    // `crates` is `eval_always`, so it was re-executed although nothing changed its result.
+    dep_graph_explorer()
+        .args(["why", "prev", "next", "crates"])
+        .run()
+        .assert_stdout_contains(": green (re-executed)\n")
+        .assert_stdout_contains("it is `eval_always`, so it is re-executed in every session");
+
+    dep_graph_explorer()
+        .args(["why", "prev", "next", "no_such_kind"])
+        .run_fail()
+        .assert_stderr_contains("no node matches `no_such_kind` in the sessions");
+}
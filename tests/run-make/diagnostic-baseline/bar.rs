@@ -0,0 +1,6 @@
+// `foo` moved down, `unused` was fixed and `y` is a new unused variable.
+
+pub fn foo() {
+    let x = 1;
+    let y = 2;
+}
@@ -0,0 +1,5 @@
+pub fn foo() {
+    let x = 1;
+}
+
+fn unused() {}
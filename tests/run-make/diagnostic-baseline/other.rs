@@ -0,0 +1,8 @@
+pub fn foo() {
+    let x = 1;
+}
+
+// The same lint in another function of the file is not in the baseline.
+pub fn other() {
+    let x = 1;
+}
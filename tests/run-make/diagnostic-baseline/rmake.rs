@@ -0,0 +1,70 @@
+// Check that `--diagnostic-baseline` records the lints emitted, suppresses them when the baseline
+// is enforced even when they moved, unless they are forced, and reports the new lints and the
+// stale entries.
+
+//@ needs-target-std
+
+use run_make_support::{rfs, rustc};
+
+fn main() {
+    rustc()
+        .crate_type("lib")
+        .input("foo.rs")
+        .arg("-Zunstable-options")
+        .arg("--diagnostic-baseline=record=foo.baseline")
+        .run()
+        .assert_stderr_contains("unused variable: `x`");
+    let baseline = rfs::read_to_string("foo.baseline");
+    assert!(baseline.contains("unused_variables\tfoo\tunused variable: `x`\n"), "{baseline}");
+    let dead_code = "dead_code\tunused\tfunction `unused` is never used\n";
+    assert!(baseline.contains(dead_code), "{baseline}");
+
+    rustc()
+        .crate_type("lib")
+        .input("foo.rs")
+        .arg("-Zunstable-options")
+        .arg("--diagnostic-baseline=foo.baseline")
+        .run()
+        .assert_stderr_equals("");
+
+    // Like `#[allow]`, the baseline doesn't lower the level of forced lints.
+    rustc()
+        .crate_type("lib")
+        .input("foo.rs")
+        .arg("-Zunstable-options")
+        .arg("--diagnostic-baseline=foo.baseline")
+        .arg("--force-warn=unused_variables")
+        .run()
+        .assert_stderr_contains("unused variable: `x`");
+    rustc()
+        .crate_type("lib")
+        .input("foo.rs")
+        .arg("-Zunstable-options")
+        .arg("--diagnostic-baseline=foo.baseline")
+        .arg("-Funused_variables")
+        .run_fail()
+        .assert_stderr_contains("unused variable: `x`");
+
+    rustc()
+        .crate_type("lib")
+        .input("bar.rs")
+        .crate_name("foo")
+        .arg("-Zunstable-options")
+        .arg("--diagnostic-baseline=foo.baseline")
+        .run()
+        .assert_stderr_not_contains("unused variable: `x`")
+        .assert_stderr_contains("unused variable: `y`")
+        .assert_stderr_contains("1 entry in the diagnostic baseline `foo.baseline`")
+        .assert_stderr_contains("stale entry: `dead_code` in `unused`");
+
+    // Lints are identified by their item, not only by their file and message.
+    rustc()
+        .crate_type("lib")
+        .input("other.rs")
+        .crate_name("foo")
+        .arg("-Zunstable-options")
+        .arg("--diagnostic-baseline=foo.baseline")
+        .run()
+        .assert_stderr_not_contains("other.rs:2:9")
+        .assert_stderr_contains("other.rs:7:9");
+}
@@ -0,0 +1,22 @@
+#[inline]
+pub fn helper(x: u32) -> u32 {
+    x.rotate_left(3) ^ 0x5a5a
+}
+
+pub mod a {
+    pub fn root_a(x: u32) -> u32 {
+        crate::helper(x) + 1
+    }
+}
+
+pub mod b {
+    pub fn root_b(x: u32) -> u32 {
+        crate::helper(x) * 2
+    }
+}
+
+pub mod c {
+    pub fn root_c(x: u32) -> u32 {
+        x.wrapping_sub(7)
+    }
+}
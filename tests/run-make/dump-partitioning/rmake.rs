@@ -0,0 +1,60 @@
+// Check that `-Z dump-partitioning=json` reports the codegen units of the crate, the merges that
+// were needed to stay within `-C codegen-units`, and why each item was placed where it is.
+
+//@ needs-target-std
+
+use run_make_support::serde_json::{self, Value};
+use run_make_support::{rfs, rustc};
+
+fn main() {
+    rustc()
+        .crate_type("lib")
+        .input("foo.rs")
+        .opt_level("2")
+        .codegen_units(2)
+        .emit("obj")
+        .arg("-Zdump-partitioning=json")
+        .run();
+    let report = rfs::read_to_string("foo.partitioning.json");
+    let report: Value = serde_json::from_str(&report).unwrap();
+
+    let codegen_units = report["codegen_units"].as_array().unwrap();
+    assert_eq!(codegen_units.len(), 2, "{report:#}");
+    assert_eq!(report["max_codegen_units"], 2);
+
+    // The three modules with root items don't fit in two codegen units.
+    let merges = report["merges"].as_array().unwrap();
+    assert!(!merges.is_empty(), "{report:#}");
+    assert!(merges.iter().all(|merge| merge["reason"] == "max-codegen-units"), "{report:#}");
+    let initial_cgus: usize =
+        codegen_units.iter().map(|cgu| cgu["initial_cgus"].as_array().unwrap().len()).sum();
+    assert_eq!(initial_cgus, codegen_units.len() + merges.len(), "{report:#}");
+
+    let items: Vec<&Value> =
+        codegen_units.iter().flat_map(|cgu| cgu["items"].as_array().unwrap()).collect();
+    let placements = |name: &str| -> Vec<&Value> {
+        items
+            .iter()
+            .filter(|item| item["item"].as_str().unwrap().ends_with(name))
+            .map(|item| &item["placement"])
+            .collect()
+    };
+
+    // Root items are placed with the module of their definition.
+    let root_c = placements("root_c");
+    assert_eq!(root_c.len(), 1, "{report:#}");
+    assert_eq!(root_c[0]["kind"], "root");
+    assert!(root_c[0]["characteristic_def"].as_str().unwrap().ends_with("c::root_c"));
+
+    // `helper` is copied into the codegen units of its users, and knows which ones they are.
+    let helper = placements("helper");
+    assert!(!helper.is_empty(), "{report:#}");
+    let inlined_for: Vec<&str> = helper
+        .iter()
+        .inspect(|placement| assert_eq!(placement["kind"], "inlined"))
+        .flat_map(|placement| placement["inlined_for"].as_array().unwrap())
+        .map(|root| root.as_str().unwrap())
+        .collect();
+    assert!(inlined_for.iter().any(|root| root.ends_with("a::root_a")), "{inlined_for:?}");
+    assert!(inlined_for.iter().any(|root| root.ends_with("b::root_b")), "{inlined_for:?}");
+}
@@ -0,0 +1,22 @@
+#![crate_type = "lib"]
+
+pub struct Point {
+    pub x: u32,
+    pub y: u32,
+}
+
+pub trait Area {
+    fn area(&self) -> u32;
+}
+
+impl Area for Point {
+    fn area(&self) -> u32 {
+        self.x * self.y
+    }
+}
+
+pub static GREETING: &str = "hello";
+
+pub fn origin() -> Point {
+    Point { x: 0, y: 0 }
+}
@@ -0,0 +1,48 @@
+// Check that `-Z emit=public-mir` writes a snapshot of the items of the crate with their bodies,
+// and of the types, ADTs and allocations that they refer to, in both formats.
+
+//@ needs-target-std
+
+use std::fs::File;
+
+use run_make_support::rustc;
+use run_make_support::rustc_public_mir::ty::{RigidTy, TyKind};
+use run_make_support::rustc_public_mir::{self, Format, ItemKind, Snapshot};
+
+fn read(path: &str, format: Format) -> Snapshot {
+    let file = File::open(path).unwrap();
+    rustc_public_mir::read(file, format).unwrap_or_else(|error| panic!("{path}: {error}"))
+}
+
+fn main() {
+    rustc().input("foo.rs").emit("metadata").arg("-Zemit=public-mir,public-mir-binary").run();
+    let snapshot = read("foo.public-mir.json", Format::Json);
+    assert_eq!(snapshot.format_version, rustc_public_mir::FORMAT_VERSION);
+    assert_eq!(snapshot.crate_name, "foo");
+
+    let item = |name: &str| snapshot.item_by_name(name).unwrap_or_else(|| panic!("{name}"));
+    let origin = item("origin");
+    assert_eq!(origin.kind, ItemKind::Fn);
+    let body = origin.body.as_ref().unwrap();
+    assert!(!body.blocks.is_empty());
+    assert_eq!(item("<Point as Area>::area").kind, ItemKind::Fn);
+    assert_eq!(item("GREETING").kind, ItemKind::Static);
+
+    // The ids that the bodies refer to have entries in the tables.
+    let return_ty = snapshot.ty(body.locals[0].ty).unwrap();
+    assert_eq!(return_ty.layout.as_ref().unwrap().size.num_bits, 64, "{return_ty:#?}");
+    let TyKind::RigidTy(RigidTy::Adt(adt, _)) = &return_ty.kind else {
+        panic!("{return_ty:#?}");
+    };
+    let point = snapshot.adt(*adt).unwrap();
+    let fields: Vec<&str> =
+        point.variants[0].fields.iter().map(|field| field.name.as_str()).collect();
+    assert_eq!(fields, ["x", "y"]);
+    assert!(snapshot.span(origin.span).is_some());
+    assert!(!snapshot.allocs.is_empty());
+    assert_eq!(snapshot.trait_decls.len(), 1);
+    assert_eq!(snapshot.trait_impls.len(), 1);
+
+    // The binary snapshot has the same contents.
+    assert_eq!(read("foo.public-mir.bin", Format::Binary), snapshot);
+}
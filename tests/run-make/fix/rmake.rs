@@ -0,0 +1,50 @@
+// Check that `-Z fix` rewrites the source files with the machine-applicable suggestions, even
+// when the compilation fails, that `-Z fix=dry-run` only prints them, and that
+// `-Z fix=recheck` compiles the fixed sources again.
+
+//@ needs-target-std
+
+use run_make_support::{rfs, rustc};
+
+const WARNINGS: &str = "fn main() {\n    let mut x = 1;\n    let _ = (x);\n}\n";
+const WARNINGS_FIXED: &str = "fn main() {\n    let x = 1;\n    let _ = x;\n}\n";
+const ERROR: &str = "fn main() {\n    let x = 1\n    struct S;\n    let _ = (x, S);\n}\n";
+const ERROR_FIXED: &str = "fn main() {\n    let x = 1;\n    struct S;\n    let _ = (x, S);\n}\n";
+
+fn main() {
+    rfs::write("warnings.rs", WARNINGS);
+    rustc()
+        .input("warnings.rs")
+        .arg("-Zfix=dry-run")
+        .run()
+        .assert_stdout_contains("--- a/warnings.rs\n+++ b/warnings.rs\n")
+        .assert_stdout_contains("-    let mut x = 1;\n-    let _ = (x);\n")
+        .assert_stdout_contains("+    let x = 1;\n+    let _ = x;\n");
+    assert_eq!(rfs::read_to_string("warnings.rs"), WARNINGS);
+
+    rustc()
+        .input("warnings.rs")
+        .arg("-Zfix")
+        .run()
+        .assert_stderr_contains("fixed `warnings.rs` (2 fixes)");
+    assert_eq!(rfs::read_to_string("warnings.rs"), WARNINGS_FIXED);
+
+    // The missing semicolon is a parse error, after which the compilation stops.
+    rfs::write("error.rs", ERROR);
+    rustc()
+        .input("error.rs")
+        .arg("-Zfix")
+        .run_fail()
+        .assert_stderr_contains("expected `;`")
+        .assert_stderr_contains("fixed `error.rs` (1 fix)");
+    assert_eq!(rfs::read_to_string("error.rs"), ERROR_FIXED);
+
+    rfs::write("error.rs", ERROR);
+    rustc()
+        .input("error.rs")
+        .arg("-Zfix=recheck")
+        .run()
+        .assert_stderr_contains("expected `;`")
+        .assert_stderr_contains("fixed `error.rs` (1 fix)");
+    assert_eq!(rfs::read_to_string("error.rs"), ERROR_FIXED);
+}
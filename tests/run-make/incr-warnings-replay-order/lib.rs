@@ -0,0 +1,25 @@
+use std::collections::HashMap;
+
+fn dead() {}
+
+pub fn first() {
+    let unused_in_first = 1;
+}
+
+pub fn second() -> u8 {
+    let unused_in_second = 2;
+    0
+}
+
+pub struct Unread {
+    field: u32,
+}
+
+pub fn third(x: Unread) {
+    let mut not_mutated = x;
+}
+
+#[expect(unused_variables)]
+pub fn expected() {
+    let unused_but_expected = 3;
+}
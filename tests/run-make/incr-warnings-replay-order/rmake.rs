@@ -0,0 +1,29 @@
+// Check that warnings replayed from the incremental cache are printed in the same order as in
+// the session that originally emitted them, rather than in the order the dep-graph happens to
+// be marked green in, that none of them get lost along the way, and that the replayed lints
+// still fulfill their `#[expect]`s.
+
+//@ needs-target-std
+
+use run_make_support::{diff, rustc};
+
+fn compile() -> String {
+    rustc().input("lib.rs").incremental("incr").crate_type("lib").run().stderr_utf8()
+}
+
+fn main() {
+    let first = compile();
+    assert!(first.contains("unused_in_first"));
+    assert!(first.contains("not_mutated"));
+    assert!(!first.contains("unused_but_expected"));
+
+    // Nothing changed, so every warning is replayed from the cache.
+    let second = compile();
+    // The replayed lint still fulfills its `#[expect]`.
+    assert!(!second.contains("unfulfilled_lint_expectations"), "{second}");
+    diff().expected_text("first", &first).actual_text("second", &second).run();
+
+    // The replayed warnings are stored again, so the order also survives a third session.
+    let third = compile();
+    diff().expected_text("first", &first).actual_text("third", &third).run();
+}
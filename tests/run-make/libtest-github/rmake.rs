@@ -0,0 +1,26 @@
+// Check that libtest's `github` output annotates the failing tests where they panicked.
+
+//@ ignore-cross-compile
+//@ needs-unwind (test file contains #[should_panic] test)
+
+use run_make_support::{cmd, rustc};
+
+fn main() {
+    rustc().arg("--test").input("f.rs").run();
+
+    let cmd_out = cmd("./f")
+        .env("RUST_BACKTRACE", "0")
+        .args(&["--test-threads=1", "--format=github"])
+        .run_fail();
+    let stdout = cmd_out.stdout_utf8();
+
+    let annotations: Vec<_> = stdout.lines().filter(|line| line.starts_with("::")).collect();
+    assert_eq!(
+        annotations,
+        ["::error file=f.rs,line=10,col=5,title=test b failed::assertion failed: false"],
+        "{stdout}"
+    );
+    // The rest is the pretty output.
+    assert!(stdout.contains("test b ... FAILED\n"), "{stdout}");
+    assert!(stdout.contains("test result: FAILED. 2 passed; 1 failed; 1 ignored;"), "{stdout}");
+}
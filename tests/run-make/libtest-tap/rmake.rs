@@ -0,0 +1,21 @@
+// Check libtest's TAP output against a snapshot.
+
+//@ ignore-cross-compile
+//@ needs-unwind (test file contains #[should_panic] test)
+
+use run_make_support::{cmd, diff, rustc};
+
+fn main() {
+    rustc().arg("--test").input("f.rs").run();
+
+    let cmd_out = cmd("./f")
+        .env("RUST_BACKTRACE", "0")
+        .args(&["--test-threads=1", "--format=tap"])
+        .run_fail();
+
+    diff()
+        .expected_file("output.tap")
+        .actual_text("stdout", &cmd_out.stdout_utf8())
+        .normalize(r"thread '(?P<name>.*?)' \(\d+\) panicked", "thread '$name' ($$TID) panicked")
+        .run();
+}
@@ -0,0 +1,35 @@
+use std::path::Path;
+use std::time::Duration;
+
+#[test]
+fn fast() {}
+
+#[test]
+fn hangs() {
+    loop {
+        std::thread::sleep(Duration::from_secs(1));
+    }
+}
+
+#[test]
+fn flaky() {
+    // Tests run in their own process with `--test-timeout`, so the state is kept in a file.
+    let marker = Path::new("flaky-ran-once");
+    if !marker.exists() {
+        std::fs::write(marker, "").unwrap();
+        panic!("first run fails");
+    }
+}
+
+#[test]
+fn catches_panics() {
+    // Only the panics which escape the test make it fail, even in its own process.
+    assert!(std::panic::catch_unwind(|| panic!("caught")).is_err());
+    assert!(std::thread::spawn(|| panic!("joined")).join().is_err());
+}
+
+#[test]
+#[should_panic = "expected"]
+fn should_panic() {
+    panic!("expected");
+}
@@ -0,0 +1,33 @@
+// Check that `--test-timeout` kills the tests which hang, and that `--retries` reports the tests
+// which pass on a retry as flaky. In their own process, the tests can still catch panics, like
+// they can in-process.
+
+//@ ignore-cross-compile
+//@ needs-subprocess
+
+use run_make_support::{cmd, rustc};
+
+fn main() {
+    rustc().arg("--test").input("f.rs").run();
+
+    let out = cmd("./f")
+        .env("RUST_BACKTRACE", "0")
+        .args(&["-Zunstable-options", "--test-timeout=2s", "--retries=1", "--format=json"])
+        .run_fail()
+        .stdout_utf8();
+    let events: Vec<_> = out.lines().collect();
+    let event = |name: &str, event: &str| {
+        let prefix = format!(r#"{{ "type": "test", "name": "{name}", "event": "{event}""#);
+        events.iter().find(|line| line.starts_with(&prefix)).copied()
+    };
+
+    assert!(event("fast", "ok").is_some_and(|e| !e.contains("flaky")), "{out}");
+    assert!(event("catches_panics", "ok").is_some(), "{out}");
+    assert!(event("should_panic", "ok").is_some(), "{out}");
+    assert!(event("flaky", "retry").is_some_and(|e| e.contains(r#""retry": 1"#)), "{out}");
+    let flaky = event("flaky", "ok");
+    assert!(flaky.is_some_and(|e| e.contains(r#""flaky": true, "retries": 1"#)), "{out}");
+    let hangs = event("hangs", "failed");
+    assert!(hangs.is_some_and(|e| e.contains(r#""reason": "time limit exceeded""#)), "{out}");
+    assert!(out.contains(r#""event": "failed", "passed": 4, "failed": 1"#), "{out}");
+}
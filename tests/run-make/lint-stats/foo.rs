@@ -0,0 +1,3 @@
+pub fn NotSnakeCase() {}
+
+pub fn snake_case() {}
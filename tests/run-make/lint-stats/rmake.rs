@@ -0,0 +1,30 @@
+// Check that `-Z lint-stats` reports the builtin lint passes on their own, along with the number
+// of lints each of them emitted.
+
+//@ needs-target-std
+
+use run_make_support::rustc;
+use run_make_support::serde_json::Value;
+
+fn main() {
+    let stderr = rustc()
+        .crate_type("lib")
+        .input("foo.rs")
+        .arg("-Zlint-stats")
+        .arg("-Zlint-stats-format=json")
+        .run()
+        .stderr_utf8();
+    let stats = stderr.lines().find(|line| line.starts_with('[')).expect("no lint stats printed");
+    let stats: Vec<Value> = run_make_support::serde_json::from_str(stats).unwrap();
+
+    let pass = |name: &str| {
+        stats
+            .iter()
+            .find(|pass| pass["name"] == name)
+            .unwrap_or_else(|| panic!("no stats for `{name}` in {stats:?}"))
+    };
+    assert_eq!(pass("NonSnakeCase")["kind"], "late-module");
+    assert_eq!(pass("NonSnakeCase")["emitted"], 1);
+    assert_eq!(pass("UnusedParens")["kind"], "early");
+    assert_eq!(pass("UnusedParens")["emitted"], 0);
+}
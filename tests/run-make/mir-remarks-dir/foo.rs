@@ -0,0 +1,8 @@
+#[inline(never)]
+pub fn callee() -> u32 {
+    1
+}
+
+pub fn caller(a: &[u32; 16], i: usize) -> u32 {
+    a[i & 15] + callee()
+}
@@ -0,0 +1,45 @@
+//@ needs-target-std
+//
+// Checks that `-Z remark-dir` writes the remarks of `-Z mir-remarks` to a YAML file instead of
+// printing them, and that an incremental build that reuses the optimized MIR from the cache
+// writes the same remarks.
+
+use run_make_support::{rfs, rustc};
+
+const EXPECTED: &str = "\
+--- !Passed
+Pass:            BoundsCheckElimination
+DebugLoc:        { File: 'foo.rs', Line: 7, Column: 5 }
+Function:        'caller'
+Args:
+  - String:          'removed a bounds check, as the index is always in bounds'
+...
+--- !Missed
+Pass:            Inline
+DebugLoc:        { File: 'foo.rs', Line: 7, Column: 17 }
+Function:        'caller'
+Args:
+  - String:          '`callee` not inlined: never inline attribute'
+...
+";
+
+fn compile() {
+    let output = rustc()
+        .opt()
+        .input("foo.rs")
+        .crate_type("lib")
+        .incremental("incr")
+        .arg("-Zmir-remarks=Inline BoundsCheckElimination")
+        .arg("-Zremark-dir=remarks")
+        .run();
+    output.assert_stderr_not_contains("note:");
+}
+
+fn main() {
+    compile();
+    assert_eq!(rfs::read_to_string("remarks/foo.mir.opt.yaml"), EXPECTED);
+
+    rfs::remove_file("remarks/foo.mir.opt.yaml");
+    compile();
+    assert_eq!(rfs::read_to_string("remarks/foo.mir.opt.yaml"), EXPECTED);
+}
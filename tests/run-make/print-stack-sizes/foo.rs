@@ -0,0 +1,35 @@
+#![crate_type = "lib"]
+
+#[inline(never)]
+fn leaf(x: u32) -> u32 {
+    let buffer = std::hint::black_box([x; 64]);
+    buffer[0] + buffer[63]
+}
+
+#[inline(never)]
+fn middle(x: u32) -> u32 {
+    leaf(x) + leaf(x + 1)
+}
+
+#[no_mangle]
+pub extern "C" fn bounded_entry(x: u32) -> u32 {
+    middle(x)
+}
+
+#[inline(never)]
+pub fn recursive_entry(n: u32) -> u32 {
+    if n == 0 { 0 } else { recursive_entry(n - 1) + leaf(n) }
+}
+
+#[inline(never)]
+pub fn indirect_entry(f: fn(u32) -> u32) -> u32 {
+    f(1) + leaf(2)
+}
+
+// The frame size of naked functions is not emitted by the backend.
+#[cfg(target_arch = "x86_64")]
+#[unsafe(naked)]
+#[no_mangle]
+pub extern "C" fn naked_entry() {
+    core::arch::naked_asm!("ret")
+}
@@ -0,0 +1,45 @@
+// Check the report of `--print stack-sizes`: the worst-case stack usage of the entry points of
+// the crate, which is unbounded when they can recurse or make indirect calls.
+
+//@ needs-target-std
+//@ only-elf
+// Reason: the frame sizes are only emitted for ELF object files.
+
+use run_make_support::{rfs, rustc, target};
+
+fn main() {
+    let output = rustc()
+        .input("foo.rs")
+        .opt_level("3")
+        .emit("obj")
+        .arg("-Zunstable-options")
+        .print("stack-sizes")
+        .print("stack-sizes-json=stack-sizes.json")
+        .run();
+
+    // The deepest path of `bounded_entry` goes through `middle` and `leaf`.
+    output
+        .assert_stdout_contains_regex(r"(?m)^bounded_entry: \d+ bytes$")
+        .assert_stdout_contains_regex(r"(?m)^    \S*middle \(\d+ bytes\)$")
+        .assert_stdout_contains_regex(r"(?m)^    \S*leaf \(\d+ bytes\)$")
+        .assert_stdout_contains_regex(
+            r"recursive_entry: unbounded, `\S*recursive_entry` is recursive",
+        )
+        .assert_stdout_contains_regex(
+            r"indirect_entry: unbounded, `\S*indirect_entry` makes indirect calls",
+        );
+
+    if target().starts_with("x86_64") {
+        output.assert_stdout_contains_regex(
+            r"(?m)^naked_entry: at least \d+ bytes, the frame size of `\S*naked_entry` is unknown$",
+        );
+    }
+
+    let json = rfs::read_to_string("stack-sizes.json");
+    assert!(json.contains(r#""symbol": "bounded_entry""#), "{json}");
+    assert!(json.contains(r#""reason": "recursion""#), "{json}");
+    assert!(json.contains(r#""reason": "indirect-call""#), "{json}");
+    if target().starts_with("x86_64") {
+        assert!(json.contains(r#""reason": "unknown-frame-size""#), "{json}");
+    }
+}
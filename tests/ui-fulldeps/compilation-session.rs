@@ -0,0 +1,128 @@
+//@ edition: 2021
+//@ run-pass
+//@ run-flags: {{sysroot-base}}
+//@ ignore-stage1 (requires matching sysroot built with in-tree compiler)
+
+// Check that a `CompilationSession` compiles the updated contents of files, and that its
+// recomputations start from the dep graph of the previous one, so that the queries of the
+// unchanged items are marked green.
+
+#![feature(rustc_private)]
+
+extern crate rustc_driver;
+extern crate rustc_hir;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate rustc_session;
+
+use std::path::PathBuf;
+
+use rustc_hir::def::DefKind;
+use rustc_interface::{CompilationSession, Linker, interface};
+use rustc_middle::dep_graph::{DepNode, dep_kinds};
+use rustc_session::config::{
+    CrateType, IncrementalStateAssertion, Input, Options, OutputType, OutputTypes, Sysroot,
+};
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let sysroot = Sysroot::new(Some(PathBuf::from(&args[1])));
+
+    // compiletest sets the current dir to `output_base_dir` when running.
+    let tmpdir = std::env::current_dir().unwrap().join("tmp");
+    std::fs::create_dir_all(&tmpdir).unwrap();
+    let lib = tmpdir.join("lib.rs");
+    std::fs::write(&lib, "pub fn stable() {}\npub fn on_disk() {}\n").unwrap();
+
+    let mut session = CompilationSession::new({
+        let (lib, tmpdir) = (lib.clone(), tmpdir.clone());
+        let mut recomputations = 0;
+        move || {
+            let mut opts = Options::default();
+            opts.crate_types = vec![CrateType::Rlib];
+            opts.output_types = OutputTypes::new(&[(OutputType::Metadata, None)]);
+            opts.sysroot = sysroot.clone();
+            opts.incremental = Some(tmpdir.join("incr"));
+            // Every recomputation after the first one starts from the previous dep graph.
+            opts.assert_incr_state = Some(if recomputations == 0 {
+                IncrementalStateAssertion::NotLoaded
+            } else {
+                IncrementalStateAssertion::Loaded
+            });
+            recomputations += 1;
+            config(opts, Input::File(lib.clone()), tmpdir.clone())
+        }
+    });
+
+    // Nothing can be green without a previous dep graph.
+    let (names, stable_is_green) = functions(&mut session);
+    assert_eq!(names, ["on_disk", "stable"]);
+    assert!(!stable_is_green);
+    session.update_file(&lib, "pub fn stable() {}\npub fn updated() {}\npub fn added() {}\n");
+    let (names, stable_is_green) = functions(&mut session);
+    assert_eq!(names, ["added", "stable", "updated"]);
+    assert!(stable_is_green);
+    // Unchanged files are compiled again from the dep graph of the previous recomputation.
+    let (names, stable_is_green) = functions(&mut session);
+    assert_eq!(names, ["added", "stable", "updated"]);
+    assert!(stable_is_green);
+    assert!(session.invalidate(&lib));
+    assert!(!session.invalidate(&lib));
+    let (names, stable_is_green) = functions(&mut session);
+    assert_eq!(names, ["on_disk", "stable"]);
+    assert!(stable_is_green);
+}
+
+/// Compiles the crate, and returns the names of its functions and whether the type checking of
+/// `stable` was marked green.
+fn functions(session: &mut CompilationSession) -> (Vec<String>, bool) {
+    session.recompute(|compiler| {
+        let krate = rustc_interface::passes::parse(&compiler.sess);
+        let (result, linker) =
+            rustc_interface::create_and_enter_global_ctxt(compiler, krate, |tcx| {
+                let _ = tcx.analysis(());
+                let mut names = Vec::new();
+                let mut stable_is_green = false;
+                for id in tcx.hir_crate_items(()).free_items() {
+                    if !matches!(tcx.def_kind(id.owner_id), DefKind::Fn) {
+                        continue;
+                    }
+                    let name = tcx.item_name(id.owner_id.to_def_id()).to_string();
+                    if name == "stable" {
+                        let node = DepNode::construct(tcx, dep_kinds::typeck, &id.owner_id.def_id);
+                        stable_is_green = tcx.dep_graph.is_green(&node);
+                    }
+                    names.push(name);
+                }
+                names.sort();
+                let linker = Linker::codegen_and_build_linker(tcx, &*compiler.codegen_backend);
+                ((names, stable_is_green), linker)
+            });
+        linker.link(&compiler.sess, &*compiler.codegen_backend);
+        result
+    })
+}
+
+fn config(opts: Options, input: Input, output_dir: PathBuf) -> interface::Config {
+    interface::Config {
+        opts,
+        crate_cfg: Default::default(),
+        crate_check_cfg: Default::default(),
+        input,
+        output_file: None,
+        output_dir: Some(output_dir),
+        ice_file: None,
+        file_loader: None,
+        locale_resources: Vec::new(),
+        lint_caps: Default::default(),
+        psess_created: None,
+        hash_untracked_state: None,
+        register_lints: None,
+        override_queries: None,
+        extra_symbols: Vec::new(),
+        make_codegen_backend: None,
+        registry: rustc_driver::diagnostics_registry(),
+        using_internal_features: &rustc_driver::USING_INTERNAL_FEATURES,
+        expanded_args: Default::default(),
+    }
+}
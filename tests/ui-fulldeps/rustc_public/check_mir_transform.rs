@@ -0,0 +1,203 @@
+//@ run-pass
+//! Test that a tool can replace the optimized MIR of an item, and that the new body is codegen'd.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+
+#![feature(rustc_private)]
+
+extern crate rustc_middle;
+
+extern crate rustc_driver;
+extern crate rustc_interface;
+#[macro_use]
+extern crate rustc_public;
+
+use rustc_public::mir::{
+    BinOp, Body, ConstOperand, Mutability, Operand, Place, Rvalue, Statement, StatementKind,
+    TerminatorKind, UnwindAction,
+};
+use rustc_public::ty::{MirConst, Ty, UintTy};
+use rustc_public::{CompilerError, CrateDef, CrateItem};
+use std::io::Write;
+use std::ops::ControlFlow;
+use std::process::Command;
+
+/// Insert a call to `log_call` at the start of `add`.
+fn log_calls(item: CrateItem, mut body: Body) -> Option<Body> {
+    if item.name() != "add" {
+        return None;
+    }
+    let log_call =
+        rustc_public::all_local_items().into_iter().find(|item| item.name() == "log_call")?;
+    let span = body.span;
+    let func = Operand::Constant(ConstOperand {
+        span,
+        user_ty: None,
+        const_: MirConst::try_new_zero_sized(log_call.ty()).unwrap(),
+    });
+    let destination = body.new_local(Ty::new_tuple(&[]), span, Mutability::Mut);
+    body.insert_call(0, 0, func, vec![], Place::from(destination), UnwindAction::Continue);
+    Some(body)
+}
+
+/// Assign a `bool` to a `u32` at the start of `add`, which the MIR validator rejects.
+fn break_add(item: CrateItem, mut body: Body) -> Option<Body> {
+    if item.name() != "add" {
+        return None;
+    }
+    let span = body.span;
+    let local = body.new_local(Ty::unsigned_ty(UintTy::U32), span, Mutability::Mut);
+    let value = ConstOperand { span, user_ty: None, const_: MirConst::from_bool(true) };
+    body.blocks[0].statements.insert(
+        0,
+        Statement {
+            kind: StatementKind::Assign(Place::from(local), Rvalue::Use(Operand::Constant(value))),
+            span,
+        },
+    );
+    Some(body)
+}
+
+/// Replace the body of `add` with one that reads the length of a place, which cannot be converted
+/// back to internal MIR.
+fn read_len(item: CrateItem, mut body: Body) -> Option<Body> {
+    if item.name() != "add" {
+        return None;
+    }
+    let span = body.span;
+    let local = body.new_local(Ty::usize_ty(), span, Mutability::Mut);
+    let len = Rvalue::Len(Place::from(1_usize));
+    body.blocks[0]
+        .statements
+        .insert(0, Statement { kind: StatementKind::Assign(Place::from(local), len), span });
+    Some(body)
+}
+
+/// Make the first block of `add` jump to a block that doesn't exist.
+fn jump_out_of_range(item: CrateItem, mut body: Body) -> Option<Body> {
+    if item.name() != "add" {
+        return None;
+    }
+    body.blocks[0].terminator.kind = TerminatorKind::Goto { target: body.blocks.len() };
+    Some(body)
+}
+
+/// Read a local of `add` that doesn't exist.
+fn local_out_of_range(item: CrateItem, mut body: Body) -> Option<Body> {
+    if item.name() != "add" {
+        return None;
+    }
+    let span = body.span;
+    let local = body.new_local(Ty::unsigned_ty(UintTy::U32), span, Mutability::Mut);
+    let missing = Operand::Copy(Place::from(body.locals().len()));
+    body.blocks[0].statements.insert(
+        0,
+        Statement { kind: StatementKind::Assign(Place::from(local), Rvalue::Use(missing)), span },
+    );
+    Some(body)
+}
+
+/// Compute a checked `&` of the arguments of `add`, which cannot overflow.
+fn checked_bit_and(item: CrateItem, mut body: Body) -> Option<Body> {
+    if item.name() != "add" {
+        return None;
+    }
+    let span = body.span;
+    let result_ty = Ty::new_tuple(&[Ty::unsigned_ty(UintTy::U32), Ty::bool_ty()]);
+    let local = body.new_local(result_ty, span, Mutability::Mut);
+    let (a, b) = (Operand::Copy(Place::from(1_usize)), Operand::Copy(Place::from(2_usize)));
+    let bit_and = Rvalue::CheckedBinaryOp(BinOp::BitAnd, a, b);
+    body.blocks[0]
+        .statements
+        .insert(0, Statement { kind: StatementKind::Assign(Place::from(local), bit_and), span });
+    Some(body)
+}
+
+fn args(input: &str, output: &str, extra: &[&str]) -> Vec<String> {
+    let mut args = vec![
+        "rustc".to_string(),
+        "--crate-type=bin".to_string(),
+        "--crate-name".to_string(),
+        "input".to_string(),
+        "-o".to_string(),
+        output.to_string(),
+        input.to_string(),
+    ];
+    args.extend(extra.iter().map(|arg| arg.to_string()));
+    args
+}
+
+fn continue_compilation() -> ControlFlow<()> {
+    ControlFlow::Continue(())
+}
+
+/// This test will generate a dummy crate, compile it with a tool that replaces the MIR of one of
+/// its functions, and then run the binary to check that the new MIR was used.
+fn main() {
+    let path = "mir_transform_input.rs";
+    generate_input(&path).unwrap();
+
+    let args_log = args(path, "mir_transform_log", &[]);
+    run!(&args_log, continue_compilation, mir_transform = log_calls).unwrap();
+    let status = Command::new("./mir_transform_log").status().unwrap();
+    assert!(status.success(), "the transformed binary failed: {status}");
+
+    // The transformed body is rejected by the MIR validator.
+    let args_broken = args(path, "mir_transform_broken", &[]);
+    let result = run!(&args_broken, continue_compilation, mir_transform = break_add);
+    assert_eq!(result, Err(CompilerError::Failed));
+
+    // The transformed body cannot be converted back to internal MIR.
+    let args_len = args(path, "mir_transform_len", &[]);
+    let result = run!(&args_len, continue_compilation, mir_transform = read_len);
+    assert_eq!(result, Err(CompilerError::Failed));
+
+    // Blocks and locals that are out of range, and checked operations that cannot overflow, are
+    // reported as errors instead of crashing the compiler.
+    let args_jump = args(path, "mir_transform_jump", &[]);
+    let result = run!(&args_jump, continue_compilation, mir_transform = jump_out_of_range);
+    assert_eq!(result, Err(CompilerError::Failed));
+
+    let args_local = args(path, "mir_transform_local", &[]);
+    let result = run!(&args_local, continue_compilation, mir_transform = local_out_of_range);
+    assert_eq!(result, Err(CompilerError::Failed));
+
+    let args_bit_and = args(path, "mir_transform_bit_and", &[]);
+    let result = run!(&args_bit_and, continue_compilation, mir_transform = checked_bit_and);
+    assert_eq!(result, Err(CompilerError::Failed));
+
+    // Transforms are rejected with incremental compilation.
+    let args_incremental = args(path, "mir_transform_incremental", &["-Cincremental=incr"]);
+    let result = run!(&args_incremental, continue_compilation, mir_transform = log_calls);
+    assert_eq!(result, Err(CompilerError::Failed));
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+        use std::sync::atomic::{{AtomicUsize, Ordering}};
+
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+        fn log_call() {{
+            CALLS.fetch_add(1, Ordering::Relaxed);
+        }}
+
+        #[inline(never)]
+        fn add(a: u32, b: u32) -> u32 {{
+            a + b
+        }}
+
+        fn main() {{
+            assert_eq!(add(1, 2), 3);
+            assert_eq!(add(3, 4), 7);
+            assert_eq!(CALLS.load(Ordering::Relaxed), 2);
+        }}
+        "#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,307 @@
+//@ run-pass
+//! Test that the snapshots of `-Z emit=public-mir` can be read back with `rustc_public_mir`, for a
+//! crate that has every kind of rvalue, terminator and rigid type.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ needs-asm-support
+//@ edition: 2024
+
+#![feature(rustc_private)]
+
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate rustc_public;
+extern crate rustc_public_mir;
+
+use std::collections::BTreeSet;
+
+use rustc_driver::Compilation;
+use rustc_interface::interface::Compiler;
+use rustc_middle::ty::TyCtxt;
+use rustc_public::rustc_internal::snapshot::write_snapshot;
+use rustc_public_mir::mir::{Rvalue, StatementKind, TerminatorKind};
+use rustc_public_mir::ty::{RigidTy, TyKind};
+use rustc_public_mir::{Format, Snapshot};
+
+const CRATE_NAME: &str = "input";
+
+fn main() {
+    let path = "snapshot_input.rs";
+    std::fs::write(path, INPUT).unwrap();
+    let args = &[
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        "--edition".to_string(),
+        "2024".to_string(),
+        // Keep the bodies as they are built, with overflow checks.
+        "-Zmir-opt-level=0".to_string(),
+        "-Coverflow-checks=on".to_string(),
+        path.to_string(),
+    ];
+    let mut callbacks = WriteSnapshots::default();
+    rustc_driver::run_compiler(args, &mut callbacks);
+
+    let snapshot = rustc_public_mir::read(&callbacks.json[..], Format::Json).unwrap();
+    assert_eq!(rustc_public_mir::read(&callbacks.binary[..], Format::Binary).unwrap(), snapshot);
+    // Writing the snapshot that was read must give the same bytes, so that the compiler and the
+    // reader agree on the serialized form of every entry.
+    for (format, written) in [(Format::Json, &callbacks.json), (Format::Binary, &callbacks.binary)]
+    {
+        let mut rewritten = Vec::new();
+        rustc_public_mir::write(&mut rewritten, format, &snapshot).unwrap();
+        assert!(&rewritten == written, "the {format:?} snapshot changed when it was written again");
+    }
+
+    check_variants(&snapshot);
+}
+
+#[derive(Default)]
+struct WriteSnapshots {
+    json: Vec<u8>,
+    binary: Vec<u8>,
+}
+
+impl rustc_driver::Callbacks for WriteSnapshots {
+    fn after_analysis<'tcx>(&mut self, _compiler: &Compiler, tcx: TyCtxt<'tcx>) -> Compilation {
+        write_snapshot(tcx, Format::Json, &mut self.json).unwrap();
+        write_snapshot(tcx, Format::Binary, &mut self.binary).unwrap();
+        Compilation::Stop
+    }
+}
+
+/// Checks that the snapshot has every kind of rvalue, terminator and rigid type.
+fn check_variants(snapshot: &Snapshot) {
+    let mut rvalues = BTreeSet::new();
+    let mut terminators = BTreeSet::new();
+    for body in snapshot.items.iter().filter_map(|item| item.body.as_ref()) {
+        for block in &body.blocks {
+            for statement in &block.statements {
+                if let StatementKind::Assign(_, rvalue) = &statement.kind {
+                    rvalues.insert(rvalue_name(rvalue));
+                }
+            }
+            terminators.insert(terminator_name(&block.terminator.kind));
+        }
+    }
+    let rigid_tys: BTreeSet<_> = snapshot
+        .types
+        .iter()
+        .filter_map(|info| match &info.kind {
+            TyKind::RigidTy(ty) => Some(rigid_ty_name(ty)),
+            _ => None,
+        })
+        .collect();
+
+    // There is no `Len` rvalue in the MIR of the compiler anymore.
+    let expected_rvalues = [
+        "AddressOf",
+        "Aggregate",
+        "BinaryOp",
+        "Cast",
+        "CheckedBinaryOp",
+        "CopyForDeref",
+        "Discriminant",
+        "NullaryOp",
+        "Ref",
+        "Repeat",
+        "ShallowInitBox",
+        "ThreadLocalRef",
+        "UnaryOp",
+        "Use",
+    ];
+    assert_eq!(rvalues, BTreeSet::from(expected_rvalues));
+    let expected_terminators = [
+        "Abort",
+        "Assert",
+        "Call",
+        "Drop",
+        "Goto",
+        "InlineAsm",
+        "Resume",
+        "Return",
+        "SwitchInt",
+        "Unreachable",
+    ];
+    assert_eq!(terminators, BTreeSet::from(expected_terminators));
+    // Coroutine witnesses only appear in trait solving, not in the types of MIR bodies.
+    let expected_rigid_tys = [
+        "Adt",
+        "Array",
+        "Bool",
+        "Char",
+        "Closure",
+        "Coroutine",
+        "CoroutineClosure",
+        "Dynamic",
+        "Float",
+        "FnDef",
+        "FnPtr",
+        "Foreign",
+        "Int",
+        "Never",
+        "Pat",
+        "RawPtr",
+        "Ref",
+        "Slice",
+        "Str",
+        "Tuple",
+        "Uint",
+    ];
+    assert_eq!(rigid_tys, BTreeSet::from(expected_rigid_tys));
+}
+
+fn rvalue_name(rvalue: &Rvalue) -> &'static str {
+    match rvalue {
+        Rvalue::AddressOf(..) => "AddressOf",
+        Rvalue::Aggregate(..) => "Aggregate",
+        Rvalue::BinaryOp(..) => "BinaryOp",
+        Rvalue::Cast(..) => "Cast",
+        Rvalue::CheckedBinaryOp(..) => "CheckedBinaryOp",
+        Rvalue::CopyForDeref(..) => "CopyForDeref",
+        Rvalue::Discriminant(..) => "Discriminant",
+        Rvalue::Len(..) => "Len",
+        Rvalue::Ref(..) => "Ref",
+        Rvalue::Repeat(..) => "Repeat",
+        Rvalue::ShallowInitBox(..) => "ShallowInitBox",
+        Rvalue::ThreadLocalRef(..) => "ThreadLocalRef",
+        Rvalue::NullaryOp(..) => "NullaryOp",
+        Rvalue::UnaryOp(..) => "UnaryOp",
+        Rvalue::Use(..) => "Use",
+    }
+}
+
+fn terminator_name(terminator: &TerminatorKind) -> &'static str {
+    match terminator {
+        TerminatorKind::Goto { .. } => "Goto",
+        TerminatorKind::SwitchInt { .. } => "SwitchInt",
+        TerminatorKind::Resume => "Resume",
+        TerminatorKind::Abort => "Abort",
+        TerminatorKind::Return => "Return",
+        TerminatorKind::Unreachable => "Unreachable",
+        TerminatorKind::Drop { .. } => "Drop",
+        TerminatorKind::Call { .. } => "Call",
+        TerminatorKind::Assert { .. } => "Assert",
+        TerminatorKind::InlineAsm { .. } => "InlineAsm",
+    }
+}
+
+fn rigid_ty_name(ty: &RigidTy) -> &'static str {
+    match ty {
+        RigidTy::Bool => "Bool",
+        RigidTy::Char => "Char",
+        RigidTy::Int(_) => "Int",
+        RigidTy::Uint(_) => "Uint",
+        RigidTy::Float(_) => "Float",
+        RigidTy::Adt(..) => "Adt",
+        RigidTy::Foreign(_) => "Foreign",
+        RigidTy::Str => "Str",
+        RigidTy::Array(..) => "Array",
+        RigidTy::Pat(..) => "Pat",
+        RigidTy::Slice(_) => "Slice",
+        RigidTy::RawPtr(..) => "RawPtr",
+        RigidTy::Ref(..) => "Ref",
+        RigidTy::FnDef(..) => "FnDef",
+        RigidTy::FnPtr(_) => "FnPtr",
+        RigidTy::Closure(..) => "Closure",
+        RigidTy::Coroutine(..) => "Coroutine",
+        RigidTy::CoroutineClosure(..) => "CoroutineClosure",
+        RigidTy::Dynamic(..) => "Dynamic",
+        RigidTy::Never => "Never",
+        RigidTy::Tuple(_) => "Tuple",
+        RigidTy::CoroutineWitness(..) => "CoroutineWitness",
+    }
+}
+
+const INPUT: &str = r#"
+#![feature(core_intrinsics, custom_mir, coroutines, stmt_expr_attributes, extern_types)]
+#![feature(thread_local, pattern_types, pattern_type_macro, liballoc_internals)]
+#![allow(internal_features, unused)]
+
+use std::intrinsics::mir::*;
+use std::pat::pattern_type;
+
+unsafe extern "C" {
+    pub type Opaque;
+}
+
+#[thread_local]
+static LOCAL: u8 = 0;
+
+pub enum Shape {
+    Circle(f32),
+    Square(f32),
+}
+
+pub struct Pair {
+    pub a: u32,
+    pub b: char,
+}
+
+pub fn rvalues(x: u32, y: u32, flag: bool, shape: &Shape) -> usize {
+    let sum = x + y;
+    let eq = x == y;
+    let byte = x as u8;
+    let not = !flag;
+    let array = [byte; 4];
+    let slice: &[u8] = &array;
+    let pair = Pair { a: sum, b: 'c' };
+    let raw = &raw const pair;
+    let local = &LOCAL;
+    let size = match shape {
+        Shape::Circle(radius) => *radius,
+        Shape::Square(side) => *side,
+    };
+    let boxed = std::boxed::box_new(pair.a);
+    let signed = int(1);
+    let s: &str = "str";
+    std::mem::offset_of!(Pair, b)
+}
+
+pub fn int(i: i64) -> i64 {
+    i
+}
+
+pub fn tys(opaque: &Opaque, pattern: pattern_type!(u32 is 1..)) -> (u32, u32) {
+    let fn_def = int;
+    let fn_ptr: fn(i64) -> i64 = int;
+    let closure = |value: u32| value;
+    let coroutine = #[coroutine]
+    || {
+        yield 1u32;
+    };
+    let coroutine_closure = async || 1u32;
+    let dynamic: &dyn Fn() -> u32 = &|| 1;
+    (closure(1), dynamic())
+}
+
+pub fn never() -> ! {
+    loop {}
+}
+
+pub fn inline_asm() {
+    unsafe { std::arch::asm!("") }
+}
+
+#[custom_mir(dialect = "runtime", phase = "optimized")]
+pub fn custom(r: &&u32) -> u32 {
+    mir! {
+        let tmp: &u32;
+        {
+            tmp = CopyForDeref(*r);
+            RET = *tmp;
+            Return()
+        }
+        bb1(cleanup) = {
+            UnwindTerminate(ReasonAbi)
+        }
+        bb2(cleanup) = {
+            UnwindResume()
+        }
+    }
+}
+"#;
@@ -0,0 +1,109 @@
+//@ run-pass
+//! Test that users are able to evaluate trait references and normalize types.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+
+#![feature(rustc_private)]
+
+extern crate rustc_middle;
+
+extern crate rustc_driver;
+extern crate rustc_interface;
+#[macro_use]
+extern crate rustc_public;
+
+use rustc_public::ty::{
+    AssocKind, GenericArgKind, GenericArgs, RigidTy, TraitEvaluation, TraitRef, Ty, TyKind,
+    TypingEnv, UintTy,
+};
+use rustc_public::{CrateDef, CrateDefItems};
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+/// This function uses the Stable MIR APIs to query the trait solver.
+fn test_trait_solver() -> ControlFlow<()> {
+    let local_crate = rustc_public::local_crate();
+    let shape = local_crate.trait_decls().into_iter().find(|def| def.name() == "Shape").unwrap();
+    let unit = shape
+        .associated_items()
+        .into_iter()
+        .find(|item| matches!(item.kind, AssocKind::Type { .. }))
+        .unwrap()
+        .def_id;
+
+    let fn_defs = local_crate.fn_defs();
+    let check = fn_defs.iter().find(|def| def.name() == "check").unwrap().fn_sig().skip_binder();
+    let [square, meters] = check.inputs() else { unreachable!() };
+    let generic = fn_defs.iter().find(|def| def.name() == "generic").unwrap();
+    let generic_sig = generic.fn_sig().skip_binder();
+    let [param] = generic_sig.inputs() else { unreachable!() };
+
+    // Evaluate trait references.
+    let env = TypingEnv::FullyMonomorphized;
+    let no_args = GenericArgs(vec![]);
+    assert_eq!(TraitRef::new(shape, *square, &no_args).evaluate(env), TraitEvaluation::Holds);
+    assert_eq!(TraitRef::new(shape, *meters, &no_args).evaluate(env), TraitEvaluation::DoesNotHold);
+    let generic_env = TypingEnv::PostAnalysis(generic.def_id());
+    assert!(TraitRef::new(shape, *param, &no_args).holds(generic_env));
+    assert!(!TraitRef::new(shape, *param, &no_args).holds(env));
+
+    // Normalize projections.
+    let projection = |self_ty: Ty| {
+        Ty::try_new_projection(unit, GenericArgs(vec![GenericArgKind::Type(self_ty)])).unwrap()
+    };
+    let normalized = projection(*square).normalize(env).unwrap();
+    assert_eq!(normalized.kind(), TyKind::RigidTy(RigidTy::Uint(UintTy::U32)));
+    assert!(projection(*meters).normalize(env).is_err());
+    let rigid = projection(*param).normalize(generic_env).unwrap();
+    assert!(matches!(rigid.kind(), TyKind::Alias(..)), "Unexpected kind: {:?}", rigid.kind());
+
+    // A projection needs the generic arguments of the trait.
+    assert!(Ty::try_new_projection(unit, no_args).is_err());
+    ControlFlow::Continue(())
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `RustcPublic` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "trait_solver_input.rs";
+    generate_input(&path).unwrap();
+    let args = &[
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run!(args, test_trait_solver).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+        pub trait Shape {{
+            type Unit;
+        }}
+
+        pub struct Square;
+
+        impl Shape for Square {{
+            type Unit = u32;
+        }}
+
+        pub struct Meters;
+
+        pub fn check(_square: Square, _meters: Meters) {{}}
+
+        pub fn generic<T: Shape>(_shape: T) {{}}
+        "#
+    )?;
+    Ok(())
+}
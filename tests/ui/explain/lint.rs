@@ -0,0 +1,2 @@
+//@ compile-flags: --explain unused-mut
+//@ check-pass
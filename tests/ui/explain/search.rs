@@ -0,0 +1,2 @@
+//@ compile-flags: -Zunstable-options --search 'while true'
+//@ check-pass
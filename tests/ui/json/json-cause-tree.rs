@@ -0,0 +1,15 @@
+//@ compile-flags: --error-format=json --json=diagnostic-short,cause-tree -Zunstable-options
+//@ normalize-stderr: "(?s)\x22code\x22:\{.*?,(\x22cause_tree\x22)" -> "...,${1}"
+
+// Check that the causes of a derived obligation are nested under it in the cause tree.
+
+trait Foo {}
+trait Bar {}
+
+impl<T> Foo for T where T: Bar {}
+fn needs_foo(_: impl Foo) {}
+
+fn main() {
+    needs_foo(());
+    //~^ ERROR the trait bound `(): Foo` is not satisfied
+}
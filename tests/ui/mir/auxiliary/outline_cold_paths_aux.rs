@@ -0,0 +1,9 @@
+//@ compile-flags: -O -Zmir-opt-level=3
+
+#[inline]
+pub fn checked_div(a: u32, b: u32) -> u32 {
+    if b == 0 {
+        panic!("cannot divide {a} by zero");
+    }
+    a / b
+}
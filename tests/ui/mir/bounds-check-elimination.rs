@@ -0,0 +1,79 @@
+//@ run-pass
+//@ needs-unwind
+//@ compile-flags: -O -Zmir-opt-level=2 -Zvalidate-mir -Zinline-mir
+
+// Bounds checks that are removed by `BoundsCheckElimination` must be the ones that always pass.
+// The checks that can fail must still panic.
+
+use std::panic::catch_unwind;
+
+#[inline(never)]
+fn sum(s: &[u32]) -> u32 {
+    let mut total = 0;
+    for i in 0..s.len() {
+        total += s[i];
+    }
+    total
+}
+
+#[inline(never)]
+fn sum_prefix(s: &[u32], n: usize) -> u32 {
+    let mut total = 0;
+    for i in 0..n {
+        total += s[i];
+    }
+    total
+}
+
+#[inline(never)]
+fn sum_shrinking(mut s: &[u32]) -> u32 {
+    let mut total = 0;
+    let len = s.len();
+    for i in 0..len {
+        total += s[i];
+        s = &s[..s.len() / 2];
+    }
+    total
+}
+
+#[inline(never)]
+fn last(s: &[u32]) -> u32 {
+    if s.len() != 0 { s[s.len() - 1] } else { 0 }
+}
+
+#[inline(never)]
+fn masked(a: &[u32; 16], i: usize) -> u32 {
+    a[i & 15] + a[i % 16]
+}
+
+#[inline(never)]
+fn wrapping(s: &[u32], i: usize) -> u32 {
+    s[i % s.len()]
+}
+
+#[inline(never)]
+fn off_by_one(s: &[u32]) -> u32 {
+    let mut total = 0;
+    for i in 0..=s.len() {
+        total += s[i];
+    }
+    total
+}
+
+fn main() {
+    let v: Vec<u32> = (1..=10).collect();
+    assert_eq!(sum(&v), 55);
+    assert_eq!(sum(&[]), 0);
+    assert_eq!(sum_prefix(&v, 4), 10);
+    assert_eq!(last(&v), 10);
+    assert_eq!(last(&[]), 0);
+    assert_eq!(wrapping(&v, 23), 4);
+
+    let a: [u32; 16] = std::array::from_fn(|i| i as u32);
+    assert_eq!(masked(&a, 35), 6);
+
+    assert!(catch_unwind(|| sum_prefix(&v, 11)).is_err());
+    assert!(catch_unwind(|| sum_shrinking(&v)).is_err());
+    assert!(catch_unwind(|| off_by_one(&v)).is_err());
+    assert!(catch_unwind(|| wrapping(&[], 0)).is_err());
+}
@@ -0,0 +1,64 @@
+//@ run-pass
+//@ compile-flags: -O -Zmir-opt-level=3 -Zvalidate-mir
+
+// Check that the clones of functions for the constant arguments of their call sites behave like
+// the original, including when the argument is mutated in the callee, when the entry block of the
+// callee is the head of a loop, and when the original is also called through a function pointer.
+
+#[inline(never)]
+fn checksum(data: &[u8], wrapping: bool, seed: u32) -> u32 {
+    let mut sum = seed;
+    for &byte in data {
+        sum = if wrapping { sum.wrapping_mul(31).wrapping_add(byte as u32) } else { sum + 1 };
+    }
+    sum
+}
+
+#[inline(never)]
+fn count_down(mut n: u32, step: u32) -> u32 {
+    let mut iterations = 0;
+    loop {
+        if n < step {
+            return iterations;
+        }
+        n -= step;
+        iterations += 1;
+    }
+}
+
+#[inline(never)]
+fn classify(c: char, strict: bool) -> u8 {
+    match c {
+        'a'..='z' => 1,
+        'A'..='Z' if !strict => 1,
+        '0'..='9' => 2,
+        _ => 0,
+    }
+}
+
+#[inline(never)]
+fn generic_len<T>(items: &[T], extra: usize) -> usize {
+    items.len() + extra
+}
+
+fn main() {
+    let data = std::hint::black_box(*b"const specialization");
+
+    let wrapped = checksum(&data, true, 7);
+    assert_eq!(wrapped, checksum(std::hint::black_box(&data), true, 7));
+    assert_eq!(checksum(&data, false, 7), 7 + data.len() as u32);
+    assert_eq!(checksum(&[], false, 0), 0);
+
+    assert_eq!(count_down(10, 3), 3);
+    assert_eq!(count_down(2, 3), 0);
+
+    let classify_ptr: fn(char, bool) -> u8 = classify;
+    for c in ['a', 'Q', '7', '-'] {
+        let c = std::hint::black_box(c);
+        assert_eq!(classify(c, true), std::hint::black_box(classify_ptr)(c, true));
+        assert_eq!(classify(c, false), std::hint::black_box(classify_ptr)(c, false));
+    }
+
+    assert_eq!(generic_len(&[1u8, 2, 3], 2), 5);
+    assert_eq!(generic_len(&["a", "b"], 2), 4);
+}
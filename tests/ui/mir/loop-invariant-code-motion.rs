@@ -0,0 +1,95 @@
+//@ run-pass
+//@ needs-unwind
+//@ compile-flags: -O -Zmir-opt-level=2 -Zvalidate-mir -Zinline-mir
+
+// Check that the values hoisted out of loops by `LoopInvariantCodeMotion` are the same as the ones
+// computed in the loop, and that hoisting does not introduce a division by zero.
+
+use std::panic::catch_unwind;
+
+#[derive(Clone, Copy)]
+enum Op {
+    Add,
+    Sub,
+}
+
+#[inline(never)]
+fn nested(a: u32, b: u32, n: u32) -> u32 {
+    let mut total = 0u32;
+    for i in 0..n {
+        for j in 0..n {
+            let invariant = a.wrapping_mul(b) ^ (a >> 3);
+            let outer = invariant.wrapping_add(i);
+            total = total.wrapping_add(outer.wrapping_mul(j));
+        }
+    }
+    total
+}
+
+#[inline(never)]
+fn chained(s: &[u64], k: u64) -> u64 {
+    let mut total = 0;
+    for &x in s {
+        let len = s.len() as u64;
+        let scaled = len * 3 + k;
+        total += x * scaled / 7;
+    }
+    total
+}
+
+#[inline(never)]
+fn apply(op: Op, s: &[i32]) -> i32 {
+    let mut acc = 0i32;
+    for &x in s {
+        match op {
+            Op::Add => acc = acc.wrapping_add(x),
+            Op::Sub => acc = acc.wrapping_sub(x),
+        }
+    }
+    acc
+}
+
+#[inline(never)]
+fn divide(s: &[u32], d: u32) -> u32 {
+    let mut total = 0;
+    for &x in s {
+        // `d` may be zero: the division must not be evaluated when the loop does not run.
+        total += x + 100 / d;
+    }
+    total
+}
+
+#[inline(never)]
+fn zero_iterations(n: u32, a: i32) -> i32 {
+    let mut total = 0;
+    for _ in 0..n {
+        total += (a as i64 * 2 + 1) as i32;
+    }
+    total
+}
+
+fn main() {
+    let mut expected = 0u32;
+    for i in 0..5u32 {
+        for j in 0..5u32 {
+            let invariant = 7u32.wrapping_mul(11) ^ (7 >> 3);
+            expected = expected.wrapping_add(invariant.wrapping_add(i).wrapping_mul(j));
+        }
+    }
+    assert_eq!(nested(7, 11, 5), expected);
+    assert_eq!(nested(7, 11, 0), 0);
+
+    let v = [1, 2, 3, 4];
+    assert_eq!(chained(&v, 2), (1 + 2 + 3 + 4) * 14 / 7);
+    assert_eq!(chained(&[], 2), 0);
+
+    assert_eq!(apply(Op::Add, &[1, 2, 3]), 6);
+    assert_eq!(apply(Op::Sub, &[1, 2, 3]), -6);
+
+    assert_eq!(divide(&[1, 2], 10), 23);
+    assert_eq!(divide(&[], 0), 0);
+    assert!(catch_unwind(|| divide(&[1], 0)).is_err());
+
+    assert_eq!(zero_iterations(0, i32::MAX), 0);
+    assert_eq!(zero_iterations(3, 4), 27);
+}
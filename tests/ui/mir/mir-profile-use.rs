@@ -0,0 +1,30 @@
+//@ build-pass
+//@ compile-flags: -O -Zinline-mir -Zmir-remarks=Inline
+//@ compile-flags: -Zmir-profile-use={{src-base}}/mir/mir-profile-use.txt
+
+// Check that `-Z mir-profile-use` keeps the MIR inliner away from cold functions, and raises the
+// threshold for call sites between hot functions.
+
+fn hot_callee(x: u32) -> u32 {
+    x * 3
+}
+
+fn cold_callee(x: u32) -> u32 {
+    x + 1
+}
+
+#[inline(never)]
+fn hot_caller(x: u32) -> u32 {
+    hot_callee(x)
+}
+
+#[inline(never)]
+fn cold_caller(x: u32) -> u32 {
+    hot_callee(x)
+}
+
+fn main() {
+    let a = hot_caller(1);
+    let b = cold_caller(2);
+    let _ = cold_callee(a + b);
+}
@@ -0,0 +1,13 @@
+//@ build-pass
+//@ compile-flags: -O -Zmir-remarks=BoundsCheckElimination
+
+// Check that `-Z mir-remarks` reports the bounds checks removed by the MIR optimizations.
+
+#[inline(never)]
+fn get(a: &[u32; 16], i: usize) -> u32 {
+    a[i & 15]
+}
+
+fn main() {
+    let _ = get(&[0; 16], std::hint::black_box(3));
+}
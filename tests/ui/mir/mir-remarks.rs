@@ -0,0 +1,13 @@
+//@ build-pass
+//@ compile-flags: -O -Zinline-mir -Zmir-remarks=Inline
+
+// Check that `-Z mir-remarks` explains why the MIR inliner did not inline a call.
+
+#[inline(never)]
+fn callee() -> u32 {
+    1
+}
+
+fn main() {
+    let _ = callee();
+}
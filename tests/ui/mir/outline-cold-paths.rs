@@ -0,0 +1,97 @@
+//@ run-pass
+//@ needs-unwind
+//@ aux-build:outline_cold_paths_aux.rs
+//@ compile-flags: -O -Zmir-opt-level=3 -Zvalidate-mir
+
+// Check that the cold paths outlined by `OutlineColdPaths` still see and update the locals of
+// the function they come from, and unwind to its cleanup blocks, also when that function is
+// inlined into another crate.
+
+extern crate outline_cold_paths_aux;
+
+use std::cell::Cell;
+use std::panic::{self, catch_unwind};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+struct Guard;
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        DROPPED.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[inline(never)]
+fn checked_get(v: &[u32], i: usize) -> u32 {
+    if i >= v.len() {
+        panic!("index {i} out of range for length {}", v.len());
+    }
+    v[i]
+}
+
+#[inline(never)]
+fn with_cleanup(fail: bool) -> usize {
+    let s = String::from("cleanup");
+    let _guard = Guard;
+    if fail {
+        panic!("failed with {s}");
+    }
+    s.len()
+}
+
+#[inline(never)]
+fn writes_before_panic(fail: bool, log: &Cell<u32>) -> u32 {
+    let mut n = 1;
+    if fail {
+        n += 10;
+        log.set(n);
+        panic!("n is {n}");
+    }
+    n
+}
+
+fn panic_message(f: impl FnOnce() + panic::UnwindSafe) -> String {
+    let payload = catch_unwind(f).unwrap_err();
+    *payload.downcast::<String>().unwrap()
+}
+
+fn main() {
+    panic::set_hook(Box::new(|_| {}));
+
+    assert_eq!(checked_get(&[1, 2, 3], 2), 3);
+    assert_eq!(
+        panic_message(|| {
+            checked_get(&[1, 2, 3], 7);
+        }),
+        "index 7 out of range for length 3"
+    );
+
+    assert_eq!(with_cleanup(false), 7);
+    assert_eq!(DROPPED.load(Ordering::Relaxed), 1);
+    assert_eq!(
+        panic_message(|| {
+            with_cleanup(true);
+        }),
+        "failed with cleanup"
+    );
+    assert_eq!(DROPPED.load(Ordering::Relaxed), 2);
+
+    let log = Cell::new(0);
+    assert_eq!(writes_before_panic(false, &log), 1);
+    assert_eq!(log.get(), 0);
+    let message = panic_message(panic::AssertUnwindSafe(|| {
+        writes_before_panic(true, &log);
+    }));
+    assert_eq!(message, "n is 11");
+    assert_eq!(log.get(), 11);
+
+    assert_eq!(outline_cold_paths_aux::checked_div(12, 4), 3);
+    assert_eq!(
+        panic_message(|| {
+            outline_cold_paths_aux::checked_div(12, 0);
+        }),
+        "cannot divide 12 by zero"
+    );
+}
@@ -23,6 +23,12 @@
 //@ revisions: check_cfg
 //@[check_cfg] compile-flags: --print=check-cfg
 
+//@ revisions: stack_sizes
+//@[stack_sizes] compile-flags: --print=stack-sizes
+
+//@ revisions: stack_sizes_json
+//@[stack_sizes_json] compile-flags: --print=stack-sizes-json
+
 //@ revisions: supported_crate_types
 //@[supported_crate_types] compile-flags: --print=supported-crate-types
 
@@ -108,5 +114,7 @@ fn main() {}
 //[all_target_specs_json]~? ERROR the `-Z unstable-options` flag must also be passed to enable the `all-target-specs-json` print option
 //[crate_root_lint_levels]~? ERROR the `-Z unstable-options` flag must also be passed to enable the `crate-root-lint-levels` print option
 //[check_cfg]~? ERROR the `-Z unstable-options` flag must also be passed to enable the `check-cfg` print option
+//[stack_sizes]~? ERROR the `-Z unstable-options` flag must also be passed to enable the `stack-sizes` print option
+//[stack_sizes_json]~? ERROR the `-Z unstable-options` flag must also be passed to enable the `stack-sizes-json` print option
 //[supported_crate_types]~? ERROR the `-Z unstable-options` flag must also be passed to enable the `supported-crate-types` print option
 //[target_spec_json]~? ERROR the `-Z unstable-options` flag must also be passed to enable the `target-spec-json` print option